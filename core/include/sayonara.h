@@ -0,0 +1,139 @@
+/*
+ * sayonara.h - C interface to the sayonara-wipe engine (libsayonara_wipe.so)
+ *
+ * A wipe runs on a background thread behind an opaque handle:
+ *
+ *     SayonaraWipeConfig config;
+ *     SayonaraWipe *wipe;
+ *     char *report;
+ *
+ *     sayonara_config_default(&config);
+ *     config.algorithm = SAYONARA_ALGORITHM_NIST_CLEAR;
+ *     if (sayonara_wipe_start("/dev/sdb", &config, &wipe) != SAYONARA_OK)
+ *         fprintf(stderr, "%s\n", sayonara_last_error());
+ *     ... poll sayonara_wipe_progress(wipe, &progress) ...
+ *     if (sayonara_wipe_wait(wipe, &report) == SAYONARA_OK)
+ *         sayonara_string_free(report);
+ *     sayonara_wipe_free(wipe);
+ *
+ * Every function returns a SAYONARA_* code. sayonara_last_error() describes
+ * the last failure on the calling thread; the library owns that string.
+ * Strings the library hands out otherwise are freed with
+ * sayonara_string_free(). Input strings are copied and may be freed once the
+ * call returns.
+ */
+
+#ifndef SAYONARA_H
+#define SAYONARA_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SAYONARA_OK                       0
+#define SAYONARA_ERR_INVALID_ARGUMENT     1  /* NULL pointer, bad UTF-8, unknown algorithm */
+#define SAYONARA_ERR_CONFIG               2  /* config_json invalid */
+#define SAYONARA_ERR_IN_PROGRESS          3  /* no result yet */
+#define SAYONARA_ERR_INTERNAL             4
+#define SAYONARA_ERR_IO                   10
+#define SAYONARA_ERR_DRIVE_FROZEN         11
+#define SAYONARA_ERR_HARDWARE_COMMAND     12
+#define SAYONARA_ERR_MISSING_TOOL         13
+#define SAYONARA_ERR_SMART                14
+#define SAYONARA_ERR_TEMPERATURE          15
+#define SAYONARA_ERR_TRIM                 16
+#define SAYONARA_ERR_CRYPTO_ERASE         17
+#define SAYONARA_ERR_UNLOCK               18
+#define SAYONARA_ERR_TIMEOUT              19
+#define SAYONARA_ERR_PERMISSION_DENIED    20
+#define SAYONARA_ERR_NOT_FOUND            21
+#define SAYONARA_ERR_UNSUPPORTED          22
+#define SAYONARA_ERR_SYSTEM_DISK          23
+#define SAYONARA_ERR_MOUNTED              24
+#define SAYONARA_ERR_UNHEALTHY            25
+#define SAYONARA_ERR_CHECKPOINT_MISMATCH  26
+#define SAYONARA_ERR_INTERRUPTED          27
+#define SAYONARA_ERR_CANCELLED            28
+#define SAYONARA_ERR_SIZE_MISMATCH        29  /* device ends before its detected size */
+#define SAYONARA_ERR_SERIAL_NOT_PERMITTED 30  /* serial on the denylist or missing from the allowlist */
+
+#define SAYONARA_ALGORITHM_DOD5220        0
+#define SAYONARA_ALGORITHM_GUTMANN        1
+#define SAYONARA_ALGORITHM_RANDOM         2
+#define SAYONARA_ALGORITHM_ZERO           3
+#define SAYONARA_ALGORITHM_SECURE_ERASE   4
+#define SAYONARA_ALGORITHM_CRYPTO_ERASE   5
+#define SAYONARA_ALGORITHM_SANITIZE       6
+#define SAYONARA_ALGORITHM_TRIM_ONLY      7
+#define SAYONARA_ALGORITHM_NIST_CLEAR     8
+#define SAYONARA_ALGORITHM_NIST_PURGE     9
+#define SAYONARA_ALGORITHM_BSI_GS         10
+#define SAYONARA_ALGORITHM_BSI_GSE        11
+#define SAYONARA_ALGORITHM_VSITR          12
+#define SAYONARA_ALGORITHM_HMG_BASELINE   13
+#define SAYONARA_ALGORITHM_HMG_ENHANCED   14
+
+#define SAYONARA_STATE_RUNNING            0
+#define SAYONARA_STATE_SUCCEEDED          1
+#define SAYONARA_STATE_FAILED             2
+
+/* When config_json is set it is the whole configuration (the JSON policy
+ * file format, including custom pass lists) and the other fields are ignored. */
+typedef struct SayonaraWipeConfig {
+    int algorithm;                  /* SAYONARA_ALGORITHM_* */
+    bool verify;
+    bool dry_run;
+    bool force;                     /* wipe a drive whose SMART health is failing */
+    bool allow_system_disk;
+    const char *certificate_path;   /* may be NULL */
+    const char *config_json;        /* may be NULL */
+} SayonaraWipeConfig;
+
+typedef struct SayonaraProgress {
+    int state;                      /* SAYONARA_STATE_* */
+    uint32_t current_pass;          /* 1-based; 0 before the first pass */
+    uint32_t total_passes;
+    uint64_t bytes_written;
+    uint64_t total_bytes;
+    double percent;
+    double bytes_per_second;
+    int64_t eta_seconds;            /* -1 while unknown */
+} SayonaraProgress;
+
+typedef struct SayonaraWipe SayonaraWipe;
+
+/* Fill config with the library defaults. */
+int sayonara_config_default(SayonaraWipeConfig *config);
+
+/* Start wiping device_path in the background. Detection happens on the wipe
+ * thread, so a missing or protected device is reported by the result. */
+int sayonara_wipe_start(const char *device_path, const SayonaraWipeConfig *config, SayonaraWipe **wipe);
+
+int sayonara_wipe_progress(const SayonaraWipe *wipe, SayonaraProgress *progress);
+
+/* Stop at the next chunk; the wipe checkpoints and ends with SAYONARA_ERR_CANCELLED. */
+int sayonara_wipe_cancel(const SayonaraWipe *wipe);
+
+/* SAYONARA_OK with the report as JSON in *report_json (if not NULL; free it
+ * with sayonara_string_free), the wipe's error code, or SAYONARA_ERR_IN_PROGRESS. */
+int sayonara_wipe_result(SayonaraWipe *wipe, char **report_json);
+
+/* Like sayonara_wipe_result, after blocking until the wipe finishes. */
+int sayonara_wipe_wait(SayonaraWipe *wipe, char **report_json);
+
+/* Cancel if still running, wait for the thread, and free the handle. */
+void sayonara_wipe_free(SayonaraWipe *wipe);
+
+/* Last error on this thread, or NULL; valid until the next failing call. */
+const char *sayonara_last_error(void);
+
+void sayonara_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SAYONARA_H */
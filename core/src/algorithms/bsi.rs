@@ -0,0 +1,52 @@
+// BSI (German Federal Office for Information Security) overwrite standards
+//
+// BSI-GS follows the IT-Grundschutz guidance for reusing media: one pass of
+// random data, then a fixed pass that is read back to prove the drive
+// accepted every write. BSI-GSE, the extended variant for data with a higher
+// protection need, adds a second random pass before the fixed one. VSITR,
+// the older BSI directive for classified material, alternates 0x00 and 0xFF
+// for six passes and finishes with 0xAA. All three are run by the
+// orchestrator's multi-pass engine like DoD 5220.22-M.
+
+use super::PatternSpec;
+
+pub struct BsiWipe;
+
+impl BsiWipe {
+    /// BSI-GS pass sequence; the final 0x00 pass is read back for verification
+    pub(crate) const BSI_GS_PASSES: [(PatternSpec, &'static str); 2] = [
+        (PatternSpec::Random, "Writing random data"),
+        (PatternSpec::Byte(0x00), "Writing 0x00 (verified)"),
+    ];
+
+    /// BSI-GSE pass sequence; the final 0x00 pass is read back for verification
+    pub(crate) const BSI_GSE_PASSES: [(PatternSpec, &'static str); 3] = [
+        (PatternSpec::Random, "Writing random data"),
+        (PatternSpec::Random, "Writing random data (second pass)"),
+        (PatternSpec::Byte(0x00), "Writing 0x00 (verified)"),
+    ];
+
+    /// VSITR pass sequence; the final 0xAA pass is read back for verification
+    pub(crate) const VSITR_PASSES: [(PatternSpec, &'static str); 7] = [
+        (PatternSpec::Byte(0x00), "Writing 0x00"),
+        (PatternSpec::Byte(0xFF), "Writing 0xFF"),
+        (PatternSpec::Byte(0x00), "Writing 0x00"),
+        (PatternSpec::Byte(0xFF), "Writing 0xFF"),
+        (PatternSpec::Byte(0x00), "Writing 0x00"),
+        (PatternSpec::Byte(0xFF), "Writing 0xFF"),
+        (PatternSpec::Byte(0xAA), "Writing 0xAA (verified)"),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vsitr_sequence_matches_spec() {
+        let bytes: Vec<PatternSpec> = BsiWipe::VSITR_PASSES.iter().map(|(pattern, _)| pattern.clone()).collect();
+        let expected: Vec<PatternSpec> =
+            [0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0xAA].into_iter().map(PatternSpec::Byte).collect();
+        assert_eq!(bytes, expected);
+    }
+}
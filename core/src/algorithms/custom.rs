@@ -0,0 +1,157 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use crate::crypto::secure_rng::secure_random_bytes;
+use crate::{DriveError, DriveResult};
+use super::GutmannPass;
+
+/// What one pass of an overwrite writes. Built-in algorithms map onto these,
+/// and `Algorithm::Custom` takes a list of them so any mandated sequence can
+/// be encoded without code changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternSpec {
+    /// The same byte everywhere
+    Byte(u8),
+    /// A repeating multi-byte sequence, kept in phase across chunks
+    Sequence(Vec<u8>),
+    /// Cryptographically secure random data
+    Random,
+}
+
+impl PatternSpec {
+    /// Fill `buf` with this pass's data.
+    ///
+    /// `offset` is the position of `buf[0]` within the region being wiped, so
+    /// sequences stay in phase across buffers whose size isn't a multiple of
+    /// the sequence length.
+    pub fn fill(&self, buf: &mut [u8], offset: u64) -> Result<()> {
+        match self {
+            PatternSpec::Byte(byte) => {
+                buf.fill(*byte);
+                Ok(())
+            }
+            PatternSpec::Sequence(bytes) => {
+                let phase = (offset % bytes.len() as u64) as usize;
+                for (i, byte) in buf.iter_mut().enumerate() {
+                    *byte = bytes[(phase + i) % bytes.len()];
+                }
+                Ok(())
+            }
+            PatternSpec::Random => secure_random_bytes(buf),
+        }
+    }
+
+    /// Length of the repeating unit; None for random data
+    pub fn period(&self) -> Option<usize> {
+        match self {
+            PatternSpec::Byte(_) => Some(1),
+            PatternSpec::Sequence(bytes) => Some(bytes.len()),
+            PatternSpec::Random => None,
+        }
+    }
+
+    /// Human-readable description, e.g. "0xFF" or "0x92 0x49 0x24"
+    pub fn description(&self) -> String {
+        match self {
+            PatternSpec::Byte(byte) => format!("0x{:02X}", byte),
+            PatternSpec::Sequence(bytes) => bytes
+                .iter()
+                .map(|byte| format!("0x{:02X}", byte))
+                .collect::<Vec<_>>()
+                .join(" "),
+            PatternSpec::Random => "Random data".to_string(),
+        }
+    }
+
+    /// Check a custom pass list: at least one pass, and no empty sequences
+    pub fn validate(passes: &[PatternSpec]) -> DriveResult<()> {
+        if passes.is_empty() {
+            return Err(DriveError::Unsupported("Custom algorithm needs at least one pass".to_string()));
+        }
+        if let Some(index) = passes.iter().position(|pass| *pass == PatternSpec::Sequence(Vec::new())) {
+            return Err(DriveError::Unsupported(format!("Custom pass {} has an empty byte sequence", index + 1)));
+        }
+        Ok(())
+    }
+
+    /// Parse a comma-separated pass list such as "0x00,0xFF,random"
+    pub fn parse_list(list: &str) -> Result<Vec<PatternSpec>> {
+        list.split(',').map(str::parse).collect()
+    }
+}
+
+/// Parses "random", a single hex byte ("0xFF" or "ff"), or a run of hex
+/// bytes for a sequence ("0x924924")
+impl FromStr for PatternSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("random") {
+            return Ok(PatternSpec::Random);
+        }
+
+        let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+        if hex.is_empty() || !hex.len().is_multiple_of(2) {
+            return Err(anyhow!("Invalid pattern '{}': expected 'random' or whole hex bytes", s));
+        }
+
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .map_err(|_| anyhow!("Invalid pattern '{}': expected 'random' or whole hex bytes", s))?;
+
+        Ok(match bytes.as_slice() {
+            [byte] => PatternSpec::Byte(*byte),
+            _ => PatternSpec::Sequence(bytes),
+        })
+    }
+}
+
+impl From<GutmannPass> for PatternSpec {
+    fn from(pass: GutmannPass) -> Self {
+        match pass {
+            GutmannPass::Pattern([byte]) => PatternSpec::Byte(*byte),
+            GutmannPass::Pattern(bytes) => PatternSpec::Sequence(bytes.to_vec()),
+            GutmannPass::Random => PatternSpec::Random,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern_list() {
+        assert_eq!(
+            PatternSpec::parse_list("0x00, ff,0x924924,Random").unwrap(),
+            vec![
+                PatternSpec::Byte(0x00),
+                PatternSpec::Byte(0xFF),
+                PatternSpec::Sequence(vec![0x92, 0x49, 0x24]),
+                PatternSpec::Random,
+            ]
+        );
+        assert!(PatternSpec::parse_list("0x0").is_err());
+        assert!(PatternSpec::parse_list("zz").is_err());
+        assert!(PatternSpec::parse_list("").is_err());
+    }
+
+    #[test]
+    fn test_sequence_stays_in_phase() {
+        let pattern = PatternSpec::Sequence(vec![1, 2, 3]);
+        let mut buf = [0u8; 4];
+        pattern.fill(&mut buf, 4).unwrap();
+        assert_eq!(buf, [2, 3, 1, 2]);
+        assert_eq!(pattern.description(), "0x01 0x02 0x03");
+    }
+
+    #[test]
+    fn test_validate_requires_a_pass() {
+        assert!(PatternSpec::validate(&[]).is_err());
+        assert!(PatternSpec::validate(&[PatternSpec::Sequence(vec![])]).is_err());
+        assert!(PatternSpec::validate(&[PatternSpec::Byte(0), PatternSpec::Random]).is_ok());
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use crate::crypto::secure_rng::secure_random_bytes;
 use crate::ui::progress::ProgressBar;
 use crate::io::{OptimizedIO, IOConfig, IOHandle};
@@ -7,10 +7,21 @@ use crate::{DriveResult, DriveError};
 use crate::WipeConfig;
 use crate::error::{RecoveryCoordinator, Progress, ErrorContext};
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::Read;
 
 pub struct DoDWipe;
 
 impl DoDWipe {
+    /// DoD 5220.22-M pass sequence: a fixed byte, its complement, then random data
+    /// (`None` marks a random pass). The final pass is read back for verification.
+    pub(crate) const DOD_PATTERNS: [(Option<&'static [u8]>, &'static str); 3] = [
+        (Some(&[0x00]), "Writing 0x00"),
+        (Some(&[0xFF]), "Writing 0xFF"),
+        (None, "Writing random data"),
+    ];
+
     pub fn wipe_drive(
         device_path: &str,
         size: u64,
@@ -20,7 +31,7 @@ impl DoDWipe {
         println!("Starting DoD 5220.22-M 3-pass wipe with error recovery on {}", device_path);
 
         // Initialize recovery coordinator
-        let mut coordinator = RecoveryCoordinator::new(device_path, config)?;
+        let coordinator = RecoveryCoordinator::new(device_path, config)?;
 
         // Check for existing checkpoint
         let start_pass = if let Some(resume) = coordinator.resume_from_checkpoint("DoD")? {
@@ -48,7 +59,9 @@ impl DoDWipe {
             coordinator.execute_with_recovery("pass_1", context, || -> DriveResult<()> { Self::write_pattern(&mut io_handle, size, 0x00).map_err(|e| DriveError::IoError(std::io::Error::new(std::io::ErrorKind::Other, format!("{}", e))))?; Ok(()) })?;
             coordinator.maybe_checkpoint("DoD", 3, size * 3, &Progress {
                 current_pass: 1,
+                pass_offset: 0,
                 bytes_written: size,
+                current_offset: Some(size),
                 state: json!({"pass": 1}),
             })?;
         }
@@ -60,7 +73,9 @@ impl DoDWipe {
             coordinator.execute_with_recovery("pass_2", context, || -> DriveResult<()> { Self::write_pattern(&mut io_handle, size, 0xFF).map_err(|e| DriveError::IoError(std::io::Error::new(std::io::ErrorKind::Other, format!("{}", e))))?; Ok(()) })?;
             coordinator.maybe_checkpoint("DoD", 3, size * 3, &Progress {
                 current_pass: 2,
+                pass_offset: 0,
                 bytes_written: size * 2,
+                current_offset: Some(size),
                 state: json!({"pass": 2}),
             })?;
         }
@@ -69,10 +84,21 @@ impl DoDWipe {
         if start_pass <= 2 {
             println!("\n🔄 Pass 3/3: Writing random data");
             let context = ErrorContext::new("dod_pass_3", device_path);
-            coordinator.execute_with_recovery("pass_3", context, || -> DriveResult<()> { Self::write_random(&mut io_handle, size).map_err(|e| DriveError::IoError(std::io::Error::new(std::io::ErrorKind::Other, format!("{}", e))))?; Ok(()) })?;
+            let digest = coordinator.execute_with_recovery("pass_3", context, || -> DriveResult<[u8; 32]> {
+                Self::write_random(&mut io_handle, size).map_err(|e| DriveError::IoError(std::io::Error::other(e.to_string())))
+            })?;
+
+            // DoD 5220.22-M requires the final pass to be verified
+            io_handle.sync()?;
+            println!("\n🔍 Verifying pass 3/3");
+            Self::verify_final_pass(device_path, size, &digest)?;
+            println!("✓ Final pass verified");
+
             coordinator.maybe_checkpoint("DoD", 3, size * 3, &Progress {
                 current_pass: 3,
+                pass_offset: 0,
                 bytes_written: size * 3,
+                current_offset: Some(size),
                 state: json!({"pass": 3}),
             })?;
         }
@@ -113,15 +139,20 @@ impl DoDWipe {
         Ok(())
     }
 
-    fn write_random(io_handle: &mut IOHandle, size: u64) -> Result<()> {
+    /// Write random data, returning the SHA-256 of everything written so the
+    /// pass can be read back and verified
+    fn write_random(io_handle: &mut IOHandle, size: u64) -> Result<[u8; 32]> {
         let mut bytes_written = 0u64;
         let mut bar = ProgressBar::new(48);
+        let mut hasher = Sha256::new();
 
         OptimizedIO::sequential_write(io_handle, size, |buffer| {
             // Fill buffer with cryptographically secure random data
             let buf = buffer.as_mut_slice();
             secure_random_bytes(buf)?;
 
+            let len = (buf.len() as u64).min(size.saturating_sub(bytes_written)) as usize;
+            hasher.update(&buf[..len]);
             bytes_written += buf.len() as u64;
 
             if bytes_written % (50 * 1024 * 1024) == 0 || bytes_written >= size {
@@ -133,6 +164,29 @@ impl DoDWipe {
         })?;
 
         bar.render(100.0, Some(size), Some(size));
+        Ok(hasher.finalize().into())
+    }
+
+    /// Read the device back and compare it with the digest of the final pass
+    fn verify_final_pass(device_path: &str, size: u64, expected: &[u8; 32]) -> Result<()> {
+        // The pass was synced, so dropping its cached pages sends the reads
+        // below to the media rather than back to RAM
+        crate::io::drop_page_cache(device_path)?;
+        let mut file = File::open(device_path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 4 * 1024 * 1024];
+        let mut remaining = size;
+
+        while remaining > 0 {
+            let len = remaining.min(buffer.len() as u64) as usize;
+            file.read_exact(&mut buffer[..len])?;
+            hasher.update(&buffer[..len]);
+            remaining -= len as u64;
+        }
+
+        if hasher.finalize().as_slice() != expected {
+            return Err(anyhow!("DoD final pass verification failed: data read back from {} differs from what was written", device_path));
+        }
         Ok(())
     }
 }
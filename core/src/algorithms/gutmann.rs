@@ -18,6 +18,43 @@ pub enum DriveEncoding {
     Unknown,  // Default to most comprehensive patterns
 }
 
+/// A single Gutmann pass: a repeating byte pattern or cryptographically secure random data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GutmannPass {
+    Pattern(&'static [u8]),
+    Random,
+}
+
+impl GutmannPass {
+    /// Fill `buf` with this pass's data.
+    ///
+    /// `offset` is the position of `buf[0]` within the region being wiped, so
+    /// 3-byte patterns stay in phase across buffers whose size isn't a multiple of 3.
+    pub fn fill(&self, buf: &mut [u8], offset: u64) -> Result<()> {
+        match self {
+            GutmannPass::Pattern(pattern) => {
+                let phase = (offset % pattern.len() as u64) as usize;
+                for (i, byte) in buf.iter_mut().enumerate() {
+                    *byte = pattern[(phase + i) % pattern.len()];
+                }
+                Ok(())
+            }
+            GutmannPass::Random => secure_random_bytes(buf),
+        }
+    }
+}
+
+/// The exact ordered list of 35 Gutmann passes
+pub fn gutmann_patterns() -> Vec<GutmannPass> {
+    GutmannWipe::GUTMANN_PATTERNS
+        .iter()
+        .map(|(pattern, _)| match pattern {
+            Some(bytes) => GutmannPass::Pattern(bytes),
+            None => GutmannPass::Random,
+        })
+        .collect()
+}
+
 pub struct GutmannWipe;
 
 impl GutmannWipe {
@@ -82,7 +119,7 @@ impl GutmannWipe {
         println!("Detected drive encoding: {:?}", encoding);
 
         // Initialize recovery coordinator
-        let mut coordinator = RecoveryCoordinator::new(device_path, config)?;
+        let coordinator = RecoveryCoordinator::new(device_path, config)?;
 
         // Check for existing checkpoint and resume if available
         let start_pass = if let Some(resume) = coordinator.resume_from_checkpoint("Gutmann")? {
@@ -147,7 +184,9 @@ impl GutmannWipe {
                 size * 35,
                 &Progress {
                     current_pass: pass_num + 1,
+                    pass_offset: 0,
                     bytes_written,
+                    current_offset: Some(size),
                     state: json!({
                         "encoding": format!("{:?}", encoding),
                         "total_passes": 35,
@@ -173,7 +212,7 @@ impl GutmannWipe {
 
     /// Detect the drive's encoding type for optimal pattern selection
     pub(crate) fn detect_drive_encoding(device_path: &str) -> Result<DriveEncoding> {
-        use std::process::Command;
+        use crate::commands::Command;
 
         // Try to get drive information via smartctl
         let output = Command::new("smartctl")
@@ -219,10 +258,11 @@ impl GutmannWipe {
 
         // Write phase using OptimizedIO
         OptimizedIO::sequential_write(io_handle, size, |buffer| {
-            // Fill buffer with repeating pattern
+            // Fill buffer with repeating pattern, keeping phase across buffers
             let buf = buffer.as_mut_slice();
+            let phase = (bytes_written % pattern.len() as u64) as usize;
             for (i, byte) in buf.iter_mut().enumerate() {
-                *byte = pattern[i % pattern.len()];
+                *byte = pattern[(phase + i) % pattern.len()];
             }
 
             bytes_written += buf.len() as u64;
@@ -313,9 +353,9 @@ impl GutmannWipe {
 
             let buffer = OptimizedIO::read_range(&mut handle, offset, read_size)?;
 
-            // Check pattern matches
+            // Check pattern matches (phase is relative to the start of the device)
             for (j, byte) in buffer.iter().enumerate() {
-                let expected = expected_pattern[j % expected_pattern.len()];
+                let expected = expected_pattern[((offset + j as u64) % expected_pattern.len() as u64) as usize];
                 if *byte != expected {
                     return Err(anyhow!(
                         "Verification failed at offset {}: expected 0x{:02x}, got 0x{:02x}",
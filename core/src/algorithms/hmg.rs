@@ -0,0 +1,23 @@
+// HMG Infosec Standard 5 (UK government) overwrite standards
+//
+// The baseline level is a single pass of zeros; the enhanced level writes
+// 0x00, its complement 0xFF, then random data. Both levels require the last
+// pass to be read back, so the orchestrator's multi-pass engine verifies it.
+
+use super::PatternSpec;
+
+pub struct HmgWipe;
+
+impl HmgWipe {
+    /// HMG IS5 Baseline pass sequence; the 0x00 pass is read back for verification
+    pub(crate) const BASELINE_PASSES: [(PatternSpec, &'static str); 1] = [
+        (PatternSpec::Byte(0x00), "Writing 0x00 (verified)"),
+    ];
+
+    /// HMG IS5 Enhanced pass sequence; the random pass is read back for verification
+    pub(crate) const ENHANCED_PASSES: [(PatternSpec, &'static str); 3] = [
+        (PatternSpec::Byte(0x00), "Writing 0x00"),
+        (PatternSpec::Byte(0xFF), "Writing 0xFF"),
+        (PatternSpec::Random, "Writing random data (verified)"),
+    ];
+}
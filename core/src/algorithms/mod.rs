@@ -1,13 +1,21 @@
 pub mod gutmann;
 pub mod dod;
+pub mod bsi;
+pub mod hmg;
+pub mod custom;
 pub mod random;
 pub mod zero;
 
 #[cfg(test)]
 mod gutmann_test;
+#[cfg(test)]
+pub(crate) mod reproducibility_test;
 
 // Re-export the main wiping implementations
 pub use dod::DoDWipe;
-pub use gutmann::GutmannWipe;
+pub use bsi::BsiWipe;
+pub use hmg::HmgWipe;
+pub use custom::PatternSpec;
+pub use gutmann::{GutmannWipe, GutmannPass, gutmann_patterns};
 pub use random::RandomWipe;
 pub use zero::ZeroWipe;
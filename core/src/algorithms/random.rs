@@ -20,7 +20,7 @@ impl RandomWipe {
         println!("Starting single-pass random wipe with error recovery on {}", device_path);
 
         // Initialize recovery coordinator
-        let mut coordinator = RecoveryCoordinator::new(device_path, config)?;
+        let coordinator = RecoveryCoordinator::new(device_path, config)?;
 
         // Check for existing checkpoint
         let should_resume = coordinator.resume_from_checkpoint("Random")?.is_some();
@@ -46,7 +46,9 @@ impl RandomWipe {
         // Save final checkpoint
         coordinator.maybe_checkpoint("Random", 1, size, &Progress {
             current_pass: 1,
+            pass_offset: 0,
             bytes_written: size,
+            current_offset: Some(size),
             state: json!({"complete": true}),
         })?;
 
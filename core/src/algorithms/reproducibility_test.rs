@@ -0,0 +1,206 @@
+// End-to-end wipe harness - every algorithm against a file target
+//
+// Fills an image with a recognizable marker, wipes it through the
+// orchestrator exactly as a drive would be wiped, and checks what is left
+// against the final pass the algorithm's standard prescribes: a fixed byte or
+// sequence everywhere, or data that passes the randomness tests with no trace
+// of the marker. The helpers are crate-visible so other tests can wipe an
+// image and assert on the result the same way.
+
+use crate::algorithms::PatternSpec;
+use crate::commands::{scoped_runner, MockCommandRunner};
+use crate::crypto::secure_rng::SecureRNG;
+use crate::verification::recovery_test::{RecoveryTest, VerificationConfig};
+use crate::{Algorithm, CancellationToken, WipeConfig, WipeOrchestrator, WipeReport};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+/// Marker the image is filled with before a wipe; its 7-byte period matches
+/// no pass pattern, so any block still holding it was missed
+pub(crate) const FILL_MARKER: &[u8] = b"SAYONAR";
+
+/// Image size: enough data for the randomness tests to mean something
+pub(crate) const IMAGE_SIZE: usize = 1 << 20;
+
+/// Chunk size for harness wipes, so every image spans several chunks
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Block size used when looking for remnants of the marker
+const BLOCK_SIZE: usize = 4096;
+
+/// Lowest Shannon entropy (bits per byte) accepted for a random final pass
+const MIN_RANDOM_ENTROPY: f64 = 7.99;
+
+/// Every algorithm, with a custom sequence ending in a multi-byte pattern.
+/// Hardware erases are included: an image can't erase itself, so they
+/// overwrite it.
+pub(crate) fn all_algorithms() -> Vec<Algorithm> {
+    vec![
+        Algorithm::DoD5220,
+        Algorithm::Gutmann,
+        Algorithm::Random,
+        Algorithm::Zero,
+        Algorithm::SecureErase,
+        Algorithm::CryptoErase,
+        Algorithm::Sanitize,
+        Algorithm::TrimOnly,
+        Algorithm::NIST80088Clear,
+        Algorithm::NIST80088Purge,
+        Algorithm::BSI_GS,
+        Algorithm::BSI_GSE,
+        Algorithm::VSITR,
+        Algorithm::HMGIS5Baseline,
+        Algorithm::HMGIS5Enhanced,
+        Algorithm::Custom {
+            passes: vec![PatternSpec::Random, PatternSpec::Sequence(vec![0xDE, 0xAD, 0xBE, 0xEF])],
+        },
+    ]
+}
+
+/// Passes `algorithm` writes to an image and what its last one leaves
+/// behind, taken from the standards rather than the implementation
+pub(crate) fn expected_result(algorithm: &Algorithm) -> (usize, PatternSpec) {
+    match algorithm {
+        Algorithm::Zero | Algorithm::NIST80088Clear | Algorithm::HMGIS5Baseline => (1, PatternSpec::Byte(0x00)),
+        Algorithm::DoD5220 => (3, PatternSpec::Random),
+        Algorithm::Gutmann => (35, PatternSpec::Random),
+        Algorithm::Random => (1, PatternSpec::Random),
+        Algorithm::SecureErase
+        | Algorithm::CryptoErase
+        | Algorithm::Sanitize
+        | Algorithm::TrimOnly
+        | Algorithm::NIST80088Purge => (1, PatternSpec::Random),
+        Algorithm::BSI_GS => (2, PatternSpec::Byte(0x00)),
+        Algorithm::BSI_GSE => (3, PatternSpec::Byte(0x00)),
+        Algorithm::VSITR => (7, PatternSpec::Byte(0xAA)),
+        Algorithm::HMGIS5Enhanced => (3, PatternSpec::Random),
+        Algorithm::Custom { passes } => (passes.len(), passes.last().cloned().unwrap_or(PatternSpec::Random)),
+    }
+}
+
+/// What the image held at `offset` before the wipe
+fn original_content(offset: usize, len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    PatternSpec::Sequence(FILL_MARKER.to_vec()).fill(&mut data, offset as u64).unwrap();
+    data
+}
+
+/// An image of `size` bytes filled with `FILL_MARKER`
+pub(crate) fn pattern_image(size: usize) -> NamedTempFile {
+    let image = NamedTempFile::new().unwrap();
+    std::fs::write(image.path(), original_content(0, size)).unwrap();
+    image
+}
+
+/// Wipe `path` with `algorithm` through the full detect-and-execute path.
+/// Commands are answered by a mock, so nothing outside the file is touched.
+pub(crate) async fn wipe_image(path: &str, algorithm: &Algorithm) -> (WipeReport, Arc<MockCommandRunner>) {
+    let mock = Arc::new(MockCommandRunner::new());
+    let _runner = scoped_runner(mock.clone());
+
+    let config = WipeConfig {
+        algorithm: algorithm.clone(),
+        chunk_size: CHUNK_SIZE,
+        ..Default::default()
+    };
+    let mut orchestrator = WipeOrchestrator::new(path.to_string(), config).unwrap();
+    let report = orchestrator
+        .execute(CancellationToken::new())
+        .await
+        .unwrap_or_else(|e| panic!("{:?} wipe failed: {}", algorithm, e));
+    (report, mock)
+}
+
+/// Assert `data` is what a final pass of `expected` leaves: the pattern
+/// in phase from byte 0, or random data with no block of the marker left
+pub(crate) fn assert_final_pass(data: &[u8], expected: &PatternSpec, label: &str) {
+    match expected {
+        PatternSpec::Byte(_) | PatternSpec::Sequence(_) => {
+            let mut pattern = vec![0u8; data.len()];
+            expected.fill(&mut pattern, 0).unwrap();
+            if let Some(at) = data.iter().zip(&pattern).position(|(a, b)| a != b) {
+                panic!("{}: byte {} is {:#04x}, expected {:#04x}", label, at, data[at], pattern[at]);
+            }
+        }
+        PatternSpec::Random => {
+            let remnants = data
+                .chunks(BLOCK_SIZE)
+                .enumerate()
+                .filter(|(index, block)| **block == original_content(index * BLOCK_SIZE, block.len()))
+                .count();
+            assert_eq!(remnants, 0, "{}: {} block(s) still hold the fill marker", label, remnants);
+
+            let entropy = SecureRNG::calculate_entropy(data);
+            assert!(entropy >= MIN_RANDOM_ENTROPY, "{}: entropy {:.4} bits/byte", label, entropy);
+
+            let scores = RecoveryTest::randomness_scores(data, &VerificationConfig::default());
+            assert!(scores.passed(), "{}: randomness tests failed: {:?}", label, scores);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_every_algorithm_leaves_its_final_pass() {
+        for algorithm in all_algorithms() {
+            let label = format!("{:?}", algorithm);
+            let image = pattern_image(IMAGE_SIZE);
+            let path = image.path().to_str().unwrap();
+            let (passes, expected) = expected_result(&algorithm);
+
+            let (report, mock) = wipe_image(path, &algorithm).await;
+            assert_eq!(report.passes.len(), passes, "{}", label);
+            assert!(report.method().starts_with("overwrite"), "{}: {}", label, report.method());
+            assert!(mock.calls().is_empty(), "{}: unexpected commands {:?}", label, mock.calls());
+
+            let data = std::fs::read(image.path()).unwrap();
+            assert_eq!(data.len(), IMAGE_SIZE, "{}", label);
+            assert_final_pass(&data, &expected, &label);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixed_patterns_reproduce_and_random_passes_do_not() {
+        for algorithm in [Algorithm::Zero, Algorithm::VSITR, Algorithm::Random] {
+            let first = pattern_image(IMAGE_SIZE);
+            let second = pattern_image(IMAGE_SIZE);
+            wipe_image(first.path().to_str().unwrap(), &algorithm).await;
+            wipe_image(second.path().to_str().unwrap(), &algorithm).await;
+
+            let identical = std::fs::read(first.path()).unwrap() == std::fs::read(second.path()).unwrap();
+            let random = matches!(expected_result(&algorithm).1, PatternSpec::Random);
+            assert_eq!(identical, !random, "{:?}", algorithm);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_wipe_ending_in_zeros_passes_recovery_verification() {
+        use crate::verification::recovery_test::ExpectedPattern;
+
+        let algorithm = Algorithm::Custom { passes: vec![PatternSpec::Byte(0xFF), PatternSpec::Byte(0x00)] };
+        let image = pattern_image(IMAGE_SIZE);
+        let path = image.path().to_str().unwrap();
+        wipe_image(path, &algorithm).await;
+
+        // The check the CLI runs after a wipe
+        let expected = ExpectedPattern::for_algorithm(&algorithm);
+        assert_eq!(expected, ExpectedPattern::Zeros);
+        let outcome =
+            RecoveryTest::verify_wipe_with_config(path, IMAGE_SIZE as u64, &VerificationConfig::default(), expected).unwrap();
+        assert!(outcome.passed, "failed at {:?}", outcome.first_failure);
+        assert!(outcome.randomness.is_none());
+    }
+
+    #[test]
+    fn test_harness_rejects_a_missed_block() {
+        let mut data = vec![0u8; IMAGE_SIZE];
+        SecureRNG::new().unwrap().fill_bytes(&mut data).unwrap();
+        data[BLOCK_SIZE * 3..BLOCK_SIZE * 4].copy_from_slice(&original_content(BLOCK_SIZE * 3, BLOCK_SIZE));
+
+        let missed = std::panic::catch_unwind(|| assert_final_pass(&data, &PatternSpec::Random, "missed"));
+        assert!(missed.is_err());
+    }
+}
@@ -29,7 +29,7 @@ impl ZeroWipe {
         println!("Drive size: {} bytes ({} GB)", size, size / (1024 * 1024 * 1024));
 
         // Initialize recovery coordinator
-        let mut coordinator = RecoveryCoordinator::new(device_path, config)?;
+        let coordinator = RecoveryCoordinator::new(device_path, config)?;
 
         // Check for existing checkpoint
         let should_resume = coordinator.resume_from_checkpoint("Zero")?.is_some();
@@ -57,7 +57,9 @@ impl ZeroWipe {
         // Save final checkpoint
         coordinator.maybe_checkpoint("Zero", 1, size, &Progress {
             current_pass: 1,
+            pass_offset: 0,
             bytes_written: size,
+            current_offset: Some(size),
             state: json!({"complete": true}),
         })?;
 
@@ -0,0 +1,600 @@
+// External commands - every hardware tool the wipe paths run
+//
+// `Command` mirrors the std builder, but hands each invocation to the current
+// `CommandRunner` instead of starting a process itself. `SystemRunner` runs
+// the real tools; tests install a `MockCommandRunner` with `scoped_runner` to
+// script responses and check exactly which commands a wipe would issue.
+//
+// A tool that isn't installed is reported as `DriveError::MissingTool`, apart
+// from a tool that ran and failed; `check_dependencies` lists the tools a
+// drive type needs before a wipe starts.
+//
+// Drives can hang forever on a secure erase or sanitize, so the wipe paths
+// give their commands the deadline of the wipe's `CancellationToken` (set by
+// `operation_timeout`) with `Command::deadline`: a command still running then
+// is killed and fails with `TimedOut`. The deadline travels with the command,
+// so it holds on whichever thread or task the command ends up running.
+
+use crate::{DriveError, DriveResult, DriveType};
+use std::cell::RefCell;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a command running under a deadline is checked
+const DEADLINE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A fully built command: program, arguments and working directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub program: OsString,
+    pub args: Vec<OsString>,
+    pub current_dir: Option<PathBuf>,
+}
+
+impl Invocation {
+    /// Program and arguments joined with spaces, e.g. "nvme sanitize-log /dev/nvme0n1"
+    pub fn command_line(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Executes external commands on behalf of `Command`
+pub trait CommandRunner: Send + Sync {
+    /// Run to completion, capturing stdout and stderr
+    fn output(&self, invocation: &Invocation) -> io::Result<Output>;
+
+    /// Start without waiting, inheriting stdio
+    fn spawn(&self, invocation: &Invocation) -> io::Result<Box<dyn RunningCommand>>;
+
+    /// Whether `program` can be found to run
+    fn is_installed(&self, program: &str) -> bool;
+
+    /// Like `output`, but kill the command and fail with `TimedOut` if it is
+    /// still running at `deadline`. Runners whose commands can't hang (mocks)
+    /// don't need to override this.
+    fn output_until(&self, invocation: &Invocation, deadline: Instant) -> io::Result<Output> {
+        let _ = deadline;
+        self.output(invocation)
+    }
+}
+
+/// A command started by `CommandRunner::spawn`
+pub trait RunningCommand: Send {
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>>;
+    fn wait(&mut self) -> io::Result<ExitStatus>;
+    fn kill(&mut self) -> io::Result<()>;
+}
+
+/// Runs commands as real processes
+pub struct SystemRunner;
+
+impl SystemRunner {
+    fn build(invocation: &Invocation) -> std::process::Command {
+        let mut command = std::process::Command::new(&invocation.program);
+        command.args(&invocation.args);
+        if let Some(dir) = &invocation.current_dir {
+            command.current_dir(dir);
+        }
+        command
+    }
+}
+
+impl CommandRunner for SystemRunner {
+    fn output(&self, invocation: &Invocation) -> io::Result<Output> {
+        Self::build(invocation).output()
+    }
+
+    fn spawn(&self, invocation: &Invocation) -> io::Result<Box<dyn RunningCommand>> {
+        Ok(Box::new(Self::build(invocation).spawn()?))
+    }
+
+    fn is_installed(&self, program: &str) -> bool {
+        std::env::var_os("PATH")
+            .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+    }
+
+    fn output_until(&self, invocation: &Invocation, deadline: Instant) -> io::Result<Output> {
+        let mut child = Self::build(invocation)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Drain both pipes while waiting so a chatty command can't stall on a full pipe
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+        let status = wait_until(&mut child, deadline, invocation)?;
+
+        Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// Wait for `process`, killing it once `deadline` passes
+fn wait_until(process: &mut dyn RunningCommand, deadline: Instant, invocation: &Invocation) -> io::Result<ExitStatus> {
+    loop {
+        if let Some(status) = process.try_wait()? {
+            return Ok(status);
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            let _ = process.kill();
+            let _ = process.wait();
+            return Err(timed_out(invocation));
+        }
+        thread::sleep(DEADLINE_POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+fn timed_out(invocation: &Invocation) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("`{}` was still running at the operation deadline and was killed", invocation.command_line()),
+    )
+}
+
+/// A spawned command that is killed if it outlives the deadline it was started under
+struct DeadlineCommand {
+    process: Box<dyn RunningCommand>,
+    deadline: Instant,
+    invocation: Invocation,
+}
+
+impl RunningCommand for DeadlineCommand {
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        match self.process.try_wait()? {
+            None if Instant::now() >= self.deadline => wait_until(&mut *self.process, self.deadline, &self.invocation).map(Some),
+            status => Ok(status),
+        }
+    }
+
+    fn wait(&mut self) -> io::Result<ExitStatus> {
+        wait_until(&mut *self.process, self.deadline, &self.invocation)
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        self.process.kill()
+    }
+}
+
+impl RunningCommand for std::process::Child {
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        std::process::Child::try_wait(self)
+    }
+
+    fn wait(&mut self) -> io::Result<ExitStatus> {
+        std::process::Child::wait(self)
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        std::process::Child::kill(self)
+    }
+}
+
+thread_local! {
+    static RUNNER: RefCell<Option<Arc<dyn CommandRunner>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous runner when dropped
+pub struct RunnerGuard {
+    previous: Option<Arc<dyn CommandRunner>>,
+}
+
+impl Drop for RunnerGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        RUNNER.with(|runner| *runner.borrow_mut() = previous);
+    }
+}
+
+/// Send this thread's commands to `runner` until the guard is dropped.
+/// Works across `.await` on a current-thread runtime, as `#[tokio::test]` uses.
+pub fn scoped_runner(runner: Arc<dyn CommandRunner>) -> RunnerGuard {
+    let previous = RUNNER.with(|current| current.borrow_mut().replace(runner));
+    RunnerGuard { previous }
+}
+
+fn current_runner() -> Arc<dyn CommandRunner> {
+    RUNNER
+        .with(|runner| runner.borrow().clone())
+        .unwrap_or_else(|| Arc::new(SystemRunner))
+}
+
+/// Builder for an external command, with the std `Command` API used here
+#[derive(Debug, Clone)]
+pub struct Command {
+    invocation: Invocation,
+    deadline: Option<Instant>,
+}
+
+impl Command {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            invocation: Invocation {
+                program: program.as_ref().to_owned(),
+                args: Vec::new(),
+                current_dir: None,
+            },
+            deadline: None,
+        }
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.invocation.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.invocation.args.extend(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    pub fn current_dir(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.invocation.current_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Kill the command if it is still running at `deadline`, failing it
+    /// with `TimedOut`; usually `cancel.deadline()` of the wipe's token.
+    /// None (the default) lets it run as long as it takes.
+    pub fn deadline(&mut self, deadline: Option<Instant>) -> &mut Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub fn output(&mut self) -> io::Result<Output> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(timed_out(&self.invocation)),
+            Some(deadline) => current_runner().output_until(&self.invocation, deadline),
+            None => current_runner().output(&self.invocation),
+        }
+    }
+
+    /// Run to completion and return only the exit status
+    pub fn status(&mut self) -> io::Result<ExitStatus> {
+        Ok(self.output()?.status)
+    }
+
+    pub fn spawn(&mut self) -> io::Result<Box<dyn RunningCommand>> {
+        let Some(deadline) = self.deadline else {
+            return current_runner().spawn(&self.invocation);
+        };
+        if Instant::now() >= deadline {
+            return Err(timed_out(&self.invocation));
+        }
+
+        Ok(Box::new(DeadlineCommand {
+            process: current_runner().spawn(&self.invocation)?,
+            deadline,
+            invocation: self.invocation.clone(),
+        }))
+    }
+
+    /// Run to completion, returning `MissingTool` if the program isn't
+    /// installed and `HardwareCommandFailed` if it exits non-zero
+    pub fn run(&mut self) -> DriveResult<Output> {
+        let program = self.invocation.program.to_string_lossy().into_owned();
+        let output = self.output().map_err(|e| spawn_error(&program, e))?;
+        if !output.status.success() {
+            return Err(DriveError::HardwareCommandFailed(format!(
+                "`{}` failed ({})",
+                self.invocation.command_line(),
+                command_failure(&output)
+            )));
+        }
+        Ok(output)
+    }
+}
+
+/// Exit code and trimmed stderr of a failed command, for error messages,
+/// e.g. "exit code 5: SG_IO: bad/missing sense data". Falls back to stdout
+/// for tools such as sedutil-cli that report errors there.
+pub fn command_failure(output: &Output) -> String {
+    let status = exit_description(output.status);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = match stderr.trim() {
+        "" => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        stderr => stderr.to_string(),
+    };
+
+    if detail.is_empty() {
+        status
+    } else {
+        format!("{}: {}", status, detail)
+    }
+}
+
+/// "exit code N", or the signal that killed the command
+pub fn exit_description(status: ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("exit code {}", code),
+        None => status.to_string(),
+    }
+}
+
+/// Classify an error starting `program`: `MissingTool` when it isn't
+/// installed, `Timeout` when it was killed at the deadline, and
+/// `HardwareCommandFailed` for anything else
+pub fn spawn_error(program: &str, error: io::Error) -> DriveError {
+    match error.kind() {
+        io::ErrorKind::NotFound => DriveError::MissingTool(program.to_string()),
+        io::ErrorKind::TimedOut => DriveError::Timeout(error.to_string()),
+        _ => DriveError::HardwareCommandFailed(format!("Failed to run {}: {}", program, error)),
+    }
+}
+
+/// Where to get `tool`, for the `MissingTool` message
+pub fn install_hint(tool: &str) -> &'static str {
+    match tool {
+        "nvme" => "nvme-cli",
+        "hdparm" => "hdparm",
+        "smartctl" => "smartmontools",
+        "blkdiscard" | "blkzone" | "blockdev" | "lsblk" | "rtcwake" => "util-linux",
+        "sg_inq" | "sg_raw" | "sg_format" | "sg_sanitize" | "sg_unmap" | "sg_rep_zones" | "sg_reset_wp"
+        | "sg_zone" | "sg_readcap" | "sg_opcodes" | "sg_requests" | "sg_turs" => "sg3-utils",
+        "mmc" => "mmc-utils",
+        "mdadm" => "mdadm",
+        "sedutil-cli" => "sedutil",
+        "ipmitool" => "ipmitool",
+        "cryptsetup" => "cryptsetup",
+        "ndctl" => "ndctl",
+        _ => "the vendor's tool",
+    }
+}
+
+/// External tools the wipe paths for `drive_type` rely on
+pub fn required_tools(drive_type: &DriveType) -> &'static [&'static str] {
+    match drive_type {
+        DriveType::NVMe | DriveType::Optane => &["nvme"],
+        DriveType::SSD | DriveType::HybridSSHD => &["hdparm", "blkdiscard"],
+        DriveType::HDD => &["hdparm"],
+        DriveType::SMR => &["blkzone", "sg_reset_wp"],
+        DriveType::EMMC => &["mmc", "blkdiscard"],
+        DriveType::SDCard => &["blkdiscard"],
+        DriveType::UFS => &["sg_format"],
+        DriveType::SAS => &["sg_opcodes", "sg_sanitize", "sg_format", "sg_requests", "sg_turs"],
+        DriveType::RAID => &["mdadm"],
+        DriveType::USB | DriveType::Unknown => &[],
+    }
+}
+
+/// Tools from `required_tools(drive_type)` that aren't on the PATH
+pub fn check_dependencies(drive_type: &DriveType) -> Vec<&'static str> {
+    let runner = current_runner();
+    required_tools(drive_type)
+        .iter()
+        .copied()
+        .filter(|tool| !runner.is_installed(tool))
+        .collect()
+}
+
+/// Scripted runner for tests: answers commands from a table of responses and
+/// records every command line it was asked to run
+#[derive(Default)]
+pub struct MockCommandRunner {
+    responses: Mutex<Vec<(String, Output)>>,
+    missing: Mutex<Vec<String>>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockCommandRunner {
+    /// Commands without a scripted response succeed with no output
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Succeed with `stdout` for commands whose line starts with `prefix`.
+    /// Later responses take precedence over earlier ones.
+    pub fn respond(&self, prefix: &str, stdout: &str) -> &Self {
+        self.script(prefix, 0, stdout, "")
+    }
+
+    /// Exit with status 1 and `stderr` for commands starting with `prefix`
+    pub fn fail(&self, prefix: &str, stderr: &str) -> &Self {
+        self.script(prefix, 1, "", stderr)
+    }
+
+    /// Act as if `program` isn't installed: running it fails with `NotFound`
+    pub fn missing(&self, program: &str) -> &Self {
+        self.missing.lock().unwrap().push(program.to_string());
+        self
+    }
+
+    fn script(&self, prefix: &str, code: i32, stdout: &str, stderr: &str) -> &Self {
+        self.responses.lock().unwrap().push((
+            prefix.to_string(),
+            Output {
+                status: exit_status(code),
+                stdout: stdout.as_bytes().to_vec(),
+                stderr: stderr.as_bytes().to_vec(),
+            },
+        ));
+        self
+    }
+
+    /// Every command line run so far, in order
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Whether any command line started with `prefix`
+    pub fn ran(&self, prefix: &str) -> bool {
+        self.calls().iter().any(|call| call.starts_with(prefix))
+    }
+
+    fn answer(&self, invocation: &Invocation) -> io::Result<Output> {
+        let line = invocation.command_line();
+        self.calls.lock().unwrap().push(line.clone());
+
+        let program = invocation.program.to_string_lossy();
+        if !self.is_installed(&program) {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} not found", program)));
+        }
+
+        Ok(self
+            .responses
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(prefix, _)| line.starts_with(prefix.as_str()))
+            .map(|(_, output)| output.clone())
+            .unwrap_or_else(|| Output { status: exit_status(0), stdout: Vec::new(), stderr: Vec::new() }))
+    }
+}
+
+impl CommandRunner for MockCommandRunner {
+    fn output(&self, invocation: &Invocation) -> io::Result<Output> {
+        self.answer(invocation)
+    }
+
+    fn spawn(&self, invocation: &Invocation) -> io::Result<Box<dyn RunningCommand>> {
+        Ok(Box::new(FinishedCommand(self.answer(invocation)?.status)))
+    }
+
+    fn is_installed(&self, program: &str) -> bool {
+        !self.missing.lock().unwrap().iter().any(|missing| missing == program)
+    }
+}
+
+/// A mocked command that has already exited
+struct FinishedCommand(ExitStatus);
+
+impl RunningCommand for FinishedCommand {
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        Ok(Some(self.0))
+    }
+
+    fn wait(&mut self) -> io::Result<ExitStatus> {
+        Ok(self.0)
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw(code << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_scripts_responses_and_records_calls() {
+        let mock = Arc::new(MockCommandRunner::new());
+        mock.respond("nvme id-ctrl", "sanicap : 0x2\n").fail("hdparm", "permission denied");
+
+        let _guard = scoped_runner(mock.clone());
+        let output = Command::new("nvme").args(["id-ctrl", "/dev/nvme0n1"]).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "sanicap : 0x2\n");
+
+        let status = Command::new("hdparm").arg("-I").arg("/dev/sda").status().unwrap();
+        assert_eq!(status.code(), Some(1));
+        assert!(Command::new("blkdiscard").arg("/dev/sda").spawn().unwrap().wait().unwrap().success());
+
+        assert_eq!(mock.calls(), ["nvme id-ctrl /dev/nvme0n1", "hdparm -I /dev/sda", "blkdiscard /dev/sda"]);
+    }
+
+    #[test]
+    fn test_missing_tool_is_distinct_from_failure() {
+        let mock = Arc::new(MockCommandRunner::new());
+        mock.missing("nvme").fail("hdparm", "SG_IO: bad/missing sense data");
+        let _guard = scoped_runner(mock.clone());
+
+        let missing = Command::new("nvme").args(["sanitize-log", "/dev/nvme0n1"]).run().unwrap_err();
+        assert!(matches!(missing, DriveError::MissingTool(ref tool) if tool == "nvme"));
+        assert!(missing.to_string().contains("nvme-cli"));
+
+        let failed = Command::new("hdparm").args(["-I", "/dev/sda"]).run().unwrap_err();
+        assert!(matches!(failed, DriveError::HardwareCommandFailed(ref msg) if msg.contains("bad/missing sense data")));
+
+        assert_eq!(check_dependencies(&DriveType::NVMe), ["nvme"]);
+        assert!(check_dependencies(&DriveType::HDD).is_empty());
+    }
+
+    #[test]
+    fn test_command_failure_detail() {
+        let output = |stdout: &str, stderr: &str| Output {
+            status: exit_status(5),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        };
+
+        assert_eq!(command_failure(&output("", "  Device busy\n")), "exit code 5: Device busy");
+        assert_eq!(command_failure(&output("NOT_AUTHORIZED\n", "")), "exit code 5: NOT_AUTHORIZED");
+        assert_eq!(command_failure(&output("", "")), "exit code 5");
+    }
+
+    #[test]
+    fn test_deadline_kills_hung_commands() {
+        let started = Instant::now();
+        let deadline = Some(started + Duration::from_millis(300));
+
+        let mut spawned = Command::new("sleep").arg("30").deadline(deadline).spawn().unwrap();
+        assert!(Command::new("true").deadline(deadline).run().is_ok());
+
+        // The deadline goes with the command onto another thread
+        let hung = thread::spawn(move || Command::new("sleep").arg("30").deadline(deadline).run())
+            .join()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(hung, DriveError::Timeout(ref msg) if msg.contains("sleep 30")));
+        assert_eq!(spawned.wait().unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // Past the deadline nothing else is started, while commands without one still run
+        assert_eq!(Command::new("true").deadline(deadline).output().unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(Command::new("true").run().is_ok());
+    }
+
+    #[test]
+    fn test_guard_restores_system_runner() {
+        {
+            let mock = Arc::new(MockCommandRunner::new());
+            let _guard = scoped_runner(mock.clone());
+            Command::new("definitely-not-a-real-tool").output().unwrap();
+            assert!(mock.ran("definitely-not-a-real-tool"));
+        }
+        let result = Command::new("definitely-not-a-real-tool").output();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
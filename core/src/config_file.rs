@@ -0,0 +1,319 @@
+// Wipe policy files - `WipeConfig` saved as JSON or YAML
+//
+// A policy committed to version control can be applied to every wipe with
+// `WipeConfig::from_file`. Fields left out of the file keep their defaults,
+// unknown fields are rejected so a typo can't silently weaken a policy, and
+// the loaded values are checked with `WipeConfig::validate`.
+// `WipeConfig::to_file` writes a policy back out; YAML output carries a
+// comment describing each field, so the defaults make a starting template.
+
+use crate::{DriveError, DriveResult, IoPriority, WipeConfig};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{Map, Number, Value};
+use std::path::Path;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+/// Policy file formats, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// `.json`, or `.yaml` / `.yml`
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("json") => Ok(ConfigFormat::Json),
+            Some("yaml") | Some("yml") => Ok(ConfigFormat::Yaml),
+            _ => Err(anyhow!("{} is not a .json, .yaml or .yml file", path.display())),
+        }
+    }
+}
+
+/// Comment written above each field of a YAML policy, in struct order
+const FIELD_DOCS: &[(&str, &str)] = &[
+    ("algorithm", "DoD5220, Gutmann, Random, Zero, SecureErase, CryptoErase, Sanitize, TrimOnly,\nNIST80088Clear, NIST80088Purge, BSI_GS, BSI_GSE, VSITR,\nHMGIS5Baseline, HMGIS5Enhanced, or Custom: { passes: [...] }"),
+    ("policy", "HighSecurity, Fast or Compliance: Nist80088Clear / Nist80088Purge picks the\nalgorithm per detected drive, replacing `algorithm`; ~ uses `algorithm` as given"),
+    ("verify", "Read the device back after the wipe"),
+    ("verification", "How much `verify` reads back, and the entropy and randomness scores needed to pass"),
+    ("multiple_passes", "Override the algorithm's pass count"),
+    ("preserve_partition_table", "Leave the partition table in place"),
+    ("unlock_encrypted", "Unlock a locked self-encrypting drive before wiping"),
+    ("handle_hpa_dco", "Ignore, Detect, TemporaryRemove or PermanentRemove hidden areas"),
+    ("use_trim_after", "Discard flash blocks after overwriting"),
+    ("overwrite_after_unverified_trim", "Overwrite when discarded blocks don't read back as zeros"),
+    ("temperature_monitoring", "Watch drive temperature during the wipe"),
+    ("max_temperature_celsius", "Pause overwriting above this temperature"),
+    ("resume_temperature_celsius", "Resume once cooled to this; ~ means 5°C below the maximum"),
+    ("freeze_mitigation", "Suspend and resume the host to unfreeze security-frozen drives"),
+    ("sed_crypto_erase", "Allow crypto erase on self-encrypting drives"),
+    ("luks_header_erase", "Destroy LUKS headers and key slots before the wipe (crypto-shred)"),
+    ("ata_secure_erase", "Prefer ATA Secure Erase on SATA drives that support it"),
+    ("smart_health_check", "Refuse failing drives and record SMART health before and after"),
+    ("verify_hardware_erase", "Sample the device after a hardware erase reports success"),
+    ("verify_each_pass", "Read every chunk back as it is written (much slower)"),
+    ("clear_device_logs", "Clear NVMe/ATA logs that reveal usage history after the wipe"),
+    ("final_hash", "Hash the wiped device for the certificate: kind: full, or kind: sampled with\nranges and range_len; ~ skips it"),
+    ("reprovision", "After a verified wipe, create a GPT with one ext4 or exfat partition;\n~ leaves the drive blank"),
+    ("chunk_size", "Bytes per write buffer; a multiple of 512"),
+    ("write_mode", "Buffered, Sync (O_SYNC) or Direct (O_DIRECT, bypassing the page cache)"),
+    ("sync_interval_bytes", "Flush to the device, and checkpoint, after this many bytes"),
+    ("parallel_regions", "Regions overwritten concurrently; ~ picks a per-drive default"),
+    ("max_bytes_per_sec", "Throughput cap across all regions; ~ is unlimited"),
+    ("io_priority", "ionice class for overwrite I/O: Idle, or BestEffort: 0 (highest) to 7;\n~ leaves it unchanged"),
+    ("fadvise_hints", "Hint sequential writes and drop written pages from the page cache"),
+    ("region", "[start, length] in bytes to overwrite instead of the whole device"),
+    ("nvme_sanitize_action", "BlockErase, CryptoErase or Overwrite; ~ picks the most thorough supported"),
+    ("target_namespaces", "NVMe namespace IDs to wipe; ~ wipes all of them"),
+    ("max_bad_sectors", "Abort once more sectors than this fail to write"),
+    ("transient_retries", "Retries of a write or hardware command failing transiently (EAGAIN, bus reset)"),
+    ("retry_backoff_ms", "Milliseconds before the first retry, doubling after each"),
+    ("operation_timeout", "Seconds before the wipe, or a hung hardware command, fails with a timeout;\n~ waits indefinitely"),
+    ("stall_timeout", "Seconds a polled hardware erase may report no progress; ~ never times out"),
+    ("dry_run", "Print the plan without writing"),
+    ("allow_system_disk", "Allow wiping the disk hosting /, /boot or active swap"),
+    ("serial_allowlist", "Only wipe drives with these serials; ~ allows any drive"),
+    ("serial_denylist", "Never wipe drives with these serials"),
+    ("force_unmount", "Unmount the target's filesystems instead of refusing"),
+    ("force", "Proceed with wipes refused by default, such as a single RAID member"),
+    ("certificate_path", "Write the wipe certificate here"),
+    ("certificate_log_path", "Append every certificate to this JSON-lines audit log"),
+    ("certificate_log_hash_chain", "Chain each audit log entry to the hash of the previous one"),
+    ("log_path", "Append the audit log here"),
+    ("syslog", "Send wipe start, completion and failure to journald/syslog (`syslog` build feature)"),
+    ("signing_key_path", "Ed25519 key used to sign the certificate"),
+    ("pattern_seed_key_path", "Key sealing the seed of reproducible random passes; ~ for unseeded"),
+    ("notes", "Operator notes recorded in the certificate"),
+];
+
+impl WipeConfig {
+    /// Load a policy from a `.json`, `.yaml` or `.yml` file and validate it
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)?;
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read wipe config {}", path.display()))?;
+        Self::parse(&contents, format).with_context(|| format!("Invalid wipe config {}", path.display()))
+    }
+
+    /// Parse and validate a policy in `format`
+    pub fn parse(contents: &str, format: ConfigFormat) -> Result<Self> {
+        let config: WipeConfig = match format {
+            ConfigFormat::Json => serde_json::from_str(contents)?,
+            ConfigFormat::Yaml => {
+                let document = YamlLoader::load_from_str(contents)?.into_iter().next().unwrap_or(Yaml::Null);
+                match yaml_to_json(document)? {
+                    // An empty file is an empty policy
+                    Value::Null => WipeConfig::default(),
+                    value => serde_json::from_value(value)?,
+                }
+            }
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Write this config as a policy file, in the format its extension names
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = self.render(ConfigFormat::from_path(path)?)?;
+        std::fs::write(path, contents).with_context(|| format!("Failed to write wipe config {}", path.display()))
+    }
+
+    /// The policy file text for this config
+    pub fn render(&self, format: ConfigFormat) -> Result<String> {
+        match format {
+            ConfigFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+            ConfigFormat::Yaml => {
+                let Value::Object(mut fields) = serde_json::to_value(self)? else {
+                    unreachable!("WipeConfig serializes to a map");
+                };
+
+                let mut out = String::from("# Sayonara wipe policy\n");
+                for (name, doc) in FIELD_DOCS {
+                    let Some(value) = fields.remove(*name) else { continue };
+                    out.push('\n');
+                    for line in doc.lines() {
+                        out.push_str(&format!("# {}\n", line));
+                    }
+                    out.push_str(&emit_field(name, value)?);
+                }
+                // Fields added without a doc entry still round-trip
+                for (name, value) in fields {
+                    out.push('\n');
+                    out.push_str(&emit_field(&name, value)?);
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    /// Reject settings that can't run: an invalid custom algorithm, zero
+    /// sizes and counts, an empty region, or a resume temperature above the
+    /// pause temperature
+    pub fn validate(&self) -> DriveResult<()> {
+        let invalid = |message: &str| Err(DriveError::Unsupported(message.to_string()));
+
+        self.algorithm.validate()?;
+        if self.multiple_passes == Some(0) {
+            return invalid("multiple_passes must be at least 1");
+        }
+        if self.chunk_size == 0 || !self.chunk_size.is_multiple_of(512) {
+            return invalid("chunk_size must be a non-zero multiple of 512 bytes");
+        }
+        if self.sync_interval_bytes == 0 {
+            return invalid("sync_interval_bytes must be at least 1");
+        }
+        if self.parallel_regions == Some(0) {
+            return invalid("parallel_regions must be at least 1");
+        }
+        if self.max_bytes_per_sec == Some(0) {
+            return invalid("max_bytes_per_sec must be at least 1; leave it unset for no limit");
+        }
+        if matches!(self.io_priority, Some(IoPriority::BestEffort(level)) if level > 7) {
+            return invalid("io_priority BestEffort level must be from 0 to 7");
+        }
+        if let Some(scheme) = &self.final_hash {
+            scheme.validate().map_err(|e| DriveError::Unsupported(format!("final_hash: {}", e)))?;
+        }
+        if self.reprovision.is_some() && (self.region.is_some() || self.preserve_partition_table) {
+            return invalid("reprovision replaces the partition table, so it needs a whole-device wipe without preserve_partition_table");
+        }
+        if self.region.is_some_and(|(_, len)| len == 0) {
+            return invalid("region length must be non-zero");
+        }
+        if let (Some(max), Some(resume)) = (self.max_temperature_celsius, self.resume_temperature_celsius) {
+            if resume > max {
+                return invalid("resume_temperature_celsius must not exceed max_temperature_celsius");
+            }
+        }
+        if self.target_namespaces.as_ref().is_some_and(|nsids| nsids.is_empty() || nsids.contains(&0)) {
+            return invalid("target_namespaces must list namespace IDs from 1");
+        }
+        if self.operation_timeout.is_some_and(|timeout| timeout.is_zero())
+            || self.stall_timeout.is_some_and(|timeout| timeout.is_zero())
+        {
+            return invalid("operation_timeout and stall_timeout must be at least 1 second");
+        }
+        if !(0.0..=8.0).contains(&self.verification.min_entropy) {
+            return invalid("verification.min_entropy must be between 0 and 8 bits per byte");
+        }
+        if self.verification.min_chi_square > self.verification.max_chi_square || self.verification.max_pi_error <= 0.0 {
+            return invalid("verification needs min_chi_square <= max_chi_square and a positive max_pi_error");
+        }
+        Ok(())
+    }
+}
+
+/// One top-level `name: value` entry as block YAML
+fn emit_field(name: &str, value: Value) -> Result<String> {
+    let mut entry = yaml_rust::yaml::Hash::new();
+    entry.insert(Yaml::String(name.to_string()), json_to_yaml(value));
+
+    let mut out = String::new();
+    YamlEmitter::new(&mut out).dump(&Yaml::Hash(entry))?;
+    let body = out.strip_prefix("---\n").unwrap_or(&out);
+    Ok(format!("{}\n", body))
+}
+
+fn yaml_to_json(yaml: Yaml) -> Result<Value> {
+    Ok(match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Integer(i) => Value::Number(i.into()),
+        Yaml::Real(text) => {
+            let real: f64 = text.parse().with_context(|| format!("Invalid number {}", text))?;
+            Value::Number(Number::from_f64(real).ok_or_else(|| anyhow!("{} is not a finite number", text))?)
+        }
+        Yaml::String(s) => Value::String(s),
+        Yaml::Array(items) => Value::Array(items.into_iter().map(yaml_to_json).collect::<Result<_>>()?),
+        Yaml::Hash(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let key = match key {
+                    Yaml::String(s) => s,
+                    Yaml::Integer(i) => i.to_string(),
+                    Yaml::Boolean(b) => b.to_string(),
+                    other => return Err(anyhow!("Unsupported YAML key {:?}", other)),
+                };
+                map.insert(key, yaml_to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        Yaml::Alias(_) | Yaml::BadValue => return Err(anyhow!("YAML aliases are not supported")),
+    })
+}
+
+fn json_to_yaml(value: Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(b) => Yaml::Boolean(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Yaml::Integer(i),
+            None => Yaml::Real(n.to_string()),
+        },
+        Value::String(s) => Yaml::String(s),
+        Value::Array(items) => Yaml::Array(items.into_iter().map(json_to_yaml).collect()),
+        Value::Object(entries) => Yaml::Hash(
+            entries.into_iter().map(|(key, value)| (Yaml::String(key), json_to_yaml(value))).collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::PatternSpec;
+    use crate::{Algorithm, NvmeSanitizeAction};
+
+    #[test]
+    fn test_yaml_template_round_trips() {
+        let config = WipeConfig {
+            algorithm: Algorithm::Custom { passes: vec![PatternSpec::Byte(0xFF), PatternSpec::Random] },
+            max_bytes_per_sec: Some(200 * 1024 * 1024),
+            region: Some((4096, 1 << 30)),
+            nvme_sanitize_action: Some(NvmeSanitizeAction::CryptoErase),
+            operation_timeout: Some(std::time::Duration::from_secs(7200)),
+            notes: Some("Quarterly disposal: batch #12".to_string()),
+            ..Default::default()
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["policy.yaml", "policy.json"] {
+            let path = dir.path().join(name);
+            config.to_file(&path).unwrap();
+            let loaded = WipeConfig::from_file(&path).unwrap();
+            assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&config).unwrap());
+        }
+
+        let yaml = std::fs::read_to_string(dir.path().join("policy.yaml")).unwrap();
+        assert!(yaml.contains("# Throughput cap across all regions; ~ is unlimited\nmax_bytes_per_sec: 209715200\n"));
+        assert!(yaml.contains("\noperation_timeout: 7200\n"));
+    }
+
+    #[test]
+    fn test_partial_policy_keeps_defaults() {
+        let config = WipeConfig::parse("algorithm: NIST80088Purge\nverification:\n  level: Full\n", ConfigFormat::Yaml).unwrap();
+        assert_eq!(config.algorithm, Algorithm::NIST80088Purge);
+        assert_eq!(config.verification.min_entropy, 7.5);
+        assert_eq!(config.chunk_size, WipeConfig::default().chunk_size);
+        assert!(WipeConfig::parse("", ConfigFormat::Yaml).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_policy_rejected() {
+        let unknown = WipeConfig::parse(r#"{"algoritm": "Zero"}"#, ConfigFormat::Json).unwrap_err();
+        assert!(unknown.to_string().contains("algoritm"));
+
+        let invalid = WipeConfig::parse("chunk_size: 1000\n", ConfigFormat::Yaml).unwrap_err();
+        assert!(invalid.to_string().contains("chunk_size"));
+        assert!(WipeConfig::parse(r#"{"sync_interval_bytes": 0}"#, ConfigFormat::Json).is_err());
+        assert!(WipeConfig::parse("operation_timeout: 0\n", ConfigFormat::Yaml).is_err());
+        assert!(WipeConfig::parse("algorithm:\n  Custom:\n    passes: []\n", ConfigFormat::Yaml).is_err());
+        assert!(WipeConfig::parse("io_priority:\n  BestEffort: 8\n", ConfigFormat::Yaml).is_err());
+        assert!(WipeConfig::parse("final_hash:\n  kind: sampled\n  ranges: 0\n  range_len: 4096\n", ConfigFormat::Yaml).is_err());
+        assert!(WipeConfig::parse("reprovision: exfat\nregion: [0, 4096]\n", ConfigFormat::Yaml).is_err());
+        assert!(ConfigFormat::from_path(Path::new("policy.toml")).is_err());
+    }
+}
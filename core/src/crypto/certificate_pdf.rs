@@ -0,0 +1,316 @@
+// One-page PDF rendering of a wipe certificate
+//
+// The PDF is written by hand (PDF 1.4, standard Type 1 fonts, uncompressed
+// content stream) so the `pdf` feature pulls in no extra crates.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::fmt::Write as _;
+use super::certificates::{WipeCertificate, WipeCompletion};
+use super::qr::QrCode;
+
+/// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+/// Side of the signature QR code, in points
+const QR_SIDE: f32 = 150.0;
+
+/// Hex characters per line when printing signatures
+const HEX_LINE_LEN: usize = 64;
+
+impl WipeCertificate {
+    /// Render the certificate as a one-page PDF: device details, method,
+    /// timestamps, operator, verification result and signature, plus a QR
+    /// code of the signature for scanning against the JSON record
+    pub fn to_pdf(&self) -> Result<Vec<u8>> {
+        let mut page = PageContent::new();
+
+        page.text(MARGIN, PAGE_HEIGHT - MARGIN - 10.0, Font::Bold, 18.0, "Certificate of Data Sanitization");
+        page.cursor = PAGE_HEIGHT - MARGIN - 30.0;
+        page.line(Font::Regular, 9.0, &format!("Certificate ID: {}", self.certificate_id));
+        page.line(Font::Regular, 9.0, &format!("Issued: {}", format_time(&self.timestamp)));
+
+        let device = &self.device_info;
+        page.heading("Device");
+        page.field("Path", &device.device_path);
+        page.field("Model", &device.model);
+        page.field("Serial", &device.serial);
+        page.field("Capacity", &format!(
+            "{} bytes ({:.1} GB)",
+            device.size,
+            device.size as f64 / (1024.0 * 1024.0 * 1024.0)
+        ));
+        page.field("Device hash", &device.device_hash);
+
+        let wipe = &self.wipe_details;
+        page.heading("Sanitization");
+        page.field("Algorithm", &wipe.algorithm_used);
+        if let Some(standard) = &wipe.standard {
+            page.field("Standard", standard);
+        }
+        page.field("Method", &wipe.method);
+        page.field("Passes", &wipe.passes_completed.to_string());
+        page.field("Started", &format_time(&wipe.started_at));
+        page.field("Completed", &format_time(&wipe.completed_at));
+        page.field("Duration", &format!("{} s", wipe.duration_seconds));
+        page.field("Operator", wipe.operator_id.as_deref().unwrap_or("Not recorded"));
+        page.field("Result", &match &wipe.completion {
+            WipeCompletion::FullyWiped => "All sectors overwritten".to_string(),
+            WipeCompletion::WipedExceptBadSectors { bad_sector_count, ranges } => format!(
+                "Wiped except {} unreadable sectors in {} ranges",
+                bad_sector_count,
+                ranges.len()
+            ),
+        });
+        if let Some(source) = &wipe.entropy_source {
+            page.field("Entropy source", source);
+        }
+        for namespace in &wipe.nvme_namespaces {
+            let metadata = if !namespace.format.has_metadata() {
+                ""
+            } else if namespace.metadata_erased {
+                ", metadata erased"
+            } else {
+                ", metadata NOT erased"
+            };
+            page.field(&namespace.device_path, &format!("{}{}", namespace.format, metadata));
+        }
+        if wipe.retries.total() > 0 {
+            page.field("Retries", &format!(
+                "{} writes, {} operations after transient errors",
+                wipe.retries.write_retries, wipe.retries.operation_retries
+            ));
+        }
+        if let Some(signatures) = wipe.signatures.as_ref().filter(|s| !s.before.is_empty()) {
+            let found: Vec<&str> = signatures.before.iter().map(|s| s.kind.as_str()).collect();
+            page.field("Found before wipe", &found.join(", "));
+            page.field("After wipe", &match &signatures.remaining {
+                None => "Not re-scanned".to_string(),
+                Some(remaining) if remaining.is_empty() => "None of these structures remain".to_string(),
+                Some(remaining) => format!("{} structure(s) still present", remaining.len()),
+            });
+        }
+        if let Some(hash) = &wipe.final_hash {
+            page.field("Final state hash", &format!("{} of {}", hash.algorithm, hash.scheme));
+            page.field("Digest", &hash.digest);
+        }
+        if let Some(capabilities) = &wipe.capabilities {
+            page.field("Drive capabilities", &capabilities.summary());
+        }
+        if let Some(time) = &wipe.advertised_erase_time {
+            page.field("Drive erase estimate", &time.to_string());
+        }
+        if let Some(reprovisioned) = &wipe.reprovisioned {
+            page.field("Reprovisioned", &reprovisioned.to_string());
+        }
+        if let Some(notes) = &wipe.notes {
+            page.field("Notes", notes);
+        }
+
+        let verification = &self.verification;
+        page.heading("Verification");
+        page.field("Verified", if verification.verified { "Yes" } else { "No" });
+        page.field("Entropy score", &format!("{:.4} bits/byte", verification.entropy_score));
+        if let Some(randomness) = &verification.randomness {
+            let verdict = |passed: bool| if passed { "passed" } else { "failed" };
+            page.field(
+                "Chi-square",
+                &format!("{:.2} ({})", randomness.chi_square, verdict(randomness.chi_square_passed)),
+            );
+            if let (Some(pi), Some(error)) = (randomness.monte_carlo_pi, randomness.monte_carlo_error) {
+                page.field(
+                    "Monte-Carlo pi",
+                    &format!("{:.6}, error {:.3}% ({})", pi, error, verdict(randomness.monte_carlo_passed)),
+                );
+            }
+        }
+        page.field("Recovery test", if verification.recovery_test_passed { "Passed" } else { "Not passed" });
+        page.field("Checked at", &format_time(&verification.verification_timestamp));
+
+        // The QR code sits to the right of the signature block
+        page.heading("Signature");
+        let qr_top = page.cursor;
+        page.line(Font::Bold, 9.0, "SHA-256");
+        page.hex(&self.signature);
+        if let (Some(signature), Some(public_key)) = (&self.ed25519_signature, &self.ed25519_public_key) {
+            page.line(Font::Bold, 9.0, "Ed25519 signature");
+            page.hex(signature);
+            page.line(Font::Bold, 9.0, "Ed25519 public key");
+            page.hex(public_key);
+        }
+
+        let qr = QrCode::encode(self.qr_payload().as_bytes())?;
+        page.qr(&qr, PAGE_WIDTH - MARGIN - QR_SIDE, qr_top - QR_SIDE, QR_SIDE);
+
+        Ok(build_document(&page.ops))
+    }
+
+    /// Save the PDF rendering of the certificate to `path`
+    pub fn save_pdf(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.to_pdf()?)?;
+        Ok(())
+    }
+
+    /// Certificate ID and the strongest signature available, one per line
+    fn qr_payload(&self) -> String {
+        let signature = self.ed25519_signature.as_deref().unwrap_or(&self.signature);
+        format!("{}\n{}", self.certificate_id, signature)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+}
+
+/// Page content stream built top to bottom
+struct PageContent {
+    ops: String,
+    cursor: f32,
+}
+
+impl PageContent {
+    fn new() -> Self {
+        Self {
+            ops: String::new(),
+            cursor: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn text(&mut self, x: f32, y: f32, font: Font, size: f32, text: &str) {
+        let _ = writeln!(
+            self.ops,
+            "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET",
+            font.resource(),
+            size,
+            x,
+            y,
+            escape(text)
+        );
+    }
+
+    fn line(&mut self, font: Font, size: f32, text: &str) {
+        self.text(MARGIN, self.cursor, font, size, text);
+        self.cursor -= size + 4.0;
+    }
+
+    fn heading(&mut self, title: &str) {
+        self.cursor -= 10.0;
+        self.line(Font::Bold, 12.0, title);
+        let _ = writeln!(
+            self.ops,
+            "0.5 w {:.2} {:.2} m {:.2} {:.2} l S",
+            MARGIN,
+            self.cursor + 12.0,
+            PAGE_WIDTH - MARGIN,
+            self.cursor + 12.0
+        );
+    }
+
+    fn field(&mut self, label: &str, value: &str) {
+        self.text(MARGIN, self.cursor, Font::Bold, 9.0, label);
+        self.text(MARGIN + 100.0, self.cursor, Font::Regular, 9.0, value);
+        self.cursor -= 13.0;
+    }
+
+    fn hex(&mut self, hex: &str) {
+        for chunk in hex.as_bytes().chunks(HEX_LINE_LEN) {
+            self.line(Font::Mono, 7.0, &String::from_utf8_lossy(chunk));
+        }
+    }
+
+    /// Draw `qr` with a four-module quiet zone inside a `side`-point square
+    fn qr(&mut self, qr: &QrCode, x: f32, y: f32, side: f32) {
+        let module = side / (qr.size() + 8) as f32;
+        let _ = writeln!(self.ops, "0 g");
+        for row in 0..qr.size() {
+            for col in 0..qr.size() {
+                if qr.is_dark(col, row) {
+                    let _ = writeln!(
+                        self.ops,
+                        "{:.3} {:.3} {:.3} {:.3} re f",
+                        x + (col + 4) as f32 * module,
+                        y + side - (row + 5) as f32 * module,
+                        module,
+                        module
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Escape a PDF string literal; anything outside printable ASCII becomes '?'
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+/// Assemble the objects and cross-reference table around `content`
+fn build_document(content: &str) -> Vec<u8> {
+    let font = |name: &str| {
+        format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", name)
+    };
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 4 0 R /F2 5 0 R /F3 6 0 R >> >> /Contents 7 0 R >>",
+            PAGE_WIDTH, PAGE_HEIGHT
+        ),
+        font("Helvetica"),
+        font("Helvetica-Bold"),
+        font("Courier"),
+        format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content),
+    ];
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+
+    let xref_offset = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        trailer,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    );
+    pdf.extend_from_slice(trailer.as_bytes());
+    pdf
+}
@@ -0,0 +1,294 @@
+// Certificate Store - an append-only log of every wipe certificate
+//
+// Each certificate is appended to a JSON-lines file as one entry. With hash
+// chaining on, every entry records the SHA-256 of the line before it, so
+// changing, removing or reordering any earlier line breaks the chain at the
+// entry that follows it. `verify()` walks the chain and returns its head,
+// the hash of the last line: keep a copy of the head elsewhere (a ticket, a
+// second system) and truncating the log is detectable too.
+
+use super::certificates::{to_hex, WipeCertificate};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// `previous_hash` of the first entry in a chained log
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One line of the log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateLogEntry {
+    /// 1-based position in the log
+    pub sequence: u64,
+    pub appended_at: DateTime<Utc>,
+    /// SHA-256 of the previous line (zeros for the first); None when the
+    /// log isn't hash-chained
+    #[serde(default)]
+    pub previous_hash: Option<String>,
+    pub certificate: WipeCertificate,
+}
+
+/// Result of walking the log with `CertificateStore::verify`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainVerification {
+    /// Entries checked before the first problem, or all of them
+    pub entries: u64,
+    /// SHA-256 of the last line, to compare against a copy kept elsewhere
+    pub head: Option<String>,
+    /// Line of the first entry that fails, and why
+    pub broken_at: Option<(u64, String)>,
+}
+
+impl ChainVerification {
+    /// Whether every entry checked out
+    pub fn intact(&self) -> bool {
+        self.broken_at.is_none()
+    }
+}
+
+impl std::fmt::Display for ChainVerification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.broken_at {
+            None => write!(f, "{} entries intact, head {}", self.entries, self.head.as_deref().unwrap_or("(empty log)")),
+            Some((line, reason)) => write!(f, "broken at line {}: {}", line, reason),
+        }
+    }
+}
+
+/// Append-only JSON-lines log of wipe certificates
+#[derive(Debug, Clone)]
+pub struct CertificateStore {
+    path: PathBuf,
+    hash_chain: bool,
+}
+
+impl CertificateStore {
+    /// Store at `path`, created on the first append. With `hash_chain` each
+    /// entry references the hash of the one before it.
+    pub fn new(path: impl Into<PathBuf>, hash_chain: bool) -> Self {
+        Self { path: path.into(), hash_chain }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `certificate` as the next entry. The file is locked while the
+    /// last entry is read and the new one written, so concurrent wipes
+    /// append one at a time; a log whose last line is unreadable is left
+    /// alone rather than extended.
+    pub fn append(&self, certificate: &WipeCertificate) -> Result<CertificateLogEntry> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).context("Failed to create certificate log directory")?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open certificate log {}", self.path.display()))?;
+        lock_exclusive(&file)?;
+
+        let last = last_line(&file)?;
+        let sequence = match &last {
+            Some(line) => {
+                let previous: CertificateLogEntry = serde_json::from_str(line)
+                    .map_err(|e| anyhow!("Last entry of {} is corrupt ({}); not appending", self.path.display(), e))?;
+                previous.sequence + 1
+            }
+            None => 1,
+        };
+
+        let entry = CertificateLogEntry {
+            sequence,
+            appended_at: Utc::now(),
+            previous_hash: self.hash_chain.then(|| last.as_deref().map_or(GENESIS_HASH.to_string(), line_hash)),
+            certificate: certificate.clone(),
+        };
+
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        file.sync_all()?;
+        Ok(entry)
+    }
+
+    /// Every entry in the log, oldest first
+    pub fn entries(&self) -> Result<Vec<CertificateLogEntry>> {
+        read_lines(&self.path)?
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str(line).with_context(|| format!("Line {} of {} is not a log entry", index + 1, self.path.display()))
+            })
+            .collect()
+    }
+
+    /// Walk the log, checking that entries are numbered in order, that each
+    /// chains to the exact line before it, and that certificates carrying
+    /// an Ed25519 signature still verify. Stops at the first failure.
+    pub fn verify(&self) -> Result<ChainVerification> {
+        let lines = read_lines(&self.path)?;
+        let mut previous: Option<&str> = None;
+        let mut checked = 0;
+
+        for (index, line) in lines.iter().enumerate() {
+            let number = index as u64 + 1;
+            let broken = |reason: String| ChainVerification {
+                entries: checked,
+                head: previous.map(line_hash),
+                broken_at: Some((number, reason)),
+            };
+
+            let entry: CertificateLogEntry = match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                Err(e) => return Ok(broken(format!("not a log entry: {}", e))),
+            };
+            if entry.sequence != number {
+                return Ok(broken(format!("sequence {} where {} was expected (entries removed or reordered)", entry.sequence, number)));
+            }
+
+            match &entry.previous_hash {
+                Some(hash) => {
+                    let expected = previous.map_or(GENESIS_HASH.to_string(), line_hash);
+                    if *hash != expected {
+                        return Ok(broken("does not chain to the line before it (that line was changed, removed or reordered)".to_string()));
+                    }
+                }
+                None if self.hash_chain => return Ok(broken("entry is not hash-chained".to_string())),
+                None => {}
+            }
+
+            if entry.certificate.ed25519_signature.is_some() && !entry.certificate.verify_signature()? {
+                return Ok(broken(format!("certificate {} fails its Ed25519 signature", entry.certificate.certificate_id)));
+            }
+
+            previous = Some(line);
+            checked += 1;
+        }
+
+        Ok(ChainVerification {
+            entries: checked,
+            head: previous.map(line_hash),
+            broken_at: None,
+        })
+    }
+}
+
+/// Hex SHA-256 of one log line, without its newline
+fn line_hash(line: &str) -> String {
+    to_hex(&Sha256::digest(line.as_bytes()))
+}
+
+/// Block until no other process holds the log; released when `file` closes
+#[cfg(unix)]
+fn lock_exclusive(file: &File) -> Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+        return Err(anyhow!("Failed to lock certificate log: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// Block until no other process holds the log; released when `file` closes
+#[cfg(windows)]
+fn lock_exclusive(file: &File) -> Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::fileapi::LockFileEx;
+    use winapi::um::minwinbase::{LOCKFILE_EXCLUSIVE_LOCK, OVERLAPPED};
+
+    // Lock the largest possible range, so appends past the end are covered
+    let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+    let locked = unsafe {
+        LockFileEx(file.as_raw_handle() as _, LOCKFILE_EXCLUSIVE_LOCK, 0, u32::MAX, u32::MAX, &mut overlapped)
+    };
+    if locked == 0 {
+        return Err(anyhow!("Failed to lock certificate log: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// The last non-empty line of an open log
+fn last_line(file: &File) -> Result<Option<String>> {
+    let mut last = None;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            last = Some(line);
+        }
+    }
+    Ok(last)
+}
+
+/// Non-empty lines of the log at `path`; none when it doesn't exist yet
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(text.lines().filter(|line| !line.trim().is_empty()).map(str::to_string).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read certificate log {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::certificates::tests::test_certificate;
+
+    #[test]
+    fn test_chain_detects_edits_and_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit/certificates.jsonl");
+        let store = CertificateStore::new(&path, true);
+        assert_eq!(store.verify().unwrap().entries, 0);
+
+        for _ in 0..3 {
+            store.append(&test_certificate()).unwrap();
+        }
+        let entries = store.entries().unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(entries[0].previous_hash.as_deref(), Some(GENESIS_HASH));
+
+        let verification = store.verify().unwrap();
+        assert!(verification.intact(), "{}", verification);
+        assert_eq!(verification.entries, 3);
+
+        // Changing an earlier certificate breaks the link from the next entry
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        let edited = lines[0].replace("\"serial\":\"TEST123\"", "\"serial\":\"OTHER\"");
+        assert_ne!(edited, lines[0]);
+        std::fs::write(&path, format!("{}\n{}\n{}\n", edited, lines[1], lines[2])).unwrap();
+        let verification = store.verify().unwrap();
+        assert_eq!(verification.broken_at.as_ref().map(|(line, _)| *line), Some(2));
+        assert_eq!(verification.entries, 1);
+
+        // So does removing one
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert_eq!(store.verify().unwrap().broken_at.map(|(line, _)| line), Some(2));
+    }
+
+    #[test]
+    fn test_unchained_store_and_corrupt_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("certificates.jsonl");
+        let store = CertificateStore::new(&path, false);
+
+        let entry = store.append(&test_certificate()).unwrap();
+        assert_eq!((entry.sequence, entry.previous_hash), (1, None));
+        assert!(store.verify().unwrap().intact());
+
+        // A chained view of the same log notices the missing links
+        assert!(!CertificateStore::new(&path, true).verify().unwrap().intact());
+
+        // A half-written line is never built upon
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"sequence\":2,\"appended").unwrap();
+        assert!(store.append(&test_certificate()).is_err());
+        assert_eq!(store.verify().unwrap().broken_at.map(|(line, _)| line), Some(2));
+    }
+}
@@ -1,8 +1,20 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha2::{Sha256, Digest};
+use std::path::Path;
+use std::time::Duration;
 use uuid::Uuid;
+use crate::error::BadSectorRange;
+use crate::HealthStatus;
+use crate::drives::operations::smart::SMARTHealth;
+use crate::drives::Capabilities;
+use crate::drives::operations::{EraseTime, PartitionTableArea, Reprovisioning};
+use crate::verification::DeviceHash;
+use crate::drives::types::NamespaceFormat;
+use crate::verification::DiskSignature;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WipeCertificate {
@@ -12,6 +24,113 @@ pub struct WipeCertificate {
     pub verification: VerificationResult,
     pub timestamp: DateTime<Utc>,
     pub signature: String,
+    /// Hex-encoded detached Ed25519 signature over the canonical JSON
+    #[serde(default)]
+    pub ed25519_signature: Option<String>,
+    /// Hex-encoded Ed25519 public key that verifies `ed25519_signature`
+    #[serde(default)]
+    pub ed25519_public_key: Option<String>,
+}
+
+impl WipeCertificate {
+    /// Serialize the certificate as pretty-printed JSON for audit records
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deterministic JSON encoding: object keys sorted, no whitespace.
+    /// This is the exact byte string covered by the Ed25519 signature.
+    pub fn canonical_json(&self) -> Result<String> {
+        let mut out = String::new();
+        write_canonical_json(&serde_json::to_value(self)?, &mut out);
+        Ok(out)
+    }
+
+    /// Sign the certificate with the Ed25519 private key at `key_path`
+    /// (PKCS#8 DER, or a raw 32-byte seed) and embed the signature and public key
+    pub fn sign_ed25519(&mut self, key_path: &Path) -> Result<()> {
+        let key_bytes = std::fs::read(key_path)
+            .map_err(|e| anyhow!("Failed to read signing key {}: {}", key_path.display(), e))?;
+
+        let key_pair = if key_bytes.len() == 32 {
+            Ed25519KeyPair::from_seed_unchecked(&key_bytes)
+        } else {
+            Ed25519KeyPair::from_pkcs8_maybe_unchecked(&key_bytes)
+        }
+        .map_err(|e| anyhow!("Invalid Ed25519 key {}: {}", key_path.display(), e))?;
+
+        self.ed25519_public_key = Some(to_hex(key_pair.public_key().as_ref()));
+        self.ed25519_signature = None;
+
+        let signature = key_pair.sign(self.canonical_json()?.as_bytes());
+        self.ed25519_signature = Some(to_hex(signature.as_ref()));
+
+        Ok(())
+    }
+
+    /// Check the embedded Ed25519 signature against the embedded public key.
+    /// Returns false if the certificate was modified after signing.
+    pub fn verify_signature(&self) -> Result<bool> {
+        let (signature, public_key) = match (&self.ed25519_signature, &self.ed25519_public_key) {
+            (Some(signature), Some(public_key)) => (from_hex(signature)?, from_hex(public_key)?),
+            _ => return Err(anyhow!("Certificate has no Ed25519 signature")),
+        };
+
+        let mut unsigned = self.clone();
+        unsigned.ed25519_signature = None;
+
+        let payload = unsigned.canonical_json()?;
+        Ok(UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(payload.as_bytes(), &signature)
+            .is_ok())
+    }
+}
+
+/// Write `value` as JSON with object keys in sorted order and no whitespace
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(anyhow!("Invalid hex string length"));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| anyhow!("Invalid hex: {}", e)))
+        .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,9 +145,237 @@ pub struct DeviceCertInfo {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WipeDetails {
     pub algorithm_used: String,
+    /// Sanitization standard the algorithm implements, e.g. "BSI IT-Grundschutz (BSI-GS)"
+    #[serde(default)]
+    pub standard: Option<String>,
     pub passes_completed: u32,
     pub duration_seconds: u64,
     pub operator_id: Option<String>,
+    /// The erase method that actually ran, e.g. "nvme sanitize -a 2" or "overwrite (...)"
+    pub method: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Free-form notes supplied by the operator
+    pub notes: Option<String>,
+    /// Whether every sector was overwritten, or which ones could not be
+    #[serde(default)]
+    pub completion: WipeCompletion,
+    /// HPA/DCO hidden areas found before the wipe, if they were checked
+    #[serde(default)]
+    pub hidden_areas: Option<HiddenAreaReport>,
+    /// Read-back check of discarded blocks, when the wipe used TRIM
+    #[serde(default)]
+    pub trim_verification: Option<TrimVerification>,
+    /// SMART health read before and after the wipe, when it was available
+    #[serde(default)]
+    pub health: Option<HealthReport>,
+    /// RNG source behind the random passes (e.g. "RDSEED"), when any were written
+    #[serde(default)]
+    pub entropy_source: Option<String>,
+    /// Usage-history logs cleared after the wipe (`clear_device_logs`); logs
+    /// the drive doesn't support are left out
+    #[serde(default)]
+    pub device_logs: Vec<DeviceLogClearing>,
+    /// Timing and throughput of each overwrite pass; empty for hardware erases
+    #[serde(default)]
+    pub passes: Vec<PassStats>,
+    /// Seed of the random passes, sealed with the operator's seed key, when
+    /// they were derived from one (`pattern_seed_key_path`)
+    #[serde(default)]
+    pub random_seed: Option<String>,
+    /// LBA format and metadata settings of each NVMe namespace wiped
+    #[serde(default)]
+    pub nvme_namespaces: Vec<NamespaceFormatReport>,
+    /// Operations that failed transiently and were retried
+    #[serde(default)]
+    pub retries: RetryCounts,
+    /// Partition tables and filesystem headers found before the wipe, and
+    /// any still present after it
+    #[serde(default)]
+    pub signatures: Option<SignatureReport>,
+    /// Primary and backup GPT areas zeroed after an erase that didn't
+    /// overwrite them itself
+    #[serde(default)]
+    pub partition_tables_cleared: Vec<PartitionTableArea>,
+    /// Digest of the device's contents once the wipe finished
+    /// (`final_hash`), for re-reading the device and comparing later
+    #[serde(default)]
+    pub final_hash: Option<DeviceHash>,
+    /// Partition and filesystem created after the wipe (`reprovision`)
+    #[serde(default)]
+    pub reprovisioned: Option<Reprovisioning>,
+    /// Erase capabilities the drive reported, which the method was chosen from
+    #[serde(default)]
+    pub capabilities: Option<Capabilities>,
+    /// The drive's own estimate for the ATA secure erase it ran
+    #[serde(default)]
+    pub advertised_erase_time: Option<EraseTime>,
+}
+
+/// Retries run after transient failures (bus resets, busy devices)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryCounts {
+    /// Overwrite chunk writes repeated after a transient I/O error
+    pub write_retries: u64,
+    /// Hardware commands and other recovered operations repeated
+    pub operation_retries: u64,
+}
+
+impl RetryCounts {
+    pub fn total(&self) -> u64 {
+        self.write_retries + self.operation_retries
+    }
+}
+
+/// On-disk structures seen before the wipe and re-checked after it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignatureReport {
+    pub before: Vec<DiskSignature>,
+    /// Signatures found by the post-wipe scan; None when it didn't run
+    pub remaining: Option<Vec<DiskSignature>>,
+}
+
+impl SignatureReport {
+    /// Whether the post-wipe scan ran and found nothing
+    pub fn erased(&self) -> bool {
+        self.remaining.as_ref().is_some_and(Vec::is_empty)
+    }
+}
+
+/// LBA format of an NVMe namespace, and whether its metadata was erased
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceFormatReport {
+    pub device_path: String,
+    #[serde(flatten)]
+    pub format: NamespaceFormat,
+    /// Formats and sanitizes erase metadata with the data; an overwrite
+    /// only replaces it when it is nothing but regenerated PI
+    pub metadata_erased: bool,
+}
+
+/// Timing and throughput of one overwrite pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassStats {
+    /// 1-based pass number
+    pub pass: usize,
+    pub description: String,
+    /// Bytes written in this session (less than the region when resumed)
+    pub bytes_written: u64,
+    pub duration: Duration,
+    /// Average write rate over the pass
+    pub bytes_per_second: u64,
+    /// Whether the pass was read back and matched what was written
+    pub verified: bool,
+}
+
+impl PassStats {
+    pub fn new(pass: usize, description: String, bytes_written: u64, duration: Duration, verified: bool) -> Self {
+        let seconds = duration.as_secs_f64();
+        let bytes_per_second = if seconds > 0.0 { (bytes_written as f64 / seconds) as u64 } else { 0 };
+        Self { pass, description, bytes_written, duration, bytes_per_second, verified }
+    }
+}
+
+/// Outcome of clearing one controller log after the wipe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceLogClearing {
+    pub log: String,
+    pub cleared: bool,
+    /// Why the clear failed, when it did
+    pub error: Option<String>,
+}
+
+/// Host Protected Area / Device Configuration Overlay found on an ATA drive
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HiddenAreaReport {
+    pub hpa_hidden_bytes: u64,
+    pub dco_hidden_bytes: u64,
+    /// Whether the HPA was removed so its sectors were overwritten
+    pub hpa_removed: bool,
+    /// Whether the DCO was removed so its sectors were overwritten
+    pub dco_removed: bool,
+    /// Whether the HPA was put back after the wipe
+    pub hpa_restored: bool,
+}
+
+/// Whether trimmed blocks were confirmed to read back as zeros
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrimVerification {
+    /// Drive advertises Read Zero After TRIM (RZAT)
+    pub read_zero_after_trim: bool,
+    /// Drive advertises Deterministic Read After TRIM (DRAT)
+    pub deterministic_read_after_trim: bool,
+    pub ranges_sampled: u32,
+    pub ranges_zero: u32,
+    /// Whether an overwrite pass also covered the device
+    pub overwritten: bool,
+}
+
+impl TrimVerification {
+    /// Every sampled range read back as zeros
+    pub fn zeros_confirmed(&self) -> bool {
+        self.ranges_sampled > 0 && self.ranges_zero == self.ranges_sampled
+    }
+}
+
+/// SMART error counters at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartSnapshot {
+    pub status: HealthStatus,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub uncorrectable_errors: Option<u64>,
+    /// NVMe media and data integrity errors
+    pub media_errors: Option<u64>,
+}
+
+impl SmartSnapshot {
+    pub fn from_health(health: &SMARTHealth) -> Self {
+        Self {
+            status: health.overall_health.clone(),
+            reallocated_sectors: health.reallocated_sectors,
+            pending_sectors: health.pending_sectors,
+            uncorrectable_errors: health.uncorrectable_errors,
+            media_errors: health.media_errors,
+        }
+    }
+}
+
+/// Drive condition around the wipe. A jump in reallocated sectors while
+/// writing is evidence the media is wearing out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub before: SmartSnapshot,
+    /// None if SMART could not be read again after the wipe
+    pub after: Option<SmartSnapshot>,
+}
+
+impl HealthReport {
+    /// Sectors reallocated during the wipe
+    pub fn reallocated_delta(&self) -> Option<i64> {
+        Self::delta(self.before.reallocated_sectors, self.after.as_ref()?.reallocated_sectors)
+    }
+
+    /// Change in sectors pending reallocation during the wipe (writes
+    /// usually resolve pending sectors, so this is often negative)
+    pub fn pending_delta(&self) -> Option<i64> {
+        Self::delta(self.before.pending_sectors, self.after.as_ref()?.pending_sectors)
+    }
+
+    fn delta(before: Option<u64>, after: Option<u64>) -> Option<i64> {
+        Some(after? as i64 - before? as i64)
+    }
+}
+
+/// Distinguishes a complete wipe from one that skipped unwritable sectors
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum WipeCompletion {
+    #[default]
+    FullyWiped,
+    WipedExceptBadSectors {
+        bad_sector_count: u64,
+        ranges: Vec<BadSectorRange>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +384,31 @@ pub struct VerificationResult {
     pub entropy_score: f64,
     pub recovery_test_passed: bool,
     pub verification_timestamp: DateTime<Utc>,
+    /// Statistical tests beyond entropy, when the wipe ended with random data
+    #[serde(default)]
+    pub randomness: Option<RandomnessScores>,
+}
+
+/// Statistical tests of random-wiped data read back from the drive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RandomnessScores {
+    /// Chi-square statistic of the byte histogram (255 degrees of freedom,
+    /// so ~255 for random data)
+    pub chi_square: f64,
+    pub chi_square_passed: bool,
+    /// Pi estimated from the data as 24-bit coordinate pairs, when enough
+    /// was read for the estimate to mean anything
+    pub monte_carlo_pi: Option<f64>,
+    /// Error of that estimate, in percent of pi
+    pub monte_carlo_error: Option<f64>,
+    pub monte_carlo_passed: bool,
+}
+
+impl RandomnessScores {
+    /// Every test that ran passed
+    pub fn passed(&self) -> bool {
+        self.chi_square_passed && self.monte_carlo_passed
+    }
 }
 
 pub struct CertificateGenerator {
@@ -74,6 +446,8 @@ impl CertificateGenerator {
             verification,
             timestamp: Utc::now(),
             signature: String::new(), // Will be filled by signing
+            ed25519_signature: None,
+            ed25519_public_key: None,
         };
         
         certificate.signature = self.sign_certificate(&certificate)?;
@@ -94,6 +468,8 @@ impl CertificateGenerator {
         // Create a signing payload (excluding the signature field)
         let mut signing_data = certificate.clone();
         signing_data.signature = String::new();
+        signing_data.ed25519_signature = None;
+        signing_data.ed25519_public_key = None;
         
         let json_data = serde_json::to_string(&signing_data)?;
         
@@ -109,9 +485,171 @@ impl CertificateGenerator {
         Ok(expected_signature == certificate.signature)
     }
     
+    /// Write the certificate as JSON, or as a PDF when `path` ends in ".pdf"
+    /// and the `pdf` feature is enabled
     pub fn save_certificate(&self, certificate: &WipeCertificate, path: &str) -> Result<()> {
-        let json_data = serde_json::to_string_pretty(certificate)?;
-        std::fs::write(path, json_data)?;
+        #[cfg(feature = "pdf")]
+        if path.to_ascii_lowercase().ends_with(".pdf") {
+            return certificate.save_pdf(path);
+        }
+        std::fs::write(path, certificate.to_json()?)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+
+    pub(crate) fn test_certificate() -> WipeCertificate {
+        let drive = crate::DriveInfo {
+            device_path: "/dev/sdz".to_string(),
+            model: "Test".to_string(),
+            serial: "TEST123".to_string(),
+            size: 1024 * 1024,
+            drive_type: crate::DriveType::HDD,
+            encryption_status: crate::EncryptionStatus::None,
+            capabilities: Default::default(),
+            health_status: None,
+            temperature_celsius: None,
+        };
+
+        let now = Utc::now();
+        CertificateGenerator::new().generate_certificate(
+            &drive,
+            WipeDetails {
+                algorithm_used: "Zero".to_string(),
+                standard: None,
+                passes_completed: 1,
+                duration_seconds: 0,
+                operator_id: None,
+                method: "overwrite".to_string(),
+                started_at: now,
+                completed_at: now,
+                notes: None,
+                completion: WipeCompletion::FullyWiped,
+                hidden_areas: None,
+                trim_verification: None,
+                health: None,
+                entropy_source: None,
+                device_logs: Vec::new(),
+                passes: Vec::new(),
+                random_seed: None,
+                nvme_namespaces: Vec::new(),
+                retries: RetryCounts::default(),
+                signatures: None,
+                partition_tables_cleared: Vec::new(),
+                final_hash: None,
+                reprovisioned: None,
+                capabilities: None,
+                advertised_erase_time: None,
+            },
+            VerificationResult {
+                verified: true,
+                entropy_score: 7.998_213_456_901_234,
+                recovery_test_passed: false,
+                verification_timestamp: now,
+                randomness: None,
+            },
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        let mut out = String::new();
+        let value: Value = serde_json::from_str(r#"{"b": [1, {"z": 0, "a": 1}], "a": "x"}"#).unwrap();
+        write_canonical_json(&value, &mut out);
+        assert_eq!(out, r#"{"a":"x","b":[1,{"a":1,"z":0}]}"#);
+    }
+
+    #[test]
+    fn test_ed25519_sign_and_verify() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), pkcs8.as_ref()).unwrap();
+
+        let mut certificate = test_certificate();
+        assert!(certificate.verify_signature().is_err());
+
+        certificate.sign_ed25519(key_file.path()).unwrap();
+        assert!(certificate.verify_signature().unwrap());
+
+        // The embedded signature must survive a JSON round trip
+        let reloaded: WipeCertificate = serde_json::from_str(&certificate.to_json().unwrap()).unwrap();
+        assert!(reloaded.verify_signature().unwrap());
+        assert!(CertificateGenerator::new().verify_certificate(&reloaded).unwrap());
+
+        certificate.wipe_details.passes_completed = 3;
+        assert!(!certificate.verify_signature().unwrap());
+    }
+
+    #[test]
+    fn test_ed25519_raw_seed_key() {
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), [7u8; 32]).unwrap();
+
+        let mut certificate = test_certificate();
+        certificate.sign_ed25519(key_file.path()).unwrap();
+        assert!(certificate.verify_signature().unwrap());
+    }
+
+    #[test]
+    fn test_health_report_deltas() {
+        let before = SmartSnapshot {
+            status: HealthStatus::Good,
+            reallocated_sectors: Some(8),
+            pending_sectors: Some(3),
+            uncorrectable_errors: None,
+            media_errors: None,
+        };
+        let mut report = HealthReport { before: before.clone(), after: None };
+        assert_eq!(report.reallocated_delta(), None);
+
+        report.after = Some(SmartSnapshot {
+            reallocated_sectors: Some(20),
+            pending_sectors: Some(0),
+            ..before
+        });
+        assert_eq!(report.reallocated_delta(), Some(12));
+        assert_eq!(report.pending_delta(), Some(-3));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn test_pdf_rendering() {
+        let mut certificate = test_certificate();
+        certificate.wipe_details.notes = Some("Decommissioned (rack 4) \\ café".to_string());
+
+        let pdf = certificate.to_pdf().unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+        assert!(text.contains("(TEST123) Tj"));
+        assert!(text.contains(&certificate.signature[..64]));
+        assert!(text.contains("Decommissioned \\(rack 4\\) \\\\ caf?"));
+        assert!(text.contains(" re f"));
+
+        // The QR code drawn on the page scans back to the ID and signature
+        let modules: Vec<(f32, f32, f32)> = text.lines()
+            .filter_map(|line| line.strip_suffix(" re f"))
+            .map(|rect| {
+                let values: Vec<f32> = rect.split(' ').map(|v| v.parse().unwrap()).collect();
+                (values[0], values[1], values[2])
+            })
+            .collect();
+        let side = modules[0].2;
+        let left = modules.iter().map(|m| m.0).fold(f32::MAX, f32::min);
+        let top = modules.iter().map(|m| m.1).fold(f32::MIN, f32::max);
+        let dark: std::collections::HashSet<(usize, usize)> = modules.iter()
+            .map(|m| (((m.0 - left) / side).round() as usize, ((top - m.1) / side).round() as usize))
+            .collect();
+        let size = dark.iter().map(|&(x, _)| x).max().unwrap() + 1;
+        let payload = crate::crypto::qr::tests::decode(size, |x, y| dark.contains(&(x, y)));
+        assert_eq!(String::from_utf8(payload).unwrap(), format!("{}\n{}", certificate.certificate_id, certificate.signature));
+
+        // startxref must point at the cross-reference table
+        let startxref: usize = text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        assert!(pdf[startxref..].starts_with(b"xref"));
+    }
+}
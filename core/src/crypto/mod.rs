@@ -1,8 +1,16 @@
 pub mod certificates;
+pub mod certificate_store;
+pub mod pattern_seed;
 pub(crate) mod secure_rng;
+#[cfg(feature = "pdf")]
+mod certificate_pdf;
+#[cfg(feature = "pdf")]
+mod qr;
 
 #[cfg(test)]
 mod secure_rng_tests;
 
 // Re-export
-pub use certificates::{CertificateGenerator, WipeCertificate, WipeDetails, VerificationResult};
+pub use certificate_store::{CertificateStore, CertificateLogEntry, ChainVerification};
+pub use certificates::{CertificateGenerator, WipeCertificate, WipeDetails, WipeCompletion, DeviceLogClearing, HiddenAreaReport, TrimVerification, VerificationResult};
+pub use secure_rng::secure_rng_source;
@@ -0,0 +1,168 @@
+// Reproducible random passes - a recorded seed instead of unrepeatable noise
+//
+// With `WipeConfig::pattern_seed_key_path` set, random passes are a ChaCha20
+// keystream keyed by a 256-bit seed drawn from `SecureRNG`. Each pass uses
+// its own stream and the stream position is the device offset, so any byte
+// of any pass can be regenerated and verification compares the drive
+// byte-for-byte instead of estimating entropy. Knowing the seed is knowing
+// the data, so it is only stored sealed (ChaCha20-Poly1305) under the key at
+// that path.
+
+use super::certificates::{from_hex, to_hex};
+use super::secure_rng::secure_random_bytes;
+use anyhow::{anyhow, Context, Result};
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use std::path::Path;
+
+/// Associated data binding sealed seeds to their purpose
+const SEAL_AAD: &[u8] = b"sayonara-wipe pattern seed v1";
+
+/// Seed of a reproducible random wipe
+#[derive(Clone, PartialEq, Eq)]
+pub struct PatternSeed([u8; 32]);
+
+impl std::fmt::Debug for PatternSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PatternSeed(..)")
+    }
+}
+
+impl PatternSeed {
+    /// A fresh seed from the secure RNG
+    pub fn generate() -> Result<Self> {
+        let mut seed = [0u8; 32];
+        secure_random_bytes(&mut seed)?;
+        Ok(Self(seed))
+    }
+
+    /// Fill `buf` with pass `pass`'s stream starting at device byte `offset`
+    pub fn fill(&self, pass: usize, buf: &mut [u8], offset: u64) {
+        let mut rng = ChaCha20Rng::from_seed(self.0);
+        rng.set_stream(pass as u64);
+        rng.set_word_pos((offset / 4) as u128);
+
+        // Discard the part of the first word before `offset`
+        let skip = (offset % 4) as usize;
+        if skip > 0 {
+            let mut word = [0u8; 4];
+            rng.fill_bytes(&mut word);
+            let take = (4 - skip).min(buf.len());
+            buf[..take].copy_from_slice(&word[skip..skip + take]);
+            rng.fill_bytes(&mut buf[take..]);
+        } else {
+            rng.fill_bytes(buf);
+        }
+    }
+
+    /// Device offset of the first byte of `actual`, read from `offset`,
+    /// that differs from pass `pass`'s stream, or None when all of it matches
+    pub fn first_mismatch(&self, pass: usize, offset: u64, actual: &[u8]) -> Option<u64> {
+        let mut expected = vec![0u8; actual.len()];
+        self.fill(pass, &mut expected, offset);
+        expected.iter().zip(actual).position(|(e, a)| e != a).map(|i| offset + i as u64)
+    }
+
+    /// Encrypt the seed under `key`, as hex of nonce || ciphertext || tag
+    pub fn seal(&self, key: &[u8; 32]) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        secure_random_bytes(&mut nonce)?;
+
+        let mut sealed = self.0.to_vec();
+        sealing_key(key)?
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(SEAL_AAD), &mut sealed)
+            .map_err(|_| anyhow!("Failed to seal pattern seed"))?;
+
+        Ok(to_hex(&nonce) + &to_hex(&sealed))
+    }
+
+    /// Recover a seed sealed with `seal`
+    pub fn unseal(sealed: &str, key: &[u8; 32]) -> Result<Self> {
+        let bytes = from_hex(sealed)?;
+        if bytes.len() < NONCE_LEN {
+            return Err(anyhow!("Sealed pattern seed is too short"));
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("Invalid seed nonce"))?;
+        let mut in_out = ciphertext.to_vec();
+        let seed = sealing_key(key)?
+            .open_in_place(nonce, Aad::from(SEAL_AAD), &mut in_out)
+            .map_err(|_| anyhow!("Pattern seed does not open with this key"))?;
+
+        Ok(Self(seed.try_into().map_err(|_| anyhow!("Sealed pattern seed has the wrong length"))?))
+    }
+}
+
+/// Read the 32-byte seed key at `path`, creating it (mode 0600) with a
+/// random key on first use
+pub fn load_or_create_seed_key(path: impl AsRef<Path>) -> Result<[u8; 32]> {
+    let path = path.as_ref();
+    if path.exists() {
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read seed key {}", path.display()))?;
+        return bytes
+            .try_into()
+            .map_err(|_| anyhow!("Seed key {} must be exactly 32 bytes", path.display()));
+    }
+
+    let mut key = [0u8; 32];
+    secure_random_bytes(&mut key)?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path).with_context(|| format!("Failed to create seed key {}", path.display()))?;
+    std::io::Write::write_all(&mut file, &key)?;
+
+    println!("🔑 Created seed key {}", path.display());
+    Ok(key)
+}
+
+fn sealing_key(key: &[u8; 32]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(&CHACHA20_POLY1305, key).map_err(|_| anyhow!("Invalid seed key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_is_addressable_by_offset() {
+        let seed = PatternSeed::generate().unwrap();
+        let mut whole = vec![0u8; 4096];
+        seed.fill(2, &mut whole, 0);
+
+        // Any sub-range regenerates the same bytes, aligned or not
+        for (start, len) in [(512, 1024), (1001, 77), (3, 1)] {
+            let mut part = vec![0u8; len];
+            seed.fill(2, &mut part, start as u64);
+            assert_eq!(part, whole[start..start + len]);
+        }
+
+        // Other passes get independent streams
+        let mut other = vec![0u8; 4096];
+        seed.fill(1, &mut other, 0);
+        assert_ne!(other, whole);
+    }
+
+    #[test]
+    fn test_seal_round_trip_and_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = load_or_create_seed_key(dir.path().join("seed.key")).unwrap();
+        assert_eq!(load_or_create_seed_key(dir.path().join("seed.key")).unwrap(), key);
+
+        let seed = PatternSeed::generate().unwrap();
+        let sealed = seed.seal(&key).unwrap();
+        assert_eq!(PatternSeed::unseal(&sealed, &key).unwrap(), seed);
+        assert!(PatternSeed::unseal(&sealed, &[0u8; 32]).is_err());
+
+        let mut data = vec![0u8; 8192];
+        seed.fill(0, &mut data, 0);
+        data[5000] ^= 1;
+        assert_eq!(seed.first_mismatch(0, 0, &data[..4096]), None);
+        assert_eq!(seed.first_mismatch(0, 4096, &data[4096..]), Some(5000));
+    }
+}
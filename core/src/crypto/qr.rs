@@ -0,0 +1,638 @@
+// QR code encoder for the signature printed on PDF certificates
+//
+// Byte mode only, error correction level M, versions 1-40, following
+// ISO/IEC 18004. Kept in-tree so the `pdf` feature needs no extra crates.
+
+use anyhow::{Result, anyhow};
+
+/// Error correction codewords per block for level M, indexed by version
+const ECC_CODEWORDS_PER_BLOCK: [usize; 41] = [
+    0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26,
+    26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+];
+
+/// Error correction blocks for level M, indexed by version
+const NUM_ERROR_CORRECTION_BLOCKS: [usize; 41] = [
+    0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16,
+    17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+];
+
+/// Format information bits identifying error correction level M
+const ECC_LEVEL_M_BITS: u32 = 0;
+
+/// A square grid of dark and light modules, without the quiet zone
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode `data` in the smallest version that fits, picking the mask
+    /// with the lowest penalty score
+    pub fn encode(data: &[u8]) -> Result<Self> {
+        Self::encode_with_mask(data, None)
+    }
+
+    fn encode_with_mask(data: &[u8], mask: Option<u8>) -> Result<Self> {
+        let version = (1..=40)
+            .find(|&version| Self::data_bits_needed(data.len(), version) <= Self::data_codewords(version) * 8)
+            .ok_or_else(|| anyhow!("{} bytes is too long for a QR code", data.len()))?;
+
+        let mut qr = Self::blank(version);
+        qr.draw_function_patterns(version);
+        let codewords = Self::add_ecc_and_interleave(&Self::data_codewords_for(data, version), version);
+        qr.draw_codewords(&codewords);
+
+        let mask = mask.unwrap_or_else(|| {
+            (0..8)
+                .min_by_key(|&mask| {
+                    qr.apply_mask(mask);
+                    qr.draw_format_bits(mask);
+                    let penalty = qr.penalty_score();
+                    qr.apply_mask(mask); // XOR again to undo
+                    penalty
+                })
+                .unwrap_or(0)
+        });
+        qr.apply_mask(mask);
+        qr.draw_format_bits(mask);
+
+        Ok(qr)
+    }
+
+    /// Modules per side
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column `x`, row `y` is dark
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn blank(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    /// Mode indicator, character count and payload, in bits
+    fn data_bits_needed(len: usize, version: usize) -> usize {
+        4 + Self::count_bits(version) + len * 8
+    }
+
+    /// Width of the byte-mode character count field
+    fn count_bits(version: usize) -> usize {
+        if version < 10 { 8 } else { 16 }
+    }
+
+    /// Modules available for data and error correction once the function
+    /// patterns are placed
+    fn raw_data_modules(version: usize) -> usize {
+        let mut result = (16 * version + 128) * version + 64;
+        if version >= 2 {
+            let num_align = version / 7 + 2;
+            result -= (25 * num_align - 10) * num_align - 55;
+            if version >= 7 {
+                result -= 36;
+            }
+        }
+        result
+    }
+
+    fn data_codewords(version: usize) -> usize {
+        Self::raw_data_modules(version) / 8
+            - ECC_CODEWORDS_PER_BLOCK[version] * NUM_ERROR_CORRECTION_BLOCKS[version]
+    }
+
+    /// Byte-mode segment, terminator and padding, as codewords
+    fn data_codewords_for(data: &[u8], version: usize) -> Vec<u8> {
+        let capacity = Self::data_codewords(version) * 8;
+        let mut bits: Vec<bool> = Vec::with_capacity(capacity);
+        let mut push = |value: u32, len: usize| {
+            bits.extend((0..len).rev().map(|i| (value >> i) & 1 != 0));
+        };
+
+        push(0b0100, 4);
+        push(data.len() as u32, Self::count_bits(version));
+        for &byte in data {
+            push(byte as u32, 8);
+        }
+
+        let terminator = (capacity - bits.len()).min(4);
+        bits.extend(std::iter::repeat_n(false, terminator));
+        bits.extend(std::iter::repeat_n(false, (8 - bits.len() % 8) % 8));
+
+        let mut codewords: Vec<u8> = bits
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+            .collect();
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if codewords.len() * 8 >= capacity {
+                break;
+            }
+            codewords.push(pad);
+        }
+        codewords
+    }
+
+    /// Split the data into blocks, append Reed-Solomon codewords to each, and
+    /// interleave the blocks
+    fn add_ecc_and_interleave(data: &[u8], version: usize) -> Vec<u8> {
+        let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[version];
+        let block_ecc_len = ECC_CODEWORDS_PER_BLOCK[version];
+        let raw_codewords = Self::raw_data_modules(version) / 8;
+        let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+        let short_block_len = raw_codewords / num_blocks;
+
+        let divisor = reed_solomon_divisor(block_ecc_len);
+        let mut blocks = Vec::with_capacity(num_blocks);
+        let mut k = 0;
+        for i in 0..num_blocks {
+            let data_len = short_block_len - block_ecc_len + usize::from(i >= num_short_blocks);
+            let mut block = data[k..k + data_len].to_vec();
+            k += data_len;
+            let ecc = reed_solomon_remainder(&block, &divisor);
+            if i < num_short_blocks {
+                block.push(0); // Placeholder so every block has the same length
+            }
+            block.extend(ecc);
+            blocks.push(block);
+        }
+
+        let mut result = Vec::with_capacity(raw_codewords);
+        for i in 0..blocks[0].len() {
+            for (j, block) in blocks.iter().enumerate() {
+                if i != short_block_len - block_ecc_len || j >= num_short_blocks {
+                    result.push(block[i]);
+                }
+            }
+        }
+        result
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            self.draw_finder_pattern(x, y);
+        }
+
+        let positions = Self::alignment_pattern_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // The three corners are taken by finder patterns
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function((x as i32 + dx) as usize, (y as i32 + dy) as usize, dark);
+                    }
+                }
+            }
+        }
+
+        // Reserve the format areas; the real bits are drawn once the mask is chosen
+        self.draw_format_bits(0);
+        self.draw_version(version);
+    }
+
+    /// Finder pattern centred at (x, y), with its light separator
+    fn draw_finder_pattern(&mut self, x: usize, y: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+                    let distance = dx.abs().max(dy.abs());
+                    self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn alignment_pattern_positions(version: usize) -> Vec<usize> {
+        if version == 1 {
+            return Vec::new();
+        }
+        let num_align = version / 7 + 2;
+        let step = (version * 8 + num_align * 3 + 5) / (num_align * 4 - 4) * 2;
+        let size = version * 4 + 17;
+        let mut positions: Vec<usize> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+        positions.push(6);
+        positions.reverse();
+        positions
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        let data = (ECC_LEVEL_M_BITS << 3) | mask as u32;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+
+        // Around the top-left finder
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // Split between the other two finders
+        let size = self.size;
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true); // Always dark
+    }
+
+    fn draw_version(&mut self, version: usize) {
+        if version < 7 {
+            return;
+        }
+        let mut rem = version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = ((version as u32) << 12) | rem;
+
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Place codewords in the zig-zag order, two columns at a time from the right
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.is_function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        let size = self.size;
+        for y in 0..size {
+            for x in 0..size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.is_function[y * size + x] {
+                    self.modules[y * size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// Penalty rules N1-N4 from the standard; lower is easier to scan
+    fn penalty_score(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+
+        let lines = (0..size).flat_map(|i| {
+            [
+                (0..size).map(|x| self.is_dark(x, i)).collect::<Vec<_>>(),
+                (0..size).map(|y| self.is_dark(i, y)).collect::<Vec<_>>(),
+            ]
+        });
+        for line in lines {
+            // N1: runs of five or more modules of one colour
+            let mut run = 1;
+            for k in 1..=line.len() {
+                if k < line.len() && line[k] == line[k - 1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        penalty += run - 2;
+                    }
+                    run = 1;
+                }
+            }
+
+            // N3: 1:1:3:1:1 finder-like patterns with four light modules to one side
+            const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+            let light = |start: isize| (start..start + 4).all(|k| k < 0 || k as usize >= line.len() || !line[k as usize]);
+            for start in 0..line.len().saturating_sub(6) {
+                if line[start..start + 7] == FINDER && (light(start as isize - 4) || light(start as isize + 7)) {
+                    penalty += 40;
+                }
+            }
+        }
+
+        // N2: 2x2 blocks of one colour
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.is_dark(x, y);
+                if dark == self.is_dark(x + 1, y) && dark == self.is_dark(x, y + 1) && dark == self.is_dark(x + 1, y + 1) {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // N4: 10 points per 5% the dark proportion strays from half
+        let dark = self.modules.iter().filter(|&&dark| dark).count() as isize;
+        let total = (size * size) as isize;
+        let k = (((dark * 20 - total * 10).abs() + total - 1) / total - 1) as usize;
+        penalty + k * 10
+    }
+}
+
+/// Generator polynomial for `degree` error correction codewords
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, &coefficient) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(coefficient, factor);
+        }
+    }
+    result
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Centres of the alignment patterns, from table E.1 of ISO/IEC 18004
+    const ALIGNMENT_CENTRES: [&[usize]; 14] = [
+        &[], &[], &[6, 18], &[6, 22], &[6, 26], &[6, 30], &[6, 34],
+        &[6, 22, 38], &[6, 24, 42], &[6, 26, 46], &[6, 28, 50], &[6, 30, 54], &[6, 32, 58], &[6, 34, 62],
+    ];
+
+    /// Level M block structure from table 9: (blocks, data codewords) per
+    /// group and error correction codewords per block
+    const M_BLOCKS: [([(usize, usize); 2], usize); 14] = [
+        ([(0, 0), (0, 0)], 0),
+        ([(1, 16), (0, 0)], 10),
+        ([(1, 28), (0, 0)], 16),
+        ([(1, 44), (0, 0)], 26),
+        ([(2, 32), (0, 0)], 18),
+        ([(2, 43), (0, 0)], 24),
+        ([(4, 27), (0, 0)], 16),
+        ([(4, 31), (0, 0)], 18),
+        ([(2, 38), (2, 39)], 22),
+        ([(3, 36), (2, 37)], 22),
+        ([(4, 43), (1, 44)], 26),
+        ([(1, 50), (4, 51)], 30),
+        ([(6, 36), (2, 37)], 22),
+        ([(8, 37), (1, 38)], 22),
+    ];
+
+    /// Read a level-M byte-mode symbol of version 1-13 back to its payload,
+    /// the way a scanner would: from the modules alone, using the tables in
+    /// the standard rather than the encoder's own layout code. Panics on
+    /// anything a scanner would reject, including a Reed-Solomon block whose
+    /// syndromes aren't all zero.
+    pub(in crate::crypto) fn decode(size: usize, dark: impl Fn(usize, usize) -> bool) -> Vec<u8> {
+        let version = (size - 17) / 4;
+        assert!((1..=13).contains(&version) && version * 4 + 17 == size, "unsupported size {}", size);
+
+        // Format information, top-left copy, checked against the other copy
+        let mut format = 0u32;
+        let top_left = (0..6).map(|i| (8, i)).chain([(8, 7), (8, 8), (7, 8)]).chain((9..15).map(|i| (14 - i, 8)));
+        let split = (0..8).map(|i| (size - 1 - i, 8)).chain((8..15).map(|i| (8, size - 15 + i)));
+        for (i, ((x, y), (x2, y2))) in top_left.zip(split).enumerate() {
+            assert_eq!(dark(x, y), dark(x2, y2), "format bit {} differs between copies", i);
+            format |= u32::from(dark(x, y)) << i;
+        }
+        let format = format ^ 0x5412;
+        let bch = (0..10).fold(format >> 10, |rem, _| (rem << 1) ^ ((rem >> 9) * 0x537));
+        assert_eq!(format & 0x3FF, bch & 0x3FF, "format information fails its BCH check");
+        assert_eq!(format >> 13, 0b00, "not error correction level M");
+        let mask = (format >> 10) & 0b111;
+
+        let alignment = ALIGNMENT_CENTRES[version];
+        let is_function = |x: usize, y: usize| {
+            let in_finder = (x < 9 && y < 9) || (x >= size - 8 && y < 9) || (x < 9 && y >= size - 8);
+            let in_alignment = alignment.iter().any(|&cx| {
+                alignment.iter().any(|&cy| {
+                    let corner = (cx == 6 && cy == 6) || (cx == 6 && cy == size - 7) || (cx == size - 7 && cy == 6);
+                    !corner && x.abs_diff(cx) <= 2 && y.abs_diff(cy) <= 2
+                })
+            });
+            let in_version = version >= 7 && ((x >= size - 11 && x < size - 8 && y < 6) || (y >= size - 11 && y < size - 8 && x < 6));
+            in_finder || in_alignment || in_version || x == 6 || y == 6
+        };
+        let masked = |x: usize, y: usize| {
+            let (i, j) = (y, x);
+            match mask {
+                0 => (i + j) % 2 == 0,
+                1 => i % 2 == 0,
+                2 => j % 3 == 0,
+                3 => (i + j) % 3 == 0,
+                4 => (i / 2 + j / 3) % 2 == 0,
+                5 => (i * j) % 2 + (i * j) % 3 == 0,
+                6 => ((i * j) % 2 + (i * j) % 3) % 2 == 0,
+                _ => ((i + j) % 2 + (i * j) % 3) % 2 == 0,
+            }
+        };
+
+        // Codeword bits, upwards and downwards through column pairs from the right
+        let mut bits = Vec::new();
+        let mut right = size as isize - 1;
+        let mut upward = true;
+        while right > 0 {
+            if right == 6 {
+                right = 5;
+            }
+            for step in 0..size {
+                let y = if upward { size - 1 - step } else { step };
+                for x in [right as usize, right as usize - 1] {
+                    if !is_function(x, y) {
+                        bits.push(dark(x, y) ^ masked(x, y));
+                    }
+                }
+            }
+            upward = !upward;
+            right -= 2;
+        }
+        let codewords: Vec<u8> = bits.chunks_exact(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | u8::from(bit)))
+            .collect();
+
+        // De-interleave: data codewords block by block, then the error correction
+        let ([(n1, d1), (n2, d2)], ecc) = M_BLOCKS[version];
+        let data_lens: Vec<usize> = std::iter::repeat_n(d1, n1).chain(std::iter::repeat_n(d2, n2)).collect();
+        let mut blocks: Vec<Vec<u8>> = vec![Vec::new(); data_lens.len()];
+        let mut next = codewords.iter();
+        for i in 0..d1.max(d2) {
+            for (block, &len) in blocks.iter_mut().zip(&data_lens) {
+                if i < len {
+                    block.push(*next.next().unwrap());
+                }
+            }
+        }
+        for _ in 0..ecc {
+            for block in blocks.iter_mut() {
+                block.push(*next.next().unwrap());
+            }
+        }
+
+        // Every syndrome of an intact block is zero
+        let mut exp = [0u8; 255];
+        let mut value = 1u16;
+        for e in exp.iter_mut() {
+            *e = value as u8;
+            value <<= 1;
+            if value & 0x100 != 0 {
+                value ^= 0x11D;
+            }
+        }
+        let multiply = |a: u8, power: usize| -> u8 {
+            if a == 0 {
+                return 0;
+            }
+            let log = exp.iter().position(|&e| e == a).unwrap();
+            exp[(log + power) % 255]
+        };
+        for (b, block) in blocks.iter().enumerate() {
+            for k in 0..ecc {
+                let syndrome = block.iter().rev().enumerate().fold(0u8, |acc, (j, &c)| acc ^ multiply(c, k * j));
+                assert_eq!(syndrome, 0, "block {} fails Reed-Solomon syndrome {}", b, k);
+            }
+        }
+
+        // Byte-mode segment: mode, character count, payload
+        let data: Vec<bool> = blocks.iter()
+            .zip(&data_lens)
+            .flat_map(|(block, &len)| block[..len].to_vec())
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 != 0))
+            .collect();
+        let read = |from: usize, len: usize| data[from..from + len].iter().fold(0usize, |acc, &bit| (acc << 1) | usize::from(bit));
+        assert_eq!(read(0, 4), 0b0100, "not a byte-mode segment");
+        let count_bits = if version < 10 { 8 } else { 16 };
+        let len = read(4, count_bits);
+        (0..len).map(|i| read(4 + count_bits + i * 8, 8) as u8).collect()
+    }
+
+    #[test]
+    fn test_decodes_to_the_payload() {
+        // Versions 1, 7 (version information), 9 (8-bit count) and 10 (16-bit count, two block sizes)
+        let id_and_signature = format!("{}\n{}", uuid::Uuid::new_v4(), "9f".repeat(64));
+        let payloads = [b"sayonara".to_vec(), vec![b'a'; 115], id_and_signature.into_bytes(), (0..=200).collect()];
+        let mut versions = Vec::new();
+        for payload in payloads {
+            let qr = QrCode::encode(&payload).unwrap();
+            versions.push((qr.size() - 17) / 4);
+            assert_eq!(decode(qr.size(), |x, y| qr.is_dark(x, y)), payload);
+        }
+        assert_eq!(versions, [1, 7, 9, 10]);
+    }
+
+    #[test]
+    fn test_version_grows_with_payload() {
+        assert_eq!(QrCode::encode(b"hello").unwrap().size(), 21);
+        // An Ed25519 signature in hex plus a certificate id needs version 9 at level M
+        assert_eq!(QrCode::encode(&[b'a'; 165]).unwrap().size(), 9 * 4 + 17);
+        assert!(QrCode::encode(&[0u8; 3000]).is_err());
+    }
+
+    #[test]
+    fn test_finder_and_timing_patterns() {
+        let qr = QrCode::encode(b"sayonara").unwrap();
+        let size = qr.size();
+        for (x, y) in [(0, 0), (size - 7, 0), (0, size - 7)] {
+            assert!(qr.is_dark(x, y) && qr.is_dark(x + 6, y + 6) && qr.is_dark(x + 3, y + 3));
+            assert!(!qr.is_dark(x + 1, y + 1));
+        }
+        assert!((8..size - 8).all(|i| qr.is_dark(i, 6) == (i % 2 == 0)));
+    }
+
+    #[test]
+    fn test_format_bits_match_in_both_copies() {
+        let qr = QrCode::encode(b"certificate").unwrap();
+        let size = qr.size();
+        let first: Vec<bool> = (0..6).map(|i| qr.is_dark(8, i)).collect();
+        let second: Vec<bool> = (0..6).map(|i| qr.is_dark(size - 1 - i, 8)).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_reed_solomon_known_vector() {
+        // "HELLO WORLD" at version 1-M, from the worked example in the standard
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17];
+        let ecc = reed_solomon_remainder(&data, &reed_solomon_divisor(10));
+        assert_eq!(ecc, [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    }
+}
@@ -6,13 +6,18 @@ use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH, Instant};
 use ring::rand::{SecureRandom, SystemRandom};
 use sha2::{Sha256, Sha512, Digest};
-use std::sync::atomic::{AtomicU64, AtomicBool, Ordering};
-
-/// FIPS 140-2 compliant secure random number generator with multiple entropy sources
+use std::sync::atomic::{AtomicU64, AtomicBool, AtomicUsize, Ordering};
+
+/// FIPS 140-2 compliant secure random number generator with multiple entropy sources.
+///
+/// Sources are tried in order: a hardware source (CPU RDSEED/RDRAND, then
+/// /dev/hwrng, then /dev/random), the kernel's getrandom(2), and finally CPU
+/// jitter. Output is XORed with an HMAC-DRBG keystream; `entropy_source()`
+/// names the source that supplied the last fill.
 pub struct SecureRNG {
     /// Primary entropy source (hardware if available)
     pub(crate) primary_source: Box<dyn EntropySource>,
-    /// Secondary entropy source (OS cryptographic RNG)
+    /// Secondary entropy source (getrandom)
     pub(crate) secondary_source: Box<dyn EntropySource>,
     /// Tertiary entropy source (fallback)
     pub(crate) tertiary_source: Box<dyn EntropySource>,
@@ -28,6 +33,8 @@ pub struct SecureRNG {
     pub(crate) continuous_test: Arc<Mutex<ContinuousTest>>,
     /// Persistent HMAC-DRBG seeded from the entropy pool (fast)
     pub(crate) drbg: Option<HmacDrbg>,
+    /// Which source (0 primary, 1 secondary, 2 tertiary) supplied the last fill
+    pub(crate) active_source: Arc<AtomicUsize>,
 }
 
 /// Trait for entropy sources
@@ -107,6 +114,189 @@ impl EntropySource for HardwareRNG {
     }
 }
 
+/// RDSEED/RDRAND instruction used by `CpuRNG`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuInstruction {
+    /// Raw conditioned entropy, meant for seeding
+    RdSeed,
+    /// Output of the CPU's own DRBG, reseeded from the same entropy source
+    RdRand,
+}
+
+/// CPU hardware RNG (x86-64 RDSEED, or RDRAND when RDSEED is missing)
+pub struct CpuRNG {
+    instruction: Option<CpuInstruction>,
+}
+
+/// Intel recommends retrying a failed RDRAND/RDSEED a handful of times
+const CPU_RNG_RETRIES: usize = 10;
+
+impl CpuRNG {
+    pub fn new() -> Self {
+        Self { instruction: Self::detect() }
+    }
+
+    /// The instruction in use, or None when the CPU has neither
+    pub fn instruction(&self) -> Option<CpuInstruction> {
+        self.instruction
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn detect() -> Option<CpuInstruction> {
+        if is_x86_feature_detected!("rdseed") {
+            Some(CpuInstruction::RdSeed)
+        } else if is_x86_feature_detected!("rdrand") {
+            Some(CpuInstruction::RdRand)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn detect() -> Option<CpuInstruction> {
+        None
+    }
+
+    /// One 64-bit value, retrying while the CPU reports it has none ready
+    #[cfg(target_arch = "x86_64")]
+    fn next_u64(instruction: CpuInstruction) -> Option<u64> {
+        #[target_feature(enable = "rdseed")]
+        unsafe fn rdseed(value: &mut u64) -> i32 {
+            std::arch::x86_64::_rdseed64_step(value)
+        }
+        #[target_feature(enable = "rdrand")]
+        unsafe fn rdrand(value: &mut u64) -> i32 {
+            std::arch::x86_64::_rdrand64_step(value)
+        }
+
+        let mut value = 0u64;
+        for _ in 0..CPU_RNG_RETRIES {
+            // SAFETY: `detect()` confirmed the CPU supports the instruction
+            let ok = unsafe {
+                match instruction {
+                    CpuInstruction::RdSeed => rdseed(&mut value),
+                    CpuInstruction::RdRand => rdrand(&mut value),
+                }
+            };
+            if ok == 1 {
+                return Some(value);
+            }
+            std::hint::spin_loop();
+        }
+        None
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn next_u64(_instruction: CpuInstruction) -> Option<u64> {
+        None
+    }
+}
+
+impl EntropySource for CpuRNG {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+        let instruction = self.instruction.ok_or_else(|| anyhow!("CPU has no RDSEED/RDRAND"))?;
+
+        for chunk in dest.chunks_mut(8) {
+            let value = Self::next_u64(instruction)
+                .ok_or_else(|| anyhow!("{} returned no data after {} retries", self.name(), CPU_RNG_RETRIES))?;
+            chunk.copy_from_slice(&value.to_le_bytes()[..chunk.len()]);
+        }
+
+        Ok(())
+    }
+
+    fn quality(&self) -> f64 {
+        match self.instruction {
+            Some(CpuInstruction::RdSeed) => 1.0,
+            Some(CpuInstruction::RdRand) => 0.95,
+            None => 0.0,
+        }
+    }
+
+    fn is_available(&self) -> bool {
+        self.instruction.is_some()
+    }
+
+    fn name(&self) -> &str {
+        match self.instruction {
+            Some(CpuInstruction::RdSeed) => "RDSEED",
+            Some(CpuInstruction::RdRand) => "RDRAND",
+            None => "CpuRNG",
+        }
+    }
+}
+
+/// Kernel blocking pool (/dev/random); never blocks once the kernel CRNG is seeded
+pub struct DevRandom {
+    available: bool,
+}
+
+impl DevRandom {
+    pub fn new() -> Self {
+        Self { available: std::path::Path::new("/dev/random").exists() }
+    }
+}
+
+impl EntropySource for DevRandom {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+        if !self.available {
+            return Err(anyhow!("/dev/random not available"));
+        }
+
+        File::open("/dev/random")
+            .and_then(|mut file| file.read_exact(dest))
+            .map_err(|e| anyhow!("Failed to read from /dev/random: {}", e))
+    }
+
+    fn quality(&self) -> f64 {
+        if self.available { 0.95 } else { 0.0 }
+    }
+
+    fn is_available(&self) -> bool {
+        self.available
+    }
+
+    fn name(&self) -> &str {
+        "/dev/random"
+    }
+}
+
+/// The kernel CSPRNG via the getrandom(2) syscall
+pub struct GetRandom;
+
+impl EntropySource for GetRandom {
+    fn fill_bytes(&self, dest: &mut [u8]) -> Result<()> {
+        getrandom::getrandom(dest).map_err(|e| anyhow!("getrandom failed: {}", e))
+    }
+
+    fn quality(&self) -> f64 {
+        0.95
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "getrandom"
+    }
+}
+
+/// Most preferred hardware source this machine has: CPU RDSEED/RDRAND,
+/// then /dev/hwrng, then /dev/random. None when none of them is usable.
+pub fn preferred_hardware_source() -> Option<Box<dyn EntropySource>> {
+    let cpu = CpuRNG::new();
+    if cpu.is_available() {
+        return Some(Box::new(cpu));
+    }
+    let hwrng = HardwareRNG::new();
+    if hwrng.is_available() {
+        return Some(Box::new(hwrng));
+    }
+    let random = DevRandom::new();
+    random.is_available().then(|| Box::new(random) as Box<dyn EntropySource>)
+}
+
 /// Ring-based system random (uses OS facilities)
 pub struct RingSystemRNG {
     rng: SystemRandom,
@@ -509,8 +699,8 @@ impl SecureRNG {
         println!("Initializing Secure RNG with multiple entropy sources...");
 
         // Initialize entropy sources in order of preference
-        let primary = Box::new(HardwareRNG::new()) as Box<dyn EntropySource>;
-        let secondary = Box::new(RingSystemRNG::new()) as Box<dyn EntropySource>;
+        let primary = preferred_hardware_source().unwrap_or_else(|| Box::new(GetRandom));
+        let secondary = Box::new(GetRandom) as Box<dyn EntropySource>;
         let tertiary = Box::new(JitterEntropy::new()) as Box<dyn EntropySource>;
 
         // Check that at least one source is available
@@ -528,6 +718,7 @@ impl SecureRNG {
             is_healthy: Arc::new(AtomicBool::new(true)),
             continuous_test: Arc::new(Mutex::new(ContinuousTest::new())),
             drbg: None,
+            active_source: Arc::new(AtomicUsize::new(0)),
         };
 
         // Initial seeding from all available sources
@@ -536,7 +727,7 @@ impl SecureRNG {
         // Initialize persistent HMAC-DRBG seeded from pool
         rng.seed_drbg()?;
 
-        println!("✓ Secure RNG initialized successfully");
+        println!("✓ Secure RNG initialized successfully (entropy source: {})", rng.entropy_source());
 
         Ok(rng)
     }
@@ -555,25 +746,16 @@ impl SecureRNG {
             self.reseed()?;
         }
 
-        // Try primary source first
-        let mut success = false;
-        if self.primary_source.is_available() {
-            if let Ok(()) = self.primary_source.fill_bytes(dest) {
-                success = true;
-            }
-        }
-
-        // Fallback to secondary source
-        if !success && self.secondary_source.is_available() {
-            if let Ok(()) = self.secondary_source.fill_bytes(dest) {
-                success = true;
-            }
-        }
-
-        // Last resort: tertiary source
-        if !success {
+        // Try primary source first, then secondary, tertiary as last resort
+        let source = if self.primary_source.is_available() && self.primary_source.fill_bytes(dest).is_ok() {
+            0
+        } else if self.secondary_source.is_available() && self.secondary_source.fill_bytes(dest).is_ok() {
+            1
+        } else {
             self.tertiary_source.fill_bytes(dest)?;
-        }
+            2
+        };
+        self.active_source.store(source, Ordering::SeqCst);
 
         // Mix with entropy pool for defense in depth (use persistent HMAC-DRBG seeded from pool)
         {
@@ -718,6 +900,16 @@ impl SecureRNG {
         self.is_healthy.load(Ordering::SeqCst)
     }
 
+    /// Name of the entropy source that supplied the last fill (the preferred
+    /// one before any fill), e.g. "RDSEED", "/dev/random" or "getrandom"
+    pub fn entropy_source(&self) -> &str {
+        match self.active_source.load(Ordering::SeqCst) {
+            0 => self.primary_source.name(),
+            1 => self.secondary_source.name(),
+            _ => self.tertiary_source.name(),
+        }
+    }
+
     /// Calculate Shannon entropy of data (for verification)
     pub fn calculate_entropy(data: &[u8]) -> f64 {
         let mut counts = [0u64; 256];
@@ -770,6 +962,11 @@ impl ThreadSafeRNG {
         let rng = self.inner.lock().unwrap();
         rng.is_healthy()
     }
+
+    pub fn entropy_source(&self) -> String {
+        let rng = self.inner.lock().unwrap();
+        rng.entropy_source().to_string()
+    }
 }
 
 lazy_static::lazy_static! {
@@ -787,6 +984,11 @@ pub fn secure_random_bytes(dest: &mut [u8]) -> Result<()> {
     GLOBAL_RNG.fill_bytes(dest)
 }
 
+/// Entropy source behind the random patterns, for the wipe certificate
+pub fn secure_rng_source() -> String {
+    GLOBAL_RNG.entropy_source()
+}
+
 /// Verify randomness quality using NIST SP 800-22 tests (simplified)
 pub fn verify_randomness(data: &[u8]) -> Result<bool> {
     if data.len() < 1000 {
@@ -1,8 +1,8 @@
 #[cfg(test)]
 mod tests {
     use std::sync::{Arc, Mutex};
-    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-    use crate::crypto::secure_rng::{get_secure_rng, secure_random_bytes, verify_randomness, ContinuousTest, EntropyPool, EntropySource, HardwareRNG, HmacDrbg, JitterEntropy, RingSystemRNG, SecureRNG, ThreadSafeRNG, URandom};
+    use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+    use crate::crypto::secure_rng::{get_secure_rng, preferred_hardware_source, secure_random_bytes, secure_rng_source, verify_randomness, ContinuousTest, CpuRNG, DevRandom, EntropyPool, EntropySource, GetRandom, HardwareRNG, HmacDrbg, JitterEntropy, RingSystemRNG, SecureRNG, ThreadSafeRNG, URandom};
     type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
     /// Test that RNG can be initialized
@@ -346,6 +346,7 @@ mod tests {
             is_healthy: Arc::new(AtomicBool::new(true)),
             continuous_test: Arc::new(Mutex::new(ContinuousTest::new())),
             drbg: None,
+            active_source: Arc::new(AtomicUsize::new(0)),
         };
 
         // Initialize DRBG (this was missing!)
@@ -361,6 +362,51 @@ mod tests {
         Ok(())
     }
 
+    /// Test the hardware-first fallback chain and that the source in use is reported
+    #[test]
+    fn test_hardware_fallback_chain() -> Result<()> {
+        let cpu = CpuRNG::new();
+        println!("CPU RNG available: {} ({})", cpu.is_available(), cpu.name());
+        if cpu.is_available() {
+            let mut buffer = vec![0u8; 37];
+            cpu.fill_bytes(&mut buffer)?;
+            assert!(buffer.iter().any(|&b| b != 0));
+        } else {
+            assert!(cpu.fill_bytes(&mut [0u8; 8]).is_err());
+        }
+
+        let dev_random = DevRandom::new();
+        if dev_random.is_available() {
+            let mut buffer = vec![0u8; 32];
+            dev_random.fill_bytes(&mut buffer)?;
+        }
+
+        let mut buffer = vec![0u8; 32];
+        GetRandom.fill_bytes(&mut buffer)?;
+
+        let mut rng = SecureRNG::new()?;
+        rng.fill_bytes(&mut [0u8; 64])?;
+        let expected = preferred_hardware_source().map_or("getrandom".to_string(), |s| s.name().to_string());
+        assert_eq!(rng.entropy_source(), expected);
+        assert!(!secure_rng_source().is_empty());
+
+        Ok(())
+    }
+
+    /// Two consecutive fills must never repeat
+    #[test]
+    fn test_consecutive_fills_differ() -> Result<()> {
+        let mut rng = SecureRNG::new()?;
+        let mut first = vec![0u8; 4096];
+        let mut second = vec![0u8; 4096];
+        rng.fill_bytes(&mut first)?;
+        rng.fill_bytes(&mut second)?;
+
+        assert_ne!(first, second, "Consecutive fills should differ");
+
+        Ok(())
+    }
+
     /// NIST SP 800-22 randomness tests (simplified)
     #[test]
     fn test_nist_randomness() -> Result<()> {
@@ -473,6 +519,31 @@ mod statistical_tests {
         Ok(())
     }
 
+    /// Chi-square over a 1 MB fill, the size of a typical pass buffer
+    #[test]
+    fn test_chi_square_1mb() -> Result<()> {
+        let mut rng = SecureRNG::new()?;
+        let mut data = vec![0u8; 1024 * 1024];
+        rng.fill_bytes(&mut data)?;
+
+        let mut frequencies = [0u64; 256];
+        for &byte in &data {
+            frequencies[byte as usize] += 1;
+        }
+
+        let expected = data.len() as f64 / 256.0;
+        let chi_square: f64 = frequencies.iter()
+            .map(|&freq| (freq as f64 - expected).powi(2) / expected)
+            .sum();
+
+        // Degrees of freedom = 255, critical value at 99.9% confidence ≈ 330
+        println!("Chi-square statistic (1 MB): {:.2}", chi_square);
+        assert!(chi_square < 330.0,
+                "Chi-square too high, distribution may not be uniform");
+
+        Ok(())
+    }
+
     /// Autocorrelation test
     #[test]
     fn test_autocorrelation() -> Result<()> {
@@ -0,0 +1,202 @@
+// Capability report - everything detected about how a drive can be erased
+//
+// `DriveCapabilities` holds what detection found in the shape detection
+// fills it in. `Capabilities` is the same picture for people and front ends:
+// one field per erase-relevant feature, serializable for the UI to offer
+// only the methods a drive supports, and recorded in the certificate so the
+// report shows what the chosen method was picked from.
+
+use crate::{DriveInfo, DriveType, FreezeStatus, SEDType, SanitizeOption};
+use serde::{Deserialize, Serialize};
+
+/// Erase-relevant features of one drive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub drive_type: DriveType,
+    /// Discard (TRIM / UNMAP / Deallocate)
+    pub trim: bool,
+    /// Whether discarded blocks read back as zeros (RZAT); None when the
+    /// drive doesn't say
+    pub read_zero_after_trim: Option<bool>,
+    /// ATA SECURITY ERASE UNIT
+    pub secure_erase: bool,
+    /// ATA enhanced security erase, which also reaches reallocated sectors
+    pub enhanced_secure_erase: bool,
+    /// NVMe sanitize actions the controller supports
+    pub sanitize: Vec<SanitizeOption>,
+    /// Erase by destroying the media encryption key
+    pub crypto_erase: bool,
+    /// Self-encrypting drive standard (Opal, Enterprise, ...), if any
+    pub self_encrypting: Option<SEDType>,
+    /// Host Protected Area hiding sectors from the host
+    pub hpa_present: bool,
+    /// Device Configuration Overlay shrinking the reported capacity
+    pub dco_present: bool,
+    /// Zoned block device (host-managed or host-aware SMR)
+    pub zoned: bool,
+    /// Security-frozen, so ATA erase commands are refused until unfrozen
+    pub frozen: bool,
+    /// Behind a USB bridge that doesn't forward erase commands
+    pub usb_bridge: bool,
+}
+
+impl Capabilities {
+    /// The capability report for a detected drive
+    pub fn of(drive: &DriveInfo) -> Self {
+        let caps = &drive.capabilities;
+        Self {
+            drive_type: drive.drive_type.clone(),
+            trim: caps.trim_support,
+            read_zero_after_trim: caps.read_zero_after_trim,
+            secure_erase: caps.secure_erase,
+            enhanced_secure_erase: caps.enhanced_erase,
+            sanitize: caps.sanitize_options.clone(),
+            crypto_erase: caps.crypto_erase,
+            self_encrypting: caps.sed_type.clone().filter(|sed| *sed != SEDType::None),
+            hpa_present: caps.hpa_enabled,
+            dco_present: caps.dco_enabled,
+            zoned: caps.zoned || drive.drive_type == DriveType::SMR,
+            frozen: caps.is_frozen || matches!(caps.freeze_status, FreezeStatus::Frozen | FreezeStatus::FrozenByBIOS),
+            usb_bridge: caps.usb_bridge,
+        }
+    }
+
+    /// Whether the drive is a TCG Opal self-encrypting drive
+    pub fn opal(&self) -> bool {
+        matches!(self.self_encrypting, Some(SEDType::OPAL10 | SEDType::OPAL20))
+    }
+
+    /// Hardware erase methods the drive offers, strongest first; empty when
+    /// only overwriting is possible
+    pub fn hardware_erase_methods(&self) -> Vec<String> {
+        if self.usb_bridge {
+            return Vec::new();
+        }
+
+        let mut methods = Vec::new();
+        if let Some(sed) = &self.self_encrypting {
+            methods.push(format!("crypto erase ({:?})", sed));
+        } else if self.crypto_erase {
+            methods.push("crypto erase".to_string());
+        }
+        methods.extend(self.sanitize.iter().map(|action| format!("sanitize {:?}", action)));
+        if self.enhanced_secure_erase {
+            methods.push("enhanced secure erase".to_string());
+        }
+        if self.secure_erase {
+            methods.push("secure erase".to_string());
+        }
+        if self.trim {
+            methods.push("TRIM".to_string());
+        }
+        methods
+    }
+
+    /// One line for certificates and method explanations, e.g.
+    /// "secure erase, TRIM (reads zeros); HPA present"
+    pub fn summary(&self) -> String {
+        let mut methods = self.hardware_erase_methods();
+        if let Some(trim) = methods.iter_mut().find(|method| *method == "TRIM") {
+            match self.read_zero_after_trim {
+                Some(true) => trim.push_str(" (reads zeros)"),
+                Some(false) => trim.push_str(" (not zeroing)"),
+                None => {}
+            }
+        }
+        let mut summary = if methods.is_empty() { "overwrite only".to_string() } else { methods.join(", ") };
+
+        let notes: Vec<&str> = [
+            (self.hpa_present, "HPA present"),
+            (self.dco_present, "DCO present"),
+            (self.zoned, "zoned"),
+            (self.frozen, "frozen"),
+            (self.usb_bridge, "USB bridge"),
+        ]
+        .into_iter()
+        .filter_map(|(set, note)| set.then_some(note))
+        .collect();
+        if !notes.is_empty() {
+            summary.push_str("; ");
+            summary.push_str(&notes.join(", "));
+        }
+        summary
+    }
+
+    /// Multi-line listing of every capability, for the console
+    pub fn render(&self) -> String {
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+        let mut lines = vec![
+            format!("  TRIM: {}", match (self.trim, self.read_zero_after_trim) {
+                (false, _) => "no".to_string(),
+                (true, Some(true)) => "yes, reads zeros after TRIM (RZAT)".to_string(),
+                (true, Some(false)) => "yes, trimmed blocks are not guaranteed to read zeros".to_string(),
+                (true, None) => "yes".to_string(),
+            }),
+            format!("  Secure Erase: {}", yes_no(self.secure_erase)),
+            format!("  Enhanced Secure Erase: {}", yes_no(self.enhanced_secure_erase)),
+            format!("  Sanitize: {}", if self.sanitize.is_empty() {
+                "no".to_string()
+            } else {
+                self.sanitize.iter().map(|action| format!("{:?}", action)).collect::<Vec<_>>().join(", ")
+            }),
+            format!("  Crypto Erase: {}", yes_no(self.crypto_erase)),
+            format!("  Self-Encrypting: {}", match &self.self_encrypting {
+                Some(sed) if self.opal() => format!("yes, TCG Opal ({:?})", sed),
+                Some(sed) => format!("yes ({:?})", sed),
+                None => "no".to_string(),
+            }),
+            format!("  HPA: {}", if self.hpa_present { "⚠ present (hidden sectors)" } else { "none" }),
+            format!("  DCO: {}", if self.dco_present { "⚠ present (capacity reduced)" } else { "none" }),
+            format!("  Zoned: {}", yes_no(self.zoned)),
+            format!("  Frozen: {}", yes_no(self.frozen)),
+        ];
+        if self.usb_bridge {
+            lines.push("  ⚠ Behind a USB bridge (hardware erase unavailable, overwrite only)".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl std::fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::drive;
+    use crate::DriveCapabilities;
+
+    #[test]
+    fn test_capability_report() {
+        let ssd = Capabilities::of(&drive(DriveType::SSD, DriveCapabilities {
+            secure_erase: true,
+            enhanced_erase: true,
+            trim_support: true,
+            read_zero_after_trim: Some(true),
+            hpa_enabled: true,
+            sed_type: Some(SEDType::OPAL20),
+            crypto_erase: true,
+            ..Default::default()
+        }));
+        assert!(ssd.opal());
+        assert_eq!(ssd.summary(), "crypto erase (OPAL20), enhanced secure erase, secure erase, TRIM (reads zeros); HPA present");
+        assert!(ssd.render().contains("TRIM: yes, reads zeros after TRIM (RZAT)"));
+        assert!(ssd.render().contains("Self-Encrypting: yes, TCG Opal (OPAL20)"));
+
+        // The report round-trips for front ends
+        let json = serde_json::to_string(&ssd).unwrap();
+        assert_eq!(serde_json::from_str::<Capabilities>(&json).unwrap(), ssd);
+
+        let smr = Capabilities::of(&drive(DriveType::SMR, DriveCapabilities {
+            sed_type: Some(SEDType::None),
+            usb_bridge: true,
+            secure_erase: true,
+            ..Default::default()
+        }));
+        assert!(smr.zoned && smr.self_encrypting.is_none());
+        assert_eq!(smr.summary(), "overwrite only; zoned, USB bridge");
+    }
+}
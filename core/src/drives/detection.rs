@@ -1,9 +1,9 @@
 use crate::{
     DriveInfo, DriveType, EncryptionStatus, DriveCapabilities,
-    FreezeStatus, SEDType, SanitizeOption
+    FreezeStatus, SanitizeOption
 };
-use anyhow::Result;
-use std::process::Command;
+use anyhow::{Result, anyhow};
+use crate::commands::Command;
 use std::fs;
 use std::path::Path;
 
@@ -11,11 +11,25 @@ use std::path::Path;
 use super::freeze::FreezeMitigation;
 use super::operations::hpa_dco::HPADCOManager;
 use super::operations::sed::SEDManager;
+use super::operations::luks::LuksHeader;
 use super::operations::trim::TrimOperations;
 use super::operations::smart::SMARTMonitor;
 
 pub struct DriveDetector;
 
+/// Which block devices `DriveDetector::enumerate_drives` lists. The defaults
+/// leave out everything that is rarely a wipe target and easy to hit by mistake.
+#[derive(Debug, Clone, Default)]
+pub struct EnumerateOptions {
+    /// List the disk backing `/`, `/boot` or swap
+    pub include_system: bool,
+    /// List removable media (USB sticks, card readers), typically the boot
+    /// medium of a live environment
+    pub include_removable: bool,
+    /// List loop devices
+    pub include_loop: bool,
+}
+
 impl DriveDetector {
     /// Comprehensive drive detection with all capability checks
     pub fn detect_all_drives() -> Result<Vec<DriveInfo>> {
@@ -57,6 +71,355 @@ impl DriveDetector {
         Ok(drives)
     }
 
+    /// Every block device that could be wiped, identified with the lightweight
+    /// sysfs detection of `detect_drive`. Partitions, RAM/zram, device-mapper
+    /// and optical drives are never listed; system disks, removable media and
+    /// loop devices only when `options` asks for them.
+    pub fn enumerate_drives(options: &EnumerateOptions) -> Result<Vec<DriveInfo>> {
+        let mut drives = Vec::new();
+
+        for name in Self::candidate_devices(Path::new("/sys/block"), options)? {
+            let device_path = format!("/dev/{}", name);
+            if !Path::new(&device_path).exists() {
+                continue;
+            }
+
+            // When in doubt, treat the disk as a system disk
+            let is_system = Self::system_mounts_on_device(&device_path).map_or(true, |mounts| !mounts.is_empty());
+            if is_system && !options.include_system {
+                continue;
+            }
+
+            match Self::detect_drive(&device_path) {
+                Ok(info) => drives.push(info),
+                Err(e) => eprintln!("Warning: Failed to identify {}: {}", device_path, e),
+            }
+        }
+
+        Ok(drives)
+    }
+
+    /// Sorted names under `sys_block` that pass the device-class filters
+    fn candidate_devices(sys_block: &Path, options: &EnumerateOptions) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+
+        for entry in fs::read_dir(sys_block)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            let base = sys_block.join(&name);
+
+            let is_loop = name.starts_with("loop");
+            if (Self::should_skip_device(&name) && !is_loop) || (is_loop && !options.include_loop) {
+                continue;
+            }
+            // Partitions only show up here on some layouts, but never wipe one as a drive
+            if base.join("partition").exists() {
+                continue;
+            }
+            let removable = Self::read_sysfs_attr(base.join("removable").to_str().unwrap_or_default());
+            if removable.as_deref() == Some("1") && !options.include_removable {
+                continue;
+            }
+
+            names.push(name);
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    /// Whether `path` is a regular file (a disk image) rather than a device.
+    /// Images are wiped by overwriting alone, which lets the whole pipeline
+    /// run in CI without hardware.
+    pub fn is_image_file(path: &str) -> bool {
+        fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+    }
+
+    /// Identify a single drive from sysfs and the block layer.
+    ///
+    /// Size comes from the BLKGETSIZE64 ioctl, model/serial from
+    /// `/sys/block/<dev>/device/`, and media type from the queue attributes.
+    /// Fails if the device has no sysfs entry so callers can fall back.
+    pub fn detect_drive(device_path: &str) -> Result<DriveInfo> {
+        let dev_name = Self::block_device_name(device_path)?;
+        let sys_path = format!("/sys/block/{}", dev_name);
+
+        if !Path::new(&sys_path).exists() {
+            return Err(anyhow!("No sysfs entry for {}", device_path));
+        }
+
+        let size = Self::get_block_device_size(device_path).or_else(|_| {
+            Self::read_sysfs_attr(&format!("{}/size", sys_path))
+                .and_then(|s| s.parse::<u64>().ok())
+                .map(|sectors| sectors * 512)
+                .ok_or_else(|| anyhow!("Unable to determine size of {}", device_path))
+        })?;
+
+        let model = Self::read_sysfs_attr(&format!("{}/device/model", sys_path))
+            .or_else(|| Self::read_sysfs_attr(&format!("{}/device/name", sys_path)))
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let serial = Self::read_sysfs_attr(&format!("{}/device/serial", sys_path))
+            .or_else(|| Self::read_vpd_serial(&format!("{}/device/vpd_pg80", sys_path)))
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let rotational = Self::read_sysfs_attr(&format!("{}/queue/rotational", sys_path))
+            .map(|v| v == "1")
+            .unwrap_or(false);
+        let zoned = Self::read_sysfs_attr(&format!("{}/queue/zoned", sys_path))
+            .unwrap_or_else(|| "none".to_string());
+
+        let sas = Self::is_sas_transport(Path::new(&sys_path));
+
+        let drive_type = if Self::is_removable_flash(Path::new(&sys_path), rotational) {
+            DriveType::SDCard
+        } else {
+            Self::classify_drive_type(&dev_name, rotational, &zoned, sas)
+        };
+
+        let trim_support = Self::read_sysfs_attr(&format!("{}/queue/discard_max_bytes", sys_path))
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|max| max > 0)
+            .unwrap_or(false);
+
+        let mut capabilities = DriveCapabilities {
+            trim_support,
+            usb_bridge: Self::is_usb_bridge(Path::new(&sys_path)),
+            zoned: zoned == "host-managed" || zoned == "host-aware",
+            ..Default::default()
+        };
+        if let Some((logical, physical)) = Self::get_block_sizes(device_path)
+            .ok()
+            .or_else(|| Self::block_sizes_from_sysfs(Path::new(&sys_path)))
+        {
+            capabilities.logical_block_size = logical;
+            capabilities.physical_block_size = physical;
+        }
+
+        // A USB bridge answers for itself, so the drive's own erase and
+        // encryption capabilities can't be queried (or used) through it
+        let bridged = capabilities.usb_bridge;
+
+        // ATA IDENTIFY data (HDD/SSD/SMR are the ATA-addressable types)
+        if !bridged && matches!(drive_type, DriveType::HDD | DriveType::SSD | DriveType::SMR) {
+            capabilities.secure_erase = Self::check_secure_erase_support(device_path).unwrap_or(false);
+            capabilities.enhanced_erase = Self::check_enhanced_erase_support(device_path).unwrap_or(false);
+            if trim_support && drive_type == DriveType::SSD {
+                capabilities.read_zero_after_trim = TrimOperations::query_read_behavior(device_path).ok().map(|behavior| behavior.zeros);
+            }
+
+            if let Ok(freeze_status) = FreezeMitigation::get_freeze_status(device_path) {
+                capabilities.freeze_status = freeze_status;
+                capabilities.is_frozen = matches!(
+                    freeze_status,
+                    FreezeStatus::Frozen | FreezeStatus::FrozenByBIOS
+                );
+            }
+        }
+
+        if !bridged && drive_type == DriveType::NVMe {
+            capabilities.sanitize_options = Self::get_nvme_sanitize_options(device_path).unwrap_or_default();
+            capabilities.crypto_erase = capabilities.sanitize_options
+                .iter()
+                .any(|o| matches!(o, SanitizeOption::CryptoErase));
+        }
+
+        // Self-encrypting drives can be erased by regenerating their key
+        let mut encryption_status = EncryptionStatus::None;
+        if !bridged && matches!(drive_type, DriveType::HDD | DriveType::SSD | DriveType::SMR | DriveType::NVMe | DriveType::SAS) {
+            if let Ok(sed_info) = SEDManager::detect_sed(device_path) {
+                if sed_info.is_self_encrypting() {
+                    capabilities.sed_type = Some(sed_info.sed_type);
+                    capabilities.crypto_erase = true;
+                    encryption_status = EncryptionStatus::OPAL;
+                }
+            }
+        }
+        if matches!(encryption_status, EncryptionStatus::None) && LuksHeader::find(device_path).is_ok_and(|h| !h.is_empty()) {
+            encryption_status = EncryptionStatus::LUKS;
+        }
+
+        Ok(DriveInfo {
+            device_path: device_path.to_string(),
+            model,
+            serial,
+            size,
+            drive_type,
+            encryption_status,
+            capabilities,
+            health_status: None,
+            temperature_celsius: None,
+        })
+    }
+
+    /// World Wide Name of a drive (e.g. "naa.5002538e40a1b2c3" or
+    /// "eui.0025388b71b2c3d4"), which unlike the device path is stable
+    /// across reboots and hotplug. None when the drive doesn't report one.
+    pub fn read_wwn(device_path: &str) -> Option<String> {
+        let dev_name = Self::block_device_name(device_path).ok()?;
+        Self::wwn_from_sysfs(Path::new("/sys/block"), &dev_name)
+    }
+
+    /// NVMe exposes `wwid` on the namespace, SCSI/SATA on the device
+    fn wwn_from_sysfs(sys_block: &Path, dev_name: &str) -> Option<String> {
+        let base = sys_block.join(dev_name);
+        [base.join("wwid"), base.join("device/wwid")]
+            .iter()
+            .find_map(|path| Self::read_sysfs_attr(path.to_str()?))
+    }
+
+    /// Read the size of a block device in bytes via the BLKGETSIZE64 ioctl
+    #[cfg(target_os = "linux")]
+    pub fn get_block_device_size(device_path: &str) -> Result<u64> {
+        use std::os::unix::io::AsRawFd;
+
+        // _IOR(0x12, 114, size_t)
+        const BLKGETSIZE64: libc::c_ulong = 0x80081272;
+
+        let file = fs::File::open(device_path)?;
+        let mut size: u64 = 0;
+
+        let ret = unsafe { libc::ioctl(file.as_raw_fd(), BLKGETSIZE64 as _, &mut size) };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok(size)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn get_block_device_size(device_path: &str) -> Result<u64> {
+        Err(anyhow!("BLKGETSIZE64 not available for {}", device_path))
+    }
+
+    /// Logical and physical block sizes in bytes via the BLKSSZGET and
+    /// BLKPBSZGET ioctls: (512, 512) for legacy drives, (512, 4096) for
+    /// 512e and (4096, 4096) for 4Kn
+    #[cfg(target_os = "linux")]
+    pub fn get_block_sizes(device_path: &str) -> Result<(u32, u32)> {
+        use std::os::unix::io::AsRawFd;
+
+        // _IO(0x12, 104) and _IO(0x12, 123)
+        const BLKSSZGET: libc::c_ulong = 0x1268;
+        const BLKPBSZGET: libc::c_ulong = 0x127b;
+
+        let file = fs::File::open(device_path)?;
+        let mut logical: libc::c_int = 0;
+        let mut physical: libc::c_uint = 0;
+
+        let ret = unsafe { libc::ioctl(file.as_raw_fd(), BLKSSZGET as _, &mut logical) };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let ret = unsafe { libc::ioctl(file.as_raw_fd(), BLKPBSZGET as _, &mut physical) };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok((logical as u32, physical.max(logical as u32)))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn get_block_sizes(device_path: &str) -> Result<(u32, u32)> {
+        Err(anyhow!("BLKSSZGET not available for {}", device_path))
+    }
+
+    /// Block sizes from `queue/logical_block_size` and `queue/physical_block_size`
+    fn block_sizes_from_sysfs(sys_path: &Path) -> Option<(u32, u32)> {
+        let read = |name: &str| {
+            Self::read_sysfs_attr(sys_path.join("queue").join(name).to_str()?)?.parse::<u32>().ok()
+        };
+        let logical = read("logical_block_size")?;
+        Some((logical, read("physical_block_size").unwrap_or(logical).max(logical)))
+    }
+
+    /// Map a device path to its /sys/block entry name (e.g. /dev/sda -> sda)
+    fn block_device_name(device_path: &str) -> Result<String> {
+        Path::new(device_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.to_string())
+            .ok_or_else(|| anyhow!("Invalid device path: {}", device_path))
+    }
+
+    /// Decide the drive type from the device name and queue attributes
+    fn classify_drive_type(dev_name: &str, rotational: bool, zoned: &str, sas: bool) -> DriveType {
+        if dev_name.starts_with("nvme") {
+            DriveType::NVMe
+        } else if dev_name.starts_with("mmcblk") {
+            DriveType::EMMC
+        } else if zoned == "host-managed" || zoned == "host-aware" {
+            DriveType::SMR
+        } else if sas {
+            DriveType::SAS
+        } else if rotational {
+            DriveType::HDD
+        } else {
+            DriveType::SSD
+        }
+    }
+
+    /// Whether the SCSI device behind a /sys/block entry is a native SAS
+    /// drive: it sits on the SAS transport (a `sas_address` attribute or an
+    /// `end_device-*` in its path) and isn't a SATA drive translated by the
+    /// HBA, which exposes the ATA Information VPD page (89h)
+    fn is_sas_transport(sys_path: &Path) -> bool {
+        let device = sys_path.join("device");
+        let on_sas = device.join("sas_address").exists()
+            || fs::canonicalize(&device)
+                .map(|path| path.components().any(|c| c.as_os_str().to_string_lossy().starts_with("end_device-")))
+                .unwrap_or(false);
+
+        on_sas && !device.join("vpd_pg89").exists()
+    }
+
+    /// Whether the device behind a /sys/block entry hangs off a USB host
+    /// controller (a `usbN` bus in its device path), i.e. sits behind a
+    /// USB-SATA or USB-NVMe bridge
+    fn is_usb_bridge(sys_path: &Path) -> bool {
+        fs::canonicalize(sys_path.join("device"))
+            .map(|path| {
+                path.components().any(|c| {
+                    let name = c.as_os_str().to_string_lossy();
+                    name.strip_prefix("usb").is_some_and(|bus| !bus.is_empty() && bus.chars().all(|c| c.is_ascii_digit()))
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Whether a /sys/block entry is an SD card or USB flash drive: an MMC
+    /// device whose card type is "SD" (soldered eMMC reports "MMC"), or any
+    /// non-rotational device the kernel flags as removable, other than an
+    /// optical drive
+    fn is_removable_flash(sys_path: &Path, rotational: bool) -> bool {
+        let attr = |name: &str| Self::read_sysfs_attr(sys_path.join(name).to_str().unwrap_or_default());
+        let optical = sys_path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("sr"));
+
+        attr("device/type").as_deref() == Some("SD")
+            || (attr("removable").as_deref() == Some("1") && !rotational && !optical)
+    }
+
+    /// Read a trimmed sysfs attribute, ignoring empty values
+    fn read_sysfs_attr(path: &str) -> Option<String> {
+        fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Extract the unit serial number from a SCSI VPD page 0x80 dump
+    fn read_vpd_serial(path: &str) -> Option<String> {
+        let data = fs::read(path).ok()?;
+        if data.len() <= 4 {
+            return None;
+        }
+
+        let len = (data[3] as usize).min(data.len() - 4);
+        let serial = String::from_utf8_lossy(&data[4..4 + len]).trim().to_string();
+        if serial.is_empty() { None } else { Some(serial) }
+    }
+
     /// Check if device should be skipped
     fn should_skip_device(device_name: &str) -> bool {
         // Skip loop devices, ram disks, device mapper, etc.
@@ -266,7 +629,7 @@ impl DriveDetector {
     fn detect_encryption(device_path: &str) -> Result<EncryptionStatus> {
         // Check for OPAL (handled by SEDManager)
         if let Ok(sed_info) = SEDManager::detect_sed(device_path) {
-            if sed_info.sed_type != SEDType::None {
+            if sed_info.is_self_encrypting() {
                 return Ok(EncryptionStatus::OPAL);
             }
         }
@@ -444,6 +807,176 @@ impl DriveDetector {
         Ok(false)
     }
 
+    /// Mount points the running system depends on (`/`, `/boot`, swap) that are
+    /// backed by `device_path` or one of its partitions.
+    ///
+    /// Matching is done on device major/minor numbers, following partitions up to
+    /// their parent disk and device-mapper/md stacks down to their slaves.
+    #[cfg(target_os = "linux")]
+    pub fn system_mounts_on_device(device_path: &str) -> Result<Vec<String>> {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let target_meta = fs::metadata(device_path)?;
+        if !target_meta.file_type().is_block_device() {
+            return Ok(Vec::new());
+        }
+        let target = target_meta.rdev();
+
+        let mut candidates: Vec<(String, u64)> = Vec::new();
+
+        let mounts = fs::read_to_string("/proc/mounts")?;
+        for line in mounts.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 2 {
+                continue;
+            }
+
+            let (source, mount_point) = (parts[0], parts[1]);
+            if mount_point != "/" && mount_point != "/boot" && !mount_point.starts_with("/boot/") {
+                continue;
+            }
+
+            // st_dev of the mount point, plus the mount source for filesystems
+            // (btrfs, overlay) that report an anonymous device number
+            if let Ok(meta) = fs::metadata(mount_point) {
+                candidates.push((mount_point.to_string(), meta.dev()));
+            }
+            if let Ok(meta) = fs::metadata(source) {
+                if meta.file_type().is_block_device() {
+                    candidates.push((mount_point.to_string(), meta.rdev()));
+                }
+            }
+        }
+
+        if let Ok(swaps) = fs::read_to_string("/proc/swaps") {
+            for line in swaps.lines().skip(1) {
+                let Some(name) = line.split_whitespace().next() else { continue };
+                if let Ok(meta) = fs::metadata(name) {
+                    let dev = if meta.file_type().is_block_device() { meta.rdev() } else { meta.dev() };
+                    candidates.push((format!("swap ({})", name), dev));
+                }
+            }
+        }
+
+        let mut hits: Vec<String> = Vec::new();
+        for (label, dev) in candidates {
+            if Self::backing_devices(dev).contains(&target) && !hits.contains(&label) {
+                hits.push(label);
+            }
+        }
+
+        Ok(hits)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn system_mounts_on_device(_device_path: &str) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// All filesystems currently mounted from `device_path` or any of its
+    /// partitions, as (source, mount point) pairs from /proc/mounts
+    #[cfg(target_os = "linux")]
+    pub fn mounts_on_device(device_path: &str) -> Result<Vec<(String, String)>> {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let target_meta = fs::metadata(device_path)?;
+        if !target_meta.file_type().is_block_device() {
+            return Ok(Vec::new());
+        }
+        let target = target_meta.rdev();
+
+        let mut mounted = Vec::new();
+        let mounts = fs::read_to_string("/proc/mounts")?;
+
+        for line in mounts.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 2 || !parts[0].starts_with('/') {
+                continue;
+            }
+
+            let Ok(meta) = fs::metadata(parts[0]) else { continue };
+            if !meta.file_type().is_block_device() {
+                continue;
+            }
+
+            if Self::backing_devices(meta.rdev()).contains(&target) {
+                mounted.push((parts[0].to_string(), Self::unescape_mount_path(parts[1])));
+            }
+        }
+
+        Ok(mounted)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn mounts_on_device(_device_path: &str) -> Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+
+    /// Decode the octal escapes (`\040` for space, etc.) used in /proc/mounts
+    fn unescape_mount_path(path: &str) -> String {
+        let bytes = path.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if bytes[i] == b'\\' && i + 3 < bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+                let code = std::str::from_utf8(&bytes[i + 1..i + 4]).ok()
+                    .and_then(|s| u8::from_str_radix(s, 8).ok());
+                if let Some(code) = code {
+                    out.push(code);
+                    i += 4;
+                    continue;
+                }
+            }
+            out.push(bytes[i]);
+            i += 1;
+        }
+
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// Every device number `dev` is built on: itself, its parent disk if it is a
+    /// partition, and recursively the slaves of any dm/md device
+    #[cfg(target_os = "linux")]
+    fn backing_devices(dev: u64) -> Vec<u64> {
+        let mut result = Vec::new();
+        let mut pending = vec![dev];
+
+        while let Some(dev) = pending.pop() {
+            if result.contains(&dev) {
+                continue;
+            }
+            result.push(dev);
+
+            let sys_path = format!("/sys/dev/block/{}:{}", libc::major(dev), libc::minor(dev));
+            let Ok(sys_path) = fs::canonicalize(sys_path) else { continue };
+
+            if sys_path.join("partition").exists() {
+                if let Some(parent) = sys_path.parent().and_then(|p| Self::read_dev_number(&p.join("dev"))) {
+                    pending.push(parent);
+                }
+            }
+
+            if let Ok(slaves) = fs::read_dir(sys_path.join("slaves")) {
+                for slave in slaves.flatten() {
+                    if let Some(slave_dev) = Self::read_dev_number(&slave.path().join("dev")) {
+                        pending.push(slave_dev);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Parse a sysfs `dev` attribute ("8:0") into a device number
+    #[cfg(target_os = "linux")]
+    fn read_dev_number(path: &Path) -> Option<u64> {
+        let content = fs::read_to_string(path).ok()?;
+        let (major, minor) = content.trim().split_once(':')?;
+        Some(libc::makedev(major.parse().ok()?, minor.parse().ok()?))
+    }
+
     /// Check if drive is currently mounted
     pub fn is_mounted(device_path: &str) -> Result<bool> {
         let mounts = fs::read_to_string("/proc/mounts")?;
@@ -457,3 +990,169 @@ impl DriveDetector {
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_drive_type() {
+        assert_eq!(DriveDetector::classify_drive_type("nvme0n1", false, "none", false), DriveType::NVMe);
+        assert_eq!(DriveDetector::classify_drive_type("mmcblk0", false, "none", false), DriveType::EMMC);
+        assert_eq!(DriveDetector::classify_drive_type("sdb", true, "host-managed", true), DriveType::SMR);
+        assert_eq!(DriveDetector::classify_drive_type("sdc", true, "none", false), DriveType::HDD);
+        assert_eq!(DriveDetector::classify_drive_type("sdd", false, "none", false), DriveType::SSD);
+        assert_eq!(DriveDetector::classify_drive_type("sde", true, "none", true), DriveType::SAS);
+    }
+
+    #[test]
+    fn test_sas_transport_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let sys = dir.path();
+
+        // Native SAS: the HBA publishes the drive's SAS address
+        std::fs::create_dir_all(sys.join("sda/device")).unwrap();
+        std::fs::write(sys.join("sda/device/sas_address"), "0x5000c500a1b2c3d5\n").unwrap();
+        assert!(DriveDetector::is_sas_transport(&sys.join("sda")));
+
+        // SATA behind the same HBA also answers the ATA Information VPD page
+        std::fs::write(sys.join("sda/device/vpd_pg89"), [0u8; 4]).unwrap();
+        assert!(!DriveDetector::is_sas_transport(&sys.join("sda")));
+
+        let target = sys.join("host0/port-0:0/end_device-0:0/target0:0:0/0:0:0:0");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::create_dir_all(sys.join("sdb")).unwrap();
+        std::os::unix::fs::symlink(&target, sys.join("sdb/device")).unwrap();
+        assert!(DriveDetector::is_sas_transport(&sys.join("sdb")));
+
+        std::fs::create_dir_all(sys.join("sdc/device")).unwrap();
+        assert!(!DriveDetector::is_sas_transport(&sys.join("sdc")));
+    }
+
+    #[test]
+    fn test_usb_bridge_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let sys = dir.path();
+
+        let usb = sys.join("pci0000:00/0000:00:14.0/usb2/2-1/2-1:1.0/host6/target6:0:0/6:0:0:0");
+        std::fs::create_dir_all(&usb).unwrap();
+        std::fs::create_dir_all(sys.join("sdb")).unwrap();
+        std::os::unix::fs::symlink(&usb, sys.join("sdb/device")).unwrap();
+        assert!(DriveDetector::is_usb_bridge(&sys.join("sdb")));
+
+        // "usb" elsewhere in a name isn't a USB bus
+        let ata = sys.join("pci0000:00/0000:00:17.0/ata1/host0/usbguard/0:0:0:0");
+        std::fs::create_dir_all(&ata).unwrap();
+        std::fs::create_dir_all(sys.join("sda")).unwrap();
+        std::os::unix::fs::symlink(&ata, sys.join("sda/device")).unwrap();
+        assert!(!DriveDetector::is_usb_bridge(&sys.join("sda")));
+    }
+
+    #[test]
+    fn test_removable_flash_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let sys = dir.path();
+        let entry = |name: &str, files: &[(&str, &str)]| {
+            std::fs::create_dir_all(sys.join(name).join("device")).unwrap();
+            for (file, value) in files {
+                std::fs::write(sys.join(name).join(file), format!("{}\n", value)).unwrap();
+            }
+            sys.join(name)
+        };
+
+        // SD slots often don't set removable; the card type gives them away
+        assert!(DriveDetector::is_removable_flash(&entry("mmcblk1", &[("device/type", "SD"), ("removable", "0")]), false));
+        assert!(!DriveDetector::is_removable_flash(&entry("mmcblk0", &[("device/type", "MMC"), ("removable", "0")]), false));
+        assert!(DriveDetector::is_removable_flash(&entry("sdb", &[("removable", "1")]), false));
+        assert!(!DriveDetector::is_removable_flash(&entry("sdc", &[("removable", "0")]), false));
+        assert!(!DriveDetector::is_removable_flash(&entry("sr0", &[("removable", "1")]), false));
+        // A removable spinning disk is still magnetic media
+        assert!(!DriveDetector::is_removable_flash(&entry("sdd", &[("removable", "1")]), true));
+    }
+
+    #[test]
+    fn test_wwn_from_sysfs() {
+        let sys_block = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(sys_block.path().join("sda/device")).unwrap();
+        std::fs::write(sys_block.path().join("sda/device/wwid"), "naa.5002538e40a1b2c3\n").unwrap();
+        std::fs::create_dir_all(sys_block.path().join("nvme0n1")).unwrap();
+        std::fs::write(sys_block.path().join("nvme0n1/wwid"), "eui.0025388b71b2c3d4\n").unwrap();
+        std::fs::create_dir_all(sys_block.path().join("sdb/device")).unwrap();
+
+        let wwn = |dev| DriveDetector::wwn_from_sysfs(sys_block.path(), dev);
+        assert_eq!(wwn("sda").as_deref(), Some("naa.5002538e40a1b2c3"));
+        assert_eq!(wwn("nvme0n1").as_deref(), Some("eui.0025388b71b2c3d4"));
+        assert_eq!(wwn("sdb"), None);
+    }
+
+    #[test]
+    fn test_block_sizes_from_sysfs() {
+        let sys_block = tempfile::TempDir::new().unwrap();
+        for (name, logical, physical) in [("sda", "512", "4096"), ("sdb", "4096", "4096")] {
+            std::fs::create_dir_all(sys_block.path().join(name).join("queue")).unwrap();
+            std::fs::write(sys_block.path().join(name).join("queue/logical_block_size"), logical).unwrap();
+            std::fs::write(sys_block.path().join(name).join("queue/physical_block_size"), physical).unwrap();
+        }
+
+        let sizes = |dev: &str| DriveDetector::block_sizes_from_sysfs(&sys_block.path().join(dev));
+        assert_eq!(sizes("sda"), Some((512, 4096)));
+        assert_eq!(sizes("sdb"), Some((4096, 4096)));
+        assert_eq!(sizes("sdc"), None);
+    }
+
+    #[test]
+    fn test_candidate_devices_filters() {
+        let sys_block = tempfile::TempDir::new().unwrap();
+        for name in ["sda", "sdb", "sda1", "loop0", "dm-0", "zram0", "nvme0n1"] {
+            std::fs::create_dir_all(sys_block.path().join(name)).unwrap();
+            std::fs::write(sys_block.path().join(name).join("removable"), "0\n").unwrap();
+        }
+        std::fs::write(sys_block.path().join("sdb/removable"), "1\n").unwrap();
+        std::fs::write(sys_block.path().join("sda1/partition"), "1\n").unwrap();
+
+        let names = |options| DriveDetector::candidate_devices(sys_block.path(), &options).unwrap();
+        assert_eq!(names(EnumerateOptions::default()), ["nvme0n1", "sda"]);
+        assert_eq!(
+            names(EnumerateOptions { include_removable: true, include_loop: true, ..Default::default() }),
+            ["loop0", "nvme0n1", "sda", "sdb"]
+        );
+    }
+
+    #[test]
+    fn test_block_device_name() {
+        assert_eq!(DriveDetector::block_device_name("/dev/sda").unwrap(), "sda");
+        assert_eq!(DriveDetector::block_device_name("/dev/nvme0n1").unwrap(), "nvme0n1");
+    }
+
+    #[test]
+    fn test_system_mounts_ignore_regular_files() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        let mounts = DriveDetector::system_mounts_on_device(temp.path().to_str().unwrap()).unwrap();
+        assert!(mounts.is_empty());
+    }
+
+    #[test]
+    fn test_unescape_mount_path() {
+        assert_eq!(DriveDetector::unescape_mount_path("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(DriveDetector::unescape_mount_path("/mnt/plain"), "/mnt/plain");
+    }
+
+    #[test]
+    fn test_mounts_ignore_regular_files() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        let mounts = DriveDetector::mounts_on_device(temp.path().to_str().unwrap()).unwrap();
+        assert!(mounts.is_empty());
+    }
+
+    #[test]
+    fn test_read_dev_number() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), "8:16\n").unwrap();
+        assert_eq!(DriveDetector::read_dev_number(temp.path()), Some(libc::makedev(8, 16)));
+    }
+
+    #[test]
+    fn test_detect_drive_without_sysfs_entry() {
+        assert!(DriveDetector::detect_drive("/dev/does-not-exist-xyz").is_err());
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::{DriveError, DriveResult, FreezeStatus};
 use anyhow::Result;
-use std::process::Command;
+use crate::commands::Command;
 use std::thread;
 use std::time::{Duration, Instant};
 use std::fs;
@@ -1,6 +1,6 @@
 use crate::{DriveError, DriveResult, FreezeStatus};
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::Command;
 use std::thread;
 use std::time::Duration;
 use std::fs;
@@ -1,7 +1,7 @@
 // Detect the reason why a drive is frozen
 
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::Command;
 use std::fs;
 use std::path::Path;
 
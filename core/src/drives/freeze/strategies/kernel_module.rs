@@ -3,7 +3,7 @@
 use super::{UnfreezeStrategy, StrategyResult};
 use crate::drives::freeze::detection::FreezeReason;
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use std::path::Path;
 use std::thread;
 use std::time::Duration;
@@ -46,8 +46,7 @@ impl KernelModule {
             .output()?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("Failed to load module: {}", stderr));
+            return Err(anyhow!("Failed to load module: {}", command_failure(&output)));
         }
 
         // Wait for module to complete scan
@@ -138,8 +137,7 @@ impl KernelModule {
             .output()?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("Module build failed: {}", stderr));
+            return Err(anyhow!("Module build failed: {}", command_failure(&output)));
         }
 
         // Copy module to standard location
@@ -169,7 +167,13 @@ impl UnfreezeStrategy for KernelModule {
     }
 
     fn is_available(&self) -> bool {
+        // Loadable kernel modules are a Linux facility
+        if !cfg!(target_os = "linux") {
+            return false;
+        }
+
         // Check if we can load kernel modules (root required)
+        #[cfg(unix)]
         if unsafe { libc::geteuid() } != 0 {
             return false;
         }
@@ -3,7 +3,7 @@
 use super::{UnfreezeStrategy, StrategyResult};
 use crate::drives::freeze::detection::FreezeReason;
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use std::fs;
 use std::thread;
 use std::time::Duration;
@@ -219,8 +219,7 @@ impl AcpiSleep {
             println!("      ✅ System successfully woke from S3 sleep");
             Ok(())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("rtcwake failed: {}", stderr))
+            Err(anyhow!("rtcwake failed: {}", command_failure(&output)))
         }
     }
 
@@ -403,8 +402,7 @@ impl IpmiPower {
             .map_err(|e| anyhow!("ipmitool not found: {}", e))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("IPMI not available: {}", stderr));
+            return Err(anyhow!("IPMI not available: {}", command_failure(&output)));
         }
 
         Ok(())
@@ -420,7 +418,7 @@ impl IpmiPower {
             let status = String::from_utf8_lossy(&output.stdout);
             Ok(status.trim().to_string())
         } else {
-            Err(anyhow!("Failed to get power status"))
+            Err(anyhow!("Failed to get power status ({})", command_failure(&output)))
         }
     }
 
@@ -436,8 +434,7 @@ impl IpmiPower {
             println!("      ✅ Warm reset initiated");
             Ok(())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow!("Warm reset failed: {}", stderr))
+            Err(anyhow!("Warm reset failed: {}", command_failure(&output)))
         }
     }
 
@@ -452,7 +449,7 @@ impl IpmiPower {
             .output()?;
 
         if !off_output.status.success() {
-            return Err(anyhow!("Power off failed"));
+            return Err(anyhow!("Power off failed ({})", command_failure(&off_output)));
         }
 
         // Wait for shutdown
@@ -468,7 +465,7 @@ impl IpmiPower {
             println!("      ✅ Cold power cycle initiated");
             Ok(())
         } else {
-            Err(anyhow!("Power on failed"))
+            Err(anyhow!("Power on failed ({})", command_failure(&on_output)))
         }
     }
 
@@ -481,7 +478,7 @@ impl IpmiPower {
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
-            Err(anyhow!("Failed to get chassis status"))
+            Err(anyhow!("Failed to get chassis status ({})", command_failure(&output)))
         }
     }
 
@@ -3,7 +3,7 @@
 use super::{UnfreezeStrategy, StrategyResult};
 use crate::drives::freeze::detection::FreezeReason;
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::Command;
 use std::thread;
 use std::time::Duration;
 
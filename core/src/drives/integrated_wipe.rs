@@ -4,55 +4,104 @@
 // engine with advanced drive types (SMR, Optane, Hybrid, eMMC, RAID, NVMe).
 
 use anyhow::Result;
+use crate::{CancellationToken, DriveError};
+use crate::algorithms::PatternSpec;
 use crate::io::{OptimizedIO, IOConfig, IOHandle};
 use crate::crypto::secure_rng::secure_random_bytes;
 use crate::ui::progress::ProgressBar;
-use super::types::smr::SMRDrive;
+use super::types::smr::{SMRDrive, ZoneCondition, ZoneWipeSummary};
 use super::types::optane::OptaneDrive;
 use super::types::hybrid::HybridDrive;
 use super::types::emmc::EMMCDevice;
 use super::types::raid::RAIDArray;
-use super::types::nvme::advanced::{NVMeAdvanced, NVMeNamespace, NamespaceType};
+use super::types::nvme::advanced::{NVMeAdvanced, NVMeNamespace, NamespaceFormat, NamespaceType, ZNSZoneState};
 
 // ==================== SMR DRIVE INTEGRATION ====================
 
-/// Wipe an SMR drive using OptimizedIO with proper zone handling
+/// Wipe an SMR drive using OptimizedIO with proper zone handling, running
+/// every pass of `algorithm` and showing progress across all zones and
+/// passes. Returns how many zones of each type were wiped.
 pub fn wipe_smr_drive_integrated(
     smr_drive: &SMRDrive,
     algorithm: WipeAlgorithm,
-) -> Result<()> {
+) -> Result<ZoneWipeSummary> {
     println!("🔄 Starting SMR-aware integrated wipe");
     println!("   Drive: {}", smr_drive.device_path);
-    println!("   Algorithm: {:?}", algorithm);
+    println!("   Algorithm: {}", algorithm);
     println!("   Zone Model: {:?}", smr_drive.zone_model);
 
+    // Conventional zones take ordinary writes at full queue depth
+    let mut conventional_io = OptimizedIO::open(&smr_drive.device_path, IOConfig::hdd_optimized())?;
+
     // Configure I/O for sequential writes (optimal for SMR)
     let mut io_config = IOConfig::hdd_optimized();
     io_config.queue_depth = 2; // Lower queue depth for SMR sequential writes
 
     let mut io_handle = OptimizedIO::open(&smr_drive.device_path, io_config)?;
 
-    // Use SMR's built-in wipe function with OptimizedIO callbacks
-    smr_drive.wipe_smr_drive(|offset, size| {
-        write_pattern_to_zone(&mut io_handle, offset, size, &algorithm)
-    })?;
+    let passes = algorithm.passes();
+    let per_pass: u64 = smr_drive.zones.iter()
+        .filter(|zone| !matches!(zone.zone_condition, ZoneCondition::Offline | ZoneCondition::ReadOnly))
+        .map(|zone| zone.zone_size)
+        .sum();
+    let total = per_pass * passes.len() as u64;
+    let mut bytes_written = 0u64;
+    let mut bar = ProgressBar::new(48);
+
+    let mut summary = ZoneWipeSummary::default();
+    let mut refreshed: Option<SMRDrive> = None;
+    for (pass, pattern) in passes.iter().enumerate() {
+        if passes.len() > 1 {
+            println!("\n   Pass {}/{}: {}", pass + 1, passes.len(), pattern.description());
+        }
+
+        // The previous pass left the sequential zones full; re-read their
+        // write pointers so each one is reset before being rewritten
+        if pass > 0 {
+            refreshed = Some(SMRDrive::get_zone_configuration(&smr_drive.device_path)?);
+        }
+        let drive = refreshed.as_ref().unwrap_or(smr_drive);
+
+        // Use SMR's built-in wipe function with OptimizedIO callbacks
+        summary = drive.wipe_smr_drive(|offset, size, sequential| {
+            let io = if sequential { &mut io_handle } else { &mut conventional_io };
+            let zone_start = bytes_written;
+            write_pattern_to_zone(io, offset, size, pattern, |written| {
+                // Redraw per 64MB of a long run, and at the end of every zone
+                if written == size || written % (64 * 1024 * 1024) == 0 {
+                    let done = zone_start + written;
+                    bar.render(done as f64 / total.max(1) as f64 * 100.0, Some(done), Some(total));
+                }
+            })?;
+            bytes_written += size;
+            Ok(())
+        })?;
+    }
 
     // Validate
     smr_drive.validate_smr_wipe()?;
 
     // Print performance
+    OptimizedIO::print_performance_report(&conventional_io, None);
     OptimizedIO::print_performance_report(&io_handle, None);
 
-    println!("✅ SMR wipe completed successfully");
-    Ok(())
+    println!(
+        "✅ SMR wipe completed successfully ({} conventional, {} sequential zones{})",
+        summary.conventional,
+        summary.sequential,
+        if summary.offline > 0 { format!(", {} offline skipped", summary.offline) } else { String::new() }
+    );
+    Ok(summary)
 }
 
-/// Write pattern to a specific zone using OptimizedIO
+/// Write one pass's pattern to a specific zone using OptimizedIO, in order
+/// from `offset`, reporting the bytes written so far after every buffer
 fn write_pattern_to_zone(
     io_handle: &mut IOHandle,
     offset: u64,
     size: u64,
-    algorithm: &WipeAlgorithm,
+    pattern: &PatternSpec,
+    mut on_progress: impl FnMut(u64),
 ) -> Result<()> {
     let mut bytes_written = 0u64;
     let buffer_size = io_handle.acquire_buffer()?.as_slice().len() as u64;
@@ -61,21 +110,8 @@ fn write_pattern_to_zone(
         let write_size = (size - bytes_written).min(buffer_size);
         let mut buffer = io_handle.acquire_buffer()?;
 
-        // Fill buffer based on algorithm
-        match algorithm {
-            WipeAlgorithm::Zeros => {
-                buffer.as_mut_slice().fill(0x00);
-            }
-            WipeAlgorithm::Ones => {
-                buffer.as_mut_slice().fill(0xFF);
-            }
-            WipeAlgorithm::Random => {
-                secure_random_bytes(buffer.as_mut_slice())?;
-            }
-            WipeAlgorithm::Pattern(byte) => {
-                buffer.as_mut_slice().fill(*byte);
-            }
-        }
+        // Sequences are phased by device offset, as the pass engine writes them
+        pattern.fill(&mut buffer.as_mut_slice()[..write_size as usize], offset + bytes_written)?;
 
         // Write to zone
         let written = io_handle.write_at(
@@ -84,6 +120,7 @@ fn write_pattern_to_zone(
         )?;
 
         bytes_written += written as u64;
+        on_progress(bytes_written);
     }
 
     io_handle.sync()?;
@@ -249,27 +286,39 @@ fn wipe_with_random_progress(
 
 // ==================== eMMC / UFS INTEGRATION ====================
 
-/// Wipe eMMC/UFS embedded storage
+/// Erase an eMMC user area. With `use_hardware_erase`, runs Secure Erase
+/// (CMD38) and then discard + SANITIZE, returning the command that worked;
+/// when the device supports neither, fails with `DriveError::Unsupported` so
+/// the caller can overwrite instead. Otherwise overwrites in software.
 pub fn wipe_emmc_drive_integrated(
     emmc_drive: &EMMCDevice,
     use_hardware_erase: bool,
-) -> Result<()> {
+    cancel: &CancellationToken,
+) -> Result<&'static str> {
     println!("🔄 Starting eMMC/UFS integrated wipe");
     println!("   Device: {}", emmc_drive.device_path);
 
     if use_hardware_erase {
-        // Try hardware erase
         println!("   Attempting hardware erase...");
 
-        // Try secure erase - emmc_drive.secure_erase() if available
-        // For now, fall back to software
-        println!("   Hardware erase not yet fully implemented, using software");
-        wipe_emmc_software(emmc_drive)?;
-    } else {
-        wipe_emmc_software(emmc_drive)?;
+        if emmc_drive.secure_erase(cancel).is_ok() {
+            return Ok("eMMC secure erase (CMD38)");
+        }
+
+        // SANITIZE only purges unmapped blocks, so discard everything first
+        emmc_drive.trim()?;
+        if emmc_drive.sanitize(cancel).is_ok() {
+            return Ok("eMMC discard + sanitize");
+        }
+
+        return Err(DriveError::Unsupported(format!(
+            "{} rejected secure erase and sanitize",
+            emmc_drive.device_path
+        )).into());
     }
 
-    Ok(())
+    wipe_emmc_software(emmc_drive)?;
+    Ok("eMMC software overwrite")
 }
 
 fn wipe_emmc_software(emmc_drive: &EMMCDevice) -> Result<()> {
@@ -351,100 +400,120 @@ pub fn wipe_raid_array_integrated(
 
 // ==================== NVME ADVANCED INTEGRATION ====================
 
-/// Wipe NVMe drive with advanced features (multiple namespaces, ZNS, etc.)
+/// Wipe NVMe drive with advanced features (multiple namespaces, ZNS, etc.).
+/// Returns the namespaces that were overwritten in software rather than
+/// formatted (all of them without `use_format`).
 pub fn wipe_nvme_advanced_integrated(
     nvme_drive: &NVMeAdvanced,
     use_format: bool,
-) -> Result<()> {
+    target_nsids: Option<&[u32]>,
+    cancel: &CancellationToken,
+) -> Result<Vec<u32>> {
+    let namespaces = nvme_drive.select_namespaces(target_nsids)?;
+
     println!("🔄 Starting Advanced NVMe integrated wipe");
     println!("   Controller: {}", nvme_drive.controller_path);
     println!("   Model: {}", nvme_drive.model);
-    println!("   Namespaces: {}", nvme_drive.namespaces.len());
+    println!("   Namespaces: {} of {}", namespaces.len(), nvme_drive.namespaces.len());
     println!("   ZNS Support: {}", nvme_drive.zns_support);
 
-    if use_format {
-        // Use NVMe Format command (fastest)
-        println!("   Using NVMe Format command (hardware erase)");
+    let io_config = IOConfig::nvme_optimized();
+    let mut overwritten = Vec::new();
 
-        for namespace in &nvme_drive.namespaces {
-            if namespace.is_active {
-                println!("      Formatting namespace {}...", namespace.nsid);
-                format_nvme_namespace(&nvme_drive.controller_path, namespace.nsid)?;
-            }
+    for namespace in &namespaces {
+        if !namespace.is_active {
+            println!("   Skipping inactive namespace {}", namespace.nsid);
+            continue;
         }
 
-        println!("✅ NVMe format completed successfully");
-    } else {
-        // Software wipe with OptimizedIO
-        println!("   Using software overwrite");
-
-        let io_config = IOConfig::nvme_optimized();
-
-        for namespace in &nvme_drive.namespaces {
-            if !namespace.is_active {
-                println!("   Skipping inactive namespace {}", namespace.nsid);
-                continue;
+        if use_format {
+            // Use NVMe Format command (fastest), overwriting if the controller refuses it
+            println!("      Formatting namespace {}...", namespace.nsid);
+            match format_nvme_namespace(&nvme_drive.controller_path, namespace.nsid, namespace.format.as_ref(), cancel) {
+                Ok(()) => continue,
+                Err(e) => println!("      ⚠️  {} - falling back to software overwrite", e),
             }
+        }
 
-            println!("\n   Wiping namespace {}:", namespace.nsid);
-            println!("      Path: {}", namespace.device_path);
-            println!("      Type: {:?}", namespace.namespace_type);
-            println!("      Size: {} GB", namespace.size / (1024 * 1024 * 1024));
-
-            let mut io_handle = OptimizedIO::open(&namespace.device_path, io_config.clone())?;
-
-            match namespace.namespace_type {
-                NamespaceType::Block => {
-                    // Standard block namespace - 3 pass wipe
-                    println!("      Standard block namespace - 3 pass wipe");
-                    wipe_namespace_multipass(&mut io_handle, namespace.size)?;
-                }
+        overwrite_nvme_namespace(nvme_drive, namespace, &io_config)?;
+        overwritten.push(namespace.nsid);
+    }
 
-                NamespaceType::ZonedNamespace => {
-                    // ZNS namespace - zone-aware wipe
-                    println!("      Zoned Namespace - zone-aware wipe");
-                    wipe_zns_namespace(&mut io_handle, namespace)?;
-                }
+    println!("\n✅ Advanced NVMe wipe completed successfully");
+    Ok(overwritten)
+}
 
-                NamespaceType::KeyValue => {
-                    // Key-Value namespace - overwrite all keys
-                    println!("      Key-Value namespace - overwrite");
-                    wipe_kv_namespace(&mut io_handle, namespace.size)?;
-                }
+/// Software overwrite of one namespace with OptimizedIO, zone by zone on
+/// zoned namespaces
+fn overwrite_nvme_namespace(nvme_drive: &NVMeAdvanced, namespace: &NVMeNamespace, io_config: &IOConfig) -> Result<()> {
+    println!("\n   Wiping namespace {}:", namespace.nsid);
+    println!("      Path: {}", namespace.device_path);
+    println!("      Type: {:?}", namespace.namespace_type);
+    println!("      Size: {} GB", namespace.size / (1024 * 1024 * 1024));
+
+    let mut io_handle = OptimizedIO::open(&namespace.device_path, io_config.clone())?;
+
+    match namespace.namespace_type {
+        NamespaceType::Block => {
+            // Standard block namespace - 3 pass wipe
+            println!("      Standard block namespace - 3 pass wipe");
+            wipe_namespace_multipass(&mut io_handle, namespace.size)?;
+        }
 
-                NamespaceType::Computational => {
-                    // Computational storage - basic overwrite
-                    println!("      Computational storage - basic overwrite");
-                    wipe_namespace_multipass(&mut io_handle, namespace.size)?;
-                }
-            }
+        NamespaceType::ZonedNamespace => {
+            // ZNS namespace - zone-aware wipe
+            println!("      Zoned Namespace - zone-aware wipe");
+            wipe_zns_namespace(nvme_drive, &mut io_handle, namespace)?;
+        }
 
-            OptimizedIO::print_performance_report(&io_handle, None);
-            println!("      ✅ Namespace {} completed", namespace.nsid);
+        NamespaceType::KeyValue => {
+            // Key-Value namespace - overwrite all keys
+            println!("      Key-Value namespace - overwrite");
+            wipe_kv_namespace(&mut io_handle, namespace.size)?;
         }
 
-        println!("\n✅ NVMe software wipe completed successfully");
+        NamespaceType::Computational => {
+            // Computational storage - basic overwrite
+            println!("      Computational storage - basic overwrite");
+            wipe_namespace_multipass(&mut io_handle, namespace.size)?;
+        }
     }
 
+    OptimizedIO::print_performance_report(&io_handle, None);
+    println!("      ✅ Namespace {} completed", namespace.nsid);
     Ok(())
 }
 
-/// Format NVMe namespace using Format command
-fn format_nvme_namespace(controller_path: &str, nsid: u32) -> Result<()> {
-    use std::process::Command;
+/// Format NVMe namespace using Format command, preferring a cryptographic
+/// erase (SES=2) and falling back to a user data erase (SES=1). Both erase
+/// the metadata with the data; the namespace keeps `format` when it is known.
+/// A format still running at `cancel`'s deadline is killed.
+fn format_nvme_namespace(
+    controller_path: &str,
+    nsid: u32,
+    format: Option<&NamespaceFormat>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    use crate::commands::Command;
 
-    let output = Command::new("nvme")
-        .args(&["format", controller_path, "-n", &nsid.to_string()])
-        .output()?;
+    let nsid = nsid.to_string();
+    let mut last_error = String::new();
 
-    if !output.status.success() {
-        return Err(anyhow::anyhow!(
-            "NVMe format failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+    for ses in ["2", "1"] {
+        let output = Command::new("nvme")
+            .args(["format", controller_path, "-n", &nsid, "--ses", ses])
+            .args(format.map(NamespaceFormat::format_args).unwrap_or_default())
+            .arg("--force")
+            .deadline(cancel.deadline())
+            .output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+        last_error = String::from_utf8_lossy(&output.stderr).to_string();
     }
 
-    Ok(())
+    Err(anyhow::anyhow!("NVMe format failed: {}", last_error))
 }
 
 /// Wipe standard namespace with multiple passes
@@ -467,37 +536,75 @@ fn wipe_namespace_multipass(
     Ok(())
 }
 
-/// Wipe ZNS namespace with zone awareness
+/// Wipe ZNS namespace with zone awareness.
+///
+/// Sequential-write-required zones reject writes anywhere but their write
+/// pointer, so each written zone is reset first (moving the pointer back to
+/// the zone start) and then filled strictly in order up to its capacity.
+/// Conventional zones are overwritten in place. Offline zones can't be read
+/// or written; read-only zones still hold readable data, so they fail the wipe.
 fn wipe_zns_namespace(
+    nvme_drive: &NVMeAdvanced,
     io_handle: &mut IOHandle,
     namespace: &NVMeNamespace,
 ) -> Result<()> {
-    if let Some(zones) = &namespace.zones {
-        println!("         Wiping {} zones", zones.len());
-
-        for zone in zones {
-            if zone.needs_reset() {
-                println!("         Resetting zone {}", zone.zone_id);
-                // Reset zone using nvme-cli or ioctl
-                // This is a simplified version
-            }
+    // A zoned namespace must never get a plain chunked overwrite, so ask
+    // the controller again if detection didn't record the zones
+    let reported;
+    let zones = match &namespace.zones {
+        Some(zones) if !zones.is_empty() => zones,
+        _ if nvme_drive.zns_support => {
+            reported = NVMeAdvanced::get_zns_zones(&namespace.device_path)?;
+            &reported
+        }
+        _ => &Vec::new(),
+    };
+    if zones.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No zone report for zoned namespace {}; refusing a non-sequential overwrite",
+            namespace.device_path
+        ));
+    }
 
-            // Write to zone sequentially
-            let zone_size = zone.zone_capacity * 512; // Assuming 512-byte blocks
-            let zone_offset = zone.zone_start_lba * 512;
+    println!("         Wiping {} zones", zones.len());
+    let lba_size = namespace.lba_size as u64;
+    let mut read_only = Vec::new();
 
-            // Single pass for ZNS (sequential write constraint)
-            wipe_zone_sequential(io_handle, zone_offset, zone_size)?;
+    for zone in zones {
+        match zone.zone_state {
+            ZNSZoneState::Offline => {
+                println!("         Zone {} is offline, skipping", zone.zone_id);
+                continue;
+            }
+            ZNSZoneState::ReadOnly => {
+                read_only.push(zone.zone_id);
+                continue;
+            }
+            _ => {}
         }
-    } else {
-        // Fallback to standard wipe
-        wipe_namespace_multipass(io_handle, namespace.size)?;
+
+        if zone.needs_reset() {
+            nvme_drive.zns_reset_zone(namespace, zone)?;
+        }
+
+        // After a reset (or in an empty zone) the write pointer is the zone
+        // start, so writing in order from there satisfies the constraint
+        let zone_offset = zone.zone_start_lba * lba_size;
+        let zone_size = zone.zone_capacity * lba_size;
+        wipe_zone_sequential(io_handle, zone_offset, zone_size)?;
+    }
+
+    if !read_only.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Zones {:?} on {} are read-only and could not be overwritten",
+            read_only, namespace.device_path
+        ));
     }
 
     Ok(())
 }
 
-/// Wipe a single zone sequentially
+/// Wipe a single zone sequentially, in order from `offset`
 fn wipe_zone_sequential(
     io_handle: &mut IOHandle,
     offset: u64,
@@ -563,12 +670,110 @@ pub enum WipeAlgorithm {
     Ones,
     Random,
     Pattern(u8),
+    /// A multi-pass standard (DoD, Gutmann, BSI, ...), one pattern per pass
+    Passes(Vec<PatternSpec>),
+}
+
+impl WipeAlgorithm {
+    /// The pattern written by each pass, in order
+    pub fn passes(&self) -> Vec<PatternSpec> {
+        match self {
+            WipeAlgorithm::Zeros => vec![PatternSpec::Byte(0x00)],
+            WipeAlgorithm::Ones => vec![PatternSpec::Byte(0xFF)],
+            WipeAlgorithm::Random => vec![PatternSpec::Random],
+            WipeAlgorithm::Pattern(byte) => vec![PatternSpec::Byte(*byte)],
+            WipeAlgorithm::Passes(passes) => passes.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for WipeAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WipeAlgorithm::Passes(passes) => write!(f, "{} passes", passes.len()),
+            WipeAlgorithm::Pattern(byte) => write!(f, "Pattern(0x{:02X})", byte),
+            other => write!(f, "{:?}", other),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_zns_overwrite_resets_and_writes_zones_in_order() {
+        use crate::commands::{scoped_runner, MockCommandRunner};
+        use super::super::types::nvme::advanced::ZNSZone;
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockCommandRunner::new());
+        let _guard = scoped_runner(mock.clone());
+
+        // Three 16-LBA zones of 512 bytes, each with 8 LBAs of capacity
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), vec![0x5Au8; 3 * 16 * 512]).unwrap();
+        let device_path = temp.path().to_str().unwrap().to_string();
+
+        let zone = |zone_id: u32, zone_state, is_sequential| ZNSZone {
+            zone_id,
+            zone_start_lba: zone_id as u64 * 16,
+            zone_capacity: 8,
+            write_pointer: zone_id as u64 * 16,
+            zone_state,
+            is_sequential,
+        };
+        let mut namespace = NVMeNamespace {
+            nsid: 1,
+            size: 3 * 16 * 512,
+            namespace_type: NamespaceType::ZonedNamespace,
+            device_path: device_path.clone(),
+            is_active: true,
+            is_attached: true,
+            zones: Some(vec![
+                zone(0, ZNSZoneState::Empty, false),
+                zone(1, ZNSZoneState::Full, true),
+                zone(2, ZNSZoneState::Empty, true),
+            ]),
+            lba_size: 512,
+            format: None,
+        };
+        let nvme = NVMeAdvanced {
+            device_path: device_path.clone(),
+            controller_path: device_path.clone(),
+            namespaces: Vec::new(),
+            zns_support: true,
+            kv_support: false,
+            is_computational_storage: false,
+            model: "Test".to_string(),
+            firmware: "1.0".to_string(),
+        };
+        let io_config = IOConfig { use_direct_io: false, initial_buffer_size: 4096, ..IOConfig::default() };
+
+        let mut io_handle = OptimizedIO::open(&device_path, io_config.clone()).unwrap();
+        wipe_zns_namespace(&nvme, &mut io_handle, &namespace).unwrap();
+
+        // Only the full sequential zone needed its write pointer reset
+        assert_eq!(mock.calls(), [format!("nvme zns reset-zone {} -s 0x10", device_path)]);
+
+        // Each zone's capacity is overwritten; the LBAs beyond it are untouched
+        let data = std::fs::read(temp.path()).unwrap();
+        for zone_start in [0, 16 * 512, 32 * 512] {
+            assert!(data[zone_start..zone_start + 8 * 512].iter().any(|&b| b != 0x5A));
+            assert!(data[zone_start + 8 * 512..zone_start + 16 * 512].iter().all(|&b| b == 0x5A));
+        }
+
+        // Read-only zones still hold data, so the wipe must fail
+        namespace.zones = Some(vec![zone(0, ZNSZoneState::ReadOnly, true)]);
+        let err = wipe_zns_namespace(&nvme, &mut io_handle, &namespace).unwrap_err();
+        assert!(err.to_string().contains("read-only"));
+
+        // Without a zone report there is no safe way to overwrite
+        mock.fail("nvme zns report-zones", "not supported");
+        namespace.zones = None;
+        assert!(wipe_zns_namespace(&nvme, &mut io_handle, &namespace).is_err());
+    }
+
     #[test]
     fn test_wipe_algorithm_variants() {
         let algos = vec![
@@ -580,4 +785,24 @@ mod tests {
 
         assert_eq!(algos.len(), 4);
     }
+
+    #[test]
+    fn test_zone_writes_run_each_pass_pattern() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), vec![0u8; 3 * 4096]).unwrap();
+        let io_config = IOConfig { use_direct_io: false, initial_buffer_size: 4096, ..IOConfig::default() };
+        let mut io_handle = OptimizedIO::open(temp.path().to_str().unwrap(), io_config).unwrap();
+
+        let algorithm = WipeAlgorithm::Passes(vec![PatternSpec::Byte(0xFF), PatternSpec::Sequence(vec![0x92, 0x49, 0x24])]);
+        for pattern in algorithm.passes() {
+            write_pattern_to_zone(&mut io_handle, 4096, 8192, &pattern, |_| {}).unwrap();
+        }
+
+        // The last pass wins, phased by device offset across buffers
+        let data = std::fs::read(temp.path()).unwrap();
+        assert!(data[..4096].iter().all(|&b| b == 0));
+        let expected: Vec<u8> = (4096..3 * 4096).map(|i| [0x92, 0x49, 0x24][i % 3]).collect();
+        assert_eq!(&data[4096..], &expected[..]);
+        assert_eq!(algorithm.to_string(), "2 passes");
+    }
 }
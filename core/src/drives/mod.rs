@@ -2,6 +2,7 @@
 //
 // Organized structure:
 // - detection.rs: Core drive detection logic
+// - platform.rs: Per-OS access to raw drives (Linux, Windows)
 // - types/: Drive-type specific implementations (HDD, SSD, NVMe, SMR, etc.)
 // - operations/: Drive operations (SMART, TRIM, HPA/DCO, SED)
 // - freeze/: Freeze detection and mitigation
@@ -9,6 +10,8 @@
 
 // Core functionality
 pub mod detection;
+pub mod platform;
+pub mod capabilities;
 
 // Drive types (organized by category)
 pub mod types;
@@ -23,7 +26,9 @@ pub mod freeze;
 pub mod integrated_wipe;
 
 // Re-exports for backward compatibility and convenience
-pub use detection::DriveDetector;
+pub use detection::{DriveDetector, EnumerateOptions};
+pub use platform::{DevicePlatform, get_device_platform};
+pub use capabilities::Capabilities;
 
 // Drive types
 pub use types::{
@@ -45,6 +50,7 @@ pub use types::{
     ZoneType,
     ZoneCondition,
     ZoneModel,
+    ZoneWipeSummary,
     OptaneDrive,
     OptaneMode,
     OptaneNamespace,
@@ -63,14 +69,27 @@ pub use types::{
     RAIDController,
     MetadataRegion,
     MetadataLocation,
+    SASDrive,
+    ScsiSanitizeAction,
 };
 
 // Operations
 pub use operations::{
     HPADCOManager,
     SEDManager,
+    SEDInfo,
+    crypto_erase_sed,
     TrimOperations,
+    TrimReadBehavior,
     SMARTMonitor,
+    ATASecurityState,
+    EraseTime,
+    ata_secure_erase,
+    unfreeze_via_sleep,
+    DeviceLog,
+    LuksHeader,
+    PartitionTableArea,
+    clear_gpt,
 };
 
 // Freeze mitigation
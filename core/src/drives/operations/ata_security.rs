@@ -0,0 +1,318 @@
+// ATA Security feature set - hardware SECURITY ERASE UNIT for SATA HDDs/SSDs
+//
+// The drive erases every user-addressable sector itself (including remapped
+// ones), which is faster and more thorough than a host overwrite on flash.
+
+use crate::{CancellationToken, DriveError, DriveResult};
+use crate::ui::progress::ProgressBar;
+use crate::commands::{command_failure, exit_description, spawn_error, Command};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Temporary user password set for the erase; the drive clears it afterwards
+const ERASE_PASSWORD: &str = "sayonara";
+
+/// How often the running erase is polled for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Length of the S3 suspend used to clear the frozen state
+const UNFREEZE_SLEEP_SECONDS: u64 = 10;
+
+/// Largest erase time IDENTIFY can express; longer erases report "more than" this
+const MAX_REPORTED_ERASE_MINUTES: u32 = 508;
+
+/// The drive's own estimate of how long a security erase takes, from
+/// IDENTIFY words 89/90
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EraseTime {
+    Minutes(u32),
+    /// The IDENTIFY sentinel for erases longer than 508 minutes
+    MoreThan508Minutes,
+}
+
+impl EraseTime {
+    /// The estimate as a duration; for the sentinel, the 508 minutes it is
+    /// known to exceed
+    pub fn duration(&self) -> Duration {
+        let minutes = match self {
+            EraseTime::Minutes(minutes) => *minutes,
+            EraseTime::MoreThan508Minutes => MAX_REPORTED_ERASE_MINUTES,
+        };
+        Duration::from_secs(u64::from(minutes) * 60)
+    }
+
+    /// hdparm prints "2min", or "more than 508min" for the sentinel
+    fn parse(estimate: &str) -> Option<Self> {
+        let minutes: u32 = estimate
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix("min"))
+            .map(|m| m.trim_start_matches('>'))
+            .and_then(|m| m.parse().ok())?;
+        if minutes >= MAX_REPORTED_ERASE_MINUTES && (estimate.contains("more than") || estimate.contains('>')) {
+            Some(EraseTime::MoreThan508Minutes)
+        } else {
+            Some(EraseTime::Minutes(minutes))
+        }
+    }
+}
+
+impl std::fmt::Display for EraseTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EraseTime::Minutes(minutes) => write!(f, "{} min", minutes),
+            EraseTime::MoreThan508Minutes => write!(f, "more than {} min", MAX_REPORTED_ERASE_MINUTES),
+        }
+    }
+}
+
+/// Security section of an ATA IDENTIFY (as printed by `hdparm -I`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ATASecurityState {
+    pub supported: bool,
+    pub enabled: bool,
+    pub locked: bool,
+    pub frozen: bool,
+    pub enhanced_erase_supported: bool,
+    /// Drive's estimate for SECURITY ERASE UNIT
+    pub erase_time: Option<EraseTime>,
+    /// Drive's estimate for ENHANCED SECURITY ERASE UNIT
+    pub enhanced_erase_time: Option<EraseTime>,
+}
+
+impl ATASecurityState {
+    /// Read the security state from the drive's IDENTIFY data
+    pub fn query(device_path: &str) -> DriveResult<Self> {
+        let output = Command::new("hdparm").args(["-I", device_path]).run()?;
+        Ok(Self::parse(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse the `Security:` section of `hdparm -I` output
+    pub fn parse(identify: &str) -> Self {
+        let mut state = Self::default();
+
+        let section = identify
+            .lines()
+            .skip_while(|line| line.trim() != "Security:")
+            .skip(1)
+            .take_while(|line| line.starts_with(char::is_whitespace));
+
+        for line in section {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let negated = words.first() == Some(&"not");
+            let rest = if negated { &words[1..] } else { &words[..] };
+
+            match rest {
+                ["supported"] => state.supported = !negated,
+                ["enabled"] => state.enabled = !negated,
+                ["locked"] => state.locked = !negated,
+                ["frozen"] => state.frozen = !negated,
+                ["supported:", "enhanced", "erase"] => state.enhanced_erase_supported = !negated,
+                _ if line.contains("SECURITY ERASE UNIT") => {
+                    for estimate in line.split('.') {
+                        let time = EraseTime::parse(estimate);
+                        if estimate.contains("ENHANCED SECURITY ERASE UNIT") {
+                            state.enhanced_erase_time = time;
+                        } else if estimate.contains("SECURITY ERASE UNIT") {
+                            state.erase_time = time;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        state
+    }
+
+    /// Drive's estimate for the normal or enhanced erase
+    pub fn erase_time(&self, enhanced: bool) -> Option<EraseTime> {
+        if enhanced { self.enhanced_erase_time } else { self.erase_time }
+    }
+
+    /// Whether SECURITY ERASE UNIT can be issued right now
+    pub fn can_erase(&self) -> bool {
+        self.supported && !self.frozen && !self.locked
+    }
+}
+
+/// Clear the security-frozen state with an S3 suspend/resume cycle, woken
+/// by the RTC via `rtcwake`. BIOSes freeze drives at boot but not on resume.
+/// Returns the security state read back after waking.
+pub fn unfreeze_via_sleep(device_path: &str) -> DriveResult<ATASecurityState> {
+    let s3_supported = std::fs::read_to_string("/sys/power/state")
+        .map(|states| states.contains("mem"))
+        .unwrap_or(false);
+    if !s3_supported {
+        return Err(DriveError::Unsupported("S3 suspend (mem) is not available on this system".to_string()));
+    }
+
+    println!("💤 Suspending the system for {}s to unfreeze {} (rtcwake will wake it)", UNFREEZE_SLEEP_SECONDS, device_path);
+    let output = Command::new("rtcwake")
+        .args(["-m", "mem", "-s", &UNFREEZE_SLEEP_SECONDS.to_string()])
+        .output()
+        .map_err(|e| DriveError::Unsupported(format!("rtcwake unavailable: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(DriveError::HardwareCommandFailed(format!(
+            "rtcwake failed: {}",
+            command_failure(&output)
+        )));
+    }
+
+    // Give the SATA link time to come back before re-identifying
+    thread::sleep(Duration::from_secs(3));
+    ATASecurityState::query(device_path)
+}
+
+/// Run ATA SECURITY ERASE UNIT (or the enhanced variant): set a temporary
+/// user password, issue the erase, and poll until the drive finishes.
+/// Cancelling stops the wait only: the drive cannot abort the erase.
+pub fn ata_secure_erase(device_path: &str, enhanced: bool, cancel: &CancellationToken) -> DriveResult<()> {
+    let state = ATASecurityState::query(device_path)?;
+
+    if !state.supported {
+        return Err(DriveError::Unsupported(format!("{} does not support the ATA Security feature set", device_path)));
+    }
+    if state.frozen {
+        return Err(DriveError::DriveFrozen(format!("{} security is frozen (suspend/resume or power cycle to unfreeze)", device_path)));
+    }
+    if state.locked {
+        return Err(DriveError::UnlockFailed(format!("{} is locked with an unknown password", device_path)));
+    }
+    if enhanced && !state.enhanced_erase_supported {
+        return Err(DriveError::Unsupported(format!("{} does not support enhanced secure erase", device_path)));
+    }
+
+    let (label, erase_arg) = if enhanced {
+        ("ENHANCED SECURITY ERASE UNIT", "--security-erase-enhanced")
+    } else {
+        ("SECURITY ERASE UNIT", "--security-erase")
+    };
+    let estimate = state.erase_time(enhanced);
+
+    println!("🔐 Setting temporary ATA security password on {}", device_path);
+    Command::new("hdparm")
+        .args(["--user-master", "u", "--security-set-pass", ERASE_PASSWORD, device_path])
+        .deadline(cancel.deadline())
+        .run()?;
+
+    match estimate {
+        Some(time) => println!("🧹 Issuing {} (drive estimate: {})", label, time),
+        None => println!("🧹 Issuing {}", label),
+    }
+
+    let mut process = Command::new("hdparm")
+        .args(["--user-master", "u", erase_arg, ERASE_PASSWORD, device_path])
+        .deadline(cancel.deadline())
+        .spawn()
+        .map_err(|e| spawn_error("hdparm", e))?;
+
+    let started = Instant::now();
+    let expected = estimate.map(|time| time.duration());
+    let mut bar = ProgressBar::new(48);
+
+    let status = loop {
+        match process.try_wait()? {
+            Some(status) => break status,
+            None if cancel.is_cancelled() => {
+                println!("\n🛑 Cancelled - {} keeps running inside the drive; leave it powered until it finishes", label);
+                return Err(DriveError::Cancelled);
+            }
+            None => {
+                // The drive reports no progress; estimate from its own time figure
+                let progress = match expected {
+                    Some(total) if !total.is_zero() => {
+                        (started.elapsed().as_secs_f64() / total.as_secs_f64() * 100.0).min(99.0)
+                    }
+                    _ => 50.0,
+                };
+                bar.render(progress, None, None);
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    };
+
+    if !status.success() {
+        // Don't leave the drive locked behind our temporary password
+        let _ = Command::new("hdparm")
+            .args(["--user-master", "u", "--security-disable", ERASE_PASSWORD, device_path])
+            .output();
+        return Err(DriveError::HardwareCommandFailed(format!(
+            "{} failed on {} ({})",
+            label,
+            device_path,
+            exit_description(status)
+        )));
+    }
+
+    bar.render(100.0, None, None);
+    println!("\n✅ {} completed in {}s", label, started.elapsed().as_secs());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTIFY: &str = "\
+Commands/features:
+\tEnabled\tSupported:
+\t   *\tSMART feature set
+Security:
+\tMaster password revision code = 65534
+\t\tsupported
+\tnot\tenabled
+\tnot\tlocked
+\tnot\tfrozen
+\tnot\texpired: security count
+\t\tsupported: enhanced erase
+\t2min for SECURITY ERASE UNIT. 8min for ENHANCED SECURITY ERASE UNIT.
+Logical Unit WWN Device Identifier: 5002538e40a1b2c3
+";
+
+    #[test]
+    fn test_parse_security_section() {
+        let state = ATASecurityState::parse(IDENTIFY);
+
+        assert!(state.supported);
+        assert!(!state.enabled);
+        assert!(!state.locked);
+        assert!(!state.frozen);
+        assert!(state.enhanced_erase_supported);
+        assert_eq!(state.erase_time, Some(EraseTime::Minutes(2)));
+        assert_eq!(state.enhanced_erase_time, Some(EraseTime::Minutes(8)));
+        assert!(state.can_erase());
+    }
+
+    #[test]
+    fn test_erase_time_sentinel() {
+        let identify = IDENTIFY.replace(
+            "2min for SECURITY ERASE UNIT. 8min for",
+            "260min for SECURITY ERASE UNIT. more than 508min for",
+        );
+        let state = ATASecurityState::parse(&identify);
+
+        assert_eq!(state.erase_time(false), Some(EraseTime::Minutes(260)));
+        assert_eq!(state.erase_time(true), Some(EraseTime::MoreThan508Minutes));
+        assert_eq!(EraseTime::MoreThan508Minutes.to_string(), "more than 508 min");
+        assert_eq!(EraseTime::MoreThan508Minutes.duration(), Duration::from_secs(508 * 60));
+    }
+
+    #[test]
+    fn test_frozen_drive_cannot_erase() {
+        let identify = IDENTIFY.replace("\tnot\tfrozen", "\t\tfrozen");
+        let state = ATASecurityState::parse(&identify);
+
+        assert!(state.frozen);
+        assert!(!state.can_erase());
+    }
+
+    #[test]
+    fn test_missing_security_section() {
+        let state = ATASecurityState::parse("Commands/features:\n\t   *\tSMART feature set\n");
+        assert_eq!(state, ATASecurityState::default());
+        assert!(!state.can_erase());
+    }
+}
@@ -0,0 +1,167 @@
+// Clearing controller logs that record a drive's usage history
+//
+// The NVMe Persistent Event Log and the ATA SMART self-test log survive a
+// data wipe and show when and how the drive was used. Neither can be cleared
+// on every drive, so each clear is only attempted where the drive advertises
+// the log, and a drive that rejects the command is reported as unsupported.
+
+use crate::commands::{command_failure, Command};
+use crate::{DriveError, DriveResult, DriveType};
+use std::io::ErrorKind;
+use std::process::Output;
+
+/// LPA (Log Page Attributes) bit advertising the Persistent Event Log
+const LPA_PERSISTENT_EVENT_LOG: u32 = 1 << 4;
+
+/// ATA PASS-THROUGH (16) CDB for SMART WRITE LOG of one sector to log 0x06
+/// (the SMART self-test log), with PIO data-out from the host
+const ATA_WRITE_SELF_TEST_LOG_CDB: [&str; 16] = [
+    "85", "0a", "06", "00", "d6", "00", "01", "00", "06", "00", "4f", "00", "c2", "00", "b0", "00",
+];
+
+/// A controller log that can be cleared after the wipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceLog {
+    /// NVMe Persistent Event Log (log page 0x0D)
+    NvmePersistentEventLog,
+    /// ATA SMART self-test log (log address 0x06)
+    AtaSelfTestLog,
+}
+
+impl DeviceLog {
+    /// Logs worth clearing on a drive of this type
+    pub fn for_drive_type(drive_type: &DriveType) -> &'static [DeviceLog] {
+        match drive_type {
+            DriveType::NVMe => &[DeviceLog::NvmePersistentEventLog],
+            DriveType::SSD | DriveType::HDD | DriveType::SMR | DriveType::HybridSSHD => &[DeviceLog::AtaSelfTestLog],
+            _ => &[],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeviceLog::NvmePersistentEventLog => "NVMe persistent event log",
+            DeviceLog::AtaSelfTestLog => "ATA SMART self-test log",
+        }
+    }
+
+    /// The command that clears this log, for dry runs and the audit log
+    pub fn command(&self, device_path: &str) -> String {
+        match self {
+            DeviceLog::NvmePersistentEventLog => format!("nvme persistent-event-log {} --action=2", device_path),
+            DeviceLog::AtaSelfTestLog => format!(
+                "sg_raw -s 512 -i /dev/zero {} {}",
+                device_path,
+                ATA_WRITE_SELF_TEST_LOG_CDB.join(" ")
+            ),
+        }
+    }
+
+    /// Clear this log on `device_path`. Returns `DriveError::Unsupported`
+    /// when the drive doesn't have the log, rejects the command, or the
+    /// tool to issue it isn't installed.
+    pub fn clear(&self, device_path: &str) -> DriveResult<()> {
+        match self {
+            DeviceLog::NvmePersistentEventLog => Self::clear_nvme_persistent_event_log(device_path),
+            DeviceLog::AtaSelfTestLog => Self::clear_ata_self_test_log(device_path),
+        }
+    }
+
+    fn clear_nvme_persistent_event_log(device_path: &str) -> DriveResult<()> {
+        let id_ctrl = run_tool("nvme", &["id-ctrl", device_path])?;
+        if !Self::nvme_has_persistent_event_log(&String::from_utf8_lossy(&id_ctrl.stdout)) {
+            return Err(DriveError::Unsupported("Controller has no persistent event log".to_string()));
+        }
+
+        // Action 2 releases the reporting context, discarding the events
+        // collected for the host
+        let output = run_tool("nvme", &["persistent-event-log", device_path, "--action=2"])?;
+        if !output.status.success() {
+            return Err(DriveError::HardwareCommandFailed(format!(
+                "nvme persistent-event-log failed: {}",
+                command_failure(&output)
+            )));
+        }
+        Ok(())
+    }
+
+    fn clear_ata_self_test_log(device_path: &str) -> DriveResult<()> {
+        let capabilities = run_tool("smartctl", &["-c", device_path])?;
+        if !String::from_utf8_lossy(&capabilities.stdout).contains("Self-test supported") {
+            return Err(DriveError::Unsupported("Drive has no SMART self-test log".to_string()));
+        }
+
+        let mut args = vec!["-s", "512", "-i", "/dev/zero", device_path];
+        args.extend(ATA_WRITE_SELF_TEST_LOG_CDB);
+        let output = run_tool("sg_raw", &args)?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if Self::ata_command_rejected(&stderr) {
+            Err(DriveError::Unsupported("Drive does not allow the self-test log to be written".to_string()))
+        } else {
+            Err(DriveError::HardwareCommandFailed(format!("SMART WRITE LOG failed: {}", stderr.trim())))
+        }
+    }
+
+    /// Decode the `lpa` line of `nvme id-ctrl` output
+    pub fn nvme_has_persistent_event_log(id_ctrl: &str) -> bool {
+        id_ctrl
+            .lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                if key.trim() != "lpa" {
+                    return None;
+                }
+                let value = value.trim();
+                match value.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => value.parse().ok(),
+                }
+            })
+            .is_some_and(|lpa| lpa & LPA_PERSISTENT_EVENT_LOG != 0)
+    }
+
+    /// Whether sg_raw's sense data shows the drive aborted the command,
+    /// as drives that keep the self-test log read-only do
+    pub fn ata_command_rejected(sg_raw_stderr: &str) -> bool {
+        let stderr = sg_raw_stderr.to_ascii_lowercase();
+        stderr.contains("aborted command") || stderr.contains("illegal request")
+    }
+}
+
+/// Run `program`, treating a missing tool as an unsupported operation
+fn run_tool(program: &str, args: &[&str]) -> DriveResult<Output> {
+    Command::new(program).args(args).output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => DriveError::Unsupported(format!("{} is not installed", program)),
+        _ => DriveError::HardwareCommandFailed(format!("Failed to run {}: {}", program, e)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nvme_lpa_persistent_event_log_bit() {
+        assert!(DeviceLog::nvme_has_persistent_event_log("vid       : 0x144d\nlpa       : 0x1e\n"));
+        assert!(!DeviceLog::nvme_has_persistent_event_log("lpa       : 0xf\n"));
+        assert!(!DeviceLog::nvme_has_persistent_event_log("vid       : 0x144d\n"));
+    }
+
+    #[test]
+    fn test_logs_for_drive_type() {
+        assert_eq!(DeviceLog::for_drive_type(&DriveType::NVMe), &[DeviceLog::NvmePersistentEventLog]);
+        assert_eq!(DeviceLog::for_drive_type(&DriveType::HDD), &[DeviceLog::AtaSelfTestLog]);
+        assert!(DeviceLog::for_drive_type(&DriveType::EMMC).is_empty());
+        assert!(DeviceLog::AtaSelfTestLog.command("/dev/sdz").ends_with("4f 00 c2 00 b0 00"));
+    }
+
+    #[test]
+    fn test_rejected_ata_command() {
+        assert!(DeviceLog::ata_command_rejected("SCSI Status: Check Condition\n Sense key: Aborted Command\n"));
+        assert!(!DeviceLog::ata_command_rejected("open error: /dev/sdz: Permission denied"));
+    }
+}
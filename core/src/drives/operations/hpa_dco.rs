@@ -1,5 +1,5 @@
 use crate::{DriveError, DriveResult};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use std::str;
 
 #[derive(Debug, Clone)]
@@ -97,9 +97,8 @@ impl HPADCOManager {
             ))?;
 
         if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
             return Err(DriveError::HardwareCommandFailed(
-                format!("Failed to remove HPA: {}", error)
+                format!("Failed to remove HPA: {}", command_failure(&output))
             ));
         }
 
@@ -119,9 +118,8 @@ impl HPADCOManager {
             ))?;
 
         if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
             return Err(DriveError::HardwareCommandFailed(
-                format!("Failed to restore HPA: {}", error)
+                format!("Failed to restore HPA: {}", command_failure(&output))
             ));
         }
 
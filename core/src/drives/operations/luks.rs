@@ -0,0 +1,217 @@
+// LUKS Header Erase - crypto-shredding a LUKS container
+//
+// Everything in a LUKS container is encrypted with a volume key that exists
+// only inside the key slots, wrapped by the passphrases. Overwriting the
+// header and every key slot destroys that key, leaving the data as
+// unrecoverable ciphertext in seconds, however large the volume. The bulk
+// wipe that usually follows then only removes ciphertext.
+
+use crate::crypto::secure_rng::SecureRNG;
+use crate::verification::scan_signatures;
+use crate::io::FileExt;
+use crate::{DriveError, DriveResult};
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+
+const LUKS_MAGIC: &[u8] = b"LUKS\xba\xbe";
+
+/// Magic of the secondary LUKS2 header, stored `hdr_size` after the first
+const LUKS2_SECONDARY_MAGIC: &[u8] = b"SKUL\xba\xbe";
+
+/// Size of a LUKS2 binary header, before its JSON area
+const LUKS2_BINARY_HEADER: usize = 4096;
+
+/// Header plus key slot area cryptsetup reserves by default for LUKS2,
+/// assumed when the JSON metadata can't be read
+const LUKS2_DEFAULT_HEADER_AREA: u64 = 16 << 20;
+
+/// Largest LUKS2 header size the specification allows
+const LUKS2_MAX_HDR_SIZE: u64 = 4 << 20;
+
+/// LUKS1 key slot table: 8 slots of 48 bytes from byte 208
+const LUKS1_KEY_SLOTS: usize = 8;
+const LUKS1_KEY_SLOT_OFFSET: usize = 208;
+const LUKS1_KEY_SLOT_SIZE: usize = 48;
+
+/// Write size while overwriting the header area
+const ERASE_CHUNK: usize = 1 << 20;
+
+/// A LUKS container found on the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LuksHeader {
+    /// Byte offset of the container, 0 or the start of its partition
+    pub offset: u64,
+    pub version: u16,
+    pub uuid: String,
+    /// Bytes from `offset` holding the header(s) and every key slot
+    pub header_area: u64,
+}
+
+impl LuksHeader {
+    /// The LUKS containers on the device: at its start and at the start of
+    /// any partition
+    pub fn find(device_path: &str) -> Result<Vec<LuksHeader>> {
+        let file = File::open(device_path).with_context(|| format!("Failed to open {}", device_path))?;
+        scan_signatures(device_path)?
+            .into_iter()
+            .filter(|signature| signature.kind.starts_with("LUKS"))
+            .map(|signature| Self::read_at(&file, signature.offset))
+            .collect()
+    }
+
+    /// Parse the LUKS header at `offset`
+    fn read_at(file: &File, offset: u64) -> Result<LuksHeader> {
+        let mut header = vec![0u8; LUKS2_BINARY_HEADER];
+        file.read_exact_at(&mut header, offset)?;
+        if !header.starts_with(LUKS_MAGIC) {
+            return Err(anyhow!("No LUKS header at byte {}", offset));
+        }
+
+        let version = u16::from_be_bytes([header[6], header[7]]);
+        let uuid = String::from_utf8_lossy(&header[168..208]).trim_end_matches('\0').to_string();
+        let header_area = match version {
+            1 => Self::luks1_header_area(&header),
+            2 => {
+                let hdr_size = u64::from_be_bytes(header[8..16].try_into().unwrap());
+                if !(LUKS2_BINARY_HEADER as u64..=LUKS2_MAX_HDR_SIZE).contains(&hdr_size) {
+                    return Err(anyhow!("Implausible LUKS2 header size {} at byte {}", hdr_size, offset));
+                }
+                let mut json = vec![0u8; hdr_size as usize - LUKS2_BINARY_HEADER];
+                file.read_exact_at(&mut json, offset + LUKS2_BINARY_HEADER as u64)?;
+                Self::luks2_header_area(hdr_size, &json)
+            }
+            other => return Err(anyhow!("Unsupported LUKS version {} at byte {}", other, offset)),
+        };
+
+        Ok(LuksHeader { offset, version, uuid, header_area })
+    }
+
+    /// End of the LUKS1 header area: the payload offset, or past the last
+    /// key slot's key material for a detached header
+    fn luks1_header_area(header: &[u8]) -> u64 {
+        let be32 = |at: usize| u32::from_be_bytes(header[at..at + 4].try_into().unwrap()) as u64;
+        let payload = be32(104) * 512;
+        let key_bytes = be32(108);
+
+        let key_material_end = (0..LUKS1_KEY_SLOTS)
+            .map(|slot| LUKS1_KEY_SLOT_OFFSET + slot * LUKS1_KEY_SLOT_SIZE)
+            .map(|at| be32(at + 40) * 512 + (key_bytes * be32(at + 44)).next_multiple_of(512))
+            .max()
+            .unwrap_or(0);
+
+        payload.max(key_material_end).max(LUKS2_BINARY_HEADER as u64)
+    }
+
+    /// End of the LUKS2 key slot area, from the JSON metadata: both header
+    /// copies followed by `config.keyslots_size`, or the furthest key slot
+    fn luks2_header_area(hdr_size: u64, json: &[u8]) -> u64 {
+        let text = String::from_utf8_lossy(json);
+        let Ok(metadata) = serde_json::from_str::<Value>(text.trim_end_matches('\0')) else {
+            return LUKS2_DEFAULT_HEADER_AREA;
+        };
+        // LUKS2 writes its 64-bit numbers as strings
+        let number = |value: &Value| value.as_str().and_then(|s| s.parse::<u64>().ok());
+
+        let configured = number(&metadata["config"]["keyslots_size"]).map(|size| 2 * hdr_size + size);
+        let keyslots_end = metadata["keyslots"]
+            .as_object()
+            .into_iter()
+            .flat_map(|slots| slots.values())
+            .filter_map(|slot| Some(number(&slot["area"]["offset"])? + number(&slot["area"]["size"])?))
+            .max();
+
+        configured.into_iter().chain(keyslots_end).max().unwrap_or(LUKS2_DEFAULT_HEADER_AREA).max(2 * hdr_size)
+    }
+
+    /// Overwrite the header(s) and every key slot with random data, then
+    /// read the start back to confirm no LUKS header remains
+    pub fn erase(&self, device_path: &str) -> DriveResult<()> {
+        let file = OpenOptions::new().read(true).write(true).open(device_path)?;
+        let mut rng = SecureRNG::new().map_err(|e| DriveError::CryptoEraseFailed(format!("RNG unavailable: {}", e)))?;
+
+        let mut buffer = vec![0u8; ERASE_CHUNK];
+        let mut written = 0u64;
+        while written < self.header_area {
+            let len = (self.header_area - written).min(ERASE_CHUNK as u64) as usize;
+            rng.fill_bytes(&mut buffer[..len])
+                .map_err(|e| DriveError::CryptoEraseFailed(format!("RNG failed: {}", e)))?;
+            file.write_all_at(&buffer[..len], self.offset + written)?;
+            written += len as u64;
+        }
+        file.sync_all()?;
+
+        let mut start = [0u8; 6];
+        file.read_exact_at(&mut start, self.offset)?;
+        if start == LUKS_MAGIC || start == LUKS2_SECONDARY_MAGIC {
+            return Err(DriveError::CryptoEraseFailed(format!(
+                "LUKS header at byte {} is still present after erasing it",
+                self.offset
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for LuksHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LUKS{} {} at byte {} ({} KiB of header and key slots)", self.version, self.uuid, self.offset, self.header_area / 1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A LUKS2 container with a 16 KiB header and one key slot
+    fn luks2_image() -> Vec<u8> {
+        let mut image = vec![0x5Au8; 8 << 20];
+        let header = &mut image[..];
+        header[..6].copy_from_slice(LUKS_MAGIC);
+        header[6..8].copy_from_slice(&2u16.to_be_bytes());
+        header[8..16].copy_from_slice(&16384u64.to_be_bytes());
+        header[168..172].copy_from_slice(b"uuid");
+        header[172..208].fill(0);
+
+        let json = br#"{"keyslots":{"0":{"area":{"offset":"32768","size":"258048"}}},"config":{"json_size":"12288","keyslots_size":"4161536"}}"#;
+        header[4096..4096 + json.len()].copy_from_slice(json);
+        header[4096 + json.len()..16384].fill(0);
+        header[16384..16390].copy_from_slice(LUKS2_SECONDARY_MAGIC);
+        image
+    }
+
+    #[test]
+    fn test_luks2_header_area_from_metadata() {
+        let image = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(image.path(), luks2_image()).unwrap();
+        let path = image.path().to_str().unwrap();
+
+        let headers = LuksHeader::find(path).unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].version, 2);
+        assert_eq!(headers[0].uuid, "uuid");
+        assert_eq!(headers[0].header_area, 2 * 16384 + 4161536);
+
+        headers[0].erase(path).unwrap();
+        let data = std::fs::read(image.path()).unwrap();
+        assert!(LuksHeader::find(path).unwrap().is_empty());
+        assert_ne!(&data[16384..16390], LUKS2_SECONDARY_MAGIC);
+        // The encrypted payload past the key slots is left to the bulk wipe
+        assert!(data[headers[0].header_area as usize..].iter().all(|&b| b == 0x5A));
+    }
+
+    #[test]
+    fn test_luks1_header_area_covers_key_material() {
+        let mut header = vec![0u8; LUKS2_BINARY_HEADER];
+        header[104..108].copy_from_slice(&4096u32.to_be_bytes());
+        header[108..112].copy_from_slice(&32u32.to_be_bytes());
+        assert_eq!(LuksHeader::luks1_header_area(&header), 4096 * 512);
+
+        // Detached header: no payload offset, key material ends the area
+        header[104..108].fill(0);
+        let slot = LUKS1_KEY_SLOT_OFFSET + 7 * LUKS1_KEY_SLOT_SIZE;
+        header[slot + 40..slot + 44].copy_from_slice(&3848u32.to_be_bytes());
+        header[slot + 44..slot + 48].copy_from_slice(&4000u32.to_be_bytes());
+        assert_eq!(LuksHeader::luks1_header_area(&header), 3848 * 512 + 128000);
+    }
+}
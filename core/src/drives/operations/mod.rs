@@ -6,9 +6,19 @@ pub mod hpa_dco;   // Hidden Protected Area / Device Configuration Overlay
 pub mod sed;       // Self-Encrypting Drive operations
 pub mod trim;      // TRIM/discard operations
 pub mod smart;     // SMART monitoring and health checks
+pub mod ata_security; // ATA Security feature set (SECURITY ERASE UNIT)
+pub mod device_logs;  // Clearing usage-history logs after a wipe
+pub mod luks;         // Destroying LUKS headers and key slots
+pub mod partition_table; // Zeroing the primary and backup GPT
+pub mod reprovision;  // A fresh GPT and filesystem for reuse
 
 // Re-exports for convenience
 pub use hpa_dco::HPADCOManager;
-pub use sed::SEDManager;
-pub use trim::TrimOperations;
+pub use sed::{SEDManager, SEDInfo, crypto_erase_sed};
+pub use trim::{TrimOperations, TrimReadBehavior};
 pub use smart::SMARTMonitor;
+pub use ata_security::{ATASecurityState, EraseTime, ata_secure_erase, unfreeze_via_sleep};
+pub use device_logs::DeviceLog;
+pub use luks::LuksHeader;
+pub use partition_table::{PartitionTableArea, clear_gpt};
+pub use reprovision::{Filesystem, Reprovisioning};
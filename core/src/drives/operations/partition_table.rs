@@ -0,0 +1,173 @@
+// Partition Table Clearing - zeroing the primary and backup GPT
+//
+// GPT keeps a second copy of its header and partition array in the last
+// blocks of the disk, and partitioning tools offer to "restore" a missing
+// primary from it. An erase that doesn't overwrite every block itself (a
+// firmware erase, a discard, a LUKS shred) can leave either copy readable,
+// so both areas are zeroed explicitly and read back as zeros afterwards.
+
+use crate::io::FileExt;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+
+/// Size of a standard GPT partition array: 128 entries of 128 bytes
+const GPT_ARRAY_BYTES: u64 = 128 * 128;
+
+const GPT_SIGNATURE: &[u8] = b"EFI PART";
+
+/// Signature kinds (as reported by `scan_signatures`) that are partition tables
+pub const PARTITION_TABLE_KINDS: &[&str] = &["MBR", "GPT", "GPT backup header"];
+
+/// A range of the device holding a copy of the partition table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionTableArea {
+    /// "primary GPT", "backup GPT" or "stale backup GPT"
+    pub name: String,
+    pub offset: u64,
+    pub len: u64,
+}
+
+impl PartitionTableArea {
+    fn new(name: &str, offset: u64, end: u64) -> Self {
+        Self { name: name.to_string(), offset, len: end - offset }
+    }
+
+    /// The areas of a `size`-byte device with `block_size`-byte LBAs that
+    /// hold a GPT: the protective MBR, header and array at the start (LBA
+    /// 0-33 with 512-byte blocks) and the array and backup header in the
+    /// last blocks. A backup the primary header places elsewhere, left
+    /// behind when an image of a smaller disk was written, is included too.
+    pub fn gpt_areas(device_path: &str, size: u64, block_size: u64) -> Result<Vec<Self>> {
+        let primary_end = (2 * block_size + GPT_ARRAY_BYTES).min(size);
+        let backup_len = GPT_ARRAY_BYTES + block_size;
+        let backup_start = size.saturating_sub(backup_len).max(primary_end);
+
+        let mut areas = vec![Self::new("primary GPT", 0, primary_end)];
+        if backup_start < size {
+            areas.push(Self::new("backup GPT", backup_start, size));
+        }
+
+        let file = File::open(device_path).with_context(|| format!("Failed to open {}", device_path))?;
+        let mut header = [0u8; 40];
+        if primary_end >= 2 * block_size && file.read_exact_at(&mut header, block_size).is_ok() && header.starts_with(GPT_SIGNATURE) {
+            let alternate_end = u64::from_le_bytes(header[32..40].try_into().unwrap())
+                .saturating_add(1)
+                .saturating_mul(block_size);
+            let alternate_start = alternate_end.saturating_sub(backup_len).max(primary_end);
+            if alternate_end <= backup_start && alternate_start < alternate_end {
+                areas.push(Self::new("stale backup GPT", alternate_start, alternate_end));
+            }
+        }
+
+        Ok(areas)
+    }
+
+    /// Overwrite the area with zeros
+    pub fn zero(&self, file: &File) -> Result<()> {
+        file.write_all_at(&vec![0u8; self.len as usize], self.offset)
+            .with_context(|| format!("Failed to zero the {}", self))
+    }
+
+    /// Offset of the first byte in the area that isn't zero
+    pub fn first_nonzero(&self, file: &File) -> Result<Option<u64>> {
+        let mut data = vec![0u8; self.len as usize];
+        file.read_exact_at(&mut data, self.offset)
+            .with_context(|| format!("Failed to read back the {}", self))?;
+        Ok(data.iter().position(|&b| b != 0).map(|index| self.offset + index as u64))
+    }
+}
+
+impl std::fmt::Display for PartitionTableArea {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (bytes {}..{})", self.name, self.offset, self.offset + self.len)
+    }
+}
+
+/// Zero every GPT area of `device_path` and read each back from the media,
+/// failing if any byte is not zero
+pub fn clear_gpt(device_path: &str, size: u64, block_size: u64) -> Result<Vec<PartitionTableArea>> {
+    let areas = PartitionTableArea::gpt_areas(device_path, size, block_size)?;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .open(device_path)
+        .with_context(|| format!("Failed to open {} for writing", device_path))?;
+    for area in &areas {
+        area.zero(&file)?;
+    }
+    file.sync_all()?;
+
+    // Read back past the page cache, from the media itself
+    crate::io::drop_page_cache(device_path)?;
+    let file = File::open(device_path)?;
+    for area in &areas {
+        if let Some(offset) = area.first_nonzero(&file)? {
+            return Err(anyhow!("The {} did not read back as zeros (byte {} is not zero)", area, offset));
+        }
+    }
+
+    Ok(areas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verification::scan_signatures;
+
+    const SIZE: usize = 1 << 20;
+
+    /// A GPT image whose primary header also points at a backup left at
+    /// 512 KiB by a smaller disk
+    fn gpt_image() -> tempfile::NamedTempFile {
+        let mut data = vec![0x5Au8; SIZE];
+        data[510..512].copy_from_slice(&[0x55, 0xAA]);
+        data[512..520].copy_from_slice(GPT_SIGNATURE);
+        data[544..552].copy_from_slice(&1023u64.to_le_bytes());
+        data[512 + 72..512 + 80].copy_from_slice(&2u64.to_le_bytes());
+        data[512 + 80..512 + 84].copy_from_slice(&128u32.to_le_bytes());
+        data[512 + 84..512 + 88].copy_from_slice(&128u32.to_le_bytes());
+        data[1024..1024 + GPT_ARRAY_BYTES as usize].fill(0);
+        data[SIZE - 512..SIZE - 504].copy_from_slice(GPT_SIGNATURE);
+
+        let image = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(image.path(), &data).unwrap();
+        image
+    }
+
+    #[test]
+    fn test_gpt_areas_for_512_and_4k_blocks() {
+        let image = gpt_image();
+        let path = image.path().to_str().unwrap();
+
+        let areas = PartitionTableArea::gpt_areas(path, SIZE as u64, 512).unwrap();
+        let ranges: Vec<_> = areas.iter().map(|a| (a.name.as_str(), a.offset, a.offset + a.len)).collect();
+        assert_eq!(ranges, [
+            ("primary GPT", 0, 34 * 512),
+            ("backup GPT", SIZE as u64 - 33 * 512, SIZE as u64),
+            ("stale backup GPT", 1024 * 512 - 33 * 512, 1024 * 512),
+        ]);
+
+        // The 4Kn header would sit at 4096, where there is none
+        let areas = PartitionTableArea::gpt_areas(path, SIZE as u64, 4096).unwrap();
+        assert_eq!(areas.len(), 2);
+        assert_eq!(areas[0].len, 6 * 4096);
+        assert_eq!(areas[1].offset, SIZE as u64 - 5 * 4096);
+    }
+
+    #[test]
+    fn test_clear_gpt_leaves_no_partition_table() {
+        let image = gpt_image();
+        let path = image.path().to_str().unwrap();
+        assert!(scan_signatures(path).unwrap().iter().any(|s| s.kind == "GPT backup header"));
+
+        let areas = clear_gpt(path, SIZE as u64, 512).unwrap();
+        assert_eq!(areas.len(), 3);
+        assert!(scan_signatures(path).unwrap().iter().all(|s| !PARTITION_TABLE_KINDS.contains(&s.kind.as_str())));
+
+        // Only the areas are touched
+        let data = std::fs::read(image.path()).unwrap();
+        assert_eq!(data[34 * 512], 0x5A);
+        assert_eq!(data[SIZE - 33 * 512 - 1], 0x5A);
+    }
+}
@@ -0,0 +1,138 @@
+// Reprovisioning - a fresh partition table and filesystem after the wipe
+//
+// A drive wiped for reuse is usually handed on to be used straight away, and
+// the next step is always the same: partition it and make a filesystem. With
+// `reprovision` set that happens once the wipe has been verified: a new GPT
+// holding one partition across the whole disk, formatted as ext4 or exFAT.
+
+use crate::commands::Command;
+use crate::DriveResult;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+/// Filesystem created on the single partition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Filesystem {
+    /// For Linux systems
+    Ext4,
+    /// Readable on Windows, macOS and Linux alike
+    ExFat,
+}
+
+impl Filesystem {
+    /// sgdisk type code for the partition holding this filesystem
+    /// (Linux filesystem, Microsoft basic data)
+    pub fn gpt_type_code(&self) -> &'static str {
+        match self {
+            Filesystem::Ext4 => "8300",
+            Filesystem::ExFat => "0700",
+        }
+    }
+
+    /// mkfs command line, without the partition
+    fn mkfs(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            // -F: a whole-disk partition looks like a mistake to mke2fs
+            Filesystem::Ext4 => ("mkfs.ext4", &["-F", "-q"]),
+            Filesystem::ExFat => ("mkfs.exfat", &[]),
+        }
+    }
+}
+
+impl std::fmt::Display for Filesystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Filesystem::Ext4 => "ext4",
+            Filesystem::ExFat => "exFAT",
+        })
+    }
+}
+
+impl std::str::FromStr for Filesystem {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "ext4" => Ok(Filesystem::Ext4),
+            "exfat" => Ok(Filesystem::ExFat),
+            _ => Err(anyhow!("Unknown filesystem '{}' (use ext4 or exfat)", s)),
+        }
+    }
+}
+
+/// What was created on the drive, as recorded in the certificate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reprovisioning {
+    pub filesystem: Filesystem,
+    /// Device node of the partition, e.g. "/dev/sdb1" or "/dev/nvme0n1p1"
+    pub partition: String,
+}
+
+impl std::fmt::Display for Reprovisioning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GPT with one {} partition ({})", self.filesystem, self.partition)
+    }
+}
+
+/// Device node of partition `number` of `device_path`: devices whose name
+/// ends in a digit (nvme0n1, mmcblk0, loop0) take a "p" before the number
+pub fn partition_path(device_path: &str, number: u32) -> String {
+    if device_path.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{}p{}", device_path, number)
+    } else {
+        format!("{}{}", device_path, number)
+    }
+}
+
+/// Write a new GPT with a single partition spanning `device_path` and
+/// format it as `filesystem`
+pub fn reprovision(device_path: &str, filesystem: Filesystem) -> DriveResult<Reprovisioning> {
+    Command::new("sgdisk")
+        .args(["--clear", "--new=1:0:0", &format!("--typecode=1:{}", filesystem.gpt_type_code())])
+        .arg(device_path)
+        .run()?;
+
+    // sgdisk has the kernel re-read the table; wait for udev to create the
+    // partition's node. Systems without udev have it once the re-read returns.
+    let _ = Command::new("udevadm").arg("settle").output();
+
+    let partition = partition_path(device_path, 1);
+    let (program, args) = filesystem.mkfs();
+    Command::new(program).args(args).arg(&partition).run()?;
+
+    Ok(Reprovisioning { filesystem, partition })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{scoped_runner, MockCommandRunner};
+    use crate::DriveError;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_reprovision_commands() {
+        assert_eq!(partition_path("/dev/sdb", 1), "/dev/sdb1");
+        assert_eq!(partition_path("/dev/nvme0n1", 1), "/dev/nvme0n1p1");
+        assert_eq!("exFAT".parse::<Filesystem>().unwrap(), Filesystem::ExFat);
+        assert!("ntfs".parse::<Filesystem>().is_err());
+
+        let runner = Arc::new(MockCommandRunner::new());
+        let _guard = scoped_runner(runner.clone());
+        let created = reprovision("/dev/mmcblk0", Filesystem::ExFat).unwrap();
+        assert_eq!(created.partition, "/dev/mmcblk0p1");
+        assert_eq!(runner.calls(), [
+            "sgdisk --clear --new=1:0:0 --typecode=1:0700 /dev/mmcblk0",
+            "udevadm settle",
+            "mkfs.exfat /dev/mmcblk0p1",
+        ]);
+
+        // No filesystem is attempted on a table that wasn't written
+        let runner = Arc::new(MockCommandRunner::new());
+        runner.fail("sgdisk", "Problem opening /dev/sdz for reading!");
+        let _guard = scoped_runner(runner.clone());
+        assert!(matches!(reprovision("/dev/sdz", Filesystem::Ext4), Err(DriveError::HardwareCommandFailed(_))));
+        assert!(!runner.ran("mkfs"));
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{DriveError, DriveResult};
 use crate::SEDType;
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
+
+/// Temporary SID/Admin1 password used while taking ownership for an Opal
+/// crypto erase; the revert that follows returns the drive to factory state
+const OPAL_ERASE_PASSWORD: &str = "sayonara";
 
 #[derive(Debug, Clone)]
 pub struct SEDInfo {
@@ -15,6 +19,31 @@ pub struct SEDInfo {
     pub firmware_version: Option<String>,
 }
 
+impl SEDInfo {
+    /// A drive whose data is encrypted by the drive itself, so destroying
+    /// its media encryption key erases everything
+    pub fn is_self_encrypting(&self) -> bool {
+        self.sed_type != SEDType::None && self.supports_crypto_erase
+    }
+}
+
+/// Cryptographically erase a self-encrypting drive by regenerating its media
+/// encryption key, returning the SED type that was erased. Fails with
+/// `Unsupported` when the drive is not a SED, so callers can overwrite instead.
+pub fn crypto_erase_sed(device_path: &str) -> DriveResult<SEDType> {
+    let sed_info = SEDManager::detect_sed(device_path)?;
+
+    if !sed_info.is_self_encrypting() {
+        return Err(DriveError::Unsupported(format!("{} is not a self-encrypting drive", device_path)));
+    }
+    if sed_info.locked {
+        return Err(DriveError::UnlockFailed(format!("{} is locked; unlock it or PSID-revert it first", device_path)));
+    }
+
+    SEDManager::crypto_erase(device_path, &sed_info)?;
+    Ok(sed_info.sed_type)
+}
+
 pub struct SEDManager;
 
 impl SEDManager {
@@ -94,19 +123,9 @@ impl SEDManager {
 
     /// Parse OPAL information from sedutil output
     fn parse_opal_info(output: &str, device_path: &str) -> Result<SEDInfo> {
-        let mut sed_type = SEDType::OPAL10;
-        let mut locked = false;
-        let mut enabled = false;
-
-        for line in output.lines() {
-            if line.contains("OPAL 2") {
-                sed_type = SEDType::OPAL20;
-            } else if line.contains("Locked") && line.contains("Y") {
-                locked = true;
-            } else if line.contains("LockingEnabled") && line.contains("Y") {
-                enabled = true;
-            }
-        }
+        let sed_type = if output.contains("OPAL 2") { SEDType::OPAL20 } else { SEDType::OPAL10 };
+        let locked = Self::opal_flag(output, "Locked").unwrap_or(false);
+        let enabled = Self::opal_flag(output, "LockingEnabled").unwrap_or(false);
 
         Ok(SEDInfo {
             sed_type,
@@ -263,29 +282,65 @@ impl SEDManager {
         }
     }
 
-    /// OPAL crypto erase
+    /// OPAL crypto erase: take ownership with a temporary password, which
+    /// activates the Locking SP, then revert the TPer. Reverting with the
+    /// Locking SP active eradicates every media encryption key.
     fn opal_crypto_erase(device_path: &str) -> DriveResult<()> {
-        println!("Executing OPAL cryptographic erase...");
+        println!("Executing OPAL cryptographic erase (regenerating the media encryption key)...");
+
+        let query = Command::new("sedutil-cli")
+            .args(["--query", device_path])
+            .output()
+            .map_err(|e| DriveError::CryptoEraseFailed(format!("sedutil-cli unavailable: {}", e)))?;
+        let query = String::from_utf8_lossy(&query.stdout);
+
+        if Self::opal_flag(&query, "LockingEnabled") == Some(true) {
+            // Someone else owns the drive; only the PSID on its label can revert it
+            return Err(DriveError::CryptoEraseFailed(format!(
+                "{} is already owned - revert it with the PSID from its label: sedutil-cli --PSIDrevert <PSID> {}",
+                device_path, device_path
+            )));
+        }
+
+        Self::sedutil(&["--initialSetup", OPAL_ERASE_PASSWORD, device_path])?;
 
-        // First try revert to factory (most thorough)
+        if let Err(e) = Self::sedutil(&["--revertTPer", OPAL_ERASE_PASSWORD, device_path]) {
+            return Err(DriveError::CryptoEraseFailed(format!(
+                "{} (the drive is still owned with password '{}')",
+                e, OPAL_ERASE_PASSWORD
+            )));
+        }
+
+        println!("OPAL revert completed - media encryption key regenerated");
+        Ok(())
+    }
+
+    /// Read a `Name = Y/N` flag from `sedutil-cli --query` output, e.g.
+    /// `Locked = N, LockingEnabled = Y, LockingSupported = Y, ...`
+    fn opal_flag(output: &str, name: &str) -> Option<bool> {
+        output
+            .split([',', '\n'])
+            .filter_map(|field| field.split_once('='))
+            .find(|(key, _)| key.trim() == name)
+            .map(|(_, value)| value.trim() == "Y")
+    }
+
+    /// Run one `sedutil-cli` command, failing with its output on error
+    fn sedutil(args: &[&str]) -> DriveResult<()> {
         let output = Command::new("sedutil-cli")
-            .args(["--revertTPer", "password", device_path])
-            .output();
+            .args(args)
+            .output()
+            .map_err(|e| DriveError::CryptoEraseFailed(format!("sedutil-cli unavailable: {}", e)))?;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                println!("OPAL revert completed successfully");
-                return Ok(());
-            }
+        if !output.status.success() {
+            return Err(DriveError::CryptoEraseFailed(format!(
+                "sedutil-cli {} failed ({})",
+                args[0],
+                command_failure(&output)
+            )));
         }
 
-        // Try PSID revert if available
-        println!("Attempting PSID revert (requires physical label PSID)...");
-        // This would require user input for PSID
-        
-        Err(DriveError::CryptoEraseFailed(
-            "OPAL crypto erase requires valid credentials or PSID".to_string()
-        ))
+        Ok(())
     }
 
     /// TCG Enterprise crypto erase
@@ -296,18 +351,18 @@ impl SEDManager {
         // Try generic SCSI sanitize with crypto erase
         let output = Command::new("sg_sanitize")
             .args(["--crypto", device_path])
-            .output();
+            .output()
+            .map_err(|e| DriveError::CryptoEraseFailed(format!("TCG Enterprise crypto erase failed: {}", e)))?;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                println!("TCG Enterprise crypto erase completed");
-                return Ok(());
-            }
+        if output.status.success() {
+            println!("TCG Enterprise crypto erase completed");
+            return Ok(());
         }
 
-        Err(DriveError::CryptoEraseFailed(
-            "TCG Enterprise crypto erase failed".to_string()
-        ))
+        Err(DriveError::CryptoEraseFailed(format!(
+            "TCG Enterprise crypto erase failed ({})",
+            command_failure(&output)
+        )))
     }
 
     /// ATA secure erase (for ATA Security feature set)
@@ -324,7 +379,10 @@ impl SEDManager {
             .map_err(|e| DriveError::CryptoEraseFailed(format!("Failed to set password: {}", e)))?;
 
         if !output.status.success() {
-            return Err(DriveError::CryptoEraseFailed("Failed to set security password".to_string()));
+            return Err(DriveError::CryptoEraseFailed(format!(
+                "Failed to set security password ({})",
+                command_failure(&output)
+            )));
         }
 
         let output = Command::new("hdparm")
@@ -336,7 +394,7 @@ impl SEDManager {
             println!("ATA Secure Erase completed");
             Ok(())
         } else {
-            Err(DriveError::CryptoEraseFailed("ATA Secure Erase failed".to_string()))
+            Err(DriveError::CryptoEraseFailed(format!("ATA Secure Erase failed ({})", command_failure(&output))))
         }
     }
 
@@ -460,7 +518,10 @@ impl SEDManager {
                     println!("Drive unlocked successfully");
                     Ok(())
                 } else {
-                    Err(DriveError::UnlockFailed("Invalid password or unlock failed".to_string()))
+                    Err(DriveError::UnlockFailed(format!(
+                        "Invalid password or unlock failed ({})",
+                        command_failure(&output)
+                    )))
                 }
             }
             SEDType::ATASecurity => {
@@ -473,7 +534,10 @@ impl SEDManager {
                     println!("Drive unlocked successfully");
                     Ok(())
                 } else {
-                    Err(DriveError::UnlockFailed("Invalid password or unlock failed".to_string()))
+                    Err(DriveError::UnlockFailed(format!(
+                        "Invalid password or unlock failed ({})",
+                        command_failure(&output)
+                    )))
                 }
             }
             _ => Err(DriveError::UnlockFailed("Unlock not supported for this SED type".to_string()))
@@ -513,3 +577,38 @@ impl SEDManager {
         Ok(!all_zero && !all_ff)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: &str = "\
+/dev/sdb SATA  Samsung SSD 860 EVO 500GB RVT01B6Q S3Z1NB0K123456
+TPer function (0x0001)
+    ACKNAK = N, ASYNC = N. BufferManagement = N, comIDManagement  = N, Streaming = Y, SYNC = Y
+Locking function (0x0002)
+    Locked = N, LockingEnabled = N, LockingSupported = Y, MBRDone = N, MBREnabled = N, MediaEncrypt = Y
+OPAL 2.0 function (0x0203)
+    Base comID = 0x1001, Initial PIN = 0x0, Reverted PIN = 0x0, comIDs = 1
+";
+
+    #[test]
+    fn test_parse_opal_query() {
+        let info = SEDManager::parse_opal_info(QUERY, "/dev/null").unwrap();
+
+        assert_eq!(info.sed_type, SEDType::OPAL20);
+        assert!(!info.locked);
+        assert!(!info.enabled);
+        assert!(info.is_self_encrypting());
+    }
+
+    #[test]
+    fn test_opal_flag_owned_drive() {
+        let owned = QUERY.replace("Locked = N, LockingEnabled = N", "Locked = Y, LockingEnabled = Y");
+
+        assert_eq!(SEDManager::opal_flag(&owned, "Locked"), Some(true));
+        assert_eq!(SEDManager::opal_flag(&owned, "LockingEnabled"), Some(true));
+        assert_eq!(SEDManager::opal_flag(&owned, "LockingSupported"), Some(true));
+        assert_eq!(SEDManager::opal_flag(&owned, "Missing"), None);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{DriveError, DriveResult};
 pub(crate) use crate::HealthStatus;
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use std::collections::HashMap;
 use std::io;
 use std::io::Write;
+use std::time::Instant;
 
 #[derive(Debug, Clone)]
 pub struct SMARTHealth {
@@ -87,18 +88,24 @@ impl SMARTMonitor {
 
     /// Get comprehensive SMART health information
     pub fn get_health(device_path: &str) -> DriveResult<SMARTHealth> {
+        Self::get_health_until(device_path, None)
+    }
+
+    /// `get_health`, killing the SMART tools still running at `deadline`
+    fn get_health_until(device_path: &str, deadline: Option<Instant>) -> DriveResult<SMARTHealth> {
         // Determine drive type and use appropriate method
         if device_path.contains("nvme") {
-            Self::get_nvme_health(device_path)
+            Self::get_nvme_health(device_path, deadline)
         } else {
-            Self::get_ata_health(device_path)
+            Self::get_ata_health(device_path, deadline)
         }
     }
 
     /// Get ATA/SATA drive SMART health
-    fn get_ata_health(device_path: &str) -> DriveResult<SMARTHealth> {
+    fn get_ata_health(device_path: &str, deadline: Option<Instant>) -> DriveResult<SMARTHealth> {
         let output = Command::new("smartctl")
             .args(["-A", "-H", "-i", device_path])
+            .deadline(deadline)
             .output()
             .map_err(|e| DriveError::SMARTReadFailed(format!("smartctl failed: {}", e)))?;
 
@@ -114,22 +121,24 @@ impl SMARTMonitor {
     }
 
     /// Get NVMe drive SMART health - FIXED VERSION
-    fn get_nvme_health(device_path: &str) -> DriveResult<SMARTHealth> {
+    fn get_nvme_health(device_path: &str, deadline: Option<Instant>) -> DriveResult<SMARTHealth> {
         // Try nvme-cli first
         let output = Command::new("nvme")
             .args(["smart-log", device_path])
+            .deadline(deadline)
             .output();
 
         if let Ok(output) = output {
             if output.status.success() {
                 let output_str = String::from_utf8_lossy(&output.stdout);
-                return Self::parse_nvme_smart(&output_str, device_path);
+                return Self::parse_nvme_smart(&output_str, device_path, deadline);
             }
         }
 
         // Fall back to smartctl for NVMe
         let output = Command::new("smartctl")
             .args(["-A", "-H", device_path])
+            .deadline(deadline)
             .output()
             .map_err(|e| DriveError::SMARTReadFailed(format!("Failed to read NVMe SMART: {}", e)))?;
 
@@ -246,7 +255,7 @@ impl SMARTMonitor {
     }
 
     /// Parse NVMe SMART output from nvme-cli - FIXED VERSION
-    fn parse_nvme_smart(output: &str, device_path: &str) -> DriveResult<SMARTHealth> {
+    fn parse_nvme_smart(output: &str, device_path: &str, deadline: Option<Instant>) -> DriveResult<SMARTHealth> {
         let mut health = SMARTHealth {
             overall_health: HealthStatus::Unknown,
             temperature_celsius: None,
@@ -303,6 +312,7 @@ impl SMARTMonitor {
         // Get additional health status
         let health_output = Command::new("nvme")
             .args(["id-ctrl", device_path])
+            .deadline(deadline)
             .output();
 
         if let Ok(output) = health_output {
@@ -442,7 +452,13 @@ impl SMARTMonitor {
 
     /// Monitor temperature during operations - FIXED VERSION
     pub fn monitor_temperature(device_path: &str) -> DriveResult<TemperatureMonitor> {
-        let health = Self::get_health(device_path)?;
+        Self::monitor_temperature_until(device_path, None)
+    }
+
+    /// `monitor_temperature`, killing the SMART tools still running at
+    /// `deadline` (the wipe's, while it polls between chunks)
+    pub fn monitor_temperature_until(device_path: &str, deadline: Option<Instant>) -> DriveResult<TemperatureMonitor> {
+        let health = Self::get_health_until(device_path, deadline)?;
 
         let current = match health.temperature_celsius {
             Some(temp) => {
@@ -534,7 +550,7 @@ impl SMARTMonitor {
     /// Wait for drive to cool down if needed - FIXED VERSION with timeout
     pub fn wait_for_safe_temperature(device_path: &str, max_wait_seconds: u64) -> DriveResult<()> {
         use std::thread;
-        use std::time::{Duration, Instant};
+        use std::time::Duration;
 
         println!("\n🌡️  Temperature Safety Check");
 
@@ -778,8 +794,7 @@ impl SMARTMonitor {
             .map_err(|e| DriveError::SMARTReadFailed(format!("Failed to start self-test: {}", e)))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(DriveError::SMARTReadFailed(format!("Self-test failed: {}", stderr)));
+            return Err(DriveError::SMARTReadFailed(format!("Self-test failed: {}", command_failure(&output))));
         }
 
         println!("Self-test started. Use 'smartctl -l selftest {}' to check progress", device_path);
@@ -1,5 +1,27 @@
 use crate::{DriveError, DriveResult, DriveType};
-use std::process::Command;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use crate::commands::{command_failure, Command};
+
+/// What an ATA drive promises about reads of trimmed blocks (from IDENTIFY)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrimReadBehavior {
+    /// Deterministic Read After TRIM (DRAT): repeated reads return the same data
+    pub deterministic: bool,
+    /// Read Zero After TRIM (RZAT): trimmed blocks read back as zeros
+    pub zeros: bool,
+}
+
+impl TrimReadBehavior {
+    /// Parse the DRAT/RZAT feature lines of `hdparm -I` output
+    pub fn parse(identify: &str) -> Self {
+        let zeros = identify.contains("Deterministic read ZEROs after TRIM");
+        Self {
+            deterministic: zeros || identify.contains("Deterministic read data after TRIM"),
+            zeros,
+        }
+    }
+}
 
 pub struct TrimOperations;
 
@@ -82,9 +104,8 @@ impl TrimOperations {
             ))?;
 
         if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
             return Err(DriveError::TRIMFailed(
-                format!("blkdiscard failed: {}", error)
+                format!("blkdiscard failed: {}", command_failure(&output))
             ));
         }
 
@@ -109,9 +130,8 @@ impl TrimOperations {
             ))?;
 
         if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
             return Err(DriveError::TRIMFailed(
-                format!("hdparm TRIM failed: {}", error)
+                format!("hdparm TRIM failed: {}", command_failure(&output))
             ));
         }
 
@@ -145,9 +165,8 @@ impl TrimOperations {
             ))?;
 
         if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
             return Err(DriveError::TRIMFailed(
-                format!("NVMe deallocate failed: {}", error)
+                format!("NVMe deallocate failed: {}", command_failure(&output))
             ));
         }
 
@@ -324,4 +343,75 @@ impl TrimOperations {
         println!("Secure TRIM completed");
         Ok(())
     }
+
+    /// Query DRAT/RZAT from the drive's IDENTIFY data
+    pub fn query_read_behavior(device_path: &str) -> DriveResult<TrimReadBehavior> {
+        let output = Command::new("hdparm")
+            .args(["-I", device_path])
+            .output()
+            .map_err(|e| DriveError::HardwareCommandFailed(
+                format!("Failed to query TRIM read behavior: {}", e)
+            ))?;
+
+        Ok(TrimReadBehavior::parse(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Read `samples` ranges of `range_len` bytes spread evenly across the
+    /// device and count how many are entirely zero. Returns (sampled, zero).
+    pub fn sample_zero_ranges(device_path: &str, size: u64, samples: u32, range_len: usize) -> DriveResult<(u32, u32)> {
+        let range_len = (range_len as u64).min(size) as usize;
+        if range_len == 0 || samples == 0 {
+            return Ok((0, 0));
+        }
+
+        let mut file = File::open(device_path)?;
+        let mut buffer = vec![0u8; range_len];
+        let span = size - range_len as u64;
+        let mut zero = 0;
+
+        for i in 0..samples as u64 {
+            let offset = if samples == 1 { 0 } else { span * i / (samples as u64 - 1) / 512 * 512 };
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut buffer)?;
+
+            if buffer.iter().all(|&b| b == 0) {
+                zero += 1;
+            }
+        }
+
+        Ok((samples, zero))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_trim_read_behavior() {
+        let rzat = "\t   *\tData Set Management TRIM supported (limit 8 blocks)\n\t   *\tDeterministic read ZEROs after TRIM\n";
+        assert_eq!(TrimReadBehavior::parse(rzat), TrimReadBehavior { deterministic: true, zeros: true });
+
+        let drat = "\t   *\tDeterministic read data after TRIM\n";
+        assert_eq!(TrimReadBehavior::parse(drat), TrimReadBehavior { deterministic: true, zeros: false });
+
+        assert_eq!(TrimReadBehavior::parse("\t   *\tSMART feature set\n"), TrimReadBehavior::default());
+    }
+
+    #[test]
+    fn test_sample_zero_ranges_counts_nonzero_ranges() {
+        let mut data = vec![0u8; 64 * 1024];
+        let len = data.len();
+        data[len - 1] = 0x5A;
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+
+        let (sampled, zero) = TrimOperations::sample_zero_ranges(
+            file.path().to_str().unwrap(), len as u64, 8, 4096,
+        ).unwrap();
+
+        assert_eq!(sampled, 8);
+        assert_eq!(zero, 7);
+    }
 }
@@ -0,0 +1,494 @@
+// Platform abstraction for finding, sizing and writing raw drives
+//
+// The orchestrator reaches drives through `DevicePlatform`, so the same wipe
+// logic runs on Linux (/dev, sysfs, hdparm/nvme) and Windows
+// (\\.\PhysicalDriveN, DeviceIoControl). Hardware erase commands rely on the
+// Linux tooling; on other platforms drives are overwritten.
+
+use crate::{DriveInfo, WriteMode};
+#[cfg(any(target_os = "windows", test))]
+use crate::DriveType;
+use super::detection::EnumerateOptions;
+use anyhow::Result;
+use std::fs::File;
+
+/// Platform-specific access to whole drives
+pub trait DevicePlatform: Send + Sync {
+    /// Every drive that could be wiped, filtered by `options`
+    fn enumerate_drives(&self, options: &EnumerateOptions) -> Result<Vec<DriveInfo>>;
+
+    /// Identify a single drive
+    fn detect_drive(&self, device_path: &str) -> Result<DriveInfo>;
+
+    /// Capacity in bytes
+    fn device_size(&self, device_path: &str) -> Result<u64>;
+
+    /// What the running system needs from this drive (mount points, the
+    /// Windows system volume); empty when it is safe to wipe
+    fn system_uses(&self, device_path: &str) -> Result<Vec<String>>;
+
+    /// Open the raw drive for writing, and for reading too when `read_back`
+    /// is set. `mode` picks how writes pass the OS cache; `WriteMode::Direct`
+    /// needs buffers, offsets and lengths aligned to the logical block size.
+    fn open_device(&self, device_path: &str, read_back: bool, mode: WriteMode) -> Result<File>;
+
+    /// Whether ATA/NVMe/SED hardware erase commands can be issued here
+    fn supports_hardware_erase(&self) -> bool;
+
+    /// Get platform name
+    fn platform_name(&self) -> &str;
+}
+
+// ============= LINUX IMPLEMENTATION =============
+
+/// Linux block devices, through sysfs, ioctls and the hdparm/nvme tools.
+/// Also used on other Unix-likes, where detection is more limited.
+#[cfg(not(target_os = "windows"))]
+pub struct LinuxPlatform;
+
+#[cfg(not(target_os = "windows"))]
+impl DevicePlatform for LinuxPlatform {
+    fn enumerate_drives(&self, options: &EnumerateOptions) -> Result<Vec<DriveInfo>> {
+        super::DriveDetector::enumerate_drives(options)
+    }
+
+    fn detect_drive(&self, device_path: &str) -> Result<DriveInfo> {
+        super::DriveDetector::detect_drive(device_path)
+    }
+
+    fn device_size(&self, device_path: &str) -> Result<u64> {
+        super::DriveDetector::get_block_device_size(device_path)
+    }
+
+    fn system_uses(&self, device_path: &str) -> Result<Vec<String>> {
+        super::DriveDetector::system_mounts_on_device(device_path)
+    }
+
+    fn open_device(&self, device_path: &str, read_back: bool, mode: WriteMode) -> Result<File> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let flags = match mode {
+            WriteMode::Buffered => 0,
+            WriteMode::Sync => libc::O_SYNC,
+            #[cfg(target_os = "linux")]
+            WriteMode::Direct => libc::O_DIRECT | libc::O_SYNC,
+            // No O_DIRECT here; synchronous writes are the closest match
+            #[cfg(not(target_os = "linux"))]
+            WriteMode::Direct => libc::O_SYNC,
+        };
+
+        Ok(std::fs::OpenOptions::new()
+            .read(read_back)
+            .write(true)
+            .custom_flags(flags)
+            .open(device_path)?)
+    }
+
+    fn supports_hardware_erase(&self) -> bool {
+        true
+    }
+
+    fn platform_name(&self) -> &str {
+        "Linux"
+    }
+}
+
+// ============= WINDOWS IMPLEMENTATION =============
+
+/// Windows physical drives (`\\.\PhysicalDriveN`), identified with
+/// IOCTL_STORAGE_QUERY_PROPERTY. Only overwrites are supported.
+#[cfg(target_os = "windows")]
+pub struct WindowsPlatform;
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::*;
+    use crate::{DriveCapabilities, EncryptionStatus};
+    use anyhow::anyhow;
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::ioapiset::DeviceIoControl;
+    use winapi::um::winioctl::{
+        FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME, IOCTL_DISK_GET_LENGTH_INFO,
+        IOCTL_STORAGE_GET_DEVICE_NUMBER, IOCTL_STORAGE_QUERY_PROPERTY,
+        IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, StorageDeviceProperty,
+        StorageDeviceSeekPenaltyProperty, StorageDeviceTrimProperty,
+    };
+
+    /// Physical drive numbers probed when enumerating
+    const MAX_PHYSICAL_DRIVES: u32 = 64;
+
+    const FILE_SHARE_READ_WRITE: u32 = 0x1 | 0x2;
+    const FILE_FLAG_WRITE_THROUGH: u32 = 0x8000_0000;
+    const FILE_FLAG_NO_BUFFERING: u32 = 0x2000_0000;
+
+    impl DevicePlatform for WindowsPlatform {
+        fn enumerate_drives(&self, options: &EnumerateOptions) -> Result<Vec<DriveInfo>> {
+            let mut drives = Vec::new();
+
+            // Numbering can have gaps after hot-unplug, so probe them all
+            for number in 0..MAX_PHYSICAL_DRIVES {
+                let device_path = format!(r"\\.\PhysicalDrive{}", number);
+                let Ok(descriptor) = query_descriptor(&device_path) else {
+                    continue;
+                };
+                if descriptor.removable && !options.include_removable {
+                    continue;
+                }
+                // When in doubt, treat the disk as a system disk
+                let is_system = self.system_uses(&device_path).map_or(true, |uses| !uses.is_empty());
+                if is_system && !options.include_system {
+                    continue;
+                }
+
+                match self.detect_drive(&device_path) {
+                    Ok(info) => drives.push(info),
+                    Err(e) => eprintln!("Warning: Failed to identify {}: {}", device_path, e),
+                }
+            }
+
+            Ok(drives)
+        }
+
+        fn detect_drive(&self, device_path: &str) -> Result<DriveInfo> {
+            let descriptor = query_descriptor(device_path)?;
+            let device = open_for_query(device_path)?;
+
+            let seek_penalty = query_property(&device, StorageDeviceSeekPenaltyProperty)
+                .ok()
+                .and_then(|buf| parse_flag_descriptor(&buf));
+            let trim_support = query_property(&device, StorageDeviceTrimProperty)
+                .ok()
+                .and_then(|buf| parse_flag_descriptor(&buf))
+                .unwrap_or(false);
+
+            Ok(DriveInfo {
+                device_path: device_path.to_string(),
+                model: descriptor.model,
+                serial: descriptor.serial,
+                size: self.device_size(device_path)?,
+                drive_type: drive_type_for_bus(descriptor.bus_type, seek_penalty),
+                encryption_status: EncryptionStatus::None,
+                capabilities: DriveCapabilities {
+                    trim_support,
+                    ..Default::default()
+                },
+                health_status: None,
+                temperature_celsius: None,
+            })
+        }
+
+        fn device_size(&self, device_path: &str) -> Result<u64> {
+            let device = OpenOptions::new()
+                .read(true)
+                .share_mode(FILE_SHARE_READ_WRITE)
+                .open(device_path)?;
+
+            let mut length = [0u8; 8];
+            ioctl(&device, IOCTL_DISK_GET_LENGTH_INFO, &[], &mut length)?;
+            Ok(u64::from_le_bytes(length))
+        }
+
+        fn system_uses(&self, device_path: &str) -> Result<Vec<String>> {
+            let disk = disk_number(device_path)?;
+            let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+
+            let volume = open_for_query(&format!(r"\\.\{}", system_drive))?;
+            let mut extents = [0u8; 8 + 24 * 32];
+            ioctl(&volume, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, &[], &mut extents)?;
+
+            Ok(if parse_volume_disk_numbers(&extents).contains(&disk) {
+                vec![format!("system volume {}", system_drive)]
+            } else {
+                Vec::new()
+            })
+        }
+
+        fn open_device(&self, device_path: &str, read_back: bool, mode: WriteMode) -> Result<File> {
+            // Writes into a mounted volume's extents are refused, so take its
+            // volumes offline first
+            let disk = disk_number(device_path)?;
+            for letter in b'A'..=b'Z' {
+                let volume_path = format!(r"\\.\{}:", letter as char);
+                let Ok(volume) = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .share_mode(FILE_SHARE_READ_WRITE)
+                    .open(&volume_path)
+                else {
+                    continue;
+                };
+
+                let mut extents = [0u8; 8 + 24 * 32];
+                if ioctl(&volume, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, &[], &mut extents).is_err()
+                    || !parse_volume_disk_numbers(&extents).contains(&disk)
+                {
+                    continue;
+                }
+
+                ioctl(&volume, FSCTL_LOCK_VOLUME, &[], &mut [])
+                    .map_err(|e| anyhow!("Failed to lock {} on {}: {}", volume_path, device_path, e))?;
+                ioctl(&volume, FSCTL_DISMOUNT_VOLUME, &[], &mut [])
+                    .map_err(|e| anyhow!("Failed to dismount {} on {}: {}", volume_path, device_path, e))?;
+
+                // The lock lasts as long as the handle, so keep it until exit
+                std::mem::forget(volume);
+            }
+
+            // Physical drives are always written through the drive cache
+            let flags = match mode {
+                WriteMode::Buffered | WriteMode::Sync => FILE_FLAG_WRITE_THROUGH,
+                WriteMode::Direct => FILE_FLAG_WRITE_THROUGH | FILE_FLAG_NO_BUFFERING,
+            };
+
+            Ok(OpenOptions::new()
+                .read(read_back)
+                .write(true)
+                .share_mode(FILE_SHARE_READ_WRITE)
+                .custom_flags(flags)
+                .open(device_path)?)
+        }
+
+        fn supports_hardware_erase(&self) -> bool {
+            false
+        }
+
+        fn platform_name(&self) -> &str {
+            "Windows"
+        }
+    }
+
+    /// Open without read/write access, enough for property queries
+    fn open_for_query(path: &str) -> io::Result<File> {
+        OpenOptions::new()
+            .access_mode(0)
+            .share_mode(FILE_SHARE_READ_WRITE)
+            .open(path)
+    }
+
+    fn query_descriptor(device_path: &str) -> Result<StorageDescriptor> {
+        let device = open_for_query(device_path)?;
+        let buf = query_property(&device, StorageDeviceProperty)?;
+        parse_storage_descriptor(&buf)
+            .ok_or_else(|| anyhow!("Malformed storage descriptor for {}", device_path))
+    }
+
+    /// IOCTL_STORAGE_QUERY_PROPERTY with a standard query for `property`
+    fn query_property(device: &File, property: u32) -> io::Result<Vec<u8>> {
+        // STORAGE_PROPERTY_QUERY: PropertyId, QueryType (standard), AdditionalParameters
+        let mut query = [0u8; 12];
+        query[..4].copy_from_slice(&property.to_le_bytes());
+
+        let mut buf = vec![0u8; 1024];
+        let len = ioctl(device, IOCTL_STORAGE_QUERY_PROPERTY, &query, &mut buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    /// Number N of `\\.\PhysicalDriveN`
+    fn disk_number(device_path: &str) -> Result<u32> {
+        let device = open_for_query(device_path)?;
+        // STORAGE_DEVICE_NUMBER: DeviceType, DeviceNumber, PartitionNumber
+        let mut number = [0u8; 12];
+        ioctl(&device, IOCTL_STORAGE_GET_DEVICE_NUMBER, &[], &mut number)?;
+        Ok(u32::from_le_bytes(number[4..8].try_into().unwrap()))
+    }
+
+    fn ioctl(device: &File, code: u32, input: &[u8], output: &mut [u8]) -> io::Result<usize> {
+        let mut returned = 0u32;
+        let ok = unsafe {
+            DeviceIoControl(
+                device.as_raw_handle() as _,
+                code,
+                input.as_ptr() as *mut _,
+                input.len() as u32,
+                output.as_mut_ptr() as *mut _,
+                output.len() as u32,
+                &mut returned,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(returned as usize)
+    }
+}
+
+/// The platform this build talks to drives through
+pub fn get_device_platform() -> Box<dyn DevicePlatform> {
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(WindowsPlatform)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Box::new(LinuxPlatform)
+    }
+}
+
+// ============= WINDOWS STRUCTURE DECODING =============
+//
+// Kept free of Windows APIs so the layouts are tested on every platform.
+
+/// Fields of STORAGE_DEVICE_DESCRIPTOR used for detection
+#[cfg(any(target_os = "windows", test))]
+#[derive(Debug, Clone, PartialEq)]
+struct StorageDescriptor {
+    model: String,
+    serial: String,
+    removable: bool,
+    bus_type: u32,
+}
+
+/// STORAGE_BUS_TYPE values
+#[cfg(any(target_os = "windows", test))]
+mod bus_type {
+    pub const SCSI: u32 = 0x1;
+    pub const ATA: u32 = 0x3;
+    pub const USB: u32 = 0x7;
+    pub const RAID: u32 = 0x8;
+    pub const SAS: u32 = 0xA;
+    pub const SATA: u32 = 0xB;
+    pub const SD: u32 = 0xC;
+    pub const MMC: u32 = 0xD;
+    pub const NVME: u32 = 0x11;
+}
+
+/// Decode a STORAGE_DEVICE_DESCRIPTOR, whose strings are NUL-terminated and
+/// located by byte offsets from the start of the structure
+#[cfg(any(target_os = "windows", test))]
+fn parse_storage_descriptor(buf: &[u8]) -> Option<StorageDescriptor> {
+    let dword = |at: usize| Some(u32::from_le_bytes(buf.get(at..at + 4)?.try_into().ok()?));
+    let string = |offset: u32| -> Option<String> {
+        let start = offset as usize;
+        if start == 0 || start >= buf.len() {
+            return None;
+        }
+        let end = buf[start..].iter().position(|&b| b == 0).map_or(buf.len(), |len| start + len);
+        let text = String::from_utf8_lossy(&buf[start..end]).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    };
+
+    let vendor = string(dword(12)?);
+    let product = string(dword(16)?);
+    let model = match (vendor, product) {
+        (Some(vendor), Some(product)) => format!("{} {}", vendor, product),
+        (vendor, product) => product.or(vendor).unwrap_or_else(|| "Unknown".to_string()),
+    };
+
+    Some(StorageDescriptor {
+        model,
+        serial: string(dword(24)?).unwrap_or_else(|| "Unknown".to_string()),
+        removable: *buf.get(10)? != 0,
+        bus_type: dword(28)?,
+    })
+}
+
+/// The BOOLEAN after the Version/Size header of DEVICE_SEEK_PENALTY_DESCRIPTOR
+/// and DEVICE_TRIM_DESCRIPTOR
+#[cfg(any(target_os = "windows", test))]
+fn parse_flag_descriptor(buf: &[u8]) -> Option<bool> {
+    buf.get(8).map(|&flag| flag != 0)
+}
+
+/// Disk numbers in a VOLUME_DISK_EXTENTS: a count, then 24-byte DISK_EXTENTs
+/// (DiskNumber, StartingOffset, ExtentLength) aligned to 8 bytes
+#[cfg(any(target_os = "windows", test))]
+fn parse_volume_disk_numbers(buf: &[u8]) -> Vec<u32> {
+    let Some(count) = buf.get(..4).map(|b| u32::from_le_bytes(b.try_into().unwrap())) else {
+        return Vec::new();
+    };
+    (0..count as usize)
+        .map_while(|i| buf.get(8 + i * 24..12 + i * 24))
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        .collect()
+}
+
+/// Drive type from the bus, using the seek penalty to tell HDDs from SSDs
+#[cfg(any(target_os = "windows", test))]
+fn drive_type_for_bus(bus: u32, seek_penalty: Option<bool>) -> DriveType {
+    match bus {
+        bus_type::NVME => DriveType::NVMe,
+        bus_type::USB => DriveType::USB,
+        bus_type::RAID => DriveType::RAID,
+        bus_type::SD => DriveType::SDCard,
+        bus_type::MMC => DriveType::EMMC,
+        bus_type::SATA | bus_type::ATA | bus_type::SAS | bus_type::SCSI => match seek_penalty {
+            Some(false) => DriveType::SSD,
+            _ => DriveType::HDD,
+        },
+        _ => DriveType::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(vendor: &str, product: &str, serial: &str, removable: bool, bus: u32) -> Vec<u8> {
+        let mut buf = vec![0u8; 40];
+        buf[10] = removable as u8;
+        buf[28..32].copy_from_slice(&bus.to_le_bytes());
+        for (field, text) in [(12, vendor), (16, product), (24, serial)] {
+            if text.is_empty() {
+                continue;
+            }
+            let offset = buf.len() as u32;
+            buf[field..field + 4].copy_from_slice(&offset.to_le_bytes());
+            buf.extend_from_slice(text.as_bytes());
+            buf.push(0);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_parse_storage_descriptor() {
+        let parsed = parse_storage_descriptor(&descriptor("", "Samsung SSD 980", "S64DNX0R ", false, bus_type::NVME)).unwrap();
+        assert_eq!(parsed.model, "Samsung SSD 980");
+        assert_eq!(parsed.serial, "S64DNX0R");
+        assert!(!parsed.removable);
+        assert_eq!(parsed.bus_type, bus_type::NVME);
+
+        let usb = parse_storage_descriptor(&descriptor("SanDisk", "Ultra", "", true, bus_type::USB)).unwrap();
+        assert_eq!(usb.model, "SanDisk Ultra");
+        assert_eq!(usb.serial, "Unknown");
+        assert!(usb.removable);
+
+        assert!(parse_storage_descriptor(&[0u8; 16]).is_none());
+    }
+
+    #[test]
+    fn test_parse_volume_disk_numbers() {
+        let mut buf = vec![0u8; 8 + 24 * 2];
+        buf[..4].copy_from_slice(&2u32.to_le_bytes());
+        buf[8..12].copy_from_slice(&0u32.to_le_bytes());
+        buf[32..36].copy_from_slice(&3u32.to_le_bytes());
+        assert_eq!(parse_volume_disk_numbers(&buf), [0, 3]);
+        assert!(parse_volume_disk_numbers(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_drive_type_for_bus() {
+        assert_eq!(drive_type_for_bus(bus_type::NVME, None), DriveType::NVMe);
+        assert_eq!(drive_type_for_bus(bus_type::SATA, Some(false)), DriveType::SSD);
+        assert_eq!(drive_type_for_bus(bus_type::SATA, Some(true)), DriveType::HDD);
+        assert_eq!(drive_type_for_bus(bus_type::SATA, None), DriveType::HDD);
+        assert_eq!(drive_type_for_bus(bus_type::USB, None), DriveType::USB);
+        assert_eq!(drive_type_for_bus(bus_type::SD, None), DriveType::SDCard);
+        assert_eq!(drive_type_for_bus(bus_type::MMC, None), DriveType::EMMC);
+        assert_eq!(parse_flag_descriptor(&[8, 0, 0, 0, 12, 0, 0, 0, 1]), Some(true));
+    }
+
+    #[test]
+    fn test_current_platform_opens_regular_files() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        let platform = get_device_platform();
+        assert!(!platform.platform_name().is_empty());
+        platform.open_device(temp.path().to_str().unwrap(), true, WriteMode::Buffered).unwrap();
+        platform.open_device(temp.path().to_str().unwrap(), false, WriteMode::Sync).unwrap();
+    }
+}
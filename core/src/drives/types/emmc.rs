@@ -3,8 +3,44 @@
 // Support for embedded storage found in phones, tablets, and embedded systems
 
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, spawn_error, Command};
+use std::thread;
+use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
+use crate::{CancellationToken, DriveError};
+
+/// UFS query flag fPurgeEnable
+const UFS_FLAG_PURGE_ENABLE: u8 = 0x06;
+
+/// UFS query attribute bPurgeStatus
+const UFS_ATTR_PURGE_STATUS: u8 = 0x06;
+
+/// Delay between reads of bPurgeStatus while a purge runs
+const UFS_PURGE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Progress of a UFS purge as reported by bPurgeStatus
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UFSPurgeStatus {
+    Idle,
+    InProgress,
+    StoppedPrematurely,
+    Completed,
+    FailedQueueNotEmpty,
+    Failed,
+}
+
+impl UFSPurgeStatus {
+    pub fn from_attribute(value: u32) -> Self {
+        match value {
+            0x00 => UFSPurgeStatus::Idle,
+            0x01 => UFSPurgeStatus::InProgress,
+            0x02 => UFSPurgeStatus::StoppedPrematurely,
+            0x03 => UFSPurgeStatus::Completed,
+            0x04 => UFSPurgeStatus::FailedQueueNotEmpty,
+            _ => UFSPurgeStatus::Failed,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BootPartition {
@@ -100,8 +136,8 @@ impl EMMCDevice {
         })
     }
 
-    /// Perform eMMC secure erase (CMD38)
-    pub fn secure_erase(&self) -> Result<()> {
+    /// Perform eMMC secure erase (CMD38), killed at `cancel`'s deadline
+    pub fn secure_erase(&self, cancel: &CancellationToken) -> Result<()> {
         println!("Performing eMMC secure erase on {}", self.device_path);
 
         // Use mmc-utils secure erase
@@ -109,16 +145,16 @@ impl EMMCDevice {
             .arg("erase")
             .arg("secure")
             .arg(&self.device_path)
-            .output();
+            .deadline(cancel.deadline())
+            .output()
+            .map_err(|e| spawn_error("mmc", e))?;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                println!("eMMC secure erase completed");
-                return Ok(());
-            }
+        if output.status.success() {
+            println!("eMMC secure erase completed");
+            return Ok(());
         }
 
-        Err(anyhow!("eMMC secure erase failed"))
+        Err(anyhow!("eMMC secure erase failed ({})", command_failure(&output)))
     }
 
     /// TRIM operation
@@ -138,23 +174,48 @@ impl EMMCDevice {
         Ok(())
     }
 
-    /// Sanitize operation
-    pub fn sanitize(&self) -> Result<()> {
+    /// Sanitize operation, killed at `cancel`'s deadline
+    pub fn sanitize(&self, cancel: &CancellationToken) -> Result<()> {
         println!("Performing eMMC sanitize");
 
         let output = Command::new("mmc")
             .arg("sanitize")
             .arg(&self.device_path)
-            .output();
+            .deadline(cancel.deadline())
+            .output()
+            .map_err(|e| spawn_error("mmc", e))?;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                println!("eMMC sanitize completed");
-                return Ok(());
-            }
+        if output.status.success() {
+            println!("eMMC sanitize completed");
+            return Ok(());
         }
 
-        Err(anyhow!("eMMC sanitize failed"))
+        Err(anyhow!("eMMC sanitize failed ({})", command_failure(&output)))
+    }
+
+    /// Block device of a boot partition; the kernel numbers them from 0
+    /// (Boot1 is `/dev/mmcblk0boot0`)
+    pub fn boot_partition_path(&self, boot: &BootPartition) -> String {
+        format!("{}boot{}", self.device_path, boot.partition_number.saturating_sub(1))
+    }
+
+    /// Read a boot partition's `force_ro` flag, which the kernel sets by
+    /// default to protect the bootloader. None when the sysfs entry is missing.
+    pub fn boot_force_ro(boot_device: &str) -> Option<bool> {
+        std::fs::read_to_string(Self::force_ro_path(boot_device))
+            .ok()
+            .map(|value| value.trim() == "1")
+    }
+
+    /// Set or clear a boot partition's `force_ro` flag
+    pub fn set_boot_force_ro(boot_device: &str, read_only: bool) -> Result<()> {
+        std::fs::write(Self::force_ro_path(boot_device), if read_only { "1" } else { "0" })
+            .map_err(|e| anyhow!("Failed to set force_ro on {}: {}", boot_device, e))
+    }
+
+    fn force_ro_path(boot_device: &str) -> String {
+        let name = boot_device.rsplit('/').next().unwrap_or(boot_device);
+        format!("/sys/block/{}/force_ro", name)
     }
 
     /// Wipe boot partitions
@@ -162,7 +223,7 @@ impl EMMCDevice {
         println!("Wiping eMMC boot partitions");
 
         for boot in &self.boot_partitions {
-            let boot_dev = format!("{}boot{}", self.device_path, boot.partition_number);
+            let boot_dev = self.boot_partition_path(boot);
             println!("  Wiping {}", boot_dev);
 
             let _ = Command::new("dd")
@@ -188,17 +249,17 @@ impl EMMCDevice {
     }
 
     /// Wipe entire eMMC device
-    pub fn wipe_emmc(&self) -> Result<()> {
+    pub fn wipe_emmc(&self, cancel: &CancellationToken) -> Result<()> {
         println!("Starting eMMC wipe: {}", self.device_path);
 
         // Try sanitize first (most thorough)
-        if self.sanitize().is_ok() {
+        if self.sanitize(cancel).is_ok() {
             self.handle_rpmb()?;
             return Ok(());
         }
 
         // Fallback to secure erase
-        if self.secure_erase().is_ok() {
+        if self.secure_erase(cancel).is_ok() {
             self.wipe_boot_partitions()?;
             self.handle_rpmb()?;
             return Ok(());
@@ -236,24 +297,109 @@ impl UFSDevice {
 
     /// UFS purge command
     pub fn purge(&self) -> Result<()> {
-        println!("Performing UFS purge on {}", self.device_path);
+        Self::purge_device(&self.device_path, &CancellationToken::new())
+    }
 
-        // UFS purge is vendor-specific
-        // Most implementations use UNMAP with specific flags
+    /// Issue SCSI FORMAT UNIT via `sg_format`, which waits for the device to
+    /// report completion and fails if the command is rejected, or is killed
+    /// at `cancel`'s deadline
+    pub fn format_unit(device_path: &str, cancel: &CancellationToken) -> Result<()> {
+        println!("Issuing SCSI FORMAT UNIT on {}", device_path);
+
+        let output = Command::new("sg_format")
+            .args(["--format", "--quick", device_path])
+            .deadline(cancel.deadline())
+            .output()
+            .map_err(|e| anyhow!("sg_format unavailable: {}", e))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "FORMAT UNIT rejected: {}",
+                command_failure(&output)
+            ));
+        }
 
-        let output = Command::new("sg_unmap")
-            .arg("--all")
-            .arg(&self.device_path)
-            .output();
+        println!("FORMAT UNIT completed");
+        Ok(())
+    }
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                println!("UFS purge completed");
-                return Ok(());
+    /// UFS PURGE: unmap every LBA, then set fPurgeEnable so the device
+    /// physically erases the discarded blocks, and poll bPurgeStatus until
+    /// it reports success. Cancelling stops the wait; the device finishes
+    /// the purge on its own.
+    pub fn purge_device(device_path: &str, cancel: &CancellationToken) -> Result<()> {
+        println!("Performing UFS purge on {}", device_path);
+
+        let bsg = Self::find_bsg_node()
+            .ok_or_else(|| anyhow!("No UFS BSG node (/dev/bsg/ufs-bsg*) for query requests"))?;
+
+        // Purge only erases unmapped blocks, so discard everything first
+        let output = Command::new("blkdiscard")
+            .arg(device_path)
+            .deadline(cancel.deadline())
+            .output()
+            .map_err(|e| anyhow!("blkdiscard unavailable: {}", e))?;
+        if !output.status.success() {
+            return Err(anyhow!("Unmap before purge failed: {}", command_failure(&output)));
+        }
+
+        let output = Command::new("ufs-utils")
+            .args(["fl", "-t", &UFS_FLAG_PURGE_ENABLE.to_string(), "-e", "-p", &bsg])
+            .deadline(cancel.deadline())
+            .output()
+            .map_err(|e| anyhow!("ufs-utils unavailable: {}", e))?;
+        if !output.status.success() {
+            return Err(anyhow!("Setting fPurgeEnable rejected: {}", command_failure(&output)));
+        }
+
+        loop {
+            match Self::purge_status(&bsg, cancel.deadline())? {
+                UFSPurgeStatus::Completed => {
+                    println!("UFS purge completed");
+                    return Ok(());
+                }
+                UFSPurgeStatus::InProgress if cancel.is_cancelled() => {
+                    println!("🛑 Cancelled - the UFS purge continues in the device");
+                    return Err(DriveError::Cancelled.into());
+                }
+                UFSPurgeStatus::InProgress => thread::sleep(UFS_PURGE_POLL_INTERVAL),
+                status => return Err(anyhow!("UFS purge did not complete: {:?}", status)),
             }
         }
+    }
+
+    /// Read bPurgeStatus through the UFS BSG node
+    fn purge_status(bsg: &str, deadline: Option<Instant>) -> Result<UFSPurgeStatus> {
+        let output = Command::new("ufs-utils")
+            .args(["attr", "-t", &UFS_ATTR_PURGE_STATUS.to_string(), "-p", bsg])
+            .deadline(deadline)
+            .output()?;
+        if !output.status.success() {
+            return Err(anyhow!("Reading bPurgeStatus failed: {}", command_failure(&output)));
+        }
 
-        Err(anyhow!("UFS purge failed"))
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Self::parse_attribute_value(&stdout)
+            .map(UFSPurgeStatus::from_attribute)
+            .ok_or_else(|| anyhow!("Unrecognized bPurgeStatus output: {}", stdout.trim()))
+    }
+
+    /// Last number on the last non-empty line of `ufs-utils attr` output
+    fn parse_attribute_value(output: &str) -> Option<u32> {
+        let line = output.lines().rev().find(|line| !line.trim().is_empty())?;
+        let token = line.split(|c: char| c.is_whitespace() || c == '=' || c == ':').rfind(|t| !t.is_empty())?;
+        match token.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => token.parse().ok(),
+        }
+    }
+
+    fn find_bsg_node() -> Option<String> {
+        std::fs::read_dir("/dev/bsg").ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .find(|name| name.starts_with("ufs-bsg"))
+            .map(|name| format!("/dev/bsg/{}", name))
     }
 
     /// Wipe UFS device
@@ -273,3 +419,31 @@ impl UFSDevice {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_status_from_attribute() {
+        assert_eq!(UFSPurgeStatus::from_attribute(0x01), UFSPurgeStatus::InProgress);
+        assert_eq!(UFSPurgeStatus::from_attribute(0x03), UFSPurgeStatus::Completed);
+        assert_eq!(UFSPurgeStatus::from_attribute(0x05), UFSPurgeStatus::Failed);
+    }
+
+    #[test]
+    fn test_boot_partition_paths() {
+        let emmc = EMMCDevice::get_configuration("/dev/mmcblk0").unwrap();
+        let paths: Vec<String> = emmc.boot_partitions.iter().map(|b| emmc.boot_partition_path(b)).collect();
+
+        assert_eq!(paths, ["/dev/mmcblk0boot0", "/dev/mmcblk0boot1"]);
+        assert_eq!(EMMCDevice::force_ro_path("/dev/mmcblk0boot1"), "/sys/block/mmcblk0boot1/force_ro");
+    }
+
+    #[test]
+    fn test_parse_attribute_value() {
+        assert_eq!(UFSDevice::parse_attribute_value("bPurgeStatus := 0x03\n"), Some(3));
+        assert_eq!(UFSDevice::parse_attribute_value("Attribute 6: 1\n\n"), Some(1));
+        assert_eq!(UFSDevice::parse_attribute_value(""), None);
+    }
+}
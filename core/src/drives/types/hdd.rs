@@ -1,5 +1,5 @@
 use anyhow::Result;
-use std::process::Command;
+use crate::commands::{exit_description, Command};
 use std::time::Duration;
 use std::thread;
 use crate::ui::progress::ProgressBar;
@@ -49,7 +49,7 @@ impl HDDWipe {
                         println!("\nHardware secure erase completed successfully");
                         return Ok(());
                     } else {
-                        return Err(anyhow::anyhow!("Hardware secure erase failed"));
+                        return Err(anyhow::anyhow!("Hardware secure erase failed ({})", exit_description(status)));
                     }
                 }
                 None => {
@@ -4,7 +4,7 @@
 // Both portions must be wiped separately to ensure complete data destruction
 
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use serde::{Serialize, Deserialize};
 
 /// HDD portion information
@@ -164,7 +164,7 @@ impl HybridDrive {
             .output()?;
 
         if !output.status.success() {
-            return Err(anyhow!("Cache flush failed"));
+            return Err(anyhow!("Cache flush failed ({})", command_failure(&output)));
         }
 
         // Wait for flush to complete
@@ -219,7 +219,7 @@ impl HybridDrive {
             self.try_generic_cache_disable()?;
             Ok(())
         } else {
-            Err(anyhow!("Seagate cache disable failed"))
+            Err(anyhow!("Seagate cache disable failed ({})", command_failure(&output)))
         }
     }
 
@@ -249,7 +249,7 @@ impl HybridDrive {
         if output.status.success() {
             Ok(())
         } else {
-            Err(anyhow!("hdparm cache disable failed"))
+            Err(anyhow!("hdparm cache disable failed ({})", command_failure(&output)))
         }
     }
 
@@ -13,13 +13,15 @@ pub mod optane;    // Intel Optane / 3D XPoint
 pub mod hybrid;    // Hybrid SSHD drives
 pub mod emmc;      // eMMC/UFS embedded storage
 pub mod raid;      // RAID array handling
+pub mod sas;       // SAS/SCSI drives
 
 // Re-exports for convenience
 pub use hdd::HDDWipe;
 pub use ssd::SSDWipe;
-pub use nvme::{NVMeWipe, NVMeAdvanced, NVMeNamespace, NamespaceType, ZNSZone, ZNSZoneState};
-pub use smr::{SMRDrive, Zone, ZoneType, ZoneCondition, ZoneModel};
+pub use nvme::{NVMeWipe, NVMeAdvanced, NVMeNamespace, NamespaceFormat, NamespaceType, ZNSZone, ZNSZoneState};
+pub use smr::{SMRDrive, Zone, ZoneType, ZoneCondition, ZoneModel, ZoneWipeSummary};
 pub use optane::{OptaneDrive, OptaneMode, OptaneNamespace};
 pub use hybrid::{HybridDrive, HDDInfo, SSDCacheInfo, PinnedRegion};
-pub use emmc::{EMMCDevice, BootPartition, RPMBPartition, UserDataArea, UFSDevice, UFSLogicalUnit};
+pub use emmc::{EMMCDevice, BootPartition, RPMBPartition, UserDataArea, UFSDevice, UFSLogicalUnit, UFSPurgeStatus};
 pub use raid::{RAIDArray, RAIDType, RAIDController, MetadataRegion, MetadataLocation};
+pub use sas::{SASDrive, ScsiSanitizeAction};
@@ -3,7 +3,7 @@
 // This module extends basic NVMe support with modern advanced features
 
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use serde::{Serialize, Deserialize};
 
 /// NVMe namespace type
@@ -56,11 +56,31 @@ pub struct ZNSZone {
     pub is_sequential: bool,
 }
 
+impl ZNSZoneState {
+    /// State from the `State:` column of `nvme zns report-zones`
+    fn parse(state: &str) -> Option<Self> {
+        Some(match state.to_ascii_uppercase().as_str() {
+            "EMPTY" => ZNSZoneState::Empty,
+            "IMP_OPENED" | "IMPLICITLY_OPENED" => ZNSZoneState::ImplicitlyOpen,
+            "EXP_OPENED" | "EXPLICITLY_OPENED" => ZNSZoneState::ExplicitlyOpen,
+            "CLOSED" => ZNSZoneState::Closed,
+            "READONLY" | "READ_ONLY" => ZNSZoneState::ReadOnly,
+            "FULL" => ZNSZoneState::Full,
+            "OFFLINE" => ZNSZoneState::Offline,
+            _ => return None,
+        })
+    }
+}
+
 impl ZNSZone {
-    /// Check if zone needs reset before writing
+    /// Check if zone needs reset before writing: a sequential zone only
+    /// accepts writes at its write pointer, so anything already written
+    /// (open, closed or full) has to be reset to rewrite it from the start
     pub fn needs_reset(&self) -> bool {
-        matches!(self.zone_state,
-            ZNSZoneState::Full | ZNSZoneState::Closed)
+        self.is_sequential
+            && matches!(self.zone_state,
+                ZNSZoneState::ImplicitlyOpen | ZNSZoneState::ExplicitlyOpen |
+                ZNSZoneState::Closed | ZNSZoneState::Full)
     }
 
     /// Check if zone is writable
@@ -95,6 +115,128 @@ pub struct NVMeNamespace {
 
     /// ZNS zones (if ZNS namespace)
     pub zones: Option<Vec<ZNSZone>>,
+
+    /// Bytes per LBA in the active LBA format; zone addresses are in LBAs
+    #[serde(default = "default_lba_size")]
+    pub lba_size: u32,
+
+    /// Active LBA format with its metadata settings, when id-ns reported it
+    #[serde(default)]
+    pub format: Option<NamespaceFormat>,
+}
+
+fn default_lba_size() -> u32 {
+    512
+}
+
+/// Bytes of end-to-end protection information per block (16-bit guard,
+/// application tag, reference tag)
+const PI_BYTES: u16 = 8;
+
+/// Active LBA format of a namespace: the block size plus the metadata, and
+/// any end-to-end protection information (PI), stored with every block
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceFormat {
+    /// Index of the LBA format in use (FLBAS)
+    pub lbaf: u8,
+    pub lba_size: u32,
+    /// Metadata bytes per block (MS)
+    pub metadata_size: u16,
+    /// Metadata travels at the end of each block (extended LBA) rather
+    /// than in a separate buffer
+    pub extended: bool,
+    /// Protection information type 1-3, or 0 when PI is off (DPS)
+    pub protection_type: u8,
+    /// PI sits in the first bytes of the metadata rather than the last
+    pub pi_first: bool,
+}
+
+impl NamespaceFormat {
+    /// Read the active format of the namespace at `device_path`
+    pub fn query(device_path: &str) -> Result<Option<Self>> {
+        let output = Command::new("nvme").arg("id-ns").arg(device_path).run()?;
+        Ok(Self::parse(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Format from `nvme id-ns` output: FLBAS picks the LBA format, whose
+    /// line gives the block and metadata sizes ("lbaf  1 : ms:8 lbads:9
+    /// rp:0 (in use)"), and DPS the protection information settings
+    pub fn parse(id_ns: &str) -> Option<Self> {
+        let field = |name: &str| {
+            id_ns.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == name).then(|| parse_number(value.trim())).flatten()
+            })
+        };
+
+        let flbas = field("flbas");
+        let lbaf_line = |line: &&str| {
+            let (key, _) = line.split_once(':')?;
+            key.trim().strip_prefix("lbaf")?.trim().parse::<u8>().ok()
+        };
+        // FLBAS bits 0-3, extended by bits 5-6 beyond 16 formats
+        let (lbaf, line) = match flbas {
+            Some(flbas) => {
+                let index = (flbas & 0xF) as u8 | (((flbas >> 5) & 0x3) as u8) << 4;
+                id_ns.lines().find(|line| lbaf_line(line) == Some(index)).map(|line| (index, line))?
+            }
+            None => id_ns
+                .lines()
+                .find(|line| line.contains("(in use)"))
+                .and_then(|line| Some((lbaf_line(&line)?, line)))?,
+        };
+
+        let value = |name: &str| line.split_whitespace().find_map(|field| field.strip_prefix(name)).and_then(parse_number);
+        let lbads = value("lbads:").filter(|shift| (9..=16).contains(shift))?;
+        let dps = field("dps").unwrap_or(0);
+
+        Some(Self {
+            lbaf,
+            lba_size: 1 << lbads,
+            metadata_size: value("ms:").unwrap_or(0) as u16,
+            extended: flbas.is_some_and(|flbas| flbas & 0x10 != 0),
+            protection_type: (dps & 0x7) as u8,
+            pi_first: dps & 0x8 != 0,
+        })
+    }
+
+    /// Whether blocks carry metadata at all
+    pub fn has_metadata(&self) -> bool {
+        self.metadata_size > 0
+    }
+
+    /// Whether overwriting every block also replaces its metadata. The host
+    /// regenerates PI for each block written, but any other metadata bytes
+    /// are left as they were; only a format or sanitize erases those.
+    pub fn overwrite_covers_metadata(&self) -> bool {
+        !self.has_metadata() || (self.protection_type != 0 && self.metadata_size == PI_BYTES)
+    }
+
+    /// `nvme format` arguments that keep this format. Left out, nvme-cli
+    /// may fall back to LBA format 0 with PI off, changing the block size
+    /// and stripping protection the host relies on.
+    pub fn format_args(&self) -> Vec<String> {
+        vec![
+            format!("--lbaf={}", self.lbaf),
+            format!("--ms={}", u8::from(self.extended)),
+            format!("--pi={}", self.protection_type),
+            format!("--pil={}", u8::from(self.pi_first)),
+        ]
+    }
+}
+
+impl std::fmt::Display for NamespaceFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LBA format {}: {} bytes", self.lbaf, self.lba_size)?;
+        if self.has_metadata() {
+            let layout = if self.extended { "extended" } else { "separate" };
+            write!(f, " + {} bytes metadata ({})", self.metadata_size, layout)?;
+        }
+        match self.protection_type {
+            0 => Ok(()),
+            pi => write!(f, ", PI type {}", pi),
+        }
+    }
 }
 
 /// Advanced NVMe Drive configuration
@@ -156,7 +298,7 @@ impl NVMeAdvanced {
             .output()?;
 
         if !output.status.success() {
-            return Err(anyhow!("Failed to get controller info"));
+            return Err(anyhow!("Failed to get controller info ({})", command_failure(&output)));
         }
 
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
@@ -327,7 +469,9 @@ impl NVMeAdvanced {
         let stdout = String::from_utf8_lossy(&output.stdout);
 
         // Parse size
-        let size = Self::parse_namespace_size(&stdout);
+        let lba_size = Self::parse_lba_size(&stdout);
+        let size = Self::parse_namespace_size(&stdout, lba_size);
+        let format = NamespaceFormat::parse(&stdout);
 
         // Detect namespace type
         let namespace_type = Self::detect_namespace_type(&device_path)?;
@@ -347,6 +491,8 @@ impl NVMeAdvanced {
             is_active: true,
             is_attached: true,
             zones,
+            lba_size,
+            format,
         })
     }
 
@@ -360,16 +506,18 @@ impl NVMeAdvanced {
             is_active: true,
             is_attached: true,
             zones: None,
+            lba_size: default_lba_size(),
+            format: None,
         })
     }
 
     /// Parse namespace size from id-ns output
-    fn parse_namespace_size(output: &str) -> u64 {
+    fn parse_namespace_size(output: &str, lba_size: u32) -> u64 {
         for line in output.lines() {
             if line.contains("nsze") && line.contains(":") {
                 if let Some(size_str) = line.split(':').nth(1) {
-                    if let Ok(blocks) = size_str.trim().parse::<u64>() {
-                        return blocks * 512;  // Assume 512-byte blocks
+                    if let Some(blocks) = parse_number(size_str.trim()) {
+                        return blocks * lba_size as u64;
                     }
                 }
             }
@@ -377,6 +525,19 @@ impl NVMeAdvanced {
         0
     }
 
+    /// LBA size of the format marked "(in use)" in id-ns output, e.g.
+    /// "lbaf  1 : ms:0   lbads:12 rp:0 (in use)" is 4096 bytes
+    fn parse_lba_size(output: &str) -> u32 {
+        output
+            .lines()
+            .filter(|line| line.trim_start().starts_with("lbaf") && line.contains("(in use)"))
+            .find_map(|line| {
+                let lbads = line.split_whitespace().find_map(|field| field.strip_prefix("lbads:"))?;
+                lbads.parse::<u32>().ok().filter(|shift| (9..=16).contains(shift))
+            })
+            .map_or(default_lba_size(), |shift| 1 << shift)
+    }
+
     /// Detect namespace type
     fn detect_namespace_type(device_path: &str) -> Result<NamespaceType> {
         // Check for ZNS
@@ -399,7 +560,7 @@ impl NVMeAdvanced {
     }
 
     /// Get ZNS zones for a namespace
-    fn get_zns_zones(device_path: &str) -> Result<Vec<ZNSZone>> {
+    pub(crate) fn get_zns_zones(device_path: &str) -> Result<Vec<ZNSZone>> {
         let output = Command::new("nvme")
             .arg("zns")
             .arg("report-zones")
@@ -423,13 +584,24 @@ impl NVMeAdvanced {
             if line.contains("SLBA:") {
                 // Parse zone info
                 // Example: "SLBA: 0x0 WP: 0x0 Cap: 0x10000 State: EMPTY Type: SEQWRITE_REQ"
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let field = |name: &str| {
+                    fields.iter().position(|f| *f == name).and_then(|i| fields.get(i + 1)).copied()
+                };
+                let number = |name: &str| {
+                    field(name)
+                        .and_then(parse_number)
+                        .ok_or_else(|| anyhow!("Unparseable {} in zone report line: {}", name, line.trim()))
+                };
 
                 let zone = ZNSZone {
                     zone_id,
-                    zone_start_lba: 0,  // Would parse from SLBA
-                    zone_capacity: 0,    // Would parse from Cap
-                    write_pointer: 0,    // Would parse from WP
-                    zone_state: ZNSZoneState::Empty,  // Would parse from State
+                    zone_start_lba: number("SLBA:")?,
+                    zone_capacity: number("Cap:")?,
+                    write_pointer: number("WP:")?,
+                    zone_state: field("State:")
+                        .and_then(ZNSZoneState::parse)
+                        .ok_or_else(|| anyhow!("Unknown zone state in zone report line: {}", line.trim()))?,
                     is_sequential: line.contains("SEQWRITE"),
                 };
 
@@ -441,25 +613,25 @@ impl NVMeAdvanced {
         Ok(zones)
     }
 
-    /// Reset ZNS zone
-    pub fn zns_reset_zone(&self, ns: &NVMeNamespace, zone_id: u32) -> Result<()> {
+    /// Reset ZNS zone, moving its write pointer back to the zone start
+    pub fn zns_reset_zone(&self, ns: &NVMeNamespace, zone: &ZNSZone) -> Result<()> {
         if ns.namespace_type != NamespaceType::ZonedNamespace {
             return Err(anyhow!("Not a ZNS namespace"));
         }
 
-        println!("Resetting ZNS zone {} on {}", zone_id, ns.device_path);
+        println!("Resetting ZNS zone {} on {}", zone.zone_id, ns.device_path);
 
+        // The zone is addressed by its starting LBA, not its index
         let output = Command::new("nvme")
             .arg("zns")
             .arg("reset-zone")
             .arg(&ns.device_path)
             .arg("-s")
-            .arg(zone_id.to_string())
+            .arg(format!("{:#x}", zone.zone_start_lba))
             .output()?;
 
         if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("ZNS zone reset failed: {}", err));
+            return Err(anyhow!("ZNS zone reset failed: {}", command_failure(&output)));
         }
 
         Ok(())
@@ -469,8 +641,8 @@ impl NVMeAdvanced {
     pub fn zns_reset_all_zones(&self, ns: &NVMeNamespace) -> Result<()> {
         if let Some(ref zones) = ns.zones {
             for zone in zones {
-                if zone.is_sequential {
-                    self.zns_reset_zone(ns, zone.zone_id)?;
+                if zone.needs_reset() {
+                    self.zns_reset_zone(ns, zone)?;
                 }
             }
         }
@@ -503,18 +675,10 @@ impl NVMeAdvanced {
     fn wipe_zns_namespace(&self, ns: &NVMeNamespace) -> Result<()> {
         println!("Wiping ZNS namespace (zone-aware)");
 
-        // Reset all zones first
+        // Reset all zones first, so nothing is readable past the write
+        // pointers, then erase the media behind them
         self.zns_reset_all_zones(ns)?;
-
-        // Write to each zone sequentially
-        if let Some(ref zones) = ns.zones {
-            for zone in zones {
-                if zone.is_sequential {
-                    println!("  Writing to zone {}...", zone.zone_id);
-                    // Would write sequential data to zone
-                }
-            }
-        }
+        self.format_namespace(ns)?;
 
         println!("ZNS namespace wiped");
         Ok(())
@@ -553,16 +717,17 @@ impl NVMeAdvanced {
     fn format_namespace(&self, ns: &NVMeNamespace) -> Result<()> {
         println!("Formatting namespace {} with secure erase", ns.nsid);
 
+        // Keep the block size and metadata/PI settings the namespace has
         let output = Command::new("nvme")
             .arg("format")
             .arg(&ns.device_path)
             .arg("--ses=1")  // Secure erase
+            .args(ns.format.iter().flat_map(NamespaceFormat::format_args))
             .arg("--force")
             .output()?;
 
         if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("Format failed: {}", err));
+            return Err(anyhow!("Format failed: {}", command_failure(&output)));
         }
 
         Ok(())
@@ -600,6 +765,37 @@ impl NVMeAdvanced {
         println!("\nAll namespaces wiped successfully");
         Ok(())
     }
+
+    /// Namespaces to wipe: all of them, or only the requested IDs. Errors if
+    /// any requested ID is not present on this controller.
+    pub fn select_namespaces(&self, nsids: Option<&[u32]>) -> Result<Vec<&NVMeNamespace>> {
+        let Some(nsids) = nsids else {
+            return Ok(self.namespaces.iter().collect());
+        };
+
+        let unknown: Vec<u32> = nsids
+            .iter()
+            .copied()
+            .filter(|nsid| !self.namespaces.iter().any(|ns| ns.nsid == *nsid))
+            .collect();
+        if !unknown.is_empty() {
+            let available: Vec<u32> = self.namespaces.iter().map(|ns| ns.nsid).collect();
+            return Err(anyhow!(
+                "Unknown namespace ID(s) {:?} on {} (available: {:?})",
+                unknown, self.controller_path, available
+            ));
+        }
+
+        Ok(self.namespaces.iter().filter(|ns| nsids.contains(&ns.nsid)).collect())
+    }
+}
+
+/// Decimal or 0x-prefixed hex, as nvme-cli prints LBAs and counts
+fn parse_number(value: &str) -> Option<u64> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
 }
 
 #[cfg(test)]
@@ -620,6 +816,109 @@ mod tests {
         assert!(zone.needs_reset());
     }
 
+    #[test]
+    fn test_parse_zone_report_and_lba_format() {
+        let report = "nr_zones: 3\n\
+            SLBA: 0x0        WP: 0x0        Cap: 0x8000     State: EMPTY        Type: SEQWRITE_REQ   Attrs: 0x0\n\
+            SLBA: 0x10000    WP: 0x12000    Cap: 0x8000     State: IMP_OPENED   Type: SEQWRITE_REQ   Attrs: 0x0\n\
+            SLBA: 0x20000    WP: 0x28000    Cap: 0x8000     State: FULL         Type: SEQWRITE_REQ   Attrs: 0x0\n";
+        let zones = NVMeAdvanced::parse_zns_zones(report).unwrap();
+
+        assert_eq!(zones.len(), 3);
+        assert_eq!(zones[1].zone_start_lba, 0x10000);
+        assert_eq!(zones[1].write_pointer, 0x12000);
+        assert_eq!(zones[1].zone_capacity, 0x8000);
+        assert_eq!(zones[1].zone_state, ZNSZoneState::ImplicitlyOpen);
+        assert_eq!(zones.iter().map(ZNSZone::needs_reset).collect::<Vec<_>>(), [false, true, true]);
+        assert!(NVMeAdvanced::parse_zns_zones("SLBA: 0x0 WP: ? Cap: 0x8000 State: EMPTY").is_err());
+
+        let id_ns = "nsze    : 0x100000\n\
+            lbaf  0 : ms:0   lbads:9  rp:0x2\n\
+            lbaf  1 : ms:0   lbads:12 rp:0 (in use)\n";
+        let lba_size = NVMeAdvanced::parse_lba_size(id_ns);
+        assert_eq!(lba_size, 4096);
+        assert_eq!(NVMeAdvanced::parse_namespace_size(id_ns, lba_size), 0x100000 * 4096);
+    }
+
+    #[test]
+    fn test_parse_namespace_format_with_protection_info() {
+        let id_ns = "nsze    : 0x100000\n\
+            flbas   : 0x11\n\
+            mc      : 0x3\n\
+            dps     : 0x9\n\
+            lbaf  0 : ms:0   lbads:9  rp:0x2\n\
+            lbaf  1 : ms:8   lbads:9  rp:0 (in use)\n\
+            lbaf  2 : ms:64  lbads:12 rp:0\n";
+        let format = NamespaceFormat::parse(id_ns).unwrap();
+
+        assert_eq!(format, NamespaceFormat {
+            lbaf: 1,
+            lba_size: 512,
+            metadata_size: 8,
+            extended: true,
+            protection_type: 1,
+            pi_first: true,
+        });
+        assert!(format.overwrite_covers_metadata());
+        assert_eq!(format.format_args(), ["--lbaf=1", "--ms=1", "--pi=1", "--pil=1"]);
+        assert_eq!(format.to_string(), "LBA format 1: 512 bytes + 8 bytes metadata (extended), PI type 1");
+
+        // Metadata beyond the PI tuple survives an overwrite
+        let wide = NamespaceFormat::parse(&id_ns.replace("flbas   : 0x11", "flbas   : 0x2").replace("dps     : 0x9", "dps     : 0")).unwrap();
+        assert_eq!((wide.lbaf, wide.lba_size, wide.metadata_size, wide.extended), (2, 4096, 64, false));
+        assert!(!wide.overwrite_covers_metadata());
+
+        // Without FLBAS the "(in use)" marker picks the format
+        let plain = NamespaceFormat::parse("lbaf  0 : ms:0   lbads:9  rp:0x2\nlbaf  1 : ms:0   lbads:12 rp:0 (in use)\n").unwrap();
+        assert_eq!((plain.lbaf, plain.lba_size, plain.has_metadata()), (1, 4096, false));
+        assert!(NamespaceFormat::parse("nsze : 0x100\n").is_none());
+    }
+
+    #[test]
+    fn test_zone_reset_addresses_start_lba() {
+        use crate::commands::{scoped_runner, MockCommandRunner};
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockCommandRunner::new());
+        let _guard = scoped_runner(mock.clone());
+
+        let nvme = NVMeAdvanced {
+            device_path: "/dev/nvme0".to_string(),
+            controller_path: "/dev/nvme0".to_string(),
+            namespaces: Vec::new(),
+            zns_support: true,
+            kv_support: false,
+            is_computational_storage: false,
+            model: "Test".to_string(),
+            firmware: "1.0".to_string(),
+        };
+        let zone = |zone_id: u32, zone_state| ZNSZone {
+            zone_id,
+            zone_start_lba: zone_id as u64 * 0x10000,
+            zone_capacity: 0x8000,
+            write_pointer: zone_id as u64 * 0x10000,
+            zone_state,
+            is_sequential: true,
+        };
+        let ns = NVMeNamespace {
+            nsid: 1,
+            size: 1 << 30,
+            namespace_type: NamespaceType::ZonedNamespace,
+            device_path: "/dev/nvme0n1".to_string(),
+            is_active: true,
+            is_attached: true,
+            zones: Some(vec![zone(0, ZNSZoneState::Empty), zone(1, ZNSZoneState::Full), zone(2, ZNSZoneState::Closed)]),
+            lba_size: 4096,
+            format: None,
+        };
+
+        nvme.zns_reset_all_zones(&ns).unwrap();
+        assert_eq!(mock.calls(), [
+            "nvme zns reset-zone /dev/nvme0n1 -s 0x10000",
+            "nvme zns reset-zone /dev/nvme0n1 -s 0x20000",
+        ]);
+    }
+
     #[test]
     fn test_namespace_types() {
         assert_ne!(NamespaceType::Block, NamespaceType::ZonedNamespace);
@@ -634,4 +933,37 @@ mod tests {
         let ctrl2 = NVMeAdvanced::extract_controller_path("/dev/nvme1n5");
         assert_eq!(ctrl2, "/dev/nvme1");
     }
+
+    #[test]
+    fn test_select_namespaces() {
+        let namespace = |nsid: u32| NVMeNamespace {
+            nsid,
+            size: 1024,
+            namespace_type: NamespaceType::Block,
+            device_path: format!("/dev/nvme0n{}", nsid),
+            is_active: true,
+            is_attached: true,
+            zones: None,
+            lba_size: 512,
+            format: None,
+        };
+        let nvme = NVMeAdvanced {
+            device_path: "/dev/nvme0".to_string(),
+            controller_path: "/dev/nvme0".to_string(),
+            namespaces: vec![namespace(1), namespace(2), namespace(3)],
+            zns_support: false,
+            kv_support: false,
+            is_computational_storage: false,
+            model: "Test".to_string(),
+            firmware: "1.0".to_string(),
+        };
+
+        assert_eq!(nvme.select_namespaces(None).unwrap().len(), 3);
+
+        let selected: Vec<u32> = nvme.select_namespaces(Some(&[3, 1])).unwrap().iter().map(|ns| ns.nsid).collect();
+        assert_eq!(selected, vec![1, 3]);
+
+        let err = nvme.select_namespaces(Some(&[2, 7])).unwrap_err();
+        assert!(err.to_string().contains("[7]"));
+    }
 }
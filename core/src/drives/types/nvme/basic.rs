@@ -1,24 +1,48 @@
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{exit_description, spawn_error, Command};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use crate::ui::progress::ProgressBar;
+use super::advanced::NamespaceFormat;
+use crate::{CancellationToken, DriveError, DriveResult, NvmeSanitizeAction};
+
+/// Delay between reads of the sanitize status log page
+const SANITIZE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Polls allowed before a sanitize that never shows up in the log is an error
+const SANITIZE_START_POLLS: u32 = 15;
+
+/// Most recent sanitize operation, from the Sanitize Status log page (SSTAT/SPROG)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SanitizeStatus {
+    NeverSanitized,
+    Completed,
+    InProgress { percent: f64 },
+    Failed,
+}
 
 pub struct NVMeWipe;
 
 impl NVMeWipe {
-    pub fn secure_erase(device_path: &str) -> Result<()> {
+    /// Format NVM with a user data erase, or a crypto erase sanitize when
+    /// the controller lacks Format NVM; either is killed at `cancel`'s deadline
+    pub fn secure_erase(device_path: &str, cancel: &CancellationToken) -> Result<()> {
         println!("Starting NVMe secure erase on {}", device_path);
 
         let _device_info = Self::get_nvme_info(device_path)?;
 
         if Self::supports_format_nvm(device_path)? {
             let mut cmd = Command::new("nvme");
-            cmd.args(["format", device_path, "--ses=1", "--force"]);
+            cmd.args(["format", device_path, "--ses=1"]);
+            // Keep the namespace's block size and metadata/PI settings
+            if let Some(format) = NamespaceFormat::query(device_path).ok().flatten() {
+                cmd.args(format.format_args());
+            }
+            cmd.arg("--force").deadline(cancel.deadline());
             Self::run_command_with_bar(&mut cmd, "Format NVM secure erase")
         } else if Self::supports_sanitize(device_path)? {
             let mut cmd = Command::new("nvme");
-            cmd.args(["sanitize", device_path, "--crypto-erase", "--force"]);
+            cmd.args(["sanitize", device_path, "--crypto-erase", "--force"]).deadline(cancel.deadline());
             Self::run_command_with_bar(&mut cmd, "Sanitize crypto erase")
         } else {
             Err(anyhow!("No secure erase method available for this NVMe device"))
@@ -27,7 +51,7 @@ impl NVMeWipe {
 
     fn run_command_with_bar(cmd: &mut Command, label: &str) -> Result<()> {
         println!("Using {}...", label);
-        let mut process = cmd.spawn()?;
+        let mut process = cmd.spawn().map_err(|e| spawn_error("nvme", e))?;
         let mut bar = ProgressBar::new(48);
 
         loop {
@@ -38,7 +62,7 @@ impl NVMeWipe {
                         println!("\n{} completed successfully", label);
                         return Ok(());
                     } else {
-                        return Err(anyhow!("{} failed", label));
+                        return Err(anyhow!("{} failed ({})", label, exit_description(status)));
                     }
                 }
                 None => {
@@ -50,13 +74,7 @@ impl NVMeWipe {
     }
 
     fn get_nvme_info(device_path: &str) -> Result<String> {
-        let output = Command::new("nvme")
-            .args(["id-ctrl", device_path])
-            .output()?;
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("Failed to get NVMe info: {}", error));
-        }
+        let output = Command::new("nvme").args(["id-ctrl", device_path]).run()?;
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
@@ -67,4 +85,292 @@ impl NVMeWipe {
     fn supports_sanitize(device_path: &str) -> Result<bool> {
         Ok(Self::get_nvme_info(device_path)?.contains("Sanitize Operation Supported"))
     }
+
+    /// Sanitize actions the controller advertises in its SANICAP field
+    pub fn sanitize_capabilities(device_path: &str) -> Result<Vec<NvmeSanitizeAction>> {
+        let id_ctrl = Self::get_nvme_info(device_path)?;
+        Self::parse_sanicap(&id_ctrl)
+            .ok_or_else(|| anyhow!("No sanicap field in `nvme id-ctrl {}` output", device_path))
+    }
+
+    /// Decode the `sanicap` line of `nvme id-ctrl` output
+    pub fn parse_sanicap(id_ctrl: &str) -> Option<Vec<NvmeSanitizeAction>> {
+        let value = id_ctrl.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "sanicap").then(|| value.trim().to_string())
+        })?;
+
+        let sanicap = match value.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => value.parse().ok()?,
+        };
+
+        Some(
+            NvmeSanitizeAction::BY_THOROUGHNESS
+                .into_iter()
+                .filter(|action| sanicap & action.sanicap_bit() != 0)
+                .collect(),
+        )
+    }
+
+    /// Validate a requested sanitize action, or pick the most thorough
+    /// supported one when none was requested
+    pub fn select_sanitize_action(
+        requested: Option<NvmeSanitizeAction>,
+        supported: &[NvmeSanitizeAction],
+    ) -> DriveResult<NvmeSanitizeAction> {
+        match requested {
+            Some(action) if supported.contains(&action) => Ok(action),
+            Some(action) => Err(DriveError::Unsupported(format!(
+                "NVMe sanitize {} is not supported by this controller (supported: {:?})",
+                action.label(),
+                supported
+            ))),
+            None => NvmeSanitizeAction::BY_THOROUGHNESS
+                .into_iter()
+                .find(|action| supported.contains(action))
+                .ok_or_else(|| DriveError::Unsupported("Controller does not support any NVMe sanitize action".to_string())),
+        }
+    }
+}
+
+impl NVMeWipe {
+    /// Read the Sanitize Status log page, giving up at `deadline`
+    pub fn sanitize_status(device_path: &str, deadline: Option<Instant>) -> Result<SanitizeStatus> {
+        let output = Command::new("nvme").args(["sanitize-log", device_path]).deadline(deadline).run()?;
+        let log = String::from_utf8_lossy(&output.stdout);
+        Self::parse_sanitize_log(&log).ok_or_else(|| anyhow!("Unrecognized sanitize log output"))
+    }
+
+    /// Decode SSTAT (bits 2:0) and SPROG (progress out of 65536) from `nvme sanitize-log`
+    pub fn parse_sanitize_log(log: &str) -> Option<SanitizeStatus> {
+        let field = |name: &str| -> Option<u32> {
+            log.lines().find_map(|line| {
+                let (key, value) = line.rsplit_once(':')?;
+                if !key.contains(&format!("({})", name)) {
+                    return None;
+                }
+                let value = value.trim();
+                match value.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => value.parse().ok(),
+                }
+            })
+        };
+
+        let sstat = field("SSTAT")?;
+        Some(match sstat & 0x7 {
+            0 => SanitizeStatus::NeverSanitized,
+            1 | 4 => SanitizeStatus::Completed,
+            2 => SanitizeStatus::InProgress {
+                percent: field("SPROG").unwrap_or(0) as f64 / 65536.0 * 100.0,
+            },
+            _ => SanitizeStatus::Failed,
+        })
+    }
+
+    /// Controller's estimate for a sanitize with `action`, from the Sanitize
+    /// Status log page; None when it can't be read or isn't reported
+    pub fn sanitize_time_estimate(device_path: &str, action: NvmeSanitizeAction) -> Option<Duration> {
+        let output = Command::new("nvme")
+            .args(["sanitize-log", device_path])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        Self::parse_sanitize_estimate(&String::from_utf8_lossy(&output.stdout), action)
+    }
+
+    /// Decode the ETO/ETBE/ETCE line for `action` from `nvme sanitize-log`,
+    /// where 0xFFFFFFFF means the controller reports no time
+    pub fn parse_sanitize_estimate(log: &str, action: NvmeSanitizeAction) -> Option<Duration> {
+        let key = match action {
+            NvmeSanitizeAction::Overwrite => "Estimated Time For Overwrite",
+            NvmeSanitizeAction::BlockErase => "Estimated Time For Block Erase",
+            NvmeSanitizeAction::CryptoErase => "Estimated Time For Crypto Erase",
+        };
+
+        let seconds: u32 = log.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim() != key {
+                return None;
+            }
+            value.split_whitespace().next()?.parse().ok()
+        })?;
+
+        (seconds != u32::MAX).then(|| Duration::from_secs(seconds as u64))
+    }
+
+    /// Poll the sanitize log until the controller reports completion,
+    /// rendering its progress; a failed sanitize is returned as an error, and
+    /// one whose progress hasn't moved for `stall_timeout` as a `Timeout`.
+    /// Cancelling stops the wait, not the sanitize, which the controller
+    /// completes on its own (resuming after a power cycle).
+    pub fn wait_for_sanitize(
+        device_path: &str,
+        cancel: &CancellationToken,
+        stall_timeout: Option<Duration>,
+    ) -> DriveResult<()> {
+        let mut bar = ProgressBar::new(48);
+        let mut polls = 0u32;
+        let mut stall = StallDetector::new(stall_timeout, Instant::now());
+
+        loop {
+            if cancel.is_cancelled() {
+                println!("\n🛑 Cancelled - the NVMe sanitize continues in the controller");
+                return Err(DriveError::Cancelled);
+            }
+
+            let status = Self::sanitize_status(device_path, cancel.deadline())
+                .map_err(|e| DriveError::HardwareCommandFailed(e.to_string()))?;
+
+            match status {
+                SanitizeStatus::Completed => {
+                    bar.render(100.0, None, None);
+                    println!("\nNVMe sanitize completed");
+                    return Ok(());
+                }
+                SanitizeStatus::Failed => {
+                    return Err(DriveError::HardwareCommandFailed(format!(
+                        "NVMe sanitize failed on {} (see `nvme sanitize-log`)",
+                        device_path
+                    )));
+                }
+                SanitizeStatus::InProgress { percent } => {
+                    bar.render(percent, None, None);
+                    if let Some(stalled) = stall.observe(percent, Instant::now()) {
+                        return Err(DriveError::Timeout(format!(
+                            "NVMe sanitize on {} has been stuck at {:.1}% for {}s",
+                            device_path,
+                            percent,
+                            stalled.as_secs()
+                        )));
+                    }
+                }
+                SanitizeStatus::NeverSanitized if polls >= SANITIZE_START_POLLS => {
+                    return Err(DriveError::HardwareCommandFailed(format!(
+                        "NVMe sanitize never started on {}",
+                        device_path
+                    )));
+                }
+                SanitizeStatus::NeverSanitized => bar.render(0.0, None, None),
+            }
+
+            polls += 1;
+            thread::sleep(SANITIZE_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Notices a polled operation whose reported progress has stopped advancing
+struct StallDetector {
+    limit: Option<Duration>,
+    best: f64,
+    since: Instant,
+}
+
+impl StallDetector {
+    fn new(limit: Option<Duration>, now: Instant) -> Self {
+        Self { limit, best: f64::NEG_INFINITY, since: now }
+    }
+
+    /// Record a progress reading; returns how long progress has been stuck
+    /// once that exceeds the limit
+    fn observe(&mut self, percent: f64, now: Instant) -> Option<Duration> {
+        if percent > self.best {
+            self.best = percent;
+            self.since = now;
+            return None;
+        }
+
+        let stalled = now.saturating_duration_since(self.since);
+        self.limit.filter(|limit| stalled >= *limit).map(|_| stalled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sanicap() {
+        let id_ctrl = "vid       : 0x144d\nsanicap   : 0x3\nhmminds   : 0\n";
+        assert_eq!(
+            NVMeWipe::parse_sanicap(id_ctrl),
+            Some(vec![NvmeSanitizeAction::BlockErase, NvmeSanitizeAction::CryptoErase])
+        );
+        assert_eq!(NVMeWipe::parse_sanicap("sanicap   : 0\n"), Some(vec![]));
+        assert_eq!(NVMeWipe::parse_sanicap("vid : 0x144d\n"), None);
+    }
+
+    #[test]
+    fn test_select_sanitize_action() {
+        let supported = [NvmeSanitizeAction::CryptoErase, NvmeSanitizeAction::Overwrite];
+
+        assert_eq!(
+            NVMeWipe::select_sanitize_action(None, &supported).unwrap(),
+            NvmeSanitizeAction::CryptoErase
+        );
+        assert_eq!(
+            NVMeWipe::select_sanitize_action(Some(NvmeSanitizeAction::Overwrite), &supported).unwrap(),
+            NvmeSanitizeAction::Overwrite
+        );
+        assert!(matches!(
+            NVMeWipe::select_sanitize_action(Some(NvmeSanitizeAction::BlockErase), &supported),
+            Err(DriveError::Unsupported(_))
+        ));
+        assert!(NVMeWipe::select_sanitize_action(None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_sanitize_log() {
+        let log = |sprog: &str, sstat: &str| format!(
+            "Sanitize Progress                      (SPROG) :  {}\n\
+             Sanitize Status                        (SSTAT) :  {}\n\
+             Sanitize Command Dword 10 Information (SCDW10) :  0x4\n",
+            sprog, sstat
+        );
+
+        assert_eq!(
+            NVMeWipe::parse_sanitize_log(&log("32768", "0x2")),
+            Some(SanitizeStatus::InProgress { percent: 50.0 })
+        );
+        assert_eq!(NVMeWipe::parse_sanitize_log(&log("65535", "0x101")), Some(SanitizeStatus::Completed));
+        assert_eq!(NVMeWipe::parse_sanitize_log(&log("65535", "0x3")), Some(SanitizeStatus::Failed));
+        assert_eq!(NVMeWipe::parse_sanitize_log(&log("65535", "0")), Some(SanitizeStatus::NeverSanitized));
+        assert_eq!(NVMeWipe::parse_sanitize_log("no log here"), None);
+    }
+
+    #[test]
+    fn test_parse_sanitize_estimate() {
+        let log = "Estimated Time For Overwrite                   :  4294967295 (No time period reported)
+                   Estimated Time For Block Erase                 :  120
+                   Estimated Time For Crypto Erase                :  2
+";
+
+        assert_eq!(NVMeWipe::parse_sanitize_estimate(log, NvmeSanitizeAction::BlockErase), Some(Duration::from_secs(120)));
+        assert_eq!(NVMeWipe::parse_sanitize_estimate(log, NvmeSanitizeAction::CryptoErase), Some(Duration::from_secs(2)));
+        assert_eq!(NVMeWipe::parse_sanitize_estimate(log, NvmeSanitizeAction::Overwrite), None);
+        assert_eq!(NVMeWipe::parse_sanitize_estimate("no log here", NvmeSanitizeAction::BlockErase), None);
+    }
+
+    #[test]
+    fn test_stall_detector() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut stall = StallDetector::new(Some(Duration::from_secs(60)), start);
+
+        assert_eq!(stall.observe(0.0, at(0)), None);
+        assert_eq!(stall.observe(0.0, at(59)), None);
+        assert_eq!(stall.observe(1.5, at(100)), None);
+        assert_eq!(stall.observe(1.5, at(150)), None);
+        assert_eq!(stall.observe(1.5, at(160)), Some(Duration::from_secs(60)));
+
+        // Without a limit it never fires
+        let mut unlimited = StallDetector::new(None, start);
+        unlimited.observe(5.0, at(0));
+        assert_eq!(unlimited.observe(5.0, at(86_400)), None);
+    }
 }
@@ -8,10 +8,11 @@ pub mod basic;
 pub mod advanced;
 
 // Re-export commonly used types
-pub use basic::NVMeWipe;
+pub use basic::{NVMeWipe, SanitizeStatus};
 pub use advanced::{
     NVMeAdvanced,
     NVMeNamespace,
+    NamespaceFormat,
     NamespaceType,
     ZNSZone,
     ZNSZoneState,
@@ -4,7 +4,7 @@
 // It requires different wipe strategies and supports instant secure erase.
 
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use serde::{Serialize, Deserialize};
 
 /// Optane operating mode
@@ -42,6 +42,23 @@ pub struct OptaneNamespace {
     pub is_healthy: bool,
 }
 
+/// The libnvdimm objects behind a /dev/pmemN block device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PmemTopology {
+    /// Namespace, e.g. "namespace0.0"
+    pub namespace: String,
+
+    /// Namespace mode: fsdax, sector, raw (devdax has no block device)
+    pub mode: String,
+
+    /// Region the namespace is carved from, e.g. "region0"
+    pub region: String,
+
+    /// DIMMs interleaved into the region, with their security state
+    /// ("disabled", "unlocked", "frozen", ...; None when unsupported)
+    pub dimms: Vec<(String, Option<String>)>,
+}
+
 /// Optane/3D XPoint drive configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptaneDrive {
@@ -324,7 +341,7 @@ impl OptaneDrive {
         if output.status.success() {
             Ok(())
         } else {
-            Err(anyhow!("nvme format failed"))
+            Err(anyhow!("nvme format failed ({})", command_failure(&output)))
         }
     }
 
@@ -342,7 +359,7 @@ impl OptaneDrive {
             std::thread::sleep(std::time::Duration::from_secs(2));
             Ok(())
         } else {
-            Err(anyhow!("nvme sanitize failed"))
+            Err(anyhow!("nvme sanitize failed ({})", command_failure(&output)))
         }
     }
 
@@ -393,7 +410,7 @@ impl OptaneDrive {
     fn random_pattern(size: usize) -> Vec<u8> {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        (0..size).map(|_| rng.gen()).collect()
+        (0..size).map(|_| rng.gen::<u8>()).collect()
     }
 
     /// Wipe PMEM namespace
@@ -418,8 +435,7 @@ impl OptaneDrive {
                 .output()?;
 
             if !output.status.success() {
-                let err = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow!("PMEM wipe failed: {}", err));
+                return Err(anyhow!("PMEM wipe failed: {}", command_failure(&output)));
             }
 
             // Flush any cached data
@@ -430,6 +446,103 @@ impl OptaneDrive {
         Ok(())
     }
 
+    /// Resolve the namespace, region and DIMMs behind a pmem block device
+    /// from `ndctl list`
+    pub fn pmem_topology(device_path: &str) -> Result<PmemTopology> {
+        let blockdev = device_path.trim_start_matches("/dev/");
+
+        let namespaces = ndctl_list(&["--namespaces"])?;
+        let namespace = namespaces
+            .iter()
+            .find(|ns| ns["blockdev"].as_str() == Some(blockdev))
+            .ok_or_else(|| anyhow!("No libnvdimm namespace backs {}", device_path))?;
+        let name = namespace["dev"].as_str().unwrap_or_default().to_string();
+
+        // namespaceX.Y lives in regionX
+        let region = name
+            .strip_prefix("namespace")
+            .and_then(|id| id.split('.').next())
+            .map(|id| format!("region{}", id))
+            .ok_or_else(|| anyhow!("Unexpected namespace name {:?}", name))?;
+
+        let dimms = ndctl_list(&["--dimms", "--region", &region])?
+            .iter()
+            .filter_map(|dimm| {
+                let dev = dimm["dev"].as_str()?.to_string();
+                Some((dev, dimm["security"].as_str().map(str::to_string)))
+            })
+            .collect();
+
+        Ok(PmemTopology {
+            namespace: name,
+            mode: namespace["mode"].as_str().unwrap_or("unknown").to_string(),
+            region,
+            dimms,
+        })
+    }
+
+    /// Erase a persistent-memory namespace at the DIMM level.
+    ///
+    /// Block-level secure erase doesn't reach DAX-mapped media, so the
+    /// namespace is destroyed (tearing down any DAX mapping), its region
+    /// disabled, and every DIMM in the region sanitized: a crypto erase,
+    /// falling back to the DIMM's overwrite sanitize. The region is
+    /// re-enabled afterwards, empty; recreate namespaces with
+    /// `ndctl create-namespace`. Fails with `DriveError::Unsupported`, before
+    /// touching anything, when the DIMMs lack security support, so the caller
+    /// can overwrite the namespace instead. Returns a description of what ran.
+    pub fn pmem_secure_erase(&self) -> Result<String> {
+        let topology = Self::pmem_topology(&self.device_path)?;
+
+        let unsupported: Vec<&str> = topology.dimms.iter()
+            .filter(|(_, security)| matches!(security.as_deref(), None | Some("frozen")))
+            .map(|(dev, _)| dev.as_str())
+            .collect();
+        if topology.dimms.is_empty() || !unsupported.is_empty() {
+            return Err(crate::DriveError::Unsupported(format!(
+                "DIMMs {:?} of {} can't be sanitized (no security support or frozen)",
+                unsupported, topology.region
+            )).into());
+        }
+
+        println!("Tearing down {} ({} mode) on {}", topology.namespace, topology.mode, topology.region);
+        Command::new("ndctl").args(["destroy-namespace", &topology.namespace, "--force"]).run()?;
+        Command::new("ndctl").args(["disable-region", &topology.region]).run()?;
+
+        let mut methods = Vec::new();
+        for (dimm, security) in &topology.dimms {
+            // Without a passphrase the DIMM's key is the zero key
+            let zero_key = security.as_deref() == Some("disabled");
+            let sanitize = |action: &str| {
+                let mut command = Command::new("ndctl");
+                command.args(["sanitize-dimm", dimm.as_str(), action]);
+                if zero_key {
+                    command.arg("--zero-key");
+                }
+                command.run()
+            };
+
+            println!("Sanitizing {}...", dimm);
+            if sanitize("--crypto-erase").is_ok() {
+                methods.push("crypto-erase");
+                continue;
+            }
+            println!("⚠️  Crypto erase failed on {}, overwriting the DIMM instead", dimm);
+            sanitize("--overwrite")?;
+            Command::new("ndctl").args(["wait-overwrite", dimm.as_str()]).run()?;
+            methods.push("overwrite");
+        }
+
+        Command::new("ndctl").args(["enable-region", &topology.region]).run()?;
+
+        methods.dedup();
+        let dimms: Vec<&str> = topology.dimms.iter().map(|(dev, _)| dev.as_str()).collect();
+        Ok(format!(
+            "ndctl sanitize-dimm --{} on {} (persistent memory, {} {} namespace destroyed)",
+            methods.join("/--"), dimms.join(", "), topology.namespace, topology.mode
+        ))
+    }
+
     /// Verify Optane wipe
     pub fn verify_optane_wipe(&self) -> Result<bool> {
         println!("Verifying Optane wipe...");
@@ -463,7 +576,10 @@ impl OptaneDrive {
         for ns in &self.namespaces {
             match ns.mode {
                 OptaneMode::PersistentMemory => {
-                    self.wipe_pmem_namespace(ns)?;
+                    if let Err(e) = self.pmem_secure_erase() {
+                        println!("⚠️  {} - overwriting the namespace", e);
+                        self.wipe_pmem_namespace(ns)?;
+                    }
                 }
                 _ => {
                     // Use overwrite for block mode
@@ -483,6 +599,21 @@ impl OptaneDrive {
     }
 }
 
+/// Objects from `ndctl list`, which prints a bare object instead of a
+/// one-element array and nothing at all when there are none
+fn ndctl_list(args: &[&str]) -> Result<Vec<serde_json::Value>> {
+    let output = Command::new("ndctl").arg("list").args(args).run()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(match serde_json::from_str(&stdout)? {
+        serde_json::Value::Array(items) => items,
+        item => vec![item],
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -503,4 +634,56 @@ mod tests {
         let all_same = pattern.iter().all(|&b| b == first);
         assert!(!all_same, "Random pattern should not be uniform");
     }
+
+    fn pmem_drive() -> OptaneDrive {
+        OptaneDrive {
+            device_path: "/dev/pmem0".to_string(),
+            is_pmem: true,
+            supports_ise: false,
+            namespaces: Vec::new(),
+            total_capacity: 0,
+            generation: "Intel Optane".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pmem_erase_tears_down_namespace_and_sanitizes_dimms() {
+        use crate::commands::{scoped_runner, MockCommandRunner};
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockCommandRunner::new());
+        mock.respond(
+            "ndctl list --namespaces",
+            r#"[{"dev":"namespace1.0","mode":"raw","blockdev":"pmem1"},
+                {"dev":"namespace0.0","mode":"fsdax","blockdev":"pmem0"}]"#,
+        )
+        // A single DIMM is printed as a bare object
+        .respond("ndctl list --dimms --region region0", r#"{"dev":"nmem0","security":"disabled"}"#);
+        let _guard = scoped_runner(mock.clone());
+
+        let method = pmem_drive().pmem_secure_erase().unwrap();
+        assert!(method.contains("crypto-erase on nmem0"));
+        assert!(method.contains("persistent memory"));
+        assert_eq!(mock.calls()[2..], [
+            "ndctl destroy-namespace namespace0.0 --force",
+            "ndctl disable-region region0",
+            "ndctl sanitize-dimm nmem0 --crypto-erase --zero-key",
+            "ndctl enable-region region0",
+        ]);
+    }
+
+    #[test]
+    fn test_pmem_erase_without_dimm_security_is_unsupported() {
+        use crate::commands::{scoped_runner, MockCommandRunner};
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockCommandRunner::new());
+        mock.respond("ndctl list --namespaces", r#"{"dev":"namespace0.0","mode":"fsdax","blockdev":"pmem0"}"#)
+            .respond("ndctl list --dimms", r#"[{"dev":"nmem0","security":"unlocked"},{"dev":"nmem1"}]"#);
+        let _guard = scoped_runner(mock.clone());
+
+        let err = pmem_drive().pmem_secure_erase().unwrap_err();
+        assert!(matches!(err.downcast_ref::<crate::DriveError>(), Some(crate::DriveError::Unsupported(_))));
+        assert!(!mock.ran("ndctl destroy-namespace"));
+    }
 }
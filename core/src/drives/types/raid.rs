@@ -2,10 +2,15 @@
 //
 // Support for detecting and safely wiping RAID array members
 
-use anyhow::Result;
-use std::process::Command;
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+use crate::commands::{command_failure, Command};
 use serde::{Serialize, Deserialize};
 
+/// Block devices (including partitions) and their md holders/slaves
+const SYS_CLASS_BLOCK: &str = "/sys/class/block";
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum RAIDType {
     SoftwareRAID,    // Linux mdadm
@@ -42,6 +47,8 @@ pub struct RAIDArray {
     pub device_path: String,
     pub raid_type: RAIDType,
     pub member_drives: Vec<String>,
+    /// The assembled md device (e.g. `/dev/md0`), when there is one
+    pub array_device: Option<String>,
     pub metadata_locations: Vec<MetadataRegion>,
     pub controller: Option<RAIDController>,
     pub is_active: bool,
@@ -96,15 +103,17 @@ impl RAIDArray {
     /// Get RAID configuration
     pub fn get_configuration(device_path: &str) -> Result<RAIDArray> {
         let raid_type = Self::detect_raid_type(device_path)?;
-        let member_drives = Self::get_member_drives(device_path)?;
+        let md_name = Self::find_md_array(Path::new(SYS_CLASS_BLOCK), device_path);
+        let member_drives = Self::get_member_drives(device_path, md_name.as_deref())?;
         let controller = Self::detect_controller(device_path)?;
         let metadata_locations = Self::find_metadata_locations(&raid_type);
-        let is_active = Self::is_array_active(device_path)?;
+        let is_active = Self::is_array_active(md_name.as_deref())?;
 
         Ok(RAIDArray {
             device_path: device_path.to_string(),
             raid_type,
             member_drives,
+            array_device: md_name.map(|name| format!("/dev/{}", name)),
             metadata_locations,
             controller,
             is_active,
@@ -112,7 +121,9 @@ impl RAIDArray {
     }
 
     fn detect_raid_type(device_path: &str) -> Result<RAIDType> {
-        if Self::is_mdadm_member(device_path)? {
+        let is_md_array = Self::find_md_array(Path::new(SYS_CLASS_BLOCK), device_path)
+            .is_some_and(|md| Path::new(device_path).ends_with(md));
+        if is_md_array || Self::is_mdadm_member(device_path)? {
             return Ok(RAIDType::SoftwareRAID);
         }
 
@@ -123,9 +134,40 @@ impl RAIDArray {
         Ok(RAIDType::FakeRAID)
     }
 
-    fn get_member_drives(_device_path: &str) -> Result<Vec<String>> {
-        // Simplified - would query mdadm or controller
-        Ok(Vec::new())
+    fn get_member_drives(_device_path: &str, md_name: Option<&str>) -> Result<Vec<String>> {
+        // Hardware RAID members sit behind the controller and aren't visible here
+        Ok(md_name
+            .map(|md| Self::md_members(Path::new(SYS_CLASS_BLOCK), md))
+            .unwrap_or_default())
+    }
+
+    /// Name of the md array `device_path` is (e.g. `md0`) or is a member of,
+    /// found through the `holders` links in sysfs
+    fn find_md_array(sys_block: &Path, device_path: &str) -> Option<String> {
+        let name = Path::new(device_path).file_name()?.to_string_lossy().into_owned();
+        if sys_block.join(&name).join("md").is_dir() {
+            return Some(name);
+        }
+
+        fs::read_dir(sys_block.join(&name).join("holders"))
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .find(|holder| holder.starts_with("md"))
+    }
+
+    /// Member devices of md array `md_name`, from its `slaves` directory
+    fn md_members(sys_block: &Path, md_name: &str) -> Vec<String> {
+        let mut members: Vec<String> = fs::read_dir(sys_block.join(md_name).join("slaves"))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| format!("/dev/{}", entry.file_name().to_string_lossy()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        members.sort();
+        members
     }
 
     fn detect_controller(_device_path: &str) -> Result<Option<RAIDController>> {
@@ -145,8 +187,13 @@ impl RAIDArray {
         }
     }
 
-    fn is_array_active(_device_path: &str) -> Result<bool> {
-        Ok(false)
+    fn is_array_active(md_name: Option<&str>) -> Result<bool> {
+        let Some(md) = md_name else {
+            return Ok(false);
+        };
+        let state = fs::read_to_string(Path::new(SYS_CLASS_BLOCK).join(md).join("md/array_state"))
+            .unwrap_or_default();
+        Ok(!matches!(state.trim(), "" | "clear" | "inactive"))
     }
 
     /// Check if safe to wipe
@@ -176,4 +223,81 @@ impl RAIDArray {
 
         Ok(())
     }
+
+    /// Stop the assembled array and erase the RAID superblock on every
+    /// member, so the OS doesn't reassemble or resync it mid-wipe
+    pub fn teardown(&mut self) -> Result<()> {
+        if let Some(array) = self.array_device.as_deref().filter(|_| self.is_active) {
+            println!("🛑 Stopping RAID array {}", array);
+            let output = Command::new("mdadm")
+                .args(["--stop", array])
+                .output()
+                .map_err(|e| anyhow!("Failed to run mdadm --stop: {}", e))?;
+
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "mdadm --stop {} failed: {}",
+                    array,
+                    command_failure(&output)
+                ));
+            }
+            self.is_active = false;
+        }
+
+        if self.raid_type == RAIDType::SoftwareRAID {
+            for member in &self.member_drives {
+                println!("   Erasing RAID superblock on {}", member);
+                let output = Command::new("mdadm")
+                    .args(["--zero-superblock", member])
+                    .output()
+                    .map_err(|e| anyhow!("Failed to run mdadm --zero-superblock: {}", e))?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "mdadm --zero-superblock {} failed: {}",
+                        member,
+                        command_failure(&output)
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    /// Lay out `md0` with members sda1 and sdb1 the way sysfs links them
+    fn fake_sysfs() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        let md0 = root.path().join("md0");
+        fs::create_dir_all(md0.join("md")).unwrap();
+        fs::create_dir_all(md0.join("slaves")).unwrap();
+
+        for member in ["sdb1", "sda1"] {
+            let holders = root.path().join(member).join("holders");
+            fs::create_dir_all(&holders).unwrap();
+            symlink(&md0, holders.join("md0")).unwrap();
+            symlink(root.path().join(member), md0.join("slaves").join(member)).unwrap();
+        }
+        fs::create_dir_all(root.path().join("sdc")).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_members_found_from_any_member() {
+        let sysfs = fake_sysfs();
+
+        let md = RAIDArray::find_md_array(sysfs.path(), "/dev/sdb1");
+        assert_eq!(md.as_deref(), Some("md0"));
+        assert_eq!(RAIDArray::md_members(sysfs.path(), "md0"), vec!["/dev/sda1", "/dev/sdb1"]);
+
+        // The array device itself resolves to the same set
+        assert_eq!(RAIDArray::find_md_array(sysfs.path(), "/dev/md0").as_deref(), Some("md0"));
+        assert_eq!(RAIDArray::find_md_array(sysfs.path(), "/dev/sdc"), None);
+    }
 }
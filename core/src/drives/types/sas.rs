@@ -0,0 +1,210 @@
+// SAS/SCSI Drive Support
+//
+// SAS drives don't speak ATA, so neither hdparm's Secure Erase nor the HPA
+// tools reach them. They are purged with SCSI SANITIZE (overwrite, block
+// erase or cryptographic erase, whichever the drive implements) or FORMAT
+// UNIT, both started in the background and polled through REQUEST SENSE
+// progress indications via sg3_utils.
+
+use anyhow::{Result, anyhow};
+use crate::commands::Command;
+use std::thread;
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+use crate::{CancellationToken, DriveError};
+
+/// SANITIZE operation code
+const SANITIZE_OPCODE: &str = "48";
+
+/// Delay between REQUEST SENSE progress polls
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// SCSI SANITIZE service actions (SBC-4 5.30)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScsiSanitizeAction {
+    Overwrite,
+    BlockErase,
+    CryptoErase,
+}
+
+impl ScsiSanitizeAction {
+    /// All actions, most thorough first
+    pub const BY_THOROUGHNESS: [ScsiSanitizeAction; 3] = [
+        ScsiSanitizeAction::BlockErase,
+        ScsiSanitizeAction::CryptoErase,
+        ScsiSanitizeAction::Overwrite,
+    ];
+
+    /// SERVICE ACTION field of the SANITIZE CDB
+    pub fn service_action(&self) -> u8 {
+        match self {
+            ScsiSanitizeAction::Overwrite => 0x01,
+            ScsiSanitizeAction::BlockErase => 0x02,
+            ScsiSanitizeAction::CryptoErase => 0x03,
+        }
+    }
+
+    /// `sg_sanitize` option selecting this action; overwrites use zeros
+    fn sg_sanitize_args(&self) -> &'static [&'static str] {
+        match self {
+            ScsiSanitizeAction::Overwrite => &["--overwrite", "--zero"],
+            ScsiSanitizeAction::BlockErase => &["--block"],
+            ScsiSanitizeAction::CryptoErase => &["--crypto"],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScsiSanitizeAction::Overwrite => "overwrite",
+            ScsiSanitizeAction::BlockErase => "block erase",
+            ScsiSanitizeAction::CryptoErase => "crypto erase",
+        }
+    }
+}
+
+/// A SAS (or other SCSI) disk driven through sg3_utils
+pub struct SASDrive;
+
+impl SASDrive {
+    /// SANITIZE service actions the drive reports in REPORT SUPPORTED
+    /// OPERATION CODES; empty when it implements none
+    pub fn sanitize_actions(device_path: &str) -> Result<Vec<ScsiSanitizeAction>> {
+        let output = Command::new("sg_opcodes").args(["--no-inquiry", device_path]).run()?;
+        Ok(Self::parse_sanitize_actions(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Pick the SANITIZE rows (opcode 48h) out of the `sg_opcodes` table,
+    /// whose lines read `opcode [service action] cdb-size name`
+    pub fn parse_sanitize_actions(opcodes: &str) -> Vec<ScsiSanitizeAction> {
+        let listed: Vec<u8> = opcodes
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                if !fields.next()?.eq_ignore_ascii_case(SANITIZE_OPCODE) {
+                    return None;
+                }
+                u8::from_str_radix(fields.next()?, 16).ok()
+            })
+            .collect();
+
+        ScsiSanitizeAction::BY_THOROUGHNESS
+            .into_iter()
+            .filter(|action| listed.contains(&action.service_action()))
+            .collect()
+    }
+
+    /// Start SANITIZE with `action` and wait for the drive to finish it.
+    /// Cancelling stops the wait; the drive completes the sanitize on its
+    /// own and stays unusable until it has.
+    pub fn sanitize(device_path: &str, action: ScsiSanitizeAction, cancel: &CancellationToken) -> Result<()> {
+        println!("Issuing SCSI SANITIZE ({}) on {}", action.label(), device_path);
+
+        Command::new("sg_sanitize")
+            .args(action.sg_sanitize_args())
+            .args(["--quick", "--early", device_path])
+            .deadline(cancel.deadline())
+            .run()
+            .map_err(|e| anyhow!("SANITIZE rejected: {}", e))?;
+
+        Self::wait_for_completion(device_path, "SANITIZE", cancel)
+    }
+
+    /// Start FORMAT UNIT, which reinitializes every LBA, and wait for it
+    pub fn format_unit(device_path: &str, cancel: &CancellationToken) -> Result<()> {
+        println!("Issuing SCSI FORMAT UNIT on {}", device_path);
+
+        Command::new("sg_format")
+            .args(["--format", "--quick", "--early", device_path])
+            .deadline(cancel.deadline())
+            .run()
+            .map_err(|e| anyhow!("FORMAT UNIT rejected: {}", e))?;
+
+        Self::wait_for_completion(device_path, "FORMAT UNIT", cancel)
+    }
+
+    /// Poll the progress indication until the drive stops reporting one,
+    /// then check with TEST UNIT READY that the operation left the medium
+    /// usable (a failed sanitize leaves it in a "format corrupt" state)
+    fn wait_for_completion(device_path: &str, operation: &str, cancel: &CancellationToken) -> Result<()> {
+        loop {
+            let output = Command::new("sg_requests")
+                .args(["--progress", device_path])
+                .deadline(cancel.deadline())
+                .run()?;
+            let Some(percent) = Self::parse_progress(&String::from_utf8_lossy(&output.stdout)) else {
+                break;
+            };
+
+            if cancel.is_cancelled() {
+                println!("🛑 Cancelled - the {} continues in the drive", operation);
+                return Err(DriveError::Cancelled.into());
+            }
+            println!("   {} {:.1}% done", operation, percent);
+            thread::sleep(PROGRESS_POLL_INTERVAL);
+        }
+
+        Command::new("sg_turs")
+            .arg(device_path)
+            .deadline(cancel.deadline())
+            .run()
+            .map_err(|e| anyhow!("{} did not complete successfully: {}", operation, e))?;
+
+        println!("{} completed", operation);
+        Ok(())
+    }
+
+    /// Percentage from a `Progress indication: 12.34% done` line, or None
+    /// when the drive has no operation in progress
+    pub fn parse_progress(output: &str) -> Option<f64> {
+        output.lines().find_map(|line| {
+            let (_, rest) = line.split_once("Progress indication:")?;
+            rest.trim().split('%').next()?.trim().parse().ok()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{scoped_runner, MockCommandRunner};
+
+    #[test]
+    fn test_parse_sanitize_actions() {
+        let opcodes = "\
+Opcode  Service    CDB    Name
+(hex)   action(h)  size
+-----------------------------------------------
+ 00                  6    Test Unit Ready
+ 04                  6    Format unit
+ 48        1        10    Sanitize, overwrite
+ 48        3        10    Sanitize, cryptographic erase
+ 48       1f        10    Sanitize, exit failure mode
+";
+        assert_eq!(
+            SASDrive::parse_sanitize_actions(opcodes),
+            vec![ScsiSanitizeAction::CryptoErase, ScsiSanitizeAction::Overwrite]
+        );
+        assert!(SASDrive::parse_sanitize_actions(" 04   6   Format unit\n").is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_polls_until_done() {
+        let mock = std::sync::Arc::new(MockCommandRunner::new());
+        mock.respond("sg_requests", "Decode parameter data\n");
+        let _runner = scoped_runner(mock.clone());
+
+        assert_eq!(SASDrive::parse_progress("    Progress indication: 42.50% done\n"), Some(42.5));
+        SASDrive::sanitize("/dev/sdq", ScsiSanitizeAction::BlockErase, &CancellationToken::new()).unwrap();
+        assert_eq!(
+            mock.calls(),
+            [
+                "sg_sanitize --block --quick --early /dev/sdq",
+                "sg_requests --progress /dev/sdq",
+                "sg_turs /dev/sdq",
+            ]
+        );
+
+        mock.fail("sg_turs", "Medium format corrupted");
+        assert!(SASDrive::format_unit("/dev/sdq", &CancellationToken::new()).is_err());
+    }
+}
@@ -4,7 +4,7 @@
 // They require special handling during wipe operations due to sequential write requirements.
 
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use serde::{Serialize, Deserialize};
 
 /// SMR Zone Model types
@@ -83,6 +83,30 @@ pub struct Zone {
     pub zone_length: u64,
 }
 
+impl ZoneCondition {
+    /// Condition as printed by sg_rep_zones ("Implicitly opened") or
+    /// abbreviated by blkzone ("oi"); unknown ones, and the "not write
+    /// pointer" condition of conventional zones, count as empty
+    fn parse(condition: &str) -> Self {
+        let condition = condition.trim().to_ascii_lowercase();
+        match condition.as_str() {
+            "oi" => ZoneCondition::ImplicitlyOpen,
+            "oe" => ZoneCondition::ExplicitlyOpen,
+            "cl" => ZoneCondition::Closed,
+            "ro" => ZoneCondition::ReadOnly,
+            "fu" => ZoneCondition::Full,
+            "of" => ZoneCondition::Offline,
+            c if c.starts_with("implicit") => ZoneCondition::ImplicitlyOpen,
+            c if c.starts_with("explicit") => ZoneCondition::ExplicitlyOpen,
+            c if c.starts_with("closed") => ZoneCondition::Closed,
+            c if c.starts_with("read") => ZoneCondition::ReadOnly,
+            c if c.starts_with("full") => ZoneCondition::Full,
+            c if c.starts_with("offline") => ZoneCondition::Offline,
+            _ => ZoneCondition::Empty,
+        }
+    }
+}
+
 impl Zone {
     /// Check if zone needs to be reset before writing
     pub fn needs_reset(&self) -> bool {
@@ -103,6 +127,17 @@ impl Zone {
     }
 }
 
+/// Zones handled by `SMRDrive::wipe_smr_drive`, by type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZoneWipeSummary {
+    /// Conventional zones, overwritten in place
+    pub conventional: u32,
+    /// Sequential zones, reset and rewritten in order from the zone start
+    pub sequential: u32,
+    /// Offline zones, which can be neither read nor written
+    pub offline: u32,
+}
+
 /// SMR Drive configuration and state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SMRDrive {
@@ -311,10 +346,16 @@ impl SMRDrive {
         let mut zone_number = 0u32;
 
         for line in stdout.lines() {
-            if line.contains("Zone type:") {
-                let zone_type = if line.contains("CONVENTIONAL") {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+
+            if key == "zone type" {
+                let value = value.to_ascii_lowercase().replace('_', " ");
+                let zone_type = if value.contains("conventional") {
                     ZoneType::Conventional
-                } else if line.contains("SEQUENTIAL_WRITE_REQUIRED") {
+                } else if value.contains("required") {
                     ZoneType::SequentialWriteRequired
                 } else {
                     ZoneType::SequentialWritePreferred
@@ -333,6 +374,22 @@ impl SMRDrive {
 
                 zones.push(zone);
                 zone_number += 1;
+                continue;
+            }
+
+            // Details of the zone most recently started (LBAs of 512 bytes)
+            let Some(zone) = zones.last_mut() else {
+                continue;
+            };
+            match key.as_str() {
+                "zone start lba" => zone.zone_start_lba = parse_number(value)?,
+                "write pointer lba" => zone.write_pointer = parse_number(value)?,
+                "zone length" => {
+                    zone.zone_length = parse_number(value)?;
+                    zone.zone_size = zone.zone_length * 512;
+                }
+                "zone condition" => zone.zone_condition = ZoneCondition::parse(value),
+                _ => {}
             }
         }
 
@@ -348,7 +405,7 @@ impl SMRDrive {
             .output()?;
 
         if !output.status.success() {
-            return Err(anyhow!("blkzone command failed"));
+            return Err(anyhow!("blkzone command failed ({})", command_failure(&output)));
         }
 
         Self::parse_blkzone_output(&output.stdout)
@@ -371,9 +428,10 @@ impl SMRDrive {
         Ok(zones)
     }
 
+    /// Parse one zone of `blkzone report`, whose addresses are 512-byte
+    /// sectors and whose write pointer is relative to the zone start
     #[cfg(target_os = "linux")]
     fn parse_blkzone_line(line: &str, zone_number: u32) -> Result<Zone> {
-        // Simplified parser - in production, use proper regex
         let zone_type = if line.contains("CONVENTIONAL") {
             ZoneType::Conventional
         } else if line.contains("SEQ_WRITE_REQUIRED") {
@@ -382,14 +440,31 @@ impl SMRDrive {
             ZoneType::SequentialWritePreferred
         };
 
+        let fields: Vec<&str> = line.split(|c: char| c == ',' || c.is_whitespace()).filter(|f| !f.is_empty()).collect();
+        let field = |name: &str| fields.iter().position(|f| *f == name).and_then(|i| fields.get(i + 1)).copied();
+        let number = |name: &str| {
+            field(name)
+                .ok_or_else(|| anyhow!("No {} in blkzone line: {}", name, line.trim()))
+                .and_then(parse_number)
+        };
+
+        let start = number("start:")?;
+        let len = number("len")?;
+        // Conventional zones have no write pointer
+        let wptr = number("wptr").unwrap_or(0);
+        // "zcond: 1(em)" - the abbreviation is what blkzone prints for the condition
+        let condition = field("zcond:")
+            .and_then(|cond| cond.split_once('(')?.1.strip_suffix(')'))
+            .map_or(ZoneCondition::Empty, ZoneCondition::parse);
+
         Ok(Zone {
             zone_number,
             zone_type,
-            write_pointer: 0,
-            zone_start_lba: 0,
-            zone_size: 256 * 1024 * 1024,
-            zone_condition: ZoneCondition::Empty,
-            zone_length: 0,
+            write_pointer: start + wptr,
+            zone_start_lba: start,
+            zone_size: len * 512,
+            zone_condition: condition,
+            zone_length: len,
         })
     }
 
@@ -439,10 +514,13 @@ impl SMRDrive {
                 .output()?;
 
             if output.status.success() {
-                return Ok(());
+                Ok(())
+            } else {
+                Err(anyhow!("blkzone reset failed ({})", command_failure(&output)))
             }
         }
 
+        #[cfg(not(target_os = "linux"))]
         Err(anyhow!("blkzone reset failed"))
     }
 
@@ -457,7 +535,7 @@ impl SMRDrive {
         if output.status.success() {
             Ok(())
         } else {
-            Err(anyhow!("sg_reset_wp failed"))
+            Err(anyhow!("sg_reset_wp failed ({})", command_failure(&output)))
         }
     }
 
@@ -492,13 +570,21 @@ impl SMRDrive {
         Ok(())
     }
 
-    /// Wipe SMR drive with proper zone handling
+    /// Wipe SMR drive with proper zone handling.
+    ///
+    /// Conventional zones accept random writes, so adjacent ones are merged
+    /// into a single range and overwritten like an ordinary disk. Each
+    /// sequential zone that holds data is reset (moving its write pointer
+    /// back to the zone start) and then written in order from there.
+    /// `write_data_fn` receives (byte offset, size, sequential) for every
+    /// range, in disk order. Read-only zones still hold readable data, so
+    /// they fail the wipe once everything else is done.
     pub fn wipe_smr_drive<F>(
         &self,
         mut write_data_fn: F,
-    ) -> Result<()>
+    ) -> Result<ZoneWipeSummary>
     where
-        F: FnMut(u64, u64) -> Result<()>,  // (offset, size) -> Result
+        F: FnMut(u64, u64, bool) -> Result<()>,  // (offset, size, sequential) -> Result
     {
         println!("Starting SMR-aware wipe of {}", self.device_path);
         println!("Zone model: {:?}", self.zone_model);
@@ -506,29 +592,64 @@ impl SMRDrive {
         println!("  Conventional: {}", self.conventional_zone_count);
         println!("  Sequential: {}", self.sequential_zone_count);
 
-        // Reset all zones first
-        self.reset_all_zones()?;
+        let mut summary = ZoneWipeSummary::default();
+        let mut read_only = Vec::new();
+        // Run of adjacent conventional zones not yet written
+        let mut conventional_run: Option<(u64, u64)> = None;
 
-        // Wipe each zone sequentially
         for zone in &self.zones {
-            println!("Wiping zone {} ({:?})...", zone.zone_number, zone.zone_type);
+            let offset = zone.zone_start_lba * 512;
 
-            match zone.zone_type {
-                ZoneType::Conventional => {
-                    // Can write randomly to conventional zones
-                    write_data_fn(zone.zone_start_lba * 512, zone.zone_size)?;
+            match zone.zone_condition {
+                ZoneCondition::Offline => {
+                    summary.offline += 1;
+                    continue;
                 }
-
-                ZoneType::SequentialWriteRequired |
-                ZoneType::SequentialWritePreferred => {
-                    // MUST write sequentially from start
-                    write_data_fn(zone.zone_start_lba * 512, zone.zone_size)?;
+                ZoneCondition::ReadOnly => {
+                    read_only.push(zone.zone_number);
+                    continue;
                 }
+                _ => {}
+            }
+
+            if zone.zone_type == ZoneType::Conventional {
+                summary.conventional += 1;
+                conventional_run = match conventional_run {
+                    Some((start, len)) if start + len == offset => Some((start, len + zone.zone_size)),
+                    Some((start, len)) => {
+                        write_data_fn(start, len, false)?;
+                        Some((offset, zone.zone_size))
+                    }
+                    None => Some((offset, zone.zone_size)),
+                };
+                continue;
+            }
+
+            if let Some((start, len)) = conventional_run.take() {
+                write_data_fn(start, len, false)?;
             }
+
+            // MUST write sequentially from start
+            if zone.needs_reset() || zone.write_pointer != zone.zone_start_lba {
+                self.reset_zone(zone.zone_number)?;
+            }
+            write_data_fn(offset, zone.zone_size, true)?;
+            summary.sequential += 1;
         }
 
-        println!("SMR wipe completed successfully");
-        Ok(())
+        if let Some((start, len)) = conventional_run {
+            write_data_fn(start, len, false)?;
+        }
+
+        if !read_only.is_empty() {
+            return Err(anyhow!("Zones {:?} are read-only and could not be overwritten", read_only));
+        }
+
+        println!(
+            "SMR wipe completed successfully: {} conventional, {} sequential, {} offline zone(s)",
+            summary.conventional, summary.sequential, summary.offline
+        );
+        Ok(summary)
     }
 
     /// Validate that SMR wipe was successful
@@ -548,6 +669,15 @@ impl SMRDrive {
     }
 }
 
+/// Decimal or 0x-prefixed hex, as sg3_utils and blkzone print LBAs
+fn parse_number(value: &str) -> Result<u64> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|_| anyhow!("Invalid zone address: {}", value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -587,4 +717,79 @@ mod tests {
 
         assert!(zone.is_writable());
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_zone_reports() {
+        let line = "  start: 0x000080000, len 0x080000, cap 0x080000, wptr 0x001000 reset:0 non-seq:0, zcond: 2(oi) [type: 2(SEQ_WRITE_REQUIRED)]";
+        let zone = SMRDrive::parse_blkzone_line(line, 1).unwrap();
+        assert_eq!(zone.zone_type, ZoneType::SequentialWriteRequired);
+        assert_eq!(zone.zone_start_lba, 0x80000);
+        assert_eq!(zone.write_pointer, 0x81000);
+        assert_eq!(zone.zone_size, 0x80000 * 512);
+        assert_eq!(zone.zone_condition, ZoneCondition::ImplicitlyOpen);
+
+        let report = b" Zone descriptor: 0\n   Zone type: Conventional\n   Zone condition: Not write pointer\n\
+            \x20  Zone Length: 0x80000\n   Zone start LBA: 0x0\n   Write pointer LBA: 0xffffffffffffffff\n\
+            \x20Zone descriptor: 1\n   Zone type: Sequential write required\n   Zone condition: Full\n\
+            \x20  Zone Length: 0x80000\n   Zone start LBA: 0x80000\n   Write pointer LBA: 0x100000\n";
+        let zones = SMRDrive::parse_sg_rep_zones_output(report).unwrap();
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].zone_type, ZoneType::Conventional);
+        assert_eq!(zones[1].zone_type, ZoneType::SequentialWriteRequired);
+        assert_eq!(zones[1].zone_start_lba, 0x80000);
+        assert_eq!(zones[1].zone_condition, ZoneCondition::Full);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_wipe_merges_conventional_zones_and_resets_sequential() {
+        use crate::commands::{scoped_runner, MockCommandRunner};
+        use std::sync::Arc;
+
+        let mock = Arc::new(MockCommandRunner::new());
+        let _guard = scoped_runner(mock.clone());
+
+        let zone = |zone_number: u32, zone_type, zone_condition, written: u64| Zone {
+            zone_number,
+            zone_type,
+            write_pointer: zone_number as u64 * 2048 + written,
+            zone_start_lba: zone_number as u64 * 2048,
+            zone_size: 2048 * 512,
+            zone_condition,
+            zone_length: 2048,
+        };
+        let drive = SMRDrive {
+            device_path: "/dev/sdz".to_string(),
+            zone_model: ZoneModel::HostManaged,
+            zones: vec![
+                zone(0, ZoneType::Conventional, ZoneCondition::Empty, 0),
+                zone(1, ZoneType::Conventional, ZoneCondition::Empty, 0),
+                zone(2, ZoneType::SequentialWriteRequired, ZoneCondition::Empty, 0),
+                zone(3, ZoneType::SequentialWriteRequired, ZoneCondition::ImplicitlyOpen, 8),
+                zone(4, ZoneType::SequentialWriteRequired, ZoneCondition::Offline, 0),
+            ],
+            total_capacity: 5 * 2048 * 512,
+            conventional_zone_count: 2,
+            sequential_zone_count: 3,
+            typical_zone_size: 2048 * 512,
+        };
+
+        let mut writes = Vec::new();
+        let summary = drive.wipe_smr_drive(|offset, size, sequential| {
+            writes.push((offset, size, sequential));
+            Ok(())
+        }).unwrap();
+
+        // Both conventional zones go out as one ordinary write, ahead of the sequential ones
+        assert_eq!(writes, [
+            (0, 2 * 2048 * 512, false),
+            (2 * 2048 * 512, 2048 * 512, true),
+            (3 * 2048 * 512, 2048 * 512, true),
+        ]);
+        assert_eq!(summary, ZoneWipeSummary { conventional: 2, sequential: 2, offline: 1 });
+
+        // Only the partially written zone needed its write pointer reset
+        assert_eq!(mock.calls(), ["blkzone reset /dev/sdz -o 6144 -c 1"]);
+    }
 }
@@ -1,5 +1,5 @@
 use anyhow::{Result, anyhow};
-use std::process::Command;
+use crate::commands::{command_failure, exit_description, Command};
 use std::thread;
 use std::time::Duration;
 use crate::ui::progress::ProgressBar;
@@ -30,7 +30,7 @@ impl SSDWipe {
                         println!("\nHardware secure erase completed successfully");
                         return Ok(());
                     } else {
-                        return Err(anyhow!("Secure erase failed"));
+                        return Err(anyhow!("Secure erase failed ({})", exit_description(status)));
                     }
                 }
                 None => {
@@ -66,8 +66,7 @@ impl SSDWipe {
             .args(["--user-master", "u", "--security-set-pass", password, device_path])
             .output()?;
         if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("Failed to set security password: {}", error));
+            return Err(anyhow!("Failed to set security password: {}", command_failure(&output)));
         }
         Ok(())
     }
@@ -19,7 +19,38 @@ const DEFAULT_TIME_INTERVAL: std::time::Duration = std::time::Duration::from_sec
 const DEFAULT_BYTES_INTERVAL: u64 = 1024 * 1024 * 1024;
 
 /// Default database path
-const DEFAULT_DB_PATH: &str = "/var/lib/sayonara-wipe/checkpoints.db";
+pub(crate) const DEFAULT_DB_PATH: &str = "/var/lib/sayonara-wipe/checkpoints.db";
+
+/// Layout of `Checkpoint` written by this build. Bump whenever the meaning of
+/// the progress fields or `state` changes, and teach `migrate()` the old one.
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 3;
+
+/// Checkpoints written before the format was versioned (no device identity)
+const LEGACY_FORMAT_VERSION: u32 = 1;
+
+/// Checkpoints with device identity but only a cumulative byte count
+const IDENTITY_FORMAT_VERSION: u32 = 2;
+
+/// Columns read back into a `Checkpoint`, in `Checkpoint::from_row` order
+const CHECKPOINT_COLUMNS: &str = "id, device_path, algorithm, operation_id,
+    current_pass, total_passes, bytes_written, total_size,
+    sectors_completed, state, config,
+    created_at, updated_at, error_count, last_error,
+    format_version, device_size, device_serial, device_wwn, pass_offset";
+
+fn legacy_format_version() -> u32 {
+    LEGACY_FORMAT_VERSION
+}
+
+/// What identifies the physical drive behind a device path, which can
+/// change across reboots and hotplug
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub size: u64,
+    pub serial: Option<String>,
+    /// World Wide Name / WWID from sysfs, when the drive reports one
+    pub wwn: Option<String>,
+}
 
 /// Universal checkpoint structure supporting all algorithms
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,9 +67,13 @@ pub struct Checkpoint {
     /// Operation session ID (groups related checkpoints)
     pub operation_id: String,
 
-    /// Current pass number
+    /// Current pass number (0-based); every earlier pass is complete
     pub current_pass: usize,
 
+    /// Bytes of `current_pass` already written, where that pass restarts
+    #[serde(default)]
+    pub pass_offset: u64,
+
     /// Total number of passes
     pub total_passes: usize,
 
@@ -68,6 +103,22 @@ pub struct Checkpoint {
 
     /// Last error message (if any)
     pub last_error: Option<String>,
+
+    /// Layout version this checkpoint was written with
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
+
+    /// Size in bytes of the device the checkpoint was written for
+    #[serde(default)]
+    pub device_size: Option<u64>,
+
+    /// Serial number of the device the checkpoint was written for
+    #[serde(default)]
+    pub device_serial: Option<String>,
+
+    /// World Wide Name of the device the checkpoint was written for
+    #[serde(default)]
+    pub device_wwn: Option<String>,
 }
 
 impl Checkpoint {
@@ -86,6 +137,7 @@ impl Checkpoint {
             algorithm: algorithm.into(),
             operation_id: operation_id.into(),
             current_pass: 0,
+            pass_offset: 0,
             total_passes,
             bytes_written: 0,
             total_size,
@@ -96,12 +148,125 @@ impl Checkpoint {
             updated_at: now,
             error_count: 0,
             last_error: None,
+            format_version: CHECKPOINT_FORMAT_VERSION,
+            device_size: None,
+            device_serial: None,
+            device_wwn: None,
+        }
+    }
+
+    /// Record which physical device this checkpoint belongs to
+    pub fn with_device(mut self, identity: &DeviceIdentity) -> Self {
+        self.device_size = Some(identity.size);
+        self.device_serial = identity.serial.clone();
+        self.device_wwn = identity.wwn.clone();
+        self
+    }
+
+    /// Whether the checkpoint says which physical device it was written for
+    pub fn has_device_identity(&self) -> bool {
+        self.device_size.is_some() || self.device_serial.is_some() || self.device_wwn.is_some()
+    }
+
+    /// Bring a checkpoint written by an older build up to the current format,
+    /// or reject it when its layout is unknown (e.g. written by a newer build)
+    pub fn migrate(mut self) -> Result<Self> {
+        match self.format_version {
+            CHECKPOINT_FORMAT_VERSION => Ok(self),
+            // Only the cumulative count was recorded; every pass covers the
+            // same bytes, so the offset into the current one follows from it
+            LEGACY_FORMAT_VERSION | IDENTITY_FORMAT_VERSION => {
+                let pass_size = self.total_size / self.total_passes.max(1) as u64;
+                self.pass_offset = self
+                    .bytes_written
+                    .saturating_sub(self.current_pass as u64 * pass_size)
+                    .min(pass_size);
+                self.format_version = CHECKPOINT_FORMAT_VERSION;
+                Ok(self)
+            }
+            version => Err(anyhow!(
+                "checkpoint format version {} is not supported (this build reads up to {})",
+                version,
+                CHECKPOINT_FORMAT_VERSION
+            )),
+        }
+    }
+
+    /// Confirm the checkpoint was written for this device, so a re-plugged
+    /// or swapped drive on the same path isn't resumed at the wrong offset.
+    /// Serial and WWN are compared when both sides know them.
+    pub fn check_device(&self, identity: &DeviceIdentity) -> Result<()> {
+        if !self.has_device_identity() {
+            return Err(anyhow!("checkpoint records no device identity, so it may belong to another drive"));
+        }
+        if let (Some(recorded), Some(current)) = (&self.device_wwn, &identity.wwn) {
+            if recorded != current {
+                return Err(anyhow!("checkpoint was written for WWN {}, this drive is {}", recorded, current));
+            }
+        }
+        if let (Some(recorded), Some(current)) = (&self.device_serial, &identity.serial) {
+            if recorded != current {
+                return Err(anyhow!("checkpoint was written for serial {}, this drive is {}", recorded, current));
+            }
         }
+        if let Some(recorded) = self.device_size.filter(|&recorded| recorded != identity.size) {
+            return Err(anyhow!("checkpoint was written for a {} byte device, this one has {} bytes", recorded, identity.size));
+        }
+        Ok(())
     }
 
-    /// Update progress information
-    pub fn update_progress(&mut self, pass: usize, bytes_written: u64) {
+    /// Decode a row selected with `CHECKPOINT_COLUMNS`
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Checkpoint {
+            id: row.get(0)?,
+            device_path: row.get(1)?,
+            algorithm: row.get(2)?,
+            operation_id: row.get(3)?,
+            current_pass: row.get::<_, i64>(4)? as usize,
+            total_passes: row.get::<_, i64>(5)? as usize,
+            bytes_written: row.get::<_, i64>(6)? as u64,
+            total_size: row.get::<_, i64>(7)? as u64,
+            sectors_completed: {
+                let json: String = row.get(8)?;
+                serde_json::from_str(&json).unwrap_or_default()
+            },
+            state: {
+                let json: String = row.get(9)?;
+                serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
+            },
+            config: {
+                let json: String = row.get(10)?;
+                serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
+            },
+            created_at: {
+                let s: String = row.get(11)?;
+                DateTime::parse_from_rfc3339(&s)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(Utc::now)
+            },
+            updated_at: {
+                let s: String = row.get(12)?;
+                DateTime::parse_from_rfc3339(&s)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(Utc::now)
+            },
+            error_count: row.get::<_, i64>(13)? as u32,
+            last_error: row.get(14)?,
+            format_version: row.get::<_, i64>(15)? as u32,
+            device_size: row.get::<_, Option<i64>>(16)?.map(|size| size as u64),
+            device_serial: row.get(17)?,
+            device_wwn: row.get(18)?,
+            pass_offset: row.get::<_, Option<i64>>(19)?.unwrap_or(0) as u64,
+        })
+    }
+
+    /// Update progress: `pass_offset` bytes into `pass`, `bytes_written`
+    /// across all passes
+    pub fn update_progress(&mut self, pass: usize, pass_offset: u64, bytes_written: u64) {
         self.current_pass = pass;
+        self.pass_offset = pass_offset;
         self.bytes_written = bytes_written;
         self.updated_at = Utc::now();
     }
@@ -220,6 +385,11 @@ impl CheckpointManager {
                 updated_at TEXT NOT NULL,
                 error_count INTEGER NOT NULL DEFAULT 0,
                 last_error TEXT,
+                format_version INTEGER NOT NULL DEFAULT 1,
+                device_size INTEGER,
+                device_serial TEXT,
+                device_wwn TEXT,
+                pass_offset INTEGER,
                 UNIQUE(device_path, algorithm, operation_id)
             );
 
@@ -231,6 +401,36 @@ impl CheckpointManager {
         )
         .context("Failed to create checkpoint schema")?;
 
+        self.migrate_schema()
+    }
+
+    /// Add the versioning, device identity and pass offset columns to a
+    /// database created by an older build. Its rows keep their format
+    /// version, and `Checkpoint::migrate` fills in what they lack.
+    fn migrate_schema(&mut self) -> Result<()> {
+        let mut stmt = self.conn.prepare("SELECT name FROM pragma_table_info('checkpoints')")?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to read checkpoint schema")?;
+        drop(stmt);
+
+        let added = [
+            ("format_version", "INTEGER NOT NULL DEFAULT 1"),
+            ("device_size", "INTEGER"),
+            ("device_serial", "TEXT"),
+            ("device_wwn", "TEXT"),
+            ("pass_offset", "INTEGER"),
+        ];
+        for (name, definition) in added {
+            if !columns.iter().any(|column| column == name) {
+                self.conn
+                    .execute_batch(&format!("ALTER TABLE checkpoints ADD COLUMN {} {}", name, definition))
+                    .with_context(|| format!("Failed to add checkpoint column {}", name))?;
+                tracing::info!(column = name, "Migrated checkpoint database schema");
+            }
+        }
+
         Ok(())
     }
 
@@ -259,19 +459,25 @@ impl CheckpointManager {
                 id, device_path, algorithm, operation_id,
                 current_pass, total_passes, bytes_written, total_size,
                 sectors_completed, state, config,
-                created_at, updated_at, error_count, last_error
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                created_at, updated_at, error_count, last_error,
+                format_version, device_size, device_serial, device_wwn, pass_offset
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
             ON CONFLICT(device_path, algorithm, operation_id)
             DO UPDATE SET
                 id = excluded.id,
                 current_pass = excluded.current_pass,
+                pass_offset = excluded.pass_offset,
                 bytes_written = excluded.bytes_written,
                 sectors_completed = excluded.sectors_completed,
                 state = excluded.state,
                 config = excluded.config,
                 updated_at = excluded.updated_at,
                 error_count = excluded.error_count,
-                last_error = excluded.last_error
+                last_error = excluded.last_error,
+                format_version = excluded.format_version,
+                device_size = excluded.device_size,
+                device_serial = excluded.device_serial,
+                device_wwn = excluded.device_wwn
             "#,
             params![
                 checkpoint.id,
@@ -289,6 +495,11 @@ impl CheckpointManager {
                 checkpoint.updated_at.to_rfc3339(),
                 checkpoint.error_count as i64,
                 checkpoint.last_error,
+                checkpoint.format_version as i64,
+                checkpoint.device_size.map(|size| size as i64),
+                checkpoint.device_serial,
+                checkpoint.device_wwn,
+                checkpoint.pass_offset as i64,
             ],
         )
         .context("Failed to insert checkpoint")?;
@@ -313,57 +524,15 @@ impl CheckpointManager {
     /// Load most recent checkpoint for device and algorithm
     pub fn load(&self, device_path: &str, algorithm: &str) -> Result<Option<Checkpoint>> {
         let row = self.conn.query_row(
-            r#"
-            SELECT id, device_path, algorithm, operation_id,
-                   current_pass, total_passes, bytes_written, total_size,
-                   sectors_completed, state, config,
-                   created_at, updated_at, error_count, last_error
-            FROM checkpoints
-            WHERE device_path = ?1 AND algorithm = ?2
-            ORDER BY updated_at DESC
-            LIMIT 1
-            "#,
+            &format!(
+                "SELECT {} FROM checkpoints
+                 WHERE device_path = ?1 AND algorithm = ?2
+                 ORDER BY updated_at DESC
+                 LIMIT 1",
+                CHECKPOINT_COLUMNS
+            ),
             params![device_path, algorithm],
-            |row| {
-                Ok(Checkpoint {
-                    id: row.get(0)?,
-                    device_path: row.get(1)?,
-                    algorithm: row.get(2)?,
-                    operation_id: row.get(3)?,
-                    current_pass: row.get::<_, i64>(4)? as usize,
-                    total_passes: row.get::<_, i64>(5)? as usize,
-                    bytes_written: row.get::<_, i64>(6)? as u64,
-                    total_size: row.get::<_, i64>(7)? as u64,
-                    sectors_completed: {
-                        let json: String = row.get(8)?;
-                        serde_json::from_str(&json).unwrap_or_default()
-                    },
-                    state: {
-                        let json: String = row.get(9)?;
-                        serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
-                    },
-                    config: {
-                        let json: String = row.get(10)?;
-                        serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
-                    },
-                    created_at: {
-                        let s: String = row.get(11)?;
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(Utc::now)
-                    },
-                    updated_at: {
-                        let s: String = row.get(12)?;
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(Utc::now)
-                    },
-                    error_count: row.get::<_, i64>(13)? as u32,
-                    last_error: row.get(14)?,
-                })
-            },
+            Checkpoint::from_row,
         ).optional()
         .context("Failed to load checkpoint")?;
 
@@ -373,55 +542,9 @@ impl CheckpointManager {
     /// Load checkpoint by ID
     pub fn load_by_id(&self, checkpoint_id: &str) -> Result<Option<Checkpoint>> {
         let row = self.conn.query_row(
-            r#"
-            SELECT id, device_path, algorithm, operation_id,
-                   current_pass, total_passes, bytes_written, total_size,
-                   sectors_completed, state, config,
-                   created_at, updated_at, error_count, last_error
-            FROM checkpoints
-            WHERE id = ?1
-            "#,
+            &format!("SELECT {} FROM checkpoints WHERE id = ?1", CHECKPOINT_COLUMNS),
             params![checkpoint_id],
-            |row| {
-                Ok(Checkpoint {
-                    id: row.get(0)?,
-                    device_path: row.get(1)?,
-                    algorithm: row.get(2)?,
-                    operation_id: row.get(3)?,
-                    current_pass: row.get::<_, i64>(4)? as usize,
-                    total_passes: row.get::<_, i64>(5)? as usize,
-                    bytes_written: row.get::<_, i64>(6)? as u64,
-                    total_size: row.get::<_, i64>(7)? as u64,
-                    sectors_completed: {
-                        let json: String = row.get(8)?;
-                        serde_json::from_str(&json).unwrap_or_default()
-                    },
-                    state: {
-                        let json: String = row.get(9)?;
-                        serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
-                    },
-                    config: {
-                        let json: String = row.get(10)?;
-                        serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
-                    },
-                    created_at: {
-                        let s: String = row.get(11)?;
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(Utc::now)
-                    },
-                    updated_at: {
-                        let s: String = row.get(12)?;
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(Utc::now)
-                    },
-                    error_count: row.get::<_, i64>(13)? as u32,
-                    last_error: row.get(14)?,
-                })
-            },
+            Checkpoint::from_row,
         ).optional()
         .context("Failed to load checkpoint by ID")?;
 
@@ -443,12 +566,19 @@ impl CheckpointManager {
         Ok(())
     }
 
-    /// Delete checkpoint by device and algorithm
+    /// Delete checkpoint by device and algorithm (`"*"` matches every algorithm)
     pub fn delete_by_device(&mut self, device_path: &str, algorithm: &str) -> Result<usize> {
-        let deleted = self.conn.execute(
-            "DELETE FROM checkpoints WHERE device_path = ?1 AND algorithm = ?2",
-            params![device_path, algorithm],
-        )
+        let deleted = if algorithm == "*" {
+            self.conn.execute(
+                "DELETE FROM checkpoints WHERE device_path = ?1",
+                params![device_path],
+            )
+        } else {
+            self.conn.execute(
+                "DELETE FROM checkpoints WHERE device_path = ?1 AND algorithm = ?2",
+                params![device_path, algorithm],
+            )
+        }
         .context("Failed to delete checkpoint by device")?;
 
         Ok(deleted)
@@ -457,57 +587,11 @@ impl CheckpointManager {
     /// List all checkpoints
     pub fn list_all(&self) -> Result<Vec<Checkpoint>> {
         let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, device_path, algorithm, operation_id,
-                   current_pass, total_passes, bytes_written, total_size,
-                   sectors_completed, state, config,
-                   created_at, updated_at, error_count, last_error
-            FROM checkpoints
-            ORDER BY updated_at DESC
-            "#,
+            &format!("SELECT {} FROM checkpoints ORDER BY updated_at DESC", CHECKPOINT_COLUMNS),
         )
         .context("Failed to prepare list query")?;
 
-        let checkpoints = stmt.query_map([], |row| {
-            Ok(Checkpoint {
-                id: row.get(0)?,
-                device_path: row.get(1)?,
-                algorithm: row.get(2)?,
-                operation_id: row.get(3)?,
-                current_pass: row.get::<_, i64>(4)? as usize,
-                total_passes: row.get::<_, i64>(5)? as usize,
-                bytes_written: row.get::<_, i64>(6)? as u64,
-                total_size: row.get::<_, i64>(7)? as u64,
-                sectors_completed: {
-                    let json: String = row.get(8)?;
-                    serde_json::from_str(&json).unwrap_or_default()
-                },
-                state: {
-                    let json: String = row.get(9)?;
-                    serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
-                },
-                config: {
-                    let json: String = row.get(10)?;
-                    serde_json::from_str(&json).unwrap_or(serde_json::Value::Null)
-                },
-                created_at: {
-                    let s: String = row.get(11)?;
-                    DateTime::parse_from_rfc3339(&s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(Utc::now)
-                },
-                updated_at: {
-                    let s: String = row.get(12)?;
-                    DateTime::parse_from_rfc3339(&s)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(Utc::now)
-                },
-                error_count: row.get::<_, i64>(13)? as u32,
-                last_error: row.get(14)?,
-            })
-        })
+        let checkpoints = stmt.query_map([], Checkpoint::from_row)
         .context("Failed to query checkpoints")?
         .collect::<Result<Vec<_>, _>>()
         .context("Failed to collect checkpoints")?;
@@ -581,6 +665,14 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    fn test_identity() -> DeviceIdentity {
+        DeviceIdentity {
+            size: 1000,
+            serial: Some("S3Z9NB0K".to_string()),
+            wwn: Some("naa.5002538e40a1b2c3".to_string()),
+        }
+    }
+
     fn create_test_manager() -> (CheckpointManager, TempDir) {
         let temp_dir = TempDir::new().unwrap();
         let db_path = temp_dir.path().join("test_checkpoints.db");
@@ -603,7 +695,7 @@ mod tests {
         let (mut manager, _temp) = create_test_manager();
 
         let mut cp = Checkpoint::new("/dev/sda", "Gutmann", "op-123", 35, 1024 * 1024 * 1024);
-        cp.update_progress(5, 512 * 1024 * 1024);
+        cp.update_progress(5, 0, 512 * 1024 * 1024);
 
         manager.save(&cp).unwrap();
 
@@ -620,11 +712,11 @@ mod tests {
         let mut cp = Checkpoint::new("/dev/sda", "DoD", "op-456", 3, 1024 * 1024);
         manager.save(&cp).unwrap();
 
-        cp.update_progress(2, 800 * 1024);
+        cp.update_progress(2, 4096, 800 * 1024);
         manager.save(&cp).unwrap();
 
         let loaded = manager.load("/dev/sda", "DoD").unwrap().unwrap();
-        assert_eq!(loaded.current_pass, 2);
+        assert_eq!((loaded.current_pass, loaded.pass_offset), (2, 4096));
         assert_eq!(loaded.bytes_written, 800 * 1024);
     }
 
@@ -729,6 +821,104 @@ mod tests {
         assert_eq!(cp.completion_percentage(), 100.0);
     }
 
+    #[test]
+    fn test_version_and_identity_round_trip() {
+        let (mut manager, _temp) = create_test_manager();
+
+        let cp = Checkpoint::new("/dev/sda", "DoD", "op-1", 3, 3000).with_device(&test_identity());
+        manager.save(&cp).unwrap();
+
+        let loaded = manager.load("/dev/sda", "DoD").unwrap().unwrap();
+        assert_eq!(loaded.format_version, CHECKPOINT_FORMAT_VERSION);
+        assert_eq!(loaded.device_size, Some(1000));
+        assert_eq!(loaded.device_serial.as_deref(), Some("S3Z9NB0K"));
+        assert_eq!(loaded.device_wwn.as_deref(), Some("naa.5002538e40a1b2c3"));
+        assert_eq!(loaded, cp.clone().migrate().unwrap());
+    }
+
+    #[test]
+    fn test_check_device_rejects_other_drive() {
+        let identity = test_identity();
+        let cp = Checkpoint::new("/dev/sda", "DoD", "op-1", 3, 3000).with_device(&identity);
+
+        assert!(cp.check_device(&identity).is_ok());
+        // Serial and WWN unreadable now: the size still has to match
+        let anonymous_now = DeviceIdentity { serial: None, wwn: None, ..identity.clone() };
+        assert!(cp.check_device(&anonymous_now).is_ok());
+        assert!(cp.check_device(&DeviceIdentity { size: 2000, ..identity.clone() }).is_err());
+        assert!(cp.check_device(&DeviceIdentity { serial: Some("WD-WCC4N7".to_string()), ..identity.clone() }).is_err());
+        let other_wwn = DeviceIdentity { wwn: Some("naa.50014ee2b5c3d4e5".to_string()), ..identity.clone() };
+        assert!(cp.check_device(&other_wwn).unwrap_err().to_string().contains("WWN"));
+
+        let anonymous = Checkpoint::new("/dev/sda", "DoD", "op-1", 3, 3000);
+        assert!(!anonymous.has_device_identity());
+        assert!(anonymous.check_device(&identity).is_err());
+    }
+
+    #[test]
+    fn test_unknown_format_version_rejected() {
+        let mut cp = Checkpoint::new("/dev/sda", "DoD", "op-1", 3, 3000);
+        cp.format_version = CHECKPOINT_FORMAT_VERSION + 1;
+        assert!(cp.migrate().is_err());
+
+        // Pre-versioning JSON carries no version field
+        let mut json = serde_json::to_value(Checkpoint::new("/dev/sda", "DoD", "op-1", 3, 3000)).unwrap();
+        json.as_object_mut().unwrap().remove("format_version");
+        let legacy: Checkpoint = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.format_version, LEGACY_FORMAT_VERSION);
+        assert_eq!(legacy.migrate().unwrap().format_version, CHECKPOINT_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_legacy_database_migrated() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("legacy.db");
+
+        // Schema and row as written before checkpoints were versioned
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE checkpoints (
+                id TEXT PRIMARY KEY NOT NULL,
+                device_path TEXT NOT NULL,
+                algorithm TEXT NOT NULL,
+                operation_id TEXT NOT NULL,
+                current_pass INTEGER NOT NULL,
+                total_passes INTEGER NOT NULL,
+                bytes_written INTEGER NOT NULL,
+                total_size INTEGER NOT NULL,
+                sectors_completed TEXT,
+                state TEXT,
+                config TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                error_count INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                UNIQUE(device_path, algorithm, operation_id)
+            );
+            INSERT INTO checkpoints VALUES (
+                'old-id', '/dev/sda', 'DoD', 'op-old', 1, 3, 1500, 3000, '[]', 'null', 'null',
+                '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00', 0, NULL
+            );
+            "#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let manager = CheckpointManager::new(Some(db_path.to_str().unwrap())).unwrap();
+        let loaded = manager.load("/dev/sda", "DoD").unwrap().unwrap();
+        assert_eq!(loaded.format_version, LEGACY_FORMAT_VERSION);
+        assert_eq!(loaded.bytes_written, 1500);
+
+        // Upgradable, but it can't prove which drive it was written for
+        let migrated = loaded.migrate().unwrap();
+        assert!(!migrated.has_device_identity());
+
+        // Halfway through the second of three 1000-byte passes
+        assert_eq!((migrated.current_pass, migrated.pass_offset), (1, 500));
+        assert!(migrated.check_device(&test_identity()).is_err());
+    }
+
     #[test]
     fn test_save_performance() {
         let (mut manager, _temp) = create_test_manager();
@@ -240,6 +240,7 @@ impl ErrorClassifier {
         match error {
             // User interruption - highest priority
             DriveError::Interrupted => ErrorClass::UserInterrupted,
+            DriveError::Cancelled => ErrorClass::UserInterrupted,
 
             // Environmental errors - need time to resolve
             DriveError::TemperatureExceeded(_) => ErrorClass::Environmental,
@@ -266,9 +267,9 @@ impl ErrorClassifier {
             // I/O errors - analyze the underlying error
             DriveError::IoError(io_err) => self.classify_io_error(io_err),
 
-            // Hardware command failures - might be recoverable
+            // Hardware command failures - retried unless the drive refused the command itself
             DriveError::HardwareCommandFailed(msg) => {
-                if msg.contains("not supported") || msg.contains("invalid command") {
+                if is_permanent_command_failure(msg) {
                     ErrorClass::Fatal
                 } else {
                     ErrorClass::Transient
@@ -280,8 +281,15 @@ impl ErrorClassifier {
 
             // Fatal errors - cannot recover
             DriveError::NotFound(_) => ErrorClass::Fatal,
+            DriveError::MissingTool(_) => ErrorClass::Fatal,
             DriveError::PermissionDenied(_) => ErrorClass::Fatal,
             DriveError::Unsupported(_) => ErrorClass::Fatal,
+            DriveError::SystemDisk(_) => ErrorClass::Fatal,
+            DriveError::Mounted(_) => ErrorClass::Fatal,
+            DriveError::Unhealthy(_) => ErrorClass::Fatal,
+            DriveError::CheckpointMismatch(_) => ErrorClass::Fatal,
+            DriveError::SizeMismatch(_) => ErrorClass::Fatal,
+            DriveError::SerialNotPermitted(_) => ErrorClass::Fatal,
         }
     }
 
@@ -289,6 +297,10 @@ impl ErrorClassifier {
     fn classify_io_error(&self, io_err: &std::io::Error) -> ErrorClass {
         use std::io::ErrorKind;
 
+        if is_transient_io_error(io_err) {
+            return ErrorClass::Transient;
+        }
+
         match io_err.kind() {
             // Transient errors that may resolve on retry
             ErrorKind::Interrupted => ErrorClass::Transient,
@@ -377,6 +389,30 @@ impl Default for ErrorClassifier {
     }
 }
 
+/// Whether a failed read or write may succeed if simply repeated: the
+/// device was busy or the call interrupted, or an EIO of the kind a USB or
+/// SATA bus reset produces. Media errors also surface as EIO, so callers
+/// stop retrying ranges already known to hold bad sectors.
+pub fn is_transient_io_error(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    match error.raw_os_error() {
+        Some(libc::EAGAIN | libc::EINTR | libc::EBUSY | libc::ETIMEDOUT | libc::EIO) => true,
+        Some(_) => false,
+        None => matches!(error.kind(), ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut),
+    }
+}
+
+/// Whether a hardware command's failure message shows the drive rejected
+/// the command outright (unsupported, invalid or illegal), so repeating it
+/// can't help
+pub fn is_permanent_command_failure(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["not supported", "unsupported", "invalid", "illegal request", "permission denied"]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -518,4 +554,21 @@ mod tests {
         let classified = classifier.classify(error, context);
         assert_eq!(classified.class, ErrorClass::Fatal);
     }
+
+    #[test]
+    fn test_transient_errors() {
+        assert!(is_transient_io_error(&std::io::Error::from_raw_os_error(libc::EAGAIN)));
+        assert!(is_transient_io_error(&std::io::Error::from_raw_os_error(libc::EIO)));
+        assert!(!is_transient_io_error(&std::io::Error::from_raw_os_error(libc::ENOSPC)));
+        assert!(!is_transient_io_error(&std::io::Error::from_raw_os_error(libc::EROFS)));
+
+        assert!(is_permanent_command_failure("exit code 1: NVMe status: INVALID_FIELD(0x2)"));
+        assert!(is_permanent_command_failure("SANITIZE rejected: Illegal request, invalid opcode"));
+        assert!(!is_permanent_command_failure("exit code 2: Unit attention, power on, reset"));
+
+        let classifier = ErrorClassifier::new();
+        let context = ErrorContext::new("test_op", "/dev/sda");
+        let busy = DriveError::HardwareCommandFailed("Device or resource busy".to_string());
+        assert_eq!(classifier.classify(busy, context).class, ErrorClass::Transient);
+    }
 }
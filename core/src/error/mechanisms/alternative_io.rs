@@ -6,7 +6,12 @@
 use anyhow::{Context, Result};
 use std::fs::OpenOptions;
 use std::io::{Seek, SeekFrom, Write};
-use std::os::unix::fs::OpenOptionsExt;
+
+/// Windows counterparts of O_DIRECT and O_SYNC for `CreateFile`
+#[cfg(windows)]
+const FILE_FLAG_NO_BUFFERING: u32 = 0x2000_0000;
+#[cfg(windows)]
+const FILE_FLAG_WRITE_THROUGH: u32 = 0x8000_0000;
 
 /// I/O method types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -153,10 +158,13 @@ impl AlternativeIO {
             return Err(anyhow::anyhow!("Data not aligned for O_DIRECT"));
         }
 
-        let mut file = OpenOptions::new()
-            .write(true)
-            .custom_flags(libc::O_DIRECT)
-            .open(device)
+        let mut options = OpenOptions::new();
+        options.write(true);
+        #[cfg(target_os = "linux")]
+        std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_DIRECT);
+        #[cfg(windows)]
+        std::os::windows::fs::OpenOptionsExt::custom_flags(&mut options, FILE_FLAG_NO_BUFFERING);
+        let mut file = options.open(device)
             .context("Failed to open device with O_DIRECT")?;
 
         file.seek(SeekFrom::Start(offset))
@@ -230,16 +238,20 @@ impl AlternativeIO {
 
         #[cfg(not(target_os = "linux"))]
         {
+            let _ = (device, offset, data);
             Err(anyhow::anyhow!("Memory-mapped I/O not supported on this platform"))
         }
     }
 
     /// Synchronous write with O_SYNC
     fn write_synchronous(&self, device: &str, offset: u64, data: &[u8]) -> Result<()> {
-        let mut file = OpenOptions::new()
-            .write(true)
-            .custom_flags(libc::O_SYNC)
-            .open(device)
+        let mut options = OpenOptions::new();
+        options.write(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_SYNC);
+        #[cfg(windows)]
+        std::os::windows::fs::OpenOptionsExt::custom_flags(&mut options, FILE_FLAG_WRITE_THROUGH);
+        let mut file = options.open(device)
             .context("Failed to open device with O_SYNC")?;
 
         file.seek(SeekFrom::Start(offset))
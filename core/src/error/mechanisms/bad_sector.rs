@@ -12,7 +12,29 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 /// Default maximum bad sectors before aborting (1% of drive)
-const DEFAULT_MAX_BAD_SECTORS: usize = 10000;
+pub const DEFAULT_MAX_BAD_SECTORS: usize = 10000;
+
+/// A run of contiguous logical blocks that could not be written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BadSectorRange {
+    /// First unwritable LBA, in the drive's logical blocks
+    pub start_lba: u64,
+
+    /// Number of contiguous unwritable sectors
+    pub sector_count: u64,
+}
+
+impl BadSectorRange {
+    /// One past the last LBA in the range
+    pub fn end_lba(&self) -> u64 {
+        self.start_lba + self.sector_count
+    }
+
+    /// Whether `lba` falls inside the range
+    pub fn contains(&self, lba: u64) -> bool {
+        lba >= self.start_lba && lba < self.end_lba()
+    }
+}
 
 /// Bad sector handler with logging
 pub struct BadSectorHandler {
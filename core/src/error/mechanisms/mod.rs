@@ -10,6 +10,6 @@ pub mod self_heal;
 
 // Re-export main types
 pub use alternative_io::{AlternativeIO, IOMethod};
-pub use bad_sector::{BadSectorHandler, BadSectorReport, WriteResult};
+pub use bad_sector::{BadSectorHandler, BadSectorRange, BadSectorReport, WriteResult};
 pub use degraded_mode::{DegradedMode, DegradedModeManager};
 pub use self_heal::{HealMethod, SelfHealer};
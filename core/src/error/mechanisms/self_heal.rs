@@ -9,7 +9,7 @@
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
-use std::process::Command;
+use crate::commands::{command_failure, Command};
 use std::thread;
 use std::time::Duration;
 
@@ -132,13 +132,13 @@ impl SelfHealer {
 
         // Remove driver module
         tracing::debug!("Running: rmmod {}", driver);
-        let status = Command::new("rmmod")
+        let output = Command::new("rmmod")
             .arg(&driver)
-            .status()
+            .output()
             .context("Failed to execute rmmod")?;
 
-        if !status.success() {
-            return Err(anyhow::anyhow!("Failed to remove driver module {}", driver));
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("Failed to remove driver module {} ({})", driver, command_failure(&output)));
         }
 
         // Wait a moment
@@ -146,13 +146,13 @@ impl SelfHealer {
 
         // Reload driver module
         tracing::debug!("Running: modprobe {}", driver);
-        let status = Command::new("modprobe")
+        let output = Command::new("modprobe")
             .arg(&driver)
-            .status()
+            .output()
             .context("Failed to execute modprobe")?;
 
-        if !status.success() {
-            return Err(anyhow::anyhow!("Failed to reload driver module {}", driver));
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("Failed to reload driver module {} ({})", driver, command_failure(&output)));
         }
 
         // Wait for device re-enumeration
@@ -357,7 +357,7 @@ impl SelfHealer {
             .context("Failed to reset MegaRAID controller")?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!("MegaRAID reset command failed"));
+            return Err(anyhow::anyhow!("MegaRAID reset command failed ({})", command_failure(&output)));
         }
 
         thread::sleep(Duration::from_secs(10));
@@ -378,7 +378,7 @@ impl SelfHealer {
             .context("Failed to reset HP SmartArray")?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!("HP SmartArray reset failed"));
+            return Err(anyhow::anyhow!("HP SmartArray reset failed ({})", command_failure(&output)));
         }
 
         thread::sleep(Duration::from_secs(5));
@@ -395,7 +395,7 @@ impl SelfHealer {
             .context("Failed to unload mpt3sas")?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!("Failed to unload mpt3sas driver"));
+            return Err(anyhow::anyhow!("Failed to unload mpt3sas driver ({})", command_failure(&output)));
         }
 
         thread::sleep(Duration::from_secs(1));
@@ -406,7 +406,7 @@ impl SelfHealer {
             .context("Failed to load mpt3sas")?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!("Failed to reload mpt3sas driver"));
+            return Err(anyhow::anyhow!("Failed to reload mpt3sas driver ({})", command_failure(&output)));
         }
 
         thread::sleep(Duration::from_secs(5));
@@ -431,7 +431,7 @@ impl SelfHealer {
             .context("Failed to execute IPMI power cycle")?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!("IPMI power cycle command failed"));
+            return Err(anyhow::anyhow!("IPMI power cycle command failed ({})", command_failure(&output)));
         }
 
         Ok(())
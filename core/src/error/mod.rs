@@ -43,7 +43,7 @@
 /// use sayonara_wipe::WipeConfig;
 ///
 /// let config = WipeConfig::default();
-/// let mut coordinator = RecoveryCoordinator::new("/dev/sda", &config)?;
+/// let coordinator = RecoveryCoordinator::new("/dev/sda", &config)?;
 ///
 /// // Try to resume from checkpoint
 /// if let Some(resume) = coordinator.resume_from_checkpoint("Gutmann")? {
@@ -71,7 +71,9 @@
 ///         total_size,
 ///         &Progress {
 ///             current_pass: pass,
+///             pass_offset: 0,
 ///             bytes_written: (pass as u64 + 1) * total_size,
+///             current_offset: None,
 ///             state: serde_json::json!({"pass": pass}),
 ///         }
 ///     )?;
@@ -85,15 +87,19 @@
 pub mod checkpoint;
 pub mod classification;
 pub mod mechanisms;
+pub mod progress_record;
 pub mod recovery_coordinator;
 pub mod retry;
 
 // Re-export main types for convenience
-pub use checkpoint::{Checkpoint, CheckpointManager, CheckpointStats};
-pub use classification::{ClassifiedError, ErrorClass, ErrorClassifier, ErrorContext};
+pub use checkpoint::{Checkpoint, CheckpointManager, CheckpointStats, DeviceIdentity, CHECKPOINT_FORMAT_VERSION};
+pub use classification::{
+    is_permanent_command_failure, is_transient_io_error, ClassifiedError, ErrorClass, ErrorClassifier, ErrorContext,
+};
 pub use mechanisms::{
-    AlternativeIO, BadSectorHandler, BadSectorReport, DegradedMode, DegradedModeManager,
+    AlternativeIO, BadSectorHandler, BadSectorRange, BadSectorReport, DegradedMode, DegradedModeManager,
     HealMethod, IOMethod, SelfHealer, WriteResult,
 };
+pub use progress_record::ProgressRecord;
 pub use recovery_coordinator::{Progress, RecoveryAction, RecoveryCoordinator, ResumeState};
-pub use retry::{CircuitBreaker, ExponentialBackoff, RetryConfig, RetryStrategy};
+pub use retry::{CircuitBreaker, ExponentialBackoff, RetryConfig, RetryStrategy, TransientRetry};
@@ -0,0 +1,137 @@
+// Progress record - a human-readable snapshot of an in-flight wipe
+//
+// The recovery coordinator rewrites this JSON file as the wipe advances, in
+// a `progress/` directory beside the checkpoint database. Unlike the
+// checkpoint it is meant to be read by people and monitoring tools: another
+// process can see how far a running wipe has got, or where a crashed one
+// stopped, without attaching to it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::checkpoint::DEFAULT_DB_PATH;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressRecord {
+    pub device_path: String,
+    pub algorithm: String,
+    pub operation_id: String,
+    /// Process running the wipe
+    pub pid: u32,
+    /// 1-based pass being written
+    pub current_pass: usize,
+    pub total_passes: usize,
+    pub bytes_written: u64,
+    pub total_bytes: u64,
+    pub percent: f64,
+    /// Device offset the wipe had reached, when the algorithm tracks one
+    pub current_offset: Option<u64>,
+    /// Write rate since the previous record
+    pub bytes_per_second: f64,
+    pub updated_at: DateTime<Utc>,
+    /// Set when the wipe finished; otherwise it is running, or died
+    pub completed: bool,
+}
+
+impl ProgressRecord {
+    /// File holding the record for `device_path`, beside the checkpoint
+    /// database at `db_path` (the default location when None)
+    pub fn path_for(device_path: &str, db_path: Option<&str>) -> PathBuf {
+        let db_path = Path::new(db_path.unwrap_or(DEFAULT_DB_PATH));
+        let name = device_path.trim_start_matches('/').replace('/', "_");
+        db_path.parent().unwrap_or(Path::new(".")).join("progress").join(format!("{}.json", name))
+    }
+
+    /// Replace the record at `path`, through a rename so a reader never
+    /// sees a half-written file
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create progress record directory")?;
+        }
+        let partial = path.with_extension("json.tmp");
+        std::fs::write(&partial, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    /// The record at `path`, or None when no wipe has written one
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Ok(Some(serde_json::from_str(&json).context("Corrupt progress record")?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Whether the process that wrote the record is still running
+    pub fn process_running(&self) -> bool {
+        Path::new(&format!("/proc/{}", self.pid)).exists()
+    }
+
+    /// "running", "completed", or "interrupted" for a wipe whose process
+    /// is gone without finishing
+    pub fn state(&self) -> &'static str {
+        if self.completed {
+            "completed"
+        } else if self.process_running() {
+            "running"
+        } else {
+            "interrupted"
+        }
+    }
+}
+
+impl std::fmt::Display for ProgressRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} ({} wipe, {})", self.device_path, self.algorithm, self.state())?;
+        writeln!(f, "  Pass:       {}/{}", self.current_pass, self.total_passes)?;
+        writeln!(
+            f,
+            "  Progress:   {:.1}% ({} of {} bytes)",
+            self.percent, self.bytes_written, self.total_bytes
+        )?;
+        if let Some(offset) = self.current_offset {
+            writeln!(f, "  Offset:     {}", offset)?;
+        }
+        writeln!(f, "  Throughput: {:.1} MB/s", self.bytes_per_second / 1_000_000.0)?;
+        write!(f, "  Updated:    {} (pid {})", self.updated_at.format("%Y-%m-%d %H:%M:%S UTC"), self.pid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("checkpoints.db");
+        let path = ProgressRecord::path_for("/dev/nvme0n1", db.to_str());
+        assert_eq!(path, dir.path().join("progress/dev_nvme0n1.json"));
+        assert_eq!(ProgressRecord::read(&path).unwrap(), None);
+
+        let record = ProgressRecord {
+            device_path: "/dev/nvme0n1".to_string(),
+            algorithm: "DoD5220".to_string(),
+            operation_id: "op".to_string(),
+            pid: std::process::id(),
+            current_pass: 2,
+            total_passes: 3,
+            bytes_written: 1500,
+            total_bytes: 3000,
+            percent: 50.0,
+            current_offset: Some(500),
+            bytes_per_second: 2_000_000.0,
+            updated_at: Utc::now(),
+            completed: false,
+        };
+        record.write(&path).unwrap();
+
+        let read = ProgressRecord::read(&path).unwrap().unwrap();
+        assert_eq!(read, record);
+        assert_eq!(read.state(), "running");
+        assert!(read.to_string().contains("Pass:       2/3"));
+    }
+}
@@ -7,17 +7,23 @@
 /// - Applies recovery mechanisms (bad sector handling, self-healing, degraded mode)
 /// - Provides circuit breaker protection
 
-use super::checkpoint::{Checkpoint, CheckpointManager};
+use super::checkpoint::{Checkpoint, CheckpointManager, DeviceIdentity};
 use super::classification::{ClassifiedError, ErrorClassifier, ErrorClass, ErrorContext};
 use super::mechanisms::{
     AlternativeIO, BadSectorHandler, DegradedMode, DegradedModeManager, HealMethod, SelfHealer,
 };
-use super::retry::{CircuitBreaker, RetryConfig};
-use crate::{DriveError, DriveResult, WipeConfig};
+use super::progress_record::ProgressRecord;
+use super::retry::{CircuitBreaker, ExponentialBackoff, RetryConfig};
+use crate::{CancellationToken, DriveError, DriveResult, WipeConfig};
 use anyhow::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::path::PathBuf;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Least time between rewrites of the progress record
+const PROGRESS_RECORD_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Progress information for checkpointing
 #[derive(Debug, Clone)]
@@ -25,9 +31,15 @@ pub struct Progress {
     /// Current pass number
     pub current_pass: usize,
 
+    /// Bytes of the current pass written so far
+    pub pass_offset: u64,
+
     /// Bytes written so far
     pub bytes_written: u64,
 
+    /// Device offset reached, when the algorithm tracks one
+    pub current_offset: Option<u64>,
+
     /// Algorithm-specific state (JSON-serializable)
     pub state: serde_json::Value,
 }
@@ -60,9 +72,12 @@ pub struct ResumeState {
     /// Checkpoint that was loaded
     pub checkpoint: Checkpoint,
 
-    /// Pass to resume from
+    /// Pass to resume from; every earlier pass is complete
     pub current_pass: usize,
 
+    /// Bytes of `current_pass` already written
+    pub pass_offset: u64,
+
     /// Bytes already written
     pub bytes_written: u64,
 
@@ -101,15 +116,43 @@ pub struct RecoveryCoordinator {
 
     /// Operation ID for this session
     operation_id: String,
+
+    /// Stops retries once the wipe has been cancelled
+    cancel: CancellationToken,
+
+    /// Physical drive behind `device_path`, stamped on checkpoints and checked on resume
+    device_identity: Option<DeviceIdentity>,
+
+    /// Retries run so far across all operations, for the wipe report
+    retries: AtomicU64,
+
+    /// Human-readable progress file for `WipeOrchestrator::status`
+    progress_path: PathBuf,
+
+    /// When the progress record was last written, and the bytes it showed
+    last_progress: Mutex<Option<(Instant, u64)>>,
+
+    /// Started when the coordinator was created, for the first record's rate
+    started: Instant,
 }
 
 impl RecoveryCoordinator {
     /// Create new recovery coordinator
-    pub fn new(device_path: impl Into<String>, _config: &WipeConfig) -> Result<Self> {
+    pub fn new(device_path: impl Into<String>, config: &WipeConfig) -> Result<Self> {
+        Self::with_checkpoint_db(device_path, config, None)
+    }
+
+    /// Create a recovery coordinator whose checkpoints live in a specific
+    /// database file (the default location is used when `db_path` is None)
+    pub fn with_checkpoint_db(
+        device_path: impl Into<String>,
+        config: &WipeConfig,
+        db_path: Option<&str>,
+    ) -> Result<Self> {
         let device_path = device_path.into();
         let operation_id = uuid::Uuid::new_v4().to_string();
 
-        let checkpoint_manager = Arc::new(Mutex::new(CheckpointManager::new(None)?));
+        let checkpoint_manager = Arc::new(Mutex::new(CheckpointManager::new(db_path)?));
 
         // Set up bad sector handler if needed
         let bad_sector_handler = Some(
@@ -117,20 +160,52 @@ impl RecoveryCoordinator {
                 .with_log_file(BadSectorHandler::default_log_file(&device_path))
         );
 
+        let mut retry_config = RetryConfig::new();
+        retry_config.set_strategy(
+            ErrorClass::Transient,
+            Box::new(ExponentialBackoff::new(
+                Duration::from_millis(config.retry_backoff_ms),
+                Duration::from_secs(30),
+                config.transient_retries,
+            )),
+        );
+
         Ok(Self {
             classifier: ErrorClassifier::new(),
             checkpoint_manager,
-            retry_config: RetryConfig::new(),
+            retry_config,
             circuit_breaker: CircuitBreaker::default_device(),
             bad_sector_handler,
             self_healer: SelfHealer::new(),
             degraded_mode: Arc::new(Mutex::new(DegradedModeManager::new())),
             alternative_io: Arc::new(Mutex::new(AlternativeIO::new())),
-            device_path,
             operation_id,
+            cancel: CancellationToken::new(),
+            device_identity: None,
+            retries: AtomicU64::new(0),
+            progress_path: ProgressRecord::path_for(&device_path, db_path),
+            last_progress: Mutex::new(None),
+            started: Instant::now(),
+            device_path,
         })
     }
 
+    /// Use `cancel` to stop retrying once the wipe has been cancelled
+    pub fn set_cancellation(&mut self, cancel: CancellationToken) {
+        self.cancel = cancel;
+    }
+
+    /// Identify the physical device, so checkpoints written for a different
+    /// drive on the same path are never resumed
+    pub fn set_device_identity(&mut self, identity: DeviceIdentity) {
+        self.device_identity = Some(identity);
+    }
+
+    /// Operations retried after a transient failure so far
+    pub fn retry_count(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
     /// Execute operation with recovery
     ///
     /// This wraps any fallible operation with full error recovery:
@@ -167,8 +242,9 @@ impl RecoveryCoordinator {
                     )));
             }
 
-            // Execute operation within circuit breaker
-            match self.circuit_breaker.call(|| operation().map_err(|e| anyhow::anyhow!("{}", e))) {
+            // Execute operation within circuit breaker, keeping the
+            // DriveError itself so it is classified by kind below
+            match self.circuit_breaker.call(|| operation().map_err(anyhow::Error::from)) {
                 Ok(result) => {
                     if attempt > 0 {
                         tracing::info!(
@@ -180,6 +256,10 @@ impl RecoveryCoordinator {
                     }
                     return Ok(result);
                 }
+                Err(_) if self.cancel.is_cancelled() => {
+                    // A cancelled operation must not be retried
+                    return Err(DriveError::Cancelled);
+                }
                 Err(error) => {
                     // Convert anyhow::Error to DriveError
                     let drive_error = match error.downcast::<DriveError>() {
@@ -223,6 +303,7 @@ impl RecoveryCoordinator {
                             );
 
                             thread::sleep(after);
+                            self.retries.fetch_add(1, Ordering::Relaxed);
                             attempt += 1;
                             last_classified_error = Some(classified);
                             continue;
@@ -326,67 +407,182 @@ impl RecoveryCoordinator {
 
     /// Save checkpoint if needed
     pub fn maybe_checkpoint(
-        &mut self,
+        &self,
         algorithm: &str,
         total_passes: usize,
         total_size: u64,
         progress: &Progress,
     ) -> Result<()> {
-        let mut manager = self.checkpoint_manager.lock().unwrap();
+        let due = self.checkpoint_manager.lock().unwrap().should_save(progress.bytes_written);
 
-        if manager.should_save(progress.bytes_written) {
-            let mut checkpoint = Checkpoint::new(
-                &self.device_path,
-                algorithm,
-                &self.operation_id,
-                total_passes,
-                total_size,
-            );
+        if due {
+            self.save_checkpoint(algorithm, total_passes, total_size, progress)?;
+        }
+        self.maybe_record_progress(algorithm, total_passes, total_size, progress);
 
-            checkpoint.update_progress(progress.current_pass, progress.bytes_written);
-            checkpoint.state = progress.state.clone();
+        Ok(())
+    }
 
-            manager.save(&checkpoint)?;
+    /// Rewrite the progress record, at most once per `PROGRESS_RECORD_INTERVAL`.
+    /// A record that can't be written is logged and skipped; it never stops the wipe.
+    fn maybe_record_progress(&self, algorithm: &str, total_passes: usize, total_size: u64, progress: &Progress) {
+        let now = Instant::now();
+        let mut last = self.last_progress.lock().unwrap();
+        let (since, since_bytes) = match *last {
+            Some((at, _)) if now.duration_since(at) < PROGRESS_RECORD_INTERVAL => return,
+            Some(previous) => previous,
+            None => (self.started, 0),
+        };
 
-            tracing::debug!(
-                device = %self.device_path,
-                pass = progress.current_pass,
-                bytes = progress.bytes_written,
-                "Checkpoint saved"
-            );
+        let elapsed = now.duration_since(since).as_secs_f64().max(0.001);
+        let record = ProgressRecord {
+            device_path: self.device_path.clone(),
+            algorithm: algorithm.to_string(),
+            operation_id: self.operation_id.clone(),
+            pid: std::process::id(),
+            current_pass: (progress.current_pass + 1).min(total_passes.max(1)),
+            total_passes,
+            bytes_written: progress.bytes_written,
+            total_bytes: total_size,
+            percent: if total_size == 0 { 0.0 } else { progress.bytes_written as f64 * 100.0 / total_size as f64 },
+            current_offset: progress.current_offset,
+            bytes_per_second: progress.bytes_written.saturating_sub(since_bytes) as f64 / elapsed,
+            updated_at: chrono::Utc::now(),
+            completed: false,
+        };
+
+        match record.write(&self.progress_path) {
+            Ok(()) => *last = Some((now, progress.bytes_written)),
+            Err(e) => tracing::debug!(device = %self.device_path, error = %e, "Progress record not written"),
+        }
+    }
+
+    /// Mark the progress record finished, so monitors don't report the wipe
+    /// as interrupted once its process exits
+    fn complete_progress_record(&self) {
+        let Ok(Some(mut record)) = ProgressRecord::read(&self.progress_path) else {
+            return;
+        };
+        if record.operation_id != self.operation_id {
+            return;
         }
 
+        record.completed = true;
+        record.bytes_written = record.total_bytes;
+        record.percent = 100.0;
+        record.updated_at = chrono::Utc::now();
+        if let Err(e) = record.write(&self.progress_path) {
+            tracing::debug!(device = %self.device_path, error = %e, "Progress record not completed");
+        }
+    }
+
+    /// The last progress record written for `device_path`, beside the
+    /// checkpoint database at `db_path` (the default location when None)
+    pub fn progress_record(device_path: &str, db_path: Option<&str>) -> Result<Option<ProgressRecord>> {
+        ProgressRecord::read(&ProgressRecord::path_for(device_path, db_path))
+    }
+
+    /// Save a checkpoint now, regardless of the save interval
+    pub fn save_checkpoint(
+        &self,
+        algorithm: &str,
+        total_passes: usize,
+        total_size: u64,
+        progress: &Progress,
+    ) -> Result<()> {
+        let mut checkpoint = Checkpoint::new(
+            &self.device_path,
+            algorithm,
+            &self.operation_id,
+            total_passes,
+            total_size,
+        );
+
+        checkpoint.update_progress(progress.current_pass, progress.pass_offset, progress.bytes_written);
+        checkpoint.state = progress.state.clone();
+        if let Some(identity) = &self.device_identity {
+            checkpoint = checkpoint.with_device(identity);
+        }
+
+        self.checkpoint_manager.lock().unwrap().save(&checkpoint)?;
+
+        tracing::debug!(
+            device = %self.device_path,
+            pass = progress.current_pass,
+            bytes = progress.bytes_written,
+            "Checkpoint saved"
+        );
+
         Ok(())
     }
 
-    /// Load and resume from checkpoint
+    /// Load and resume from checkpoint.
+    ///
+    /// A checkpoint in an unknown format, or one that predates device
+    /// identity, is ignored so the wipe starts over. A checkpoint written for
+    /// a different drive than `set_device_identity` describes fails with
+    /// `DriveError::CheckpointMismatch`: the path now points at another disk,
+    /// so nothing should be written to it.
     pub fn resume_from_checkpoint(&self, algorithm: &str) -> Result<Option<ResumeState>> {
         let manager = self.checkpoint_manager.lock().unwrap();
 
-        if let Some(checkpoint) = manager.load(&self.device_path, algorithm)? {
-            tracing::info!(
-                device = %self.device_path,
-                algorithm = algorithm,
-                pass = checkpoint.current_pass,
-                bytes = checkpoint.bytes_written,
-                "Resuming from checkpoint"
-            );
+        let Some(checkpoint) = manager.load(&self.device_path, algorithm)? else {
+            return Ok(None);
+        };
+
+        let checkpoint = match checkpoint.migrate() {
+            Ok(checkpoint) if checkpoint.has_device_identity() || self.device_identity.is_none() => checkpoint,
+            Ok(_) => return Ok(self.ignore_checkpoint(algorithm, "it does not record which drive it was written for")),
+            Err(e) => return Ok(self.ignore_checkpoint(algorithm, &e.to_string())),
+        };
 
-            Ok(Some(ResumeState {
-                current_pass: checkpoint.current_pass,
-                bytes_written: checkpoint.bytes_written,
-                state: checkpoint.state.clone(),
-                checkpoint,
-            }))
-        } else {
-            Ok(None)
+        if let Some(identity) = &self.device_identity {
+            if let Err(e) = checkpoint.check_device(identity) {
+                tracing::error!(device = %self.device_path, algorithm, reason = %e, "Checkpoint is for another drive");
+                return Err(DriveError::CheckpointMismatch(format!(
+                    "{} ({} may now be a different disk; refusing to resume)",
+                    e, self.device_path
+                ))
+                .into());
+            }
         }
+
+        tracing::info!(
+            device = %self.device_path,
+            algorithm = algorithm,
+            pass = checkpoint.current_pass,
+            pass_offset = checkpoint.pass_offset,
+            bytes = checkpoint.bytes_written,
+            "Resuming from checkpoint"
+        );
+
+        Ok(Some(ResumeState {
+            current_pass: checkpoint.current_pass,
+            pass_offset: checkpoint.pass_offset,
+            bytes_written: checkpoint.bytes_written,
+            state: checkpoint.state.clone(),
+            checkpoint,
+        }))
+    }
+
+    /// Report a checkpoint that can't be trusted; the wipe starts from the beginning
+    fn ignore_checkpoint(&self, algorithm: &str, reason: &str) -> Option<ResumeState> {
+        println!("⚠️  Ignoring checkpoint for {}: {}", self.device_path, reason);
+        tracing::warn!(device = %self.device_path, algorithm, reason, "Checkpoint ignored");
+        None
+    }
+
+    /// Whether a checkpoint is stored for this device and algorithm
+    pub fn has_checkpoint(&self, algorithm: &str) -> bool {
+        let manager = self.checkpoint_manager.lock().unwrap();
+        matches!(manager.load(&self.device_path, algorithm), Ok(Some(_)))
     }
 
     /// Delete checkpoint after successful completion
     pub fn delete_checkpoint(&self) -> Result<()> {
         let mut manager = self.checkpoint_manager.lock().unwrap();
         let deleted = manager.delete_by_device(&self.device_path, "*")?;
+        self.complete_progress_record();
 
         if deleted > 0 {
             tracing::info!(
@@ -455,8 +651,14 @@ mod tests {
             self_healer: SelfHealer::new(),
             degraded_mode: Arc::new(Mutex::new(DegradedModeManager::new())),
             alternative_io: Arc::new(Mutex::new(AlternativeIO::new())),
+            progress_path: ProgressRecord::path_for(&device_path, db_path.to_str()),
+            last_progress: Mutex::new(None),
+            started: Instant::now(),
             device_path,
             operation_id,
+            cancel: CancellationToken::new(),
+            device_identity: None,
+            retries: AtomicU64::new(0),
         };
 
         (coordinator, temp_dir)
@@ -507,6 +709,7 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 42);
         assert!(attempt_count.load(Ordering::SeqCst) >= 3);
+        assert_eq!(coordinator.retry_count(), 2);
     }
 
     #[test]
@@ -528,7 +731,9 @@ mod tests {
     fn test_progress_tracking() {
         let progress = Progress {
             current_pass: 5,
+            pass_offset: 0,
             bytes_written: 1024 * 1024 * 1024,
+            current_offset: None,
             state: serde_json::json!({"test": "value"}),
         };
 
@@ -536,6 +741,36 @@ mod tests {
         assert_eq!(progress.bytes_written, 1024 * 1024 * 1024);
     }
 
+    #[test]
+    fn test_progress_record_written_and_completed() {
+        let (coordinator, temp) = create_test_coordinator();
+        let db_path = temp.path().join("test_checkpoints.db");
+        let progress = Progress {
+            current_pass: 1,
+            pass_offset: 1000,
+            bytes_written: 3000,
+            current_offset: Some(1000),
+            state: serde_json::json!({}),
+        };
+        coordinator.maybe_checkpoint("DoD", 3, 6000, &progress).unwrap();
+
+        let record = RecoveryCoordinator::progress_record("/dev/sda", db_path.to_str()).unwrap().unwrap();
+        assert_eq!((record.current_pass, record.total_passes), (2, 3));
+        assert_eq!(record.percent, 50.0);
+        assert_eq!(record.current_offset, Some(1000));
+        assert_eq!(record.state(), "running");
+
+        // Written at most once a second
+        coordinator.maybe_checkpoint("DoD", 3, 6000, &Progress { bytes_written: 4000, ..progress }).unwrap();
+        let record = RecoveryCoordinator::progress_record("/dev/sda", db_path.to_str()).unwrap().unwrap();
+        assert_eq!(record.bytes_written, 3000);
+
+        coordinator.delete_checkpoint().unwrap();
+        let record = RecoveryCoordinator::progress_record("/dev/sda", db_path.to_str()).unwrap().unwrap();
+        assert_eq!(record.state(), "completed");
+        assert_eq!(record.percent, 100.0);
+    }
+
     #[test]
     fn test_recovery_action_types() {
         let action = RecoveryAction::Retry { after: Duration::from_secs(1) };
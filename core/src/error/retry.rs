@@ -5,6 +5,7 @@
 /// and circuit breaker pattern to fail fast when service is persistently down.
 
 use super::classification::{ClassifiedError, ErrorClass};
+use crate::{CancellationToken, WipeConfig};
 use anyhow::Result;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -333,6 +334,51 @@ impl CircuitBreaker {
     }
 }
 
+/// Immediate retries of a single operation (one chunk write, one hardware
+/// command) that failed with a transient error, backing off exponentially
+/// between attempts
+#[derive(Debug, Clone)]
+pub struct TransientRetry {
+    backoff: ExponentialBackoff,
+}
+
+impl TransientRetry {
+    /// Up to `max_retries` retries, the first after `base_delay`
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            backoff: ExponentialBackoff::new(base_delay, Duration::from_secs(30), max_retries).with_jitter(0.1),
+        }
+    }
+
+    /// The `transient_retries` and `retry_backoff_ms` of a wipe config
+    pub fn from_config(config: &WipeConfig) -> Self {
+        Self::new(config.transient_retries, Duration::from_millis(config.retry_backoff_ms))
+    }
+
+    /// Run `operation`, passing it the attempt number, and repeat it while it
+    /// fails with an error `is_transient` accepts, until the retries run out
+    /// or `cancel` fires. Returns the last result and how many retries ran.
+    pub fn run<T, E>(
+        &self,
+        cancel: &CancellationToken,
+        is_transient: impl Fn(&E) -> bool,
+        mut operation: impl FnMut(u32) -> std::result::Result<T, E>,
+    ) -> (std::result::Result<T, E>, u32) {
+        let mut attempt = 0;
+        loop {
+            match operation(attempt) {
+                Err(e) if attempt < self.backoff.max_attempts && is_transient(&e) && !cancel.is_cancelled() => {
+                    let delay = self.backoff.next_delay(attempt);
+                    tracing::debug!(attempt = attempt + 1, delay_ms = delay.as_millis() as u64, "Retrying after transient error");
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                result => return (result, attempt),
+            }
+        }
+    }
+}
+
 /// Retry configuration per error class
 pub struct RetryConfig {
     strategies: std::collections::HashMap<ErrorClass, Box<dyn RetryStrategy>>,
@@ -538,6 +584,23 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_transient_retry_stops_on_permanent_errors() {
+        let retry = TransientRetry::new(3, Duration::ZERO);
+        let cancel = CancellationToken::new();
+
+        let (result, retries) = retry.run(&cancel, |e: &&str| *e == "busy", |attempt| {
+            if attempt < 2 { Err("busy") } else { Ok(attempt) }
+        });
+        assert_eq!((result, retries), (Ok(2), 2));
+
+        let (result, retries) = retry.run(&cancel, |e: &&str| *e == "busy", |_| Err::<u32, _>("gone"));
+        assert_eq!((result, retries), (Err("gone"), 0));
+
+        let (result, retries) = retry.run(&cancel, |_: &&str| true, |_| Err::<u32, _>("busy"));
+        assert_eq!((result, retries), (Err("busy"), 3));
+    }
+
     #[test]
     fn test_no_retry_strategy() {
         let strategy = NoRetry;
@@ -0,0 +1,552 @@
+// C FFI - driving the wipe engine from C and C++
+//
+// A wipe runs on its own thread behind an opaque `SayonaraWipe` handle:
+// `sayonara_wipe_start` returns it at once, the caller polls
+// `sayonara_wipe_progress`, may `sayonara_wipe_cancel`, and collects the
+// outcome with `sayonara_wipe_wait` or `sayonara_wipe_result`. The handle
+// belongs to the caller until `sayonara_wipe_free`. Strings handed out are
+// freed with `sayonara_string_free`. Every function returns a
+// `SAYONARA_*` code; `sayonara_last_error` describes the last failure on
+// the calling thread. The matching declarations are in `include/sayonara.h`.
+
+use crate::config_file::ConfigFormat;
+use crate::{Algorithm, CancellationToken, DriveError, DriveResult, EventCallback, WipeConfig, WipeEvent, WipeOrchestrator, WipeReport};
+use crate::wipe_orchestrator::creation_error;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+
+pub const SAYONARA_OK: c_int = 0;
+/// A NULL pointer, invalid UTF-8 or an unknown algorithm
+pub const SAYONARA_ERR_INVALID_ARGUMENT: c_int = 1;
+/// `config_json` could not be parsed or failed validation
+pub const SAYONARA_ERR_CONFIG: c_int = 2;
+/// The wipe is still running, so there is no result yet
+pub const SAYONARA_ERR_IN_PROGRESS: c_int = 3;
+/// The wipe thread died without producing a result
+pub const SAYONARA_ERR_INTERNAL: c_int = 4;
+
+// One code per `DriveError` variant
+pub const SAYONARA_ERR_IO: c_int = 10;
+pub const SAYONARA_ERR_DRIVE_FROZEN: c_int = 11;
+pub const SAYONARA_ERR_HARDWARE_COMMAND: c_int = 12;
+pub const SAYONARA_ERR_MISSING_TOOL: c_int = 13;
+pub const SAYONARA_ERR_SMART: c_int = 14;
+pub const SAYONARA_ERR_TEMPERATURE: c_int = 15;
+pub const SAYONARA_ERR_TRIM: c_int = 16;
+pub const SAYONARA_ERR_CRYPTO_ERASE: c_int = 17;
+pub const SAYONARA_ERR_UNLOCK: c_int = 18;
+pub const SAYONARA_ERR_TIMEOUT: c_int = 19;
+pub const SAYONARA_ERR_PERMISSION_DENIED: c_int = 20;
+pub const SAYONARA_ERR_NOT_FOUND: c_int = 21;
+pub const SAYONARA_ERR_UNSUPPORTED: c_int = 22;
+pub const SAYONARA_ERR_SYSTEM_DISK: c_int = 23;
+pub const SAYONARA_ERR_MOUNTED: c_int = 24;
+pub const SAYONARA_ERR_UNHEALTHY: c_int = 25;
+pub const SAYONARA_ERR_CHECKPOINT_MISMATCH: c_int = 26;
+pub const SAYONARA_ERR_INTERRUPTED: c_int = 27;
+pub const SAYONARA_ERR_CANCELLED: c_int = 28;
+pub const SAYONARA_ERR_SIZE_MISMATCH: c_int = 29;
+pub const SAYONARA_ERR_SERIAL_NOT_PERMITTED: c_int = 30;
+
+/// `SayonaraWipeConfig::algorithm` values, in `Algorithm` order. Custom
+/// pass lists are only available through `config_json`.
+pub const SAYONARA_ALGORITHMS: [Algorithm; 15] = [
+    Algorithm::DoD5220,
+    Algorithm::Gutmann,
+    Algorithm::Random,
+    Algorithm::Zero,
+    Algorithm::SecureErase,
+    Algorithm::CryptoErase,
+    Algorithm::Sanitize,
+    Algorithm::TrimOnly,
+    Algorithm::NIST80088Clear,
+    Algorithm::NIST80088Purge,
+    Algorithm::BSI_GS,
+    Algorithm::BSI_GSE,
+    Algorithm::VSITR,
+    Algorithm::HMGIS5Baseline,
+    Algorithm::HMGIS5Enhanced,
+];
+
+/// `SayonaraProgress::state` values
+pub const SAYONARA_STATE_RUNNING: c_int = 0;
+pub const SAYONARA_STATE_SUCCEEDED: c_int = 1;
+pub const SAYONARA_STATE_FAILED: c_int = 2;
+
+/// Wipe settings from C. When `config_json` is set it is the whole
+/// configuration, in the JSON policy-file format, and the other fields are
+/// ignored.
+#[repr(C)]
+pub struct SayonaraWipeConfig {
+    /// Index into `SAYONARA_ALGORITHMS` (0 = DoD 5220.22-M)
+    pub algorithm: c_int,
+    pub verify: bool,
+    pub dry_run: bool,
+    /// Wipe a drive whose SMART health is failing
+    pub force: bool,
+    pub allow_system_disk: bool,
+    /// Where to save the certificate JSON; may be NULL
+    pub certificate_path: *const c_char,
+    /// A complete `WipeConfig` as JSON; may be NULL
+    pub config_json: *const c_char,
+}
+
+/// Snapshot of a running wipe
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SayonaraProgress {
+    /// One of `SAYONARA_STATE_*`
+    pub state: c_int,
+    /// 1-based; 0 before the first pass starts
+    pub current_pass: u32,
+    pub total_passes: u32,
+    pub bytes_written: u64,
+    pub total_bytes: u64,
+    pub percent: f64,
+    pub bytes_per_second: f64,
+    /// -1 while unknown
+    pub eta_seconds: i64,
+}
+
+/// Progress and outcome, shared with the wipe thread
+#[derive(Default)]
+struct WipeState {
+    progress: Mutex<SayonaraProgress>,
+    result: Mutex<Option<DriveResult<WipeReport>>>,
+}
+
+/// Opaque handle to a wipe started by `sayonara_wipe_start`
+pub struct SayonaraWipe {
+    state: Arc<WipeState>,
+    cancel: CancellationToken,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SayonaraWipe {
+    /// Join the wipe thread, recording an internal error if it panicked
+    fn join(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                // The panic may have poisoned the lock; the slot is overwritten anyway
+                *self.state.result.lock().unwrap_or_else(PoisonError::into_inner) =
+                    Some(Err(DriveError::HardwareCommandFailed("wipe thread panicked".to_string())));
+            }
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|thread| thread.is_finished())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Remember `message` as this thread's last error and return `code`
+fn fail(code: c_int, message: impl std::fmt::Display) -> c_int {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    code
+}
+
+/// Lock shared wipe state from an entry point. A lock poisoned by a panic
+/// on the wipe thread fails with `SAYONARA_ERR_INTERNAL` instead of
+/// unwinding across the C boundary.
+fn lock<'a, T>(mutex: &'a Mutex<T>, what: &str) -> Result<MutexGuard<'a, T>, c_int> {
+    mutex
+        .lock()
+        .map_err(|_| fail(SAYONARA_ERR_INTERNAL, format!("The wipe {} is unavailable: the wipe thread panicked", what)))
+}
+
+/// The `SAYONARA_ERR_*` code for `error`
+pub fn error_code(error: &DriveError) -> c_int {
+    match error {
+        DriveError::IoError(_) => SAYONARA_ERR_IO,
+        DriveError::DriveFrozen(_) => SAYONARA_ERR_DRIVE_FROZEN,
+        DriveError::HardwareCommandFailed(_) => SAYONARA_ERR_HARDWARE_COMMAND,
+        DriveError::MissingTool(_) => SAYONARA_ERR_MISSING_TOOL,
+        DriveError::SMARTReadFailed(_) => SAYONARA_ERR_SMART,
+        DriveError::TemperatureExceeded(_) => SAYONARA_ERR_TEMPERATURE,
+        DriveError::TRIMFailed(_) => SAYONARA_ERR_TRIM,
+        DriveError::CryptoEraseFailed(_) => SAYONARA_ERR_CRYPTO_ERASE,
+        DriveError::UnlockFailed(_) => SAYONARA_ERR_UNLOCK,
+        DriveError::Timeout(_) => SAYONARA_ERR_TIMEOUT,
+        DriveError::PermissionDenied(_) => SAYONARA_ERR_PERMISSION_DENIED,
+        DriveError::NotFound(_) => SAYONARA_ERR_NOT_FOUND,
+        DriveError::Unsupported(_) => SAYONARA_ERR_UNSUPPORTED,
+        DriveError::SystemDisk(_) => SAYONARA_ERR_SYSTEM_DISK,
+        DriveError::Mounted(_) => SAYONARA_ERR_MOUNTED,
+        DriveError::Unhealthy(_) => SAYONARA_ERR_UNHEALTHY,
+        DriveError::CheckpointMismatch(_) => SAYONARA_ERR_CHECKPOINT_MISMATCH,
+        DriveError::Interrupted => SAYONARA_ERR_INTERRUPTED,
+        DriveError::Cancelled => SAYONARA_ERR_CANCELLED,
+        DriveError::SizeMismatch(_) => SAYONARA_ERR_SIZE_MISMATCH,
+        DriveError::SerialNotPermitted(_) => SAYONARA_ERR_SERIAL_NOT_PERMITTED,
+    }
+}
+
+/// Borrow a C string argument, which must not be NULL
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, c_int> {
+    if ptr.is_null() {
+        return Err(fail(SAYONARA_ERR_INVALID_ARGUMENT, format!("{} is NULL", name)));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| fail(SAYONARA_ERR_INVALID_ARGUMENT, format!("{} is not valid UTF-8", name)))
+}
+
+/// The `WipeConfig` a C config describes
+unsafe fn wipe_config(config: &SayonaraWipeConfig) -> Result<WipeConfig, c_int> {
+    if !config.config_json.is_null() {
+        let json = str_arg(config.config_json, "config_json")?;
+        return WipeConfig::parse(json, ConfigFormat::Json).map_err(|e| fail(SAYONARA_ERR_CONFIG, format!("{:#}", e)));
+    }
+
+    let algorithm = usize::try_from(config.algorithm)
+        .ok()
+        .and_then(|index| SAYONARA_ALGORITHMS.get(index))
+        .ok_or_else(|| fail(SAYONARA_ERR_INVALID_ARGUMENT, format!("Unknown algorithm {}", config.algorithm)))?;
+    let certificate_path = if config.certificate_path.is_null() {
+        None
+    } else {
+        Some(str_arg(config.certificate_path, "certificate_path")?.to_string())
+    };
+
+    Ok(WipeConfig {
+        algorithm: algorithm.clone(),
+        verify: config.verify,
+        dry_run: config.dry_run,
+        force: config.force,
+        allow_system_disk: config.allow_system_disk,
+        certificate_path,
+        ..WipeConfig::default()
+    })
+}
+
+/// Fill `config` with the library defaults.
+///
+/// # Safety
+/// `config` must be NULL or point to writable memory for a `SayonaraWipeConfig`.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_config_default(config: *mut SayonaraWipeConfig) -> c_int {
+    if config.is_null() {
+        return fail(SAYONARA_ERR_INVALID_ARGUMENT, "config is NULL");
+    }
+
+    let defaults = WipeConfig::default();
+    config.write(SayonaraWipeConfig {
+        algorithm: SAYONARA_ALGORITHMS.iter().position(|a| *a == defaults.algorithm).unwrap_or(0) as c_int,
+        verify: defaults.verify,
+        dry_run: defaults.dry_run,
+        force: defaults.force,
+        allow_system_disk: defaults.allow_system_disk,
+        certificate_path: std::ptr::null(),
+        config_json: std::ptr::null(),
+    });
+    SAYONARA_OK
+}
+
+/// Start wiping `device_path` on a background thread and store its handle
+/// in `*wipe`. Drive detection runs on that thread too, so a missing or
+/// protected device is reported by `sayonara_wipe_result`, not here.
+///
+/// # Safety
+/// `device_path` must be a NUL-terminated string, `config` must point to a
+/// `SayonaraWipeConfig` whose string fields are NULL or NUL-terminated, and
+/// `wipe` must point to writable memory for a handle pointer. The strings
+/// are copied, so they may be freed once this returns.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_wipe_start(
+    device_path: *const c_char,
+    config: *const SayonaraWipeConfig,
+    wipe: *mut *mut SayonaraWipe,
+) -> c_int {
+    let device_path = match str_arg(device_path, "device_path") {
+        Ok(path) => path.to_string(),
+        Err(code) => return code,
+    };
+    if config.is_null() || wipe.is_null() {
+        return fail(SAYONARA_ERR_INVALID_ARGUMENT, "config and wipe must not be NULL");
+    }
+    let mut config = match wipe_config(&*config) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+
+    let state = Arc::new(WipeState::default());
+    let events = state.clone();
+    config.event_callback = Some(EventCallback::new(move |event| {
+        let mut progress = events.progress.lock().unwrap();
+        match event {
+            WipeEvent::PassStarted { pass, total_passes, .. } => {
+                progress.current_pass = pass as u32;
+                progress.total_passes = total_passes as u32;
+            }
+            WipeEvent::Progress(update) => {
+                progress.current_pass = update.current_pass as u32;
+                progress.total_passes = update.total_passes as u32;
+                progress.bytes_written = update.bytes_written;
+                progress.total_bytes = update.total_bytes;
+                progress.percent = update.percent;
+                progress.bytes_per_second = update.smoothed_bytes_per_second;
+                progress.eta_seconds = update.eta_seconds.map_or(-1, |eta| eta as i64);
+            }
+            _ => {}
+        }
+    }));
+    match lock(&state.progress, "progress") {
+        Ok(mut progress) => progress.eta_seconds = -1,
+        Err(code) => return code,
+    }
+
+    let cancel = CancellationToken::new();
+    let worker_state = state.clone();
+    let worker_cancel = cancel.clone();
+    let spawned = std::thread::Builder::new().name("sayonara-wipe".to_string()).spawn(move || {
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(DriveError::IoError)
+            .and_then(|runtime| {
+                runtime.block_on(async {
+                    let mut orchestrator = WipeOrchestrator::new(device_path, config).map_err(creation_error)?;
+                    orchestrator.execute(worker_cancel).await
+                })
+            });
+
+        let mut progress = worker_state.progress.lock().unwrap();
+        progress.state = if result.is_ok() { SAYONARA_STATE_SUCCEEDED } else { SAYONARA_STATE_FAILED };
+        if result.is_ok() {
+            progress.percent = 100.0;
+            progress.eta_seconds = 0;
+        }
+        *worker_state.result.lock().unwrap() = Some(result);
+    });
+
+    match spawned {
+        Ok(thread) => {
+            *wipe = Box::into_raw(Box::new(SayonaraWipe { state, cancel, thread: Some(thread) }));
+            SAYONARA_OK
+        }
+        Err(e) => fail(SAYONARA_ERR_INTERNAL, format!("Failed to start the wipe thread: {}", e)),
+    }
+}
+
+/// Copy the wipe's current progress into `*progress`.
+///
+/// # Safety
+/// `wipe` must be a live handle from `sayonara_wipe_start` and `progress`
+/// must point to writable memory for a `SayonaraProgress`.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_wipe_progress(wipe: *const SayonaraWipe, progress: *mut SayonaraProgress) -> c_int {
+    if wipe.is_null() || progress.is_null() {
+        return fail(SAYONARA_ERR_INVALID_ARGUMENT, "wipe and progress must not be NULL");
+    }
+    let wipe = &*wipe;
+    match lock(&wipe.state.progress, "progress") {
+        Ok(current) => {
+            progress.write(*current);
+            SAYONARA_OK
+        }
+        Err(code) => code,
+    }
+}
+
+/// Ask the wipe to stop at its next chunk or poll. It checkpoints where it
+/// stopped and finishes with `SAYONARA_ERR_CANCELLED`.
+///
+/// # Safety
+/// `wipe` must be a live handle from `sayonara_wipe_start`.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_wipe_cancel(wipe: *const SayonaraWipe) -> c_int {
+    if wipe.is_null() {
+        return fail(SAYONARA_ERR_INVALID_ARGUMENT, "wipe is NULL");
+    }
+    let wipe = &*wipe;
+    wipe.cancel.cancel();
+    SAYONARA_OK
+}
+
+/// Block until the wipe finishes and return its outcome, as
+/// `sayonara_wipe_result` does.
+///
+/// # Safety
+/// `wipe` must be a live handle from `sayonara_wipe_start`; `report_json`
+/// as for `sayonara_wipe_result`.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_wipe_wait(wipe: *mut SayonaraWipe, report_json: *mut *mut c_char) -> c_int {
+    if wipe.is_null() {
+        return fail(SAYONARA_ERR_INVALID_ARGUMENT, "wipe is NULL");
+    }
+    (&mut *wipe).join();
+    sayonara_wipe_result(wipe, report_json)
+}
+
+/// The outcome of a finished wipe: `SAYONARA_OK`, with the `WipeReport` as
+/// JSON in `*report_json` when that is not NULL (free it with
+/// `sayonara_string_free`); the wipe's error code; or
+/// `SAYONARA_ERR_IN_PROGRESS` while it is still running.
+///
+/// # Safety
+/// `wipe` must be a live handle from `sayonara_wipe_start`; `report_json`
+/// must be NULL or point to writable memory for a string pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_wipe_result(wipe: *mut SayonaraWipe, report_json: *mut *mut c_char) -> c_int {
+    if wipe.is_null() {
+        return fail(SAYONARA_ERR_INVALID_ARGUMENT, "wipe is NULL");
+    }
+    let wipe = &mut *wipe;
+    if !wipe.finished() {
+        return fail(SAYONARA_ERR_IN_PROGRESS, "The wipe is still running");
+    }
+    wipe.join();
+
+    let result = match lock(&wipe.state.result, "result") {
+        Ok(result) => result,
+        Err(code) => return code,
+    };
+    match result.as_ref() {
+        None => fail(SAYONARA_ERR_INTERNAL, "The wipe thread produced no result"),
+        Some(Err(e)) => fail(error_code(e), e),
+        Some(Ok(report)) => {
+            if !report_json.is_null() {
+                let json = match serde_json::to_string(report) {
+                    Ok(json) => json,
+                    Err(e) => return fail(SAYONARA_ERR_INTERNAL, format!("Failed to serialize the report: {}", e)),
+                };
+                *report_json = CString::new(json).unwrap_or_default().into_raw();
+            }
+            SAYONARA_OK
+        }
+    }
+}
+
+/// Cancel the wipe if it is still running, wait for it to stop, and free
+/// the handle. NULL is ignored.
+///
+/// # Safety
+/// `wipe` must be NULL or a handle from `sayonara_wipe_start` that has not
+/// been freed; it must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_wipe_free(wipe: *mut SayonaraWipe) {
+    if wipe.is_null() {
+        return;
+    }
+    let mut wipe = Box::from_raw(wipe);
+    wipe.cancel.cancel();
+    wipe.join();
+}
+
+/// Description of the last error returned on this thread, or NULL when
+/// there has been none. The string stays valid until the next failing call
+/// on the same thread; don't free it.
+#[no_mangle]
+pub extern "C" fn sayonara_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |message| message.as_ptr()))
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `string` must be NULL or a string this library returned, freed only once.
+#[no_mangle]
+pub unsafe extern "C" fn sayonara_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(sayonara_last_error()) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_wipe_image_through_ffi() {
+        let image = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(image.path(), vec![0xA5u8; 1 << 20]).unwrap();
+        let device = CString::new(image.path().to_str().unwrap()).unwrap();
+
+        let mut config = std::mem::MaybeUninit::<SayonaraWipeConfig>::uninit();
+        let mut config = unsafe {
+            assert_eq!(sayonara_config_default(config.as_mut_ptr()), SAYONARA_OK);
+            config.assume_init()
+        };
+        config.algorithm = 3; // Zero
+        config.verify = false;
+
+        let mut wipe = std::ptr::null_mut();
+        let mut report = std::ptr::null_mut();
+        let mut progress = SayonaraProgress::default();
+        unsafe {
+            assert_eq!(sayonara_wipe_start(device.as_ptr(), &config, &mut wipe), SAYONARA_OK);
+            assert_eq!(sayonara_wipe_wait(wipe, &mut report), SAYONARA_OK);
+            assert_eq!(sayonara_wipe_progress(wipe, &mut progress), SAYONARA_OK);
+            sayonara_wipe_free(wipe);
+        }
+
+        assert_eq!(progress.state, SAYONARA_STATE_SUCCEEDED);
+        assert_eq!((progress.current_pass, progress.total_passes), (1, 1));
+        assert_eq!(progress.percent, 100.0);
+
+        let json = unsafe { CStr::from_ptr(report) }.to_str().unwrap().to_string();
+        unsafe { sayonara_string_free(report) };
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["certificate"]["wipe_details"]["algorithm_used"], "Zero");
+        assert!(std::fs::read(image.path()).unwrap().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_errors_map_to_codes_and_messages() {
+        let config = SayonaraWipeConfig {
+            algorithm: 99,
+            verify: true,
+            dry_run: true,
+            force: false,
+            allow_system_disk: false,
+            certificate_path: std::ptr::null(),
+            config_json: std::ptr::null(),
+        };
+        let device = CString::new("/dev/does-not-exist").unwrap();
+        let mut wipe = std::ptr::null_mut();
+
+        unsafe {
+            assert_eq!(sayonara_wipe_start(std::ptr::null(), &config, &mut wipe), SAYONARA_ERR_INVALID_ARGUMENT);
+            assert_eq!(last_error(), "device_path is NULL");
+            assert_eq!(sayonara_wipe_start(device.as_ptr(), &config, &mut wipe), SAYONARA_ERR_INVALID_ARGUMENT);
+            assert_eq!(last_error(), "Unknown algorithm 99");
+
+            let json = CString::new(r#"{"chunk_size": 0}"#).unwrap();
+            let config = SayonaraWipeConfig { config_json: json.as_ptr(), ..config };
+            assert_eq!(sayonara_wipe_start(device.as_ptr(), &config, &mut wipe), SAYONARA_ERR_CONFIG);
+        }
+
+        assert_eq!(error_code(&DriveError::Cancelled), SAYONARA_ERR_CANCELLED);
+        assert_eq!(error_code(&DriveError::SystemDisk(String::new())), SAYONARA_ERR_SYSTEM_DISK);
+        assert_eq!(error_code(&DriveError::SerialNotPermitted(String::new())), SAYONARA_ERR_SERIAL_NOT_PERMITTED);
+    }
+
+    #[test]
+    fn test_poisoned_state_returns_internal_error() {
+        let state = Arc::new(WipeState::default());
+        let poisoner = state.clone();
+        let _ = std::thread::spawn(move || {
+            let _progress = poisoner.progress.lock().unwrap();
+            panic!("wipe thread panicked holding the progress lock");
+        })
+        .join();
+
+        let wipe = SayonaraWipe { state, cancel: CancellationToken::new(), thread: None };
+        let mut progress = SayonaraProgress::default();
+        unsafe {
+            assert_eq!(sayonara_wipe_progress(&wipe, &mut progress), SAYONARA_ERR_INTERNAL);
+        }
+        assert_eq!(last_error(), "The wipe progress is unavailable: the wipe thread panicked");
+    }
+}
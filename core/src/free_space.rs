@@ -0,0 +1,427 @@
+// Free Space Wipe - overwrite deleted file contents on a mounted filesystem
+//
+// A drive that holds the running OS can't be wiped whole, but the blocks
+// deleted files left behind can still be overwritten through the
+// filesystem: the first pass fills it with files of the pass pattern until
+// it reports no space, later passes rewrite those files in place, each file
+// is fsynced so the data reaches the media, and all of them are deleted at
+// the end. Optionally empty files are then created to take over freed inodes
+// (MFT records on NTFS), where small files and deleted names can linger.
+//
+// Not reachable this way: slack after the end of live files, blocks still
+// held by snapshots on copy-on-write filesystems, and flash blocks the
+// controller has already remapped.
+
+use crate::ui::progress::ProgressBar;
+use crate::{Algorithm, CancellationToken, DriveError};
+use crate::algorithms::PatternSpec;
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Bytes written per call while filling (1MB)
+const FILL_CHUNK: usize = 1024 * 1024;
+
+/// Largest fill file (1GB), well under FAT32's 4GB file size limit
+const MAX_FILL_FILE: u64 = 1024 * 1024 * 1024;
+
+/// Scratch directory created under the target, suffixed with the PID
+const SCRATCH_PREFIX: &str = ".sayonara-free-space";
+
+/// What to wipe besides the free blocks, and how far to go
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeSpaceOptions {
+    /// Also create empty files to take over freed inodes and directory entries
+    pub clear_entries: bool,
+    /// Most empty files created when clearing entries
+    pub max_entries: u64,
+    /// Stop each pass after this many bytes instead of filling the filesystem
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for FreeSpaceOptions {
+    fn default() -> Self {
+        Self {
+            clear_entries: false,
+            max_entries: 100_000,
+            max_bytes: None,
+        }
+    }
+}
+
+/// Result of a free space wipe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FreeSpaceReport {
+    pub path: String,
+    pub passes: usize,
+    /// Free space each pass overwrote
+    pub bytes_per_pass: u64,
+    /// Empty files created to reuse freed entries; 0 when not requested
+    pub entries_cleared: u64,
+    /// Passes cut short because the filesystem ran out of space while
+    /// rewriting; empty when every pass covered `bytes_per_pass`
+    pub incomplete_passes: Vec<IncompletePass>,
+    pub duration_seconds: u64,
+}
+
+/// A rewrite pass that stopped when the filesystem filled up, as copy-on-write
+/// filesystems do when every rewrite needs fresh blocks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IncompletePass {
+    pub pass: usize, // 1-based
+    pub bytes_written: u64,
+}
+
+/// One fill file and how many bytes of it were written
+struct FillFile {
+    path: PathBuf,
+    len: u64,
+}
+
+/// Scratch directory for the fill files, removed with everything in it
+/// when dropped so a failed or cancelled wipe doesn't leave the disk full
+struct Scratch {
+    dir: PathBuf,
+}
+
+impl Scratch {
+    fn create(root: &Path) -> Result<Self> {
+        let dir = root.join(format!("{}-{}", SCRATCH_PREFIX, std::process::id()));
+        fs::create_dir(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            println!("⚠️  Could not remove {}: {} - delete it to free the space", self.dir.display(), e);
+        }
+    }
+}
+
+pub struct FreeSpaceWipe {
+    path: PathBuf,
+    algorithm: Algorithm,
+    options: FreeSpaceOptions,
+}
+
+impl FreeSpaceWipe {
+    /// Wipe the free space of the filesystem mounted at (or containing)
+    /// `path` with the passes of `algorithm`, which must be an overwrite
+    pub fn new(path: impl Into<PathBuf>, algorithm: Algorithm, options: FreeSpaceOptions) -> Result<Self> {
+        let path = path.into();
+        if !path.is_dir() {
+            return Err(anyhow!("{} is not a directory on a mounted filesystem", path.display()));
+        }
+        if algorithm.is_hardware_erase() {
+            return Err(DriveError::Unsupported(format!(
+                "{:?} is a whole-drive erase; free space can only be overwritten",
+                algorithm
+            ))
+            .into());
+        }
+
+        Ok(Self { path, algorithm, options })
+    }
+
+    /// Run every pass, then delete the fill files
+    pub fn run(&self, cancel: &CancellationToken) -> Result<FreeSpaceReport> {
+        let started = Instant::now();
+        println!("🧹 Wiping free space on {}", self.path.display());
+        Self::warn_about_filesystem(&self.path);
+
+        let scratch = Scratch::create(&self.path)?;
+        let passes = self.algorithm.overwrite_passes();
+        let mut files = Vec::new();
+        let mut bytes_per_pass = 0;
+        let mut incomplete_passes = Vec::new();
+
+        for pass in 0..passes {
+            let pattern = self.algorithm.pass_pattern(pass);
+            println!("Pass {}/{}: {}", pass + 1, passes, pattern.description());
+            if pass == 0 {
+                bytes_per_pass = self.fill(&scratch.dir, &pattern, cancel, &mut files)?;
+            } else {
+                let bytes_written = self.rewrite(&files, &pattern, cancel)?;
+                if bytes_written < bytes_per_pass {
+                    incomplete_passes.push(IncompletePass { pass: pass + 1, bytes_written });
+                }
+            }
+        }
+
+        let entries_cleared = if self.options.clear_entries {
+            self.clear_entries(&scratch.dir, cancel)?
+        } else {
+            0
+        };
+
+        drop(scratch);
+        // Persist the deletions so the space is really returned
+        if let Err(e) = File::open(&self.path).and_then(|dir| dir.sync_all()) {
+            println!("⚠️  Could not sync {}: {}", self.path.display(), e);
+        }
+
+        if incomplete_passes.is_empty() {
+            println!("✅ Overwrote {} bytes of free space ({} pass(es))", bytes_per_pass, passes);
+        } else {
+            println!("⚠️  Overwrote {} bytes of free space, but not every pass finished:", bytes_per_pass);
+            for incomplete in &incomplete_passes {
+                println!(
+                    "   Pass {}/{} stopped at {} of {} bytes (filesystem full)",
+                    incomplete.pass, passes, incomplete.bytes_written, bytes_per_pass
+                );
+            }
+        }
+        Ok(FreeSpaceReport {
+            path: self.path.display().to_string(),
+            passes,
+            bytes_per_pass,
+            entries_cleared,
+            incomplete_passes,
+            duration_seconds: started.elapsed().as_secs(),
+        })
+    }
+
+    /// Create fill files of `pattern` until the filesystem is full (or the
+    /// `max_bytes` limit is reached). Returns the bytes written.
+    fn fill(&self, dir: &Path, pattern: &PatternSpec, cancel: &CancellationToken, files: &mut Vec<FillFile>) -> Result<u64> {
+        let limit = self.options.max_bytes.unwrap_or(u64::MAX);
+        let total = self.options.max_bytes.or_else(|| free_bytes(dir)).unwrap_or(0);
+        let mut bar = ProgressBar::new(48);
+        let mut buffer = vec![0u8; FILL_CHUNK];
+        let mut written = 0u64;
+        let mut full = false;
+
+        while !full && written < limit {
+            let path = dir.join(format!("fill-{:06}", files.len()));
+            let mut file = match File::create(&path) {
+                Ok(file) => file,
+                Err(e) if is_full(&e) => break,
+                Err(e) => return Err(e).with_context(|| format!("Failed to create {}", path.display())),
+            };
+
+            let mut len = 0u64;
+            while len < MAX_FILL_FILE && written < limit {
+                if cancel.is_cancelled() {
+                    return Err(DriveError::Cancelled.into());
+                }
+
+                let want = (FILL_CHUNK as u64).min(MAX_FILL_FILE - len).min(limit - written) as usize;
+                pattern.fill(&mut buffer[..want], len)?;
+                match file.write(&buffer[..want]) {
+                    Ok(0) => full = true,
+                    Ok(n) => {
+                        len += n as u64;
+                        written += n as u64;
+                    }
+                    // The largest file this filesystem allows; carry on in the next one
+                    Err(e) if e.raw_os_error() == Some(libc::EFBIG) => break,
+                    Err(e) if is_full(&e) => full = true,
+                    Err(e) => return Err(e).with_context(|| format!("Failed to write {}", path.display())),
+                }
+                if full {
+                    break;
+                }
+                bar.render((written as f64 / total.max(1) as f64 * 100.0).min(100.0), Some(written), Some(total));
+            }
+
+            // With delayed allocation a full filesystem may only show at fsync
+            match file.sync_all() {
+                Ok(()) => {}
+                Err(e) if is_full(&e) => full = true,
+                Err(e) => return Err(e).with_context(|| format!("Failed to sync {}", path.display())),
+            }
+
+            if len == 0 {
+                fs::remove_file(&path)?;
+                break;
+            }
+            files.push(FillFile { path, len });
+        }
+
+        bar.render(100.0, Some(written), Some(written));
+        println!();
+        Ok(written)
+    }
+
+    /// Overwrite the fill files in place with `pattern`. Returns the bytes
+    /// written, short of the files' total when the filesystem filled up.
+    fn rewrite(&self, files: &[FillFile], pattern: &PatternSpec, cancel: &CancellationToken) -> Result<u64> {
+        let total: u64 = files.iter().map(|file| file.len).sum();
+        let mut bar = ProgressBar::new(48);
+        let mut buffer = vec![0u8; FILL_CHUNK];
+        let mut written = 0u64;
+
+        for fill in files {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .open(&fill.path)
+                .with_context(|| format!("Failed to reopen {}", fill.path.display()))?;
+
+            let mut offset = 0u64;
+            while offset < fill.len {
+                if cancel.is_cancelled() {
+                    return Err(DriveError::Cancelled.into());
+                }
+
+                let len = (fill.len - offset).min(FILL_CHUNK as u64) as usize;
+                pattern.fill(&mut buffer[..len], offset)?;
+                match file.write_all(&buffer[..len]) {
+                    Ok(()) => {}
+                    // Copy-on-write filesystems need fresh blocks for every rewrite
+                    Err(e) if is_full(&e) => {
+                        println!("\n⚠️  Filesystem full while rewriting {} - the pass is incomplete", fill.path.display());
+                        return Ok(written);
+                    }
+                    Err(e) => return Err(e).with_context(|| format!("Failed to write {}", fill.path.display())),
+                }
+                offset += len as u64;
+                written += len as u64;
+                bar.render(written as f64 / total.max(1) as f64 * 100.0, Some(written), Some(total));
+            }
+            file.sync_all().with_context(|| format!("Failed to sync {}", fill.path.display()))?;
+        }
+
+        bar.render(100.0, Some(written), Some(total));
+        println!();
+        Ok(written)
+    }
+
+    /// Create empty files with long names until no more inodes are free (or
+    /// `max_entries`), so freed inodes and entry slots are reused. Returns
+    /// how many were created; they go when the scratch directory does.
+    fn clear_entries(&self, dir: &Path, cancel: &CancellationToken) -> Result<u64> {
+        println!("Clearing freed directory entries and inodes...");
+        let entries = dir.join("entries");
+        fs::create_dir(&entries)?;
+
+        let mut created = 0u64;
+        while created < self.options.max_entries {
+            if cancel.is_cancelled() {
+                return Err(DriveError::Cancelled.into());
+            }
+
+            // Long names take over the larger freed entry slots too
+            let path = entries.join(format!("{:0>200}", created));
+            match File::create(&path) {
+                Ok(_) => created += 1,
+                Err(e) if is_full(&e) => break,
+                Err(e) => return Err(e).with_context(|| format!("Failed to create {}", path.display())),
+            }
+        }
+
+        println!("✓ Created {} entries", created);
+        Ok(created)
+    }
+
+    /// Point out what a free space wipe can't reach on this filesystem
+    #[cfg(target_os = "linux")]
+    fn warn_about_filesystem(path: &Path) {
+        use nix::sys::statfs::{statfs, BTRFS_SUPER_MAGIC};
+
+        if statfs(path).is_ok_and(|fs| fs.filesystem_type() == BTRFS_SUPER_MAGIC) {
+            println!("⚠️  Btrfs is copy-on-write: blocks still referenced by snapshots are not overwritten");
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn warn_about_filesystem(_path: &Path) {}
+}
+
+/// Out of space or quota
+fn is_full(error: &io::Error) -> bool {
+    #[cfg(unix)]
+    let os_full = matches!(error.raw_os_error(), Some(libc::ENOSPC) | Some(libc::EDQUOT));
+    #[cfg(not(unix))]
+    let os_full = false;
+    error.kind() == io::ErrorKind::StorageFull || os_full
+}
+
+/// Bytes free on the filesystem holding `path`, as the caller may use them
+#[cfg(unix)]
+fn free_bytes(path: &Path) -> Option<u64> {
+    let stats = nix::sys::statvfs::statvfs(path).ok()?;
+    Some(stats.blocks_available() as u64 * stats.fragment_size() as u64)
+}
+
+#[cfg(windows)]
+fn free_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDiskFreeSpaceExW;
+    use winapi::um::winnt::ULARGE_INTEGER;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (ok != 0).then(|| unsafe { *available.QuadPart() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wipe(dir: &Path, algorithm: Algorithm, options: FreeSpaceOptions) -> FreeSpaceWipe {
+        FreeSpaceWipe::new(dir, algorithm, options).unwrap()
+    }
+
+    #[test]
+    fn test_fill_and_rewrite_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = FreeSpaceOptions { max_bytes: Some(3 * FILL_CHUNK as u64 + 100), ..Default::default() };
+        let wipe = wipe(dir.path(), Algorithm::Zero, options);
+        let cancel = CancellationToken::new();
+
+        let mut files = Vec::new();
+        let written = wipe.fill(dir.path(), &PatternSpec::Byte(0xAA), &cancel, &mut files).unwrap();
+        assert_eq!(written, 3 * FILL_CHUNK as u64 + 100);
+        assert_eq!(files.len(), 1);
+        assert!(fs::read(&files[0].path).unwrap().iter().all(|&b| b == 0xAA));
+
+        assert_eq!(wipe.rewrite(&files, &PatternSpec::Sequence(vec![1, 2, 3]), &cancel).unwrap(), written);
+        let data = fs::read(&files[0].path).unwrap();
+        assert_eq!(data.len() as u64, written);
+        assert!(data.iter().enumerate().all(|(i, &b)| b == [1, 2, 3][i % 3]));
+
+        cancel.cancel();
+        let err = wipe.rewrite(&files, &PatternSpec::Byte(0), &cancel).unwrap_err();
+        assert!(matches!(err.downcast_ref::<DriveError>(), Some(DriveError::Cancelled)));
+    }
+
+    #[test]
+    fn test_run_removes_fill_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("keep.txt"), b"still here").unwrap();
+
+        let options = FreeSpaceOptions {
+            clear_entries: true,
+            max_entries: 50,
+            max_bytes: Some(FILL_CHUNK as u64),
+        };
+        let algorithm = Algorithm::Custom { passes: vec![PatternSpec::Random, PatternSpec::Byte(0x00)] };
+        let report = wipe(dir.path(), algorithm, options).run(&CancellationToken::new()).unwrap();
+
+        assert_eq!(report.passes, 2);
+        assert_eq!(report.bytes_per_pass, FILL_CHUNK as u64);
+        assert_eq!(report.entries_cleared, 50);
+        assert!(report.incomplete_passes.is_empty());
+
+        // Only the user's files are left
+        let names: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["keep.txt"]);
+        assert_eq!(fs::read(dir.path().join("keep.txt")).unwrap(), b"still here");
+    }
+
+    #[test]
+    fn test_rejects_hardware_erases_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(FreeSpaceWipe::new(dir.path(), Algorithm::Sanitize, FreeSpaceOptions::default()).is_err());
+
+        let file = dir.path().join("file");
+        fs::write(&file, b"x").unwrap();
+        assert!(FreeSpaceWipe::new(&file, Algorithm::Zero, FreeSpaceOptions::default()).is_err());
+    }
+}
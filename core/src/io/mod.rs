@@ -13,8 +13,161 @@ pub use optimized_engine::{OptimizedIO, IOConfig, IOHandle};
 pub use buffer_pool::{BufferPool, AlignedBuffer};
 pub use metrics::{IOMetrics, PerformanceStats};
 
+use crate::IoPriority;
 use std::time::Duration;
 
+/// Reads and writes at an offset without a shared cursor: `pread`/`pwrite`
+/// on Unix, `ReadFile`/`WriteFile` with an offset on Windows (which also
+/// moves the handle's cursor, so don't mix these with cursor I/O there)
+pub trait FileExt {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> std::io::Result<usize>;
+    fn write_at(&self, buf: &[u8], offset: u64) -> std::io::Result<usize>;
+
+    fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(buf, offset) {
+                Ok(0) => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => {
+                    buf = &mut buf[n..];
+                    offset += n as u64;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_all_at(&self, mut buf: &[u8], mut offset: u64) -> std::io::Result<()> {
+        while !buf.is_empty() {
+            match self.write_at(buf, offset) {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    buf = &buf[n..];
+                    offset += n as u64;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FileExt for std::fs::File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> std::io::Result<usize> {
+        #[cfg(unix)]
+        return std::os::unix::fs::FileExt::read_at(self, buf, offset);
+        #[cfg(windows)]
+        return std::os::windows::fs::FileExt::seek_read(self, buf, offset);
+    }
+
+    fn write_at(&self, buf: &[u8], offset: u64) -> std::io::Result<usize> {
+        #[cfg(unix)]
+        return std::os::unix::fs::FileExt::write_at(self, buf, offset);
+        #[cfg(windows)]
+        return std::os::windows::fs::FileExt::seek_write(self, buf, offset);
+    }
+}
+
+/// Drop the page cache's copy of `path`, so verification reads come from the
+/// media instead of the wipe data still cached in RAM. Only clean pages are
+/// dropped, so writers must sync first. A no-op where posix_fadvise is
+/// unavailable.
+pub fn drop_page_cache(path: &str) -> std::io::Result<()> {
+    let file = std::fs::File::open(path)?;
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+        // Offset 0 with length 0 covers the whole file
+        let result = unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result));
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    drop(file);
+    Ok(())
+}
+
+/// Sets the calling thread's I/O scheduling class (`ioprio_set`), restoring
+/// the previous one when dropped. Threads started while it is held inherit
+/// the new class.
+pub struct IoPriorityGuard {
+    #[cfg(target_os = "linux")]
+    previous: libc::c_long,
+}
+
+/// `ioprio_set`/`ioprio_get` target: a single thread, 0 meaning the caller
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+
+impl IoPriorityGuard {
+    /// Switch the calling thread to `priority`. A no-op off Linux.
+    pub fn set(priority: IoPriority) -> std::io::Result<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            let previous = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, 0) };
+            if previous < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            set_ioprio(priority.ioprio() as libc::c_long)?;
+            Ok(Self { previous })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = priority;
+            Ok(Self {})
+        }
+    }
+}
+
+impl Drop for IoPriorityGuard {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Err(e) = set_ioprio(self.previous) {
+            tracing::warn!(error = %e, "Failed to restore the I/O priority");
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_ioprio(ioprio: libc::c_long) -> std::io::Result<()> {
+    if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Pass an access-pattern hint for `len` bytes from `offset` of `file` to
+/// the kernel (`posix_fadvise`). A no-op where it is unavailable.
+pub fn advise(file: &std::fs::File, offset: u64, len: u64, advice: Advice) -> std::io::Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+        let advice = match advice {
+            Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+            Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+        };
+        let result = unsafe { libc::posix_fadvise(file.as_raw_fd(), offset as libc::off_t, len as libc::off_t, advice) };
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result));
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (file, offset, len, advice);
+    Ok(())
+}
+
+/// Hints given with `advise`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Advice {
+    /// Data will be accessed in order, so read ahead aggressively
+    Sequential,
+    /// Data won't be needed again; clean cached pages are dropped
+    DontNeed,
+}
+
 /// I/O operation mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum IOMode {
@@ -2,9 +2,6 @@
 
 use super::{IOResult, IOError};
 use std::fs::File;
-use std::os::unix::fs::OpenOptionsExt;
-
-#[cfg(target_os = "linux")]
 
 /// Platform-specific I/O handler
 pub trait PlatformIO: Send + Sync {
@@ -81,6 +78,7 @@ impl LinuxIO {
 impl PlatformIO for LinuxIO {
     fn open_optimized(&self, path: &str, direct_io: bool) -> IOResult<File> {
         use std::fs::OpenOptions;
+        use std::os::unix::fs::OpenOptionsExt;
 
         let mut opts = OpenOptions::new();
         opts.write(true).read(true);
@@ -207,18 +205,18 @@ impl PlatformIO for WindowsIO {
         })
     }
 
-    fn write_optimized(&self, file: &File, data: &[u8], _offset: u64) -> IOResult<usize> {
-        use std::io::Write;
+    fn write_optimized(&self, file: &File, data: &[u8], offset: u64) -> IOResult<usize> {
+        use std::os::windows::fs::FileExt;
 
-        // Windows IOCP will be used automatically by the OS
-        file.write(data).map_err(IOError::from)
+        // WriteFile with an OVERLAPPED offset
+        file.seek_write(data, offset).map_err(IOError::from)
     }
 
-    fn read_optimized(&self, file: &File, buffer: &mut [u8], _offset: u64) -> IOResult<usize> {
-        use std::io::Read;
+    fn read_optimized(&self, file: &File, buffer: &mut [u8], offset: u64) -> IOResult<usize> {
+        use std::os::windows::fs::FileExt;
 
-        // Windows IOCP will be used automatically by the OS
-        file.read(buffer).map_err(IOError::from)
+        // ReadFile with an OVERLAPPED offset
+        file.seek_read(buffer, offset).map_err(IOError::from)
     }
 
     fn sync_data(&self, file: &File) -> IOResult<()> {
@@ -311,6 +309,7 @@ impl FreeBSDIO {
 impl PlatformIO for FreeBSDIO {
     fn open_optimized(&self, path: &str, direct_io: bool) -> IOResult<File> {
         use std::fs::OpenOptions;
+        use std::os::unix::fs::OpenOptionsExt;
 
         let mut opts = OpenOptions::new();
         opts.write(true).read(true);
@@ -0,0 +1,215 @@
+// System Journal - wipe lifecycle events for central collection (SIEM)
+//
+// With `WipeConfig::syslog` set, the start, completion and failure of each
+// wipe are sent to journald as structured fields (SAYONARA_SERIAL,
+// SAYONARA_ALGORITHM, ...). Without journald they go to syslog at /dev/log
+// as RFC 5424 messages carrying the same fields as structured data.
+// Delivery is best effort: a missing journal never fails a wipe.
+
+use crate::{Algorithm, DriveInfo, WipeEvent};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+#[cfg(unix)]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+#[cfg(unix)]
+const SYSLOG_SOCKET: &str = "/dev/log";
+const IDENTIFIER: &str = "sayonara";
+
+/// Structured-data ID for syslog; 32473 is the enterprise number RFC 5424
+/// reserves for examples, used here as no private one is registered
+const SD_ID: &str = "sayonara@32473";
+
+/// syslog severities used for wipe events
+const LOG_ERR: u8 = 3;
+const LOG_NOTICE: u8 = 5;
+const LOG_INFO: u8 = 6;
+
+/// syslog facility (LOG_DAEMON), pre-shifted
+const FACILITY: u8 = 3 << 3;
+
+/// One lifecycle event, ready to send to either journal
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub severity: u8,
+    pub message: String,
+    /// Field names without the `SAYONARA_` prefix journald gets
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl JournalEntry {
+    /// The entry for `event` on `drive`, or None for events that aren't
+    /// part of the lifecycle (progress, passes, thermal pauses)
+    pub fn for_event(drive: &DriveInfo, algorithm: &Algorithm, event: &WipeEvent) -> Option<Self> {
+        let (severity, message, mut fields) = match event {
+            WipeEvent::DriveIdentified { .. } => (
+                LOG_INFO,
+                format!("Wipe started on {} ({} {})", drive.device_path, drive.model, drive.serial),
+                vec![("EVENT", "started".to_string())],
+            ),
+            WipeEvent::Completed { certificate_id, method, duration_seconds } => (
+                LOG_NOTICE,
+                format!("Wipe of {} completed: {}", drive.device_path, method),
+                vec![
+                    ("EVENT", "completed".to_string()),
+                    ("RESULT", "success".to_string()),
+                    ("METHOD", method.clone()),
+                    ("CERTIFICATE_ID", certificate_id.clone()),
+                    ("DURATION_SECONDS", duration_seconds.to_string()),
+                ],
+            ),
+            WipeEvent::Error { message } => (
+                LOG_ERR,
+                format!("Wipe of {} failed: {}", drive.device_path, message),
+                vec![
+                    ("EVENT", "failed".to_string()),
+                    ("RESULT", "failure".to_string()),
+                    ("ERROR", message.clone()),
+                ],
+            ),
+            _ => return None,
+        };
+
+        fields.extend([
+            ("DEVICE", drive.device_path.clone()),
+            ("SERIAL", drive.serial.clone()),
+            ("MODEL", drive.model.clone()),
+            ("DRIVE_TYPE", format!("{:?}", drive.drive_type)),
+            ("ALGORITHM", format!("{:?}", algorithm)),
+        ]);
+        Some(Self { severity, message, fields })
+    }
+
+    /// journald native protocol: `KEY=value` lines, with the length-prefixed
+    /// binary form for values containing a newline
+    pub fn journald_datagram(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut field = |key: &str, value: &str| {
+            out.extend_from_slice(key.as_bytes());
+            if value.contains('\n') {
+                out.push(b'\n');
+                out.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            } else {
+                out.push(b'=');
+            }
+            out.extend_from_slice(value.as_bytes());
+            out.push(b'\n');
+        };
+
+        field("MESSAGE", &self.message);
+        field("PRIORITY", &self.severity.to_string());
+        field("SYSLOG_IDENTIFIER", IDENTIFIER);
+        for (name, value) in &self.fields {
+            field(&format!("SAYONARA_{}", name), value);
+        }
+        out
+    }
+
+    /// RFC 5424 message with the fields as one structured-data element
+    pub fn syslog_message(&self, timestamp: chrono::DateTime<chrono::Utc>, pid: u32) -> String {
+        let params: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name.to_lowercase(), escape_param(value)))
+            .collect();
+
+        format!(
+            "<{}>1 {} - {} {} - [{} {}] {}",
+            FACILITY | self.severity,
+            timestamp.to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            IDENTIFIER,
+            pid,
+            SD_ID,
+            params.join(" "),
+            self.message
+        )
+    }
+}
+
+/// Escape `"`, `\` and `]`, which RFC 5424 reserves inside parameter values
+fn escape_param(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Send `event` to journald, or syslog when journald isn't running
+pub fn record(drive: &DriveInfo, algorithm: &Algorithm, event: &WipeEvent) {
+    let Some(entry) = JournalEntry::for_event(drive, algorithm, event) else {
+        return;
+    };
+
+    #[cfg(unix)]
+    let sent = UnixDatagram::unbound().and_then(|socket| {
+        socket
+            .send_to(&entry.journald_datagram(), JOURNALD_SOCKET)
+            .or_else(|_| socket.send_to(entry.syslog_message(chrono::Utc::now(), std::process::id()).as_bytes(), SYSLOG_SOCKET))
+    });
+    // No journald or /dev/log to reach off Unix
+    #[cfg(not(unix))]
+    let sent: std::io::Result<usize> = {
+        let _ = entry;
+        Err(std::io::ErrorKind::Unsupported.into())
+    };
+
+    if let Err(e) = sent {
+        tracing::debug!(device = %drive.device_path, error = %e, "System journal unavailable");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DriveCapabilities, DriveType};
+
+    fn drive() -> DriveInfo {
+        DriveInfo {
+            device_path: "/dev/sdz".to_string(),
+            model: "Test Disk".to_string(),
+            serial: "SN\"42]".to_string(),
+            ..crate::test_support::drive(DriveType::SSD, DriveCapabilities::default())
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_entries() {
+        let drive = drive();
+        let progress = WipeEvent::VerificationStarted { pass: 1 };
+        assert!(JournalEntry::for_event(&drive, &Algorithm::Zero, &progress).is_none());
+
+        let failed = WipeEvent::Error { message: "Wipe cancelled".to_string() };
+        let entry = JournalEntry::for_event(&drive, &Algorithm::Zero, &failed).unwrap();
+        assert_eq!(entry.severity, LOG_ERR);
+
+        let datagram = String::from_utf8(entry.journald_datagram()).unwrap();
+        assert!(datagram.starts_with("MESSAGE=Wipe of /dev/sdz failed: Wipe cancelled\nPRIORITY=3\n"));
+        assert!(datagram.contains("\nSAYONARA_RESULT=failure\n"));
+        assert!(datagram.contains("\nSAYONARA_ALGORITHM=Zero\n"));
+
+        let timestamp = chrono::DateTime::from_timestamp(0, 0).unwrap();
+        let message = entry.syslog_message(timestamp, 7);
+        assert!(message.starts_with("<27>1 1970-01-01T00:00:00.000000Z - sayonara 7 - [sayonara@32473 event=\"failed\""));
+        assert!(message.contains(" serial=\"SN\\\"42\\]\" "));
+        assert!(message.ends_with("] Wipe of /dev/sdz failed: Wipe cancelled"));
+    }
+
+    #[test]
+    fn test_multiline_values_use_binary_fields() {
+        let entry = JournalEntry {
+            severity: LOG_INFO,
+            message: "two\nlines".to_string(),
+            fields: Vec::new(),
+        };
+
+        let datagram = entry.journald_datagram();
+        let mut expected = b"MESSAGE\n".to_vec();
+        expected.extend_from_slice(&9u64.to_le_bytes());
+        expected.extend_from_slice(b"two\nlines\n");
+        assert!(datagram.starts_with(&expected));
+    }
+}
@@ -5,10 +5,21 @@ pub mod crypto;
 pub mod ui;
 pub mod io;
 pub mod wipe_orchestrator;
+pub mod multi_wipe;
 pub mod error;
+pub mod logging;
+pub mod commands;
+pub mod config_file;
+pub mod policy;
+pub mod free_space;
+pub mod ffi;
+#[cfg(feature = "syslog")]
+pub mod journal;
 
 // Re-export main wipe orchestrator for convenience
-pub use wipe_orchestrator::{WipeOrchestrator, wipe_drive};
+pub use wipe_orchestrator::{WipeOrchestrator, WipeReport, PassStats, WipeEstimate, EstimatedDuration, WipeMethod, WipePlan, wipe_drive, wipe_raid_array, RaidWipeReport, RaidMemberResult};
+pub use policy::{Policy, ComplianceStandard};
+pub use multi_wipe::{MultiWipe, MultiWipeReport, DeviceWipeResult, DeviceEventCallback};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -39,6 +50,9 @@ pub enum DriveError {
     #[error("Hardware command failed: {0}")]
     HardwareCommandFailed(String),
 
+    #[error("Required tool `{0}` is not installed (install {})", commands::install_hint(.0))]
+    MissingTool(String),
+
     #[error("SMART read failed: {0}")]
     SMARTReadFailed(String),
 
@@ -66,8 +80,29 @@ pub enum DriveError {
     #[error("Unsupported operation: {0}")]
     Unsupported(String),
 
+    #[error("Refusing to wipe system disk: {0}")]
+    SystemDisk(String),
+
+    #[error("Device has mounted filesystems: {0}")]
+    Mounted(String),
+
+    #[error("Drive is failing: {0}")]
+    Unhealthy(String),
+
+    #[error("Checkpoint belongs to a different drive: {0}")]
+    CheckpointMismatch(String),
+
+    #[error("Device size differs from the detected size: {0}")]
+    SizeMismatch(String),
+
+    #[error("Drive serial not permitted: {0}")]
+    SerialNotPermitted(String),
+
     #[error("Operation interrupted by user")]
     Interrupted,
+
+    #[error("Wipe cancelled")]
+    Cancelled,
 }
 
 // Manual Clone implementation because std::io::Error doesn't implement Clone
@@ -77,6 +112,7 @@ impl Clone for DriveError {
             DriveError::IoError(e) => DriveError::IoError(std::io::Error::new(e.kind(), e.to_string())),
             DriveError::DriveFrozen(s) => DriveError::DriveFrozen(s.clone()),
             DriveError::HardwareCommandFailed(s) => DriveError::HardwareCommandFailed(s.clone()),
+            DriveError::MissingTool(s) => DriveError::MissingTool(s.clone()),
             DriveError::SMARTReadFailed(s) => DriveError::SMARTReadFailed(s.clone()),
             DriveError::TemperatureExceeded(s) => DriveError::TemperatureExceeded(s.clone()),
             DriveError::TRIMFailed(s) => DriveError::TRIMFailed(s.clone()),
@@ -86,13 +122,24 @@ impl Clone for DriveError {
             DriveError::PermissionDenied(s) => DriveError::PermissionDenied(s.clone()),
             DriveError::NotFound(s) => DriveError::NotFound(s.clone()),
             DriveError::Unsupported(s) => DriveError::Unsupported(s.clone()),
+            DriveError::SystemDisk(s) => DriveError::SystemDisk(s.clone()),
+            DriveError::Mounted(s) => DriveError::Mounted(s.clone()),
+            DriveError::Unhealthy(s) => DriveError::Unhealthy(s.clone()),
+            DriveError::CheckpointMismatch(s) => DriveError::CheckpointMismatch(s.clone()),
+            DriveError::SizeMismatch(s) => DriveError::SizeMismatch(s.clone()),
+            DriveError::SerialNotPermitted(s) => DriveError::SerialNotPermitted(s.clone()),
             DriveError::Interrupted => DriveError::Interrupted,
+            DriveError::Cancelled => DriveError::Cancelled,
         }
     }
 }
 
 impl From<anyhow::Error> for DriveError {
     fn from(err: anyhow::Error) -> Self {
+        // A command killed at the operation deadline stays a timeout
+        if err.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::TimedOut) {
+            return DriveError::Timeout(err.to_string());
+        }
         // Map to the most appropriate variant based on error message
         DriveError::HardwareCommandFailed(err.to_string())
     }
@@ -100,39 +147,480 @@ impl From<anyhow::Error> for DriveError {
 
 pub type DriveResult<T> = Result<T, DriveError>;
 
+/// Cooperative cancellation for a running wipe. Clones share one flag, so a
+/// frontend keeps a clone and calls `cancel()` while `execute()` runs.
+/// A Ctrl+C (`set_interrupted`) cancels every token.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: std::sync::Arc<AtomicBool>,
+    deadline: Option<std::time::Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token sharing this one's flag that also counts as cancelled once
+    /// `deadline` passes; the original token is unaffected by the deadline
+    pub fn with_deadline(&self, deadline: std::time::Instant) -> Self {
+        Self {
+            cancelled: self.cancelled.clone(),
+            deadline: Some(deadline),
+        }
+    }
+
+    pub fn deadline(&self) -> Option<std::time::Instant> {
+        self.deadline
+    }
+
+    /// Request cancellation; the wipe stops at the next chunk or poll
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || is_interrupted() || self.timed_out()
+    }
+
+    /// Whether the token's deadline has passed
+    pub fn timed_out(&self) -> bool {
+        self.deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline)
+    }
+}
+
+/// Structured progress of a running overwrite, delivered per chunk written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WipeProgress {
+    pub current_pass: usize, // 1-based
+    pub total_passes: usize,
+    pub bytes_written: u64,  // across all passes
+    pub total_bytes: u64,
+    pub percent: f64,
+    pub bytes_per_second: f64, // average since this session started
+    /// Moving average of recent throughput, which `eta_seconds` is based on
+    #[serde(default)]
+    pub smoothed_bytes_per_second: f64,
+    /// Throughput has stayed near zero for a while, typically a failing
+    /// region of the surface (see `ui::progress::STALL_BYTES_PER_SEC`)
+    #[serde(default)]
+    pub stalled: bool,
+    pub eta_seconds: Option<u64>, // None while stalled
+}
+
+/// Callback receiving `WipeProgress` updates. Shared (not boxed) so the config
+/// stays `Clone` and parallel region workers can report concurrently.
+#[derive(Clone)]
+pub struct ProgressCallback(pub std::sync::Arc<dyn Fn(WipeProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(callback: impl Fn(WipeProgress) + Send + Sync + 'static) -> Self {
+        Self(std::sync::Arc::new(callback))
+    }
+}
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Lifecycle events emitted by `WipeOrchestrator`, for frontends that need
+/// structured state rather than console text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WipeEvent {
+    DetectionStarted { device_path: String },
+    DriveIdentified { drive: DriveInfo },
+    PassStarted { pass: usize, total_passes: usize, description: String }, // 1-based
+    Progress(WipeProgress),
+    PassCompleted { pass: usize, total_passes: usize, bytes_written: u64, duration_seconds: f64, bytes_per_second: u64 },
+    VerificationStarted { pass: usize },
+    /// Writing paused because the drive passed `max_temperature_celsius`
+    ThermalPaused { temperature_celsius: u32, resume_at_celsius: u32 },
+    ThermalResumed { temperature_celsius: u32, paused_seconds: u64 },
+    Completed { certificate_id: String, method: String, duration_seconds: u64 },
+    Error { message: String },
+}
+
+impl WipeEvent {
+    /// Default console subscriber, used when no `event_callback` is registered.
+    /// Progress is drawn by the progress bar, and events the per-type wipe
+    /// output already narrates print nothing extra.
+    pub fn print_to_console(&self) {
+        match self {
+            WipeEvent::DriveIdentified { drive } => {
+                println!("\n=== Starting Wipe Operation ===");
+                println!("Device: {}", drive.device_path);
+                println!("Model: {}", drive.model);
+                println!("Size: {} GB", drive.size / (1024 * 1024 * 1024));
+                println!("Type: {:?}", drive.drive_type);
+            }
+            WipeEvent::PassStarted { pass, total_passes, description } => {
+                println!("\n🔄 Pass {}/{}: {}", pass, total_passes, description);
+            }
+            WipeEvent::PassCompleted { pass, total_passes, duration_seconds, bytes_per_second, .. } => {
+                println!(
+                    "  ⏱️  Pass {}/{} took {:.1}s ({:.1} MB/s)",
+                    pass,
+                    total_passes,
+                    duration_seconds,
+                    *bytes_per_second as f64 / (1024.0 * 1024.0)
+                );
+            }
+            WipeEvent::VerificationStarted { pass } => {
+                println!("\n  🔍 Verifying pass {} ...", pass);
+            }
+            WipeEvent::ThermalPaused { temperature_celsius, resume_at_celsius } => {
+                println!("\n🌡️  Drive at {}°C - pausing until it cools to {}°C", temperature_celsius, resume_at_celsius);
+            }
+            WipeEvent::ThermalResumed { temperature_celsius, paused_seconds } => {
+                println!("🌡️  Drive cooled to {}°C after {}s - resuming", temperature_celsius, paused_seconds);
+            }
+            WipeEvent::DetectionStarted { .. }
+            | WipeEvent::Progress(_)
+            | WipeEvent::Completed { .. }
+            | WipeEvent::Error { .. } => {}
+        }
+    }
+
+    /// Record the event in the audit log (see `WipeConfig::log_path`)
+    pub fn log(&self) {
+        match self {
+            WipeEvent::DetectionStarted { device_path } => {
+                tracing::info!(device = %device_path, "Detection started");
+            }
+            WipeEvent::DriveIdentified { drive } => tracing::info!(
+                device = %drive.device_path,
+                model = %drive.model,
+                serial = %drive.serial,
+                drive_type = ?drive.drive_type,
+                size = drive.size,
+                encryption = ?drive.encryption_status,
+                health = ?drive.health_status,
+                "Drive identified"
+            ),
+            WipeEvent::PassStarted { pass, total_passes, description } => {
+                tracing::info!(pass, total_passes, %description, "Pass started");
+            }
+            WipeEvent::Progress(progress) => tracing::trace!(
+                pass = progress.current_pass,
+                bytes_written = progress.bytes_written,
+                bytes_per_second = progress.smoothed_bytes_per_second,
+                stalled = progress.stalled,
+                "Progress"
+            ),
+            WipeEvent::PassCompleted { pass, total_passes, bytes_written, duration_seconds, bytes_per_second } => {
+                tracing::info!(pass, total_passes, bytes_written, duration_seconds, bytes_per_second, "Pass completed");
+            }
+            WipeEvent::VerificationStarted { pass } => tracing::info!(pass, "Verification started"),
+            WipeEvent::ThermalPaused { temperature_celsius, resume_at_celsius } => {
+                tracing::warn!(temperature_celsius, resume_at_celsius, "Paused for drive temperature");
+            }
+            WipeEvent::ThermalResumed { temperature_celsius, paused_seconds } => {
+                tracing::info!(temperature_celsius, paused_seconds, "Resumed after cooling");
+            }
+            WipeEvent::Completed { certificate_id, method, duration_seconds } => {
+                tracing::info!(%certificate_id, %method, duration_seconds, "Wipe completed");
+            }
+            WipeEvent::Error { message } => tracing::error!(%message, "Wipe failed"),
+        }
+    }
+}
+
+/// Subscriber for `WipeEvent`s, shared like `ProgressCallback`
+#[derive(Clone)]
+pub struct EventCallback(pub std::sync::Arc<dyn Fn(WipeEvent) + Send + Sync>);
+
+impl EventCallback {
+    pub fn new(callback: impl Fn(WipeEvent) + Send + Sync + 'static) -> Self {
+        Self(std::sync::Arc::new(callback))
+    }
+}
+
+impl std::fmt::Debug for EventCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EventCallback")
+    }
+}
+
+/// Last check before anything is written: handed the detected drive, the
+/// wipe only proceeds when it returns true
+#[derive(Clone)]
+pub struct ConfirmCallback(pub std::sync::Arc<dyn Fn(&DriveInfo) -> bool + Send + Sync>);
+
+impl ConfirmCallback {
+    pub fn new(callback: impl Fn(&DriveInfo) -> bool + Send + Sync + 'static) -> Self {
+        Self(std::sync::Arc::new(callback))
+    }
+}
+
+impl std::fmt::Debug for ConfirmCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ConfirmCallback")
+    }
+}
+
+/// `Option<Duration>` as whole seconds in policy files
+mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(|duration| duration.as_secs()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+    }
+}
+
+/// Everything that controls a wipe. Serializes to the policy files read by
+/// `WipeConfig::from_file`; fields missing from a file keep their defaults.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct WipeConfig {
     pub algorithm: Algorithm,
+    /// Goal the algorithm is picked for once the drive is detected,
+    /// overriding `algorithm`; see `WipeConfig::preset`
+    pub policy: Option<Policy>,
     pub verify: bool,
+    /// How much of the device `verify` reads back after the wipe, and the pass threshold
+    pub verification: crate::verification::recovery_test::VerificationConfig,
     pub multiple_passes: Option<u32>,
     pub preserve_partition_table: bool,
     pub unlock_encrypted: bool,
     pub handle_hpa_dco: HPADCOHandling,
     pub use_trim_after: bool,
+    /// Overwrite after a TRIM-based erase when discarded blocks don't read back as zeros
+    pub overwrite_after_unverified_trim: bool,
     pub temperature_monitoring: bool,
+    /// Pause overwriting while the drive is hotter than this (high watermark)
     pub max_temperature_celsius: Option<u32>,
+    /// Resume a paused overwrite once the drive cools to this (low watermark);
+    /// None resumes 5°C below `max_temperature_celsius`
+    pub resume_temperature_celsius: Option<u32>,
+    /// Suspend and resume the host (rtcwake) to clear a drive's ATA security
+    /// freeze before a hardware erase. Off by default, since it puts the
+    /// whole machine to sleep; a frozen drive is overwritten instead.
     pub freeze_mitigation: bool,
     pub sed_crypto_erase: bool,
+    /// Destroy the header and key slots of any LUKS container on the target
+    /// before the bulk wipe, so its contents are unrecoverable from the first
+    /// seconds on. With `Algorithm::CryptoErase` on a drive that isn't a SED,
+    /// this crypto-shred is the whole wipe.
+    pub luks_header_erase: bool,
+    /// Prefer the drive's ATA Secure Erase over overwriting on SATA HDDs/SSDs that support it
+    pub ata_secure_erase: bool,
+    /// Read SMART health before and after the wipe, recording the change in
+    /// the certificate. A failing drive is refused unless `force` is set.
+    pub smart_health_check: bool,
+    /// Sample the device after a hardware erase (sanitize, secure erase,
+    /// crypto erase) reports success, failing if structured data remains
+    pub verify_hardware_erase: bool,
+    /// Read every chunk back straight after writing it (flushed, bypassing the
+    /// page cache) and abort at the first mismatch, instead of only checking
+    /// at the end. Much slower, but a silently failing surface is caught on
+    /// the pass that hits it.
+    pub verify_each_pass: bool,
+    /// After the wipe, clear controller logs that reveal usage history (NVMe
+    /// persistent event log, ATA SMART self-test log) where the drive allows
+    /// it, recording each result in the certificate
+    pub clear_device_logs: bool,
+    /// Once the wipe is done, hash the device's contents (all of it, or a
+    /// fixed sample) and record the digest in the certificate, so anyone
+    /// can re-read the device and show it hasn't changed since; None skips it
+    pub final_hash: Option<verification::device_hash::HashScheme>,
+    /// Once the wipe is verified, write a new GPT with one partition across
+    /// the drive and format it, so it is ready to hand on; None leaves the
+    /// drive blank
+    pub reprovision: Option<drives::operations::Filesystem>,
+    /// Size in bytes of each buffer streamed to the device during overwrite passes
+    pub chunk_size: usize,
+    /// How overwrite passes are written past the OS page cache
+    pub write_mode: WriteMode,
+    /// Flush to the device after this many bytes of each pass; resume
+    /// checkpoints only advance at these points
+    pub sync_interval_bytes: u64,
+    /// Number of contiguous regions overwritten concurrently; None picks a
+    /// per-drive default (1 for HDDs, more for SSD/NVMe)
+    pub parallel_regions: Option<usize>,
+    /// Cap on overwrite throughput in bytes per second, shared across
+    /// parallel regions; None writes as fast as the device allows
+    pub max_bytes_per_sec: Option<u64>,
+    /// I/O scheduling class for overwrite passes and their read-back, as
+    /// `ionice` would set it; None leaves the process's priority unchanged
+    pub io_priority: Option<IoPriority>,
+    /// Tell the kernel overwrite passes are sequential and drop written
+    /// pages from the page cache after each flush, so a wipe doesn't evict
+    /// other workloads' cached data
+    pub fadvise_hints: bool,
+    /// Overwrite only `(start, len)` bytes of the target instead of all of it.
+    /// Hardware erases can't be limited to a range, so only overwrite passes run.
+    pub region: Option<(u64, u64)>,
+    /// NVMe sanitize action to run; None picks the most thorough one the controller supports
+    pub nvme_sanitize_action: Option<NvmeSanitizeAction>,
+    /// NVMe namespace IDs to wipe on multi-namespace drives; None wipes all of them
+    pub target_namespaces: Option<Vec<u32>>,
+    /// Abort the wipe once more than this many sectors have failed to write
+    pub max_bad_sectors: usize,
+    /// Retries of a chunk write or hardware command that failed with a
+    /// transient error (EAGAIN, EBUSY, a bus reset's EIO) before it is
+    /// treated as permanent; 0 disables retrying
+    pub transient_retries: u32,
+    /// Delay before the first such retry, doubling for each one after
+    pub retry_backoff_ms: u64,
+    /// Give up on the wipe, and kill any hardware command still running,
+    /// once it has taken this long, returning `DriveError::Timeout` with
+    /// progress checkpointed; None waits as long as the drive takes
+    #[serde(with = "duration_secs")]
+    pub operation_timeout: Option<std::time::Duration>,
+    /// Fail a polled hardware erase (NVMe sanitize) whose reported progress
+    /// hasn't advanced for this long; None trusts the drive to finish
+    #[serde(with = "duration_secs")]
+    pub stall_timeout: Option<std::time::Duration>,
+    /// Run detection and print the plan, but never write to the device
+    pub dry_run: bool,
+    /// Allow wiping the disk that hosts `/`, `/boot` or active swap
+    pub allow_system_disk: bool,
+    /// Only ever wipe drives whose serial is listed here (compared ignoring
+    /// case and surrounding spaces); a drive whose serial can't be read is
+    /// refused too. None allows any drive.
+    pub serial_allowlist: Option<Vec<String>>,
+    /// Never wipe drives whose serial is listed here, e.g. the boot drive
+    pub serial_denylist: Vec<String>,
+    /// Unmount filesystems on the target before wiping instead of refusing
+    pub force_unmount: bool,
+    /// Proceed with destructive wipes that are refused by default, such as
+    /// wiping a single RAID member (which destroys the array)
+    pub force: bool,
+    /// Write the JSON wipe certificate here once the wipe completes
+    pub certificate_path: Option<String>,
+    /// Also append each certificate to this JSON-lines audit log, shared by
+    /// every wipe (see `CertificateStore`)
+    pub certificate_log_path: Option<String>,
+    /// Chain each `certificate_log_path` entry to the hash of the one
+    /// before it, making edits to the log detectable
+    pub certificate_log_hash_chain: bool,
+    /// Append a timestamped audit log of detection, passes, device commands,
+    /// errors and recovery actions here, alongside the console output
+    pub log_path: Option<String>,
+    /// Also send each wipe's start, completion and failure, with the drive
+    /// serial, algorithm and result, to journald (or syslog). Needs the
+    /// `syslog` build feature; otherwise the wipe warns and carries on.
+    pub syslog: bool,
+    /// Ed25519 private key (PKCS#8 DER or raw 32-byte seed) used to sign the certificate
+    pub signing_key_path: Option<String>,
+    /// Derive random passes from a recorded seed, sealed with the 32-byte key
+    /// at this path (created on first use), so verification can regenerate
+    /// them byte for byte; None draws random passes straight from the RNG
+    pub pattern_seed_key_path: Option<String>,
+    /// Operator-supplied notes recorded in the wipe certificate
+    pub notes: Option<String>,
+    /// Receives structured progress for GUI callers instead of parsing stdout
+    #[serde(skip)]
+    pub progress_callback: Option<ProgressCallback>,
+    /// Receives every `WipeEvent`; console output is used when unset
+    #[serde(skip)]
+    pub event_callback: Option<EventCallback>,
+    /// Asked to approve the detected drive before any write; unset proceeds
+    /// without asking. Not consulted for dry runs.
+    #[serde(skip)]
+    pub confirm_callback: Option<ConfirmCallback>,
 }
 
 impl Default for WipeConfig {
     fn default() -> Self {
         Self {
             algorithm: Algorithm::DoD5220,
+            policy: None,
             verify: true,
+            verification: Default::default(),
             multiple_passes: None,
             preserve_partition_table: false,
             unlock_encrypted: false,
             handle_hpa_dco: HPADCOHandling::Detect,
             use_trim_after: true,
+            overwrite_after_unverified_trim: true,
             temperature_monitoring: true,
             max_temperature_celsius: Some(65),
-            freeze_mitigation: true,
+            resume_temperature_celsius: None,
+            freeze_mitigation: false,
             sed_crypto_erase: true,
+            luks_header_erase: true,
+            ata_secure_erase: true,
+            smart_health_check: true,
+            verify_hardware_erase: true,
+            verify_each_pass: false,
+            clear_device_logs: false,
+            final_hash: None,
+            reprovision: None,
+            chunk_size: 16 * 1024 * 1024,
+            write_mode: WriteMode::Buffered,
+            sync_interval_bytes: 256 * 1024 * 1024,
+            parallel_regions: None,
+            max_bytes_per_sec: None,
+            io_priority: None,
+            fadvise_hints: false,
+            region: None,
+            nvme_sanitize_action: None,
+            target_namespaces: None,
+            max_bad_sectors: crate::error::mechanisms::bad_sector::DEFAULT_MAX_BAD_SECTORS,
+            transient_retries: 5,
+            retry_backoff_ms: 100,
+            operation_timeout: None,
+            stall_timeout: None,
+            dry_run: false,
+            allow_system_disk: false,
+            serial_allowlist: None,
+            serial_denylist: Vec::new(),
+            force_unmount: false,
+            force: false,
+            certificate_path: None,
+            certificate_log_path: None,
+            certificate_log_hash_chain: true,
+            log_path: None,
+            syslog: false,
+            signing_key_path: None,
+            pattern_seed_key_path: None,
+            notes: None,
+            progress_callback: None,
+            event_callback: None,
+            confirm_callback: None,
         }
     }
 }
 
+impl WipeConfig {
+    /// Refuse `drive` if its serial is on `serial_denylist`, or missing from
+    /// a `serial_allowlist`
+    pub fn check_serial(&self, drive: &DriveInfo) -> DriveResult<()> {
+        let serial = drive.serial.trim();
+        let listed = |list: &[String]| list.iter().any(|entry| entry.trim().eq_ignore_ascii_case(serial));
+
+        if listed(&self.serial_denylist) {
+            return Err(DriveError::SerialNotPermitted(format!(
+                "{} (serial {}) is on the serial denylist",
+                drive.device_path, serial
+            )));
+        }
+        if let Some(allowlist) = &self.serial_allowlist {
+            if serial.is_empty() || serial == "Unknown" {
+                return Err(DriveError::SerialNotPermitted(format!(
+                    "{} has no readable serial to check against the serial allowlist",
+                    drive.device_path
+                )));
+            }
+            if !listed(allowlist) {
+                return Err(DriveError::SerialNotPermitted(format!(
+                    "{} (serial {}) is not on the serial allowlist",
+                    drive.device_path, serial
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HPADCOHandling {
     Ignore,           // Don't check for HPA/DCO
@@ -141,6 +629,75 @@ pub enum HPADCOHandling {
     PermanentRemove,  // Remove permanently (dangerous)
 }
 
+/// How overwrite passes reach the device past the OS page cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WriteMode {
+    Buffered, // Page cache, flushed every `sync_interval_bytes`
+    Sync,     // O_SYNC: each write returns once it is on the device
+    Direct,   // O_DIRECT + O_SYNC: bypass the page cache entirely (aligned buffers)
+}
+
+/// I/O scheduling class (`ioprio_set`), as `ionice -c` selects it. Only
+/// schedulers that honour priorities (BFQ, mq-deadline) act on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoPriority {
+    BestEffort(u8), // Level 0 (highest) to 7 (lowest) among other best-effort I/O
+    Idle,           // Only served when no other process wants the disk
+}
+
+impl IoPriority {
+    /// Value passed to `ioprio_set`: the class above the 13-bit level
+    pub fn ioprio(&self) -> i32 {
+        match self {
+            IoPriority::BestEffort(level) => (2 << 13) | *level as i32,
+            IoPriority::Idle => 3 << 13,
+        }
+    }
+}
+
+/// NVMe Sanitize action (SANACT), listed from most to least thorough
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NvmeSanitizeAction {
+    BlockErase,   // Physically erase every NAND block, including over-provisioning
+    CryptoErase,  // Destroy the media encryption key
+    Overwrite,    // Controller-driven overwrite of all user data
+}
+
+impl NvmeSanitizeAction {
+    /// All actions, most thorough first
+    pub const BY_THOROUGHNESS: [NvmeSanitizeAction; 3] = [
+        NvmeSanitizeAction::BlockErase,
+        NvmeSanitizeAction::CryptoErase,
+        NvmeSanitizeAction::Overwrite,
+    ];
+
+    /// SANACT field value passed to `nvme sanitize -a`
+    pub fn sanact(&self) -> u8 {
+        match self {
+            NvmeSanitizeAction::BlockErase => 2,
+            NvmeSanitizeAction::Overwrite => 3,
+            NvmeSanitizeAction::CryptoErase => 4,
+        }
+    }
+
+    /// SANICAP bit advertising support for this action
+    pub fn sanicap_bit(&self) -> u32 {
+        match self {
+            NvmeSanitizeAction::CryptoErase => 1 << 0,
+            NvmeSanitizeAction::BlockErase => 1 << 1,
+            NvmeSanitizeAction::Overwrite => 1 << 2,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NvmeSanitizeAction::BlockErase => "block erase",
+            NvmeSanitizeAction::CryptoErase => "crypto erase",
+            NvmeSanitizeAction::Overwrite => "overwrite",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Algorithm {
     DoD5220,      // 3-pass DoD 5220.22-M
@@ -151,6 +708,93 @@ pub enum Algorithm {
     CryptoErase,  // Cryptographic erase (SED)
     Sanitize,     // NVMe sanitize command
     TrimOnly,     // TRIM/discard only (SSD)
+    NIST80088Clear, // NIST SP 800-88 Clear (single overwrite / block erase)
+    NIST80088Purge, // NIST SP 800-88 Purge (hardware sanitize, overwrite fallback)
+    #[allow(non_camel_case_types)]
+    BSI_GS,       // BSI IT-Grundschutz: random + verified 0x00
+    #[allow(non_camel_case_types)]
+    BSI_GSE,      // BSI-GS extended: 2x random + verified 0x00
+    VSITR,        // German VSITR: 0x00/0xFF x3 + verified 0xAA
+    HMGIS5Baseline, // UK HMG IS5 Baseline: verified 0x00
+    HMGIS5Enhanced, // UK HMG IS5 Enhanced: 0x00, 0xFF, verified random
+    /// User-defined overwrite: one pass per entry, final pass read back
+    Custom { passes: Vec<algorithms::PatternSpec> },
+}
+
+impl Algorithm {
+    /// Erases the drive performs itself (secure erase, crypto erase,
+    /// sanitize, TRIM, NIST Purge) rather than a sequence of overwrite passes
+    pub fn is_hardware_erase(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::SecureErase | Algorithm::CryptoErase | Algorithm::Sanitize | Algorithm::TrimOnly | Algorithm::NIST80088Purge
+        )
+    }
+
+    /// Passes an overwrite with this algorithm writes: 3 for DoD 5220.22-M,
+    /// 35 for Gutmann, 2 or 3 for BSI-GS/GSE, 7 for VSITR, 3 for HMG IS5
+    /// Enhanced, one otherwise (hardware erases count as a single pass)
+    pub fn overwrite_passes(&self) -> usize {
+        match self {
+            Algorithm::DoD5220 => algorithms::DoDWipe::DOD_PATTERNS.len(),
+            Algorithm::Gutmann => algorithms::GutmannWipe::GUTMANN_PATTERNS.len(),
+            Algorithm::BSI_GS => algorithms::BsiWipe::BSI_GS_PASSES.len(),
+            Algorithm::BSI_GSE => algorithms::BsiWipe::BSI_GSE_PASSES.len(),
+            Algorithm::VSITR => algorithms::BsiWipe::VSITR_PASSES.len(),
+            Algorithm::HMGIS5Baseline => algorithms::HmgWipe::BASELINE_PASSES.len(),
+            Algorithm::HMGIS5Enhanced => algorithms::HmgWipe::ENHANCED_PASSES.len(),
+            Algorithm::Custom { passes } => passes.len(),
+            _ => 1,
+        }
+    }
+
+    /// Pattern written by one pass of an overwrite with this algorithm;
+    /// random data for algorithms that aren't a pass sequence
+    pub fn pass_pattern(&self, pass_index: usize) -> algorithms::PatternSpec {
+        use algorithms::{BsiWipe, DoDWipe, GutmannPass, HmgWipe, PatternSpec};
+
+        match self {
+            Algorithm::Zero | Algorithm::NIST80088Clear => PatternSpec::Byte(0x00),
+            Algorithm::DoD5220 => match DoDWipe::DOD_PATTERNS[pass_index].0 {
+                Some(bytes) => GutmannPass::Pattern(bytes).into(),
+                None => PatternSpec::Random,
+            },
+            Algorithm::Gutmann => algorithms::gutmann_patterns()[pass_index].into(),
+            Algorithm::BSI_GS => BsiWipe::BSI_GS_PASSES[pass_index].0.clone(),
+            Algorithm::BSI_GSE => BsiWipe::BSI_GSE_PASSES[pass_index].0.clone(),
+            Algorithm::VSITR => BsiWipe::VSITR_PASSES[pass_index].0.clone(),
+            Algorithm::HMGIS5Baseline => HmgWipe::BASELINE_PASSES[pass_index].0.clone(),
+            Algorithm::HMGIS5Enhanced => HmgWipe::ENHANCED_PASSES[pass_index].0.clone(),
+            Algorithm::Custom { passes } => passes[pass_index].clone(),
+            _ => PatternSpec::Random,
+        }
+    }
+
+    /// The published standard this algorithm implements, as named in the
+    /// certificate
+    pub fn standard(&self) -> Option<&'static str> {
+        match self {
+            Algorithm::DoD5220 => Some("DoD 5220.22-M"),
+            Algorithm::Gutmann => Some("Gutmann (35-pass)"),
+            Algorithm::NIST80088Clear => Some("NIST SP 800-88 Rev. 1 Clear"),
+            Algorithm::NIST80088Purge => Some("NIST SP 800-88 Rev. 1 Purge"),
+            Algorithm::BSI_GS => Some("BSI IT-Grundschutz (BSI-GS)"),
+            Algorithm::BSI_GSE => Some("BSI IT-Grundschutz Extended (BSI-GSE)"),
+            Algorithm::VSITR => Some("VSITR (BSI 7-pass)"),
+            Algorithm::HMGIS5Baseline => Some("HMG Infosec Standard 5 Baseline"),
+            Algorithm::HMGIS5Enhanced => Some("HMG Infosec Standard 5 Enhanced"),
+            _ => None,
+        }
+    }
+
+    /// Reject algorithms that can't run as configured (a custom pass list
+    /// with no passes)
+    pub fn validate(&self) -> DriveResult<()> {
+        match self {
+            Algorithm::Custom { passes } => algorithms::PatternSpec::validate(passes),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -166,6 +810,111 @@ pub struct DriveInfo {
     pub temperature_celsius: Option<u32>,
 }
 
+impl DriveType {
+    /// Magnetic media, where multi-pass overwrites are meaningful
+    pub fn is_magnetic(&self) -> bool {
+        matches!(self, DriveType::HDD | DriveType::SMR | DriveType::HybridSSHD)
+    }
+
+    /// Flash or 3D XPoint media, where the controller remaps writes
+    pub fn is_flash(&self) -> bool {
+        matches!(
+            self,
+            DriveType::SSD | DriveType::NVMe | DriveType::Optane | DriveType::EMMC | DriveType::UFS | DriveType::SDCard
+        )
+    }
+
+    /// Algorithms that make sense for this media type regardless of what the
+    /// individual drive reports. See `DriveInfo::supported_algorithms` for the
+    /// capability-aware list.
+    pub fn supported_algorithms(&self) -> Vec<Algorithm> {
+        let mut algorithms = vec![
+            Algorithm::Zero,
+            Algorithm::Random,
+            Algorithm::NIST80088Clear,
+        ];
+
+        // SD cards and USB sticks have no command that reaches their spare
+        // blocks, so NIST 800-88 offers no Purge for them
+        if *self != DriveType::SDCard {
+            algorithms.push(Algorithm::NIST80088Purge);
+        }
+
+        if !self.is_flash() {
+            // Overwrite patterns only reach the platters on magnetic media;
+            // unknown/bridged devices keep them as the conservative choice
+            algorithms.extend([
+                Algorithm::DoD5220,
+                Algorithm::Gutmann,
+                Algorithm::BSI_GS,
+                Algorithm::BSI_GSE,
+                Algorithm::VSITR,
+                Algorithm::HMGIS5Baseline,
+                Algorithm::HMGIS5Enhanced,
+            ]);
+        }
+
+        // eMMC/UFS always implement SANITIZE/PURGE in the spec baseline, and
+        // SAS drives fall back to FORMAT UNIT where SANITIZE is missing
+        if matches!(self, DriveType::EMMC | DriveType::UFS | DriveType::SAS) {
+            algorithms.push(Algorithm::Sanitize);
+        }
+
+        algorithms
+    }
+}
+
+impl DriveInfo {
+    /// Algorithms this drive can actually run: the media baseline from
+    /// `DriveType::supported_algorithms` plus the hardware erase commands
+    /// its capabilities advertise. Optane ISE is reported as `crypto_erase`.
+    pub fn supported_algorithms(&self) -> Vec<Algorithm> {
+        let caps = &self.capabilities;
+        let mut algorithms = self.drive_type.supported_algorithms();
+
+        if caps.secure_erase {
+            algorithms.push(Algorithm::SecureErase);
+        }
+        if caps.crypto_erase || caps.sed_type.is_some() || matches!(self.encryption_status, EncryptionStatus::LUKS) {
+            algorithms.push(Algorithm::CryptoErase);
+        }
+        if !caps.sanitize_options.is_empty() && !algorithms.contains(&Algorithm::Sanitize) {
+            algorithms.push(Algorithm::Sanitize);
+        }
+        if caps.trim_support && self.drive_type.is_flash() {
+            algorithms.push(Algorithm::TrimOnly);
+        }
+
+        algorithms
+    }
+
+    /// Whether `algorithm` is appropriate for this drive
+    pub fn supports_algorithm(&self, algorithm: &Algorithm) -> bool {
+        self.supported_algorithms().contains(algorithm)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// A detected 1 GiB drive for tests; override other fields with struct
+    /// update syntax
+    pub(crate) fn drive(drive_type: DriveType, capabilities: DriveCapabilities) -> DriveInfo {
+        DriveInfo {
+            device_path: "/dev/test".to_string(),
+            model: "Test".to_string(),
+            serial: "TEST123".to_string(),
+            size: 1 << 30,
+            drive_type,
+            encryption_status: EncryptionStatus::None,
+            capabilities,
+            health_status: None,
+            temperature_celsius: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DriveCapabilities {
     pub secure_erase: bool,
@@ -179,6 +928,27 @@ pub struct DriveCapabilities {
     pub max_temperature: Option<u32>,
     pub is_frozen: bool,
     pub freeze_status: FreezeStatus,
+    /// Smallest addressable unit in bytes (BLKSSZGET): 4096 on 4Kn drives
+    #[serde(default = "default_block_size")]
+    pub logical_block_size: u32,
+    /// Media sector size in bytes (BLKPBSZGET): 4096 on 512e drives too
+    #[serde(default = "default_block_size")]
+    pub physical_block_size: u32,
+    /// Attached through a USB-SATA/USB-NVMe bridge, which doesn't forward
+    /// ATA or NVMe admin commands, so only overwrites reach the media
+    #[serde(default)]
+    pub usb_bridge: bool,
+    /// Whether discarded blocks read back as zeros (ATA RZAT); None when
+    /// the drive doesn't report it
+    #[serde(default)]
+    pub read_zero_after_trim: Option<bool>,
+    /// Zoned block device (host-managed or host-aware)
+    #[serde(default)]
+    pub zoned: bool,
+}
+
+fn default_block_size() -> u32 {
+    512
 }
 
 impl Default for DriveCapabilities {
@@ -195,6 +965,11 @@ impl Default for DriveCapabilities {
             max_temperature: None,
             is_frozen: false,
             freeze_status: FreezeStatus::NotFrozen,
+            logical_block_size: default_block_size(),
+            physical_block_size: default_block_size(),
+            usb_bridge: false,
+            read_zero_after_trim: None,
+            zoned: false,
         }
     }
 }
@@ -211,6 +986,8 @@ pub enum DriveType {
     HybridSSHD,       // Hybrid HDD + SSD cache
     EMMC,             // Embedded MultiMediaCard
     UFS,              // Universal Flash Storage
+    SAS,              // Serial Attached SCSI
+    SDCard,           // SD card or USB flash drive (removable, wear-leveled)
     Unknown,
 }
 
@@ -236,7 +1013,7 @@ pub enum SEDType {
     None,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SanitizeOption {
     BlockErase,
     CryptoErase,
@@ -345,3 +1122,64 @@ impl Default for SafetyConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::drive;
+
+    #[test]
+    fn test_supported_algorithms_by_media() {
+        let hdd = drive(DriveType::HDD, DriveCapabilities { secure_erase: true, ..Default::default() });
+        assert!(hdd.supports_algorithm(&Algorithm::Gutmann));
+        assert!(hdd.supports_algorithm(&Algorithm::DoD5220));
+        assert!(hdd.supports_algorithm(&Algorithm::SecureErase));
+        assert!(!hdd.supports_algorithm(&Algorithm::Sanitize));
+
+        let nvme = drive(DriveType::NVMe, DriveCapabilities {
+            sanitize_options: vec![SanitizeOption::BlockErase],
+            trim_support: true,
+            ..Default::default()
+        });
+        assert!(nvme.supports_algorithm(&Algorithm::Sanitize));
+        assert!(nvme.supports_algorithm(&Algorithm::TrimOnly));
+        assert!(!nvme.supports_algorithm(&Algorithm::Gutmann));
+
+        let optane = drive(DriveType::Optane, DriveCapabilities { crypto_erase: true, ..Default::default() });
+        assert!(optane.supports_algorithm(&Algorithm::CryptoErase));
+        assert!(!optane.supports_algorithm(&Algorithm::DoD5220));
+
+        let emmc = drive(DriveType::EMMC, DriveCapabilities::default());
+        assert_eq!(emmc.supported_algorithms().iter().filter(|a| **a == Algorithm::Sanitize).count(), 1);
+
+        let sd = drive(DriveType::SDCard, DriveCapabilities { trim_support: true, ..Default::default() });
+        assert!(sd.supports_algorithm(&Algorithm::NIST80088Clear));
+        assert!(!sd.supports_algorithm(&Algorithm::NIST80088Purge));
+        assert!(!sd.supports_algorithm(&Algorithm::Sanitize));
+    }
+
+    #[test]
+    fn test_serial_allowlist_and_denylist() {
+        let mut sdb = drive(DriveType::SSD, DriveCapabilities::default());
+        sdb.serial = " S4EWNX0R123456 ".to_string();
+        assert!(WipeConfig::default().check_serial(&sdb).is_ok());
+
+        let allow = |serials: &[&str]| WipeConfig {
+            serial_allowlist: Some(serials.iter().map(|s| s.to_string()).collect()),
+            ..Default::default()
+        };
+        assert!(allow(&["s4ewnx0r123456"]).check_serial(&sdb).is_ok());
+        assert!(matches!(allow(&["OTHER"]).check_serial(&sdb), Err(DriveError::SerialNotPermitted(_))));
+        assert!(allow(&[]).check_serial(&sdb).is_err());
+
+        // An allowlist can't vouch for a drive whose serial is unreadable
+        let mut unknown = sdb.clone();
+        unknown.serial = "Unknown".to_string();
+        assert!(allow(&["Unknown"]).check_serial(&unknown).is_err());
+
+        // The denylist wins over the allowlist
+        let config = WipeConfig { serial_denylist: vec!["S4EWNX0R123456".to_string()], ..allow(&["S4EWNX0R123456"]) };
+        let refused = config.check_serial(&sdb).unwrap_err();
+        assert!(refused.to_string().contains("denylist"), "{}", refused);
+    }
+}
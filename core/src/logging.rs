@@ -0,0 +1,64 @@
+// Audit Log - timestamped, levelled record of what a wipe did
+//
+// Console output stays on println!. Detection, passes, device commands,
+// errors and recovery actions are also emitted as tracing events, which are
+// appended to `WipeConfig::log_path` while that wipe runs.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::Dispatch;
+use tracing::level_filters::LevelFilter;
+
+/// Build a dispatcher that appends every event at DEBUG and above to `path`,
+/// one timestamped line each, creating the file if needed
+pub fn file_dispatch(path: impl AsRef<Path>) -> Result<Dispatch> {
+    let path = path.as_ref();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file {}", path.display()))?;
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_thread_names(true)
+        .with_max_level(LevelFilter::DEBUG)
+        .finish();
+
+    Ok(Dispatch::new(subscriber))
+}
+
+/// Run `f` with events going to `log`, or to the caller's subscriber when None
+pub fn scoped<T>(log: Option<&Dispatch>, f: impl FnOnce() -> T) -> T {
+    match log {
+        Some(dispatch) => tracing::dispatcher::with_default(dispatch, f),
+        None => f(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_dispatch_appends_levelled_lines() {
+        let log = tempfile::NamedTempFile::new().unwrap();
+        let dispatch = file_dispatch(log.path()).unwrap();
+
+        scoped(Some(&dispatch), || {
+            tracing::info!(device = "/dev/sdz", pass = 1, "Pass started");
+            tracing::error!("Wipe failed");
+            tracing::trace!("too chatty for the audit log");
+        });
+        scoped(None, || tracing::info!("not captured"));
+
+        let contents = std::fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("INFO") && lines[0].contains("Pass started") && lines[0].contains("device=\"/dev/sdz\""));
+        assert!(lines[1].contains("ERROR") && lines[1].contains("Wipe failed"));
+    }
+}
@@ -2,18 +2,24 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sayonara_wipe::*;
 use sayonara_wipe::drives::{
+    Capabilities,
     DriveDetector,
+    EnumerateOptions,
     FreezeMitigation,
     HPADCOManager,
     SEDManager,
+    crypto_erase_sed,
     TrimOperations,
     SMARTMonitor,
     HDDWipe,
     SSDWipe,
     NVMeWipe,
 };
-use sayonara_wipe::algorithms::{dod::DoDWipe, gutmann::GutmannWipe, random::RandomWipe};
-use sayonara_wipe::verification::recovery_test::RecoveryTest;
+use sayonara_wipe::drives::operations::reprovision::{reprovision, Filesystem};
+use sayonara_wipe::algorithms::{dod::DoDWipe, gutmann::GutmannWipe, random::RandomWipe, PatternSpec};
+use sayonara_wipe::verification::recovery_test::{
+    RecoveryTest, ExpectedPattern, VerificationConfig, VerificationLevel as RecoveryVerificationLevel,
+};
 use sayonara_wipe::verification::{
     EnhancedVerification,
     VerificationLevel,
@@ -21,8 +27,12 @@ use sayonara_wipe::verification::{
     PostWipeAnalysis,
     PreWipeTestResults,
     LiveUSBVerification,
+    hash_device,
+    HashScheme,
 };
-use sayonara_wipe::crypto::certificates::{CertificateGenerator, WipeDetails, VerificationResult};
+use sayonara_wipe::crypto::certificates::{CertificateGenerator, WipeCertificate, WipeDetails, VerificationResult};
+use sayonara_wipe::crypto::CertificateStore;
+use sayonara_wipe::free_space::{FreeSpaceOptions, FreeSpaceWipe};
 use std::time::{Duration, Instant};
 use std::io::{self, Write};
 use uuid::Uuid;
@@ -42,6 +52,26 @@ struct Cli {
     /// Disable safety checks (DANGEROUS!)
     #[arg(long, global = true)]
     unsafe_mode: bool,
+
+    /// Append a timestamped audit log of each wipe to this file
+    #[arg(long, global = true)]
+    log_file: Option<String>,
+
+    /// Send wipe start, completion and failure events to journald/syslog
+    #[arg(long, global = true)]
+    syslog: bool,
+
+    /// Append each wipe certificate to this hash-chained JSON-lines audit log
+    #[arg(long, global = true)]
+    cert_log: Option<String>,
+
+    /// Only wipe drives with these serials (comma-separated); others are refused
+    #[arg(long, global = true, value_delimiter = ',')]
+    serial_allowlist: Option<Vec<String>>,
+
+    /// Never wipe drives with these serials (comma-separated), e.g. the boot drive
+    #[arg(long, global = true, value_delimiter = ',')]
+    serial_denylist: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -55,6 +85,10 @@ enum Commands {
         /// Include system drives
         #[arg(long)]
         include_system: bool,
+
+        /// Include removable media such as USB sticks
+        #[arg(long)]
+        include_removable: bool,
     },
 
     /// Wipe a specific drive
@@ -62,7 +96,9 @@ enum Commands {
         /// Device path (e.g., /dev/sda)
         device: String,
 
-        /// Wiping algorithm (dod, gutmann, random, zero, secure, crypto, sanitize, trim, auto)
+        /// Wiping algorithm (dod, gutmann, random, zero, secure, crypto, sanitize, trim, nist-clear, nist-purge,
+        /// bsi-gs, bsi-gse, vsitr, hmg-baseline, hmg-enhanced, auto,
+        /// or custom:<pass>,<pass>,... with hex bytes/sequences or "random", e.g. custom:0x00,0xff,random)
         #[arg(short, long, default_value = "auto")]
         algorithm: String,
 
@@ -90,11 +126,35 @@ enum Commands {
         #[arg(long, default_value = "65")]
         max_temp: u32,
 
-        /// Skip freeze mitigation
+        /// Suspend and resume the host (rtcwake) to unfreeze a security-frozen drive for hardware erase
+        #[arg(long)]
+        unfreeze: bool,
+
+        /// Write with O_DIRECT, bypassing the page cache (falls back to buffered I/O when unsupported)
         #[arg(long)]
-        no_unfreeze: bool,
+        direct_io: bool,
 
-        /// Force operation even if drive is unhealthy
+        /// Derive random passes from a seed sealed with this key (created if missing),
+        /// so they are verified byte for byte
+        #[arg(long)]
+        seed_key: Option<String>,
+
+        /// Fail (with progress checkpointed) if the wipe, or any hardware command,
+        /// is still running after this many seconds
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Hash the device after the wipe and record the digest in the certificate
+        /// (full, sample or sample:<ranges>)
+        #[arg(long)]
+        final_hash: Option<HashScheme>,
+
+        /// Once the wipe is verified, create a GPT with one partition formatted
+        /// as ext4 or exfat, ready for reuse
+        #[arg(long)]
+        reprovision: Option<Filesystem>,
+
+        /// Force operation even if drive is unhealthy or a RAID member
         #[arg(long)]
         force: bool,
     },
@@ -125,11 +185,28 @@ enum Commands {
         #[arg(long)]
         no_trim: bool,
 
-        /// Force operation even if drives are unhealthy
+        /// Force operation even if drives are unhealthy or RAID members
         #[arg(long)]
         force: bool,
     },
 
+    /// Overwrite the free space of a mounted filesystem, erasing the
+    /// contents of deleted files while leaving live files alone
+    WipeFreeSpace {
+        /// A directory on the filesystem (e.g., / or /home)
+        path: String,
+
+        /// Overwrite algorithm (dod, gutmann, random, zero, nist-clear, bsi-gs, bsi-gse, vsitr,
+        /// hmg-baseline, hmg-enhanced, or custom:<pass>,<pass>,...)
+        #[arg(short, long, default_value = "random")]
+        algorithm: String,
+
+        /// Also take over freed inodes and directory entries, where deleted
+        /// names and small files can linger
+        #[arg(long)]
+        clear_entries: bool,
+    },
+
     /// Verify a previous wipe
     Verify {
         /// Device path to verify
@@ -138,6 +215,80 @@ enum Commands {
         /// Check for hidden areas
         #[arg(long)]
         check_hidden: bool,
+
+        /// Read back the entire drive instead of sampling sectors
+        #[arg(long)]
+        full: bool,
+
+        /// Number of random sectors checked when sampling
+        #[arg(long, default_value = "1000")]
+        samples: usize,
+
+        /// Minimum entropy (bits/byte) for a random wipe to pass
+        #[arg(long, default_value = "7.5")]
+        min_entropy: f64,
+
+        /// Pattern the final pass left on the drive (zero, random, erased, or a hex byte such as 0xFF)
+        #[arg(long, default_value = "random")]
+        expect: String,
+
+        /// Write a certificate recording the verification
+        #[arg(short, long)]
+        cert_output: Option<String>,
+
+        /// Sign the certificate with this Ed25519 key
+        #[arg(long, requires = "cert_output")]
+        signing_key: Option<String>,
+    },
+
+    /// Detect everything about a drive without wiping it
+    Inspect {
+        /// Device path (e.g., /dev/sda)
+        device: String,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the progress of a running or interrupted wipe
+    Status {
+        /// Device path (e.g., /dev/sda)
+        device: String,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check that a certificate audit log is intact: every entry in
+    /// order and chained to the one before it
+    VerifyCertLog {
+        /// Log file written with --cert-log
+        path: String,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Hash a device's contents, or check it still matches the final-state
+    /// hash recorded in a wipe certificate
+    HashDevice {
+        /// Device path (e.g., /dev/sda) or image file
+        device: String,
+
+        /// Bytes to hash: full, sample or sample:<ranges>
+        #[arg(long, default_value = "sample")]
+        scheme: HashScheme,
+
+        /// Compare against the hash in this certificate, using its scheme
+        #[arg(long)]
+        certificate: Option<String>,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Check drive health and capabilities
@@ -168,7 +319,9 @@ enum Commands {
         /// Device path (e.g., /dev/sda)
         device: String,
 
-        /// Wiping algorithm (dod, gutmann, random, zero, secure, crypto, sanitize, trim, auto)
+        /// Wiping algorithm (dod, gutmann, random, zero, secure, crypto, sanitize, trim, nist-clear, nist-purge,
+        /// bsi-gs, bsi-gse, vsitr, hmg-baseline, hmg-enhanced, auto,
+        /// or custom:<pass>,<pass>,... with hex bytes/sequences or "random", e.g. custom:0x00,0xff,random)
         #[arg(short, long, default_value = "auto")]
         algorithm: String,
 
@@ -200,7 +353,7 @@ enum Commands {
         #[arg(long)]
         no_trim: bool,
 
-        /// Force operation even if drive is unhealthy
+        /// Force operation even if drive is unhealthy or a RAID member
         #[arg(long)]
         force: bool,
     },
@@ -260,11 +413,33 @@ fn generate_enhanced_certificate(
     let cert_gen = CertificateGenerator::new();
 
     // Create enhanced wipe details
+    let completed_at = chrono::Utc::now();
     let wipe_details = WipeDetails {
         algorithm_used: format!("{:?}", config.algorithm),
-        passes_completed: 1,
+        standard: config.algorithm.standard().map(str::to_string),
+        passes_completed: config.algorithm.overwrite_passes() as u32,
         duration_seconds: duration.as_secs(),
         operator_id: None,
+        method: format!("{:?}", config.algorithm),
+        started_at: completed_at - chrono::Duration::seconds(duration.as_secs() as i64),
+        completed_at,
+        notes: config.notes.clone(),
+        completion: Default::default(),
+        hidden_areas: None,
+        trim_verification: None,
+        health: None,
+        entropy_source: random_entropy_source(&config.algorithm),
+        device_logs: Vec::new(),
+        passes: Vec::new(),
+        random_seed: None,
+        nvme_namespaces: Vec::new(),
+        retries: Default::default(),
+        signatures: None,
+        partition_tables_cleared: Vec::new(),
+        final_hash: None,
+        reprovisioned: None,
+        capabilities: Some(Capabilities::of(drive_info)),
+        advertised_erase_time: None,
     };
 
     // Create enhanced verification result
@@ -273,6 +448,7 @@ fn generate_enhanced_certificate(
         entropy_score: verification_report.post_wipe_analysis.entropy_score,
         recovery_test_passed: verification_report.confidence_level >= 99.0,
         verification_timestamp: verification_report.timestamp,
+        randomness: None,
     };
 
     let certificate = cert_gen.generate_certificate(
@@ -292,6 +468,20 @@ fn generate_enhanced_certificate(
     Ok(())
 }
 
+/// Entropy source to record for algorithms that write random passes
+fn random_entropy_source(algorithm: &Algorithm) -> Option<String> {
+    match algorithm {
+        Algorithm::Random | Algorithm::DoD5220 | Algorithm::Gutmann | Algorithm::BSI_GS | Algorithm::BSI_GSE
+        | Algorithm::HMGIS5Enhanced => {
+            Some(sayonara_wipe::crypto::secure_rng_source())
+        }
+        Algorithm::Custom { passes } if passes.contains(&PatternSpec::Random) => {
+            Some(sayonara_wipe::crypto::secure_rng_source())
+        }
+        _ => None,
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -311,27 +501,130 @@ async fn main() -> Result<()> {
     }
 
     match &cli.command {
-        Commands::List { detailed, include_system } => {
-            list_drives(*detailed, *include_system).await?;
+        Commands::List { detailed, include_system, include_removable } => {
+            let options = EnumerateOptions {
+                include_system: *include_system,
+                include_removable: *include_removable,
+                ..Default::default()
+            };
+            list_drives(*detailed, &options).await?;
         }
         Commands::Wipe { device, algorithm, no_verify, cert_output, hpa_dco,
-            no_trim, no_temp_check, max_temp, no_unfreeze, force } => {
-            let config = build_wipe_config(
-                algorithm, !no_verify, hpa_dco, !no_trim,
-                !no_temp_check, *max_temp, !no_unfreeze
-            )?;
+            no_trim, no_temp_check, max_temp, unfreeze, direct_io, seed_key, timeout, final_hash, reprovision, force } => {
+            let config = WipeConfig {
+                force: *force,
+                final_hash: *final_hash,
+                reprovision: *reprovision,
+                operation_timeout: timeout.map(Duration::from_secs),
+                write_mode: if *direct_io { WriteMode::Direct } else { WriteMode::Buffered },
+                pattern_seed_key_path: seed_key.clone(),
+                log_path: cli.log_file.clone(),
+                syslog: cli.syslog,
+                certificate_log_path: cli.cert_log.clone(),
+                serial_allowlist: cli.serial_allowlist.clone(),
+                serial_denylist: cli.serial_denylist.clone(),
+                ..build_wipe_config(
+                    algorithm, !no_verify, hpa_dco, !no_trim,
+                    !no_temp_check, *max_temp, *unfreeze
+                )?
+            };
             wipe_drive(device, config, cert_output.as_deref(), *force, cli.unsafe_mode).await?;
         }
         Commands::WipeAll { algorithm, no_verify, cert_dir, exclude,
             hpa_dco, no_trim, force} => {
-            let config = build_wipe_config(
-                algorithm, !no_verify, hpa_dco, !no_trim,
-                true, 65, true
-            )?;
+            let config = WipeConfig {
+                force: *force,
+                log_path: cli.log_file.clone(),
+                syslog: cli.syslog,
+                certificate_log_path: cli.cert_log.clone(),
+                serial_allowlist: cli.serial_allowlist.clone(),
+                serial_denylist: cli.serial_denylist.clone(),
+                ..build_wipe_config(
+                    algorithm, !no_verify, hpa_dco, !no_trim,
+                    true, 65, false
+                )?
+            };
             wipe_all_drives(config, cert_dir, exclude.as_deref(), cli.unsafe_mode, *force).await?;
         }
-        Commands::Verify { device, check_hidden } => {
-            verify_drive(device, *check_hidden).await?;
+        Commands::WipeFreeSpace { path, algorithm, clear_entries } => {
+            let config = build_wipe_config(algorithm, false, "ignore", false, false, 65, false)?;
+            let options = FreeSpaceOptions { clear_entries: *clear_entries, ..Default::default() };
+            FreeSpaceWipe::new(path, config.algorithm, options)?.run(&CancellationToken::new())?;
+        }
+        Commands::Verify { device, check_hidden, full, samples, min_entropy, expect, cert_output, signing_key } => {
+            let verification = VerificationConfig {
+                level: if *full { RecoveryVerificationLevel::Full } else { RecoveryVerificationLevel::Sample },
+                min_entropy: *min_entropy,
+                sector_sample_count: *samples,
+                ..Default::default()
+            };
+            let expected = match expect.to_lowercase().as_str() {
+                "zero" | "zeros" => ExpectedPattern::Zeros,
+                "random" => ExpectedPattern::Random,
+                "erased" => ExpectedPattern::Erased,
+                byte => match u8::from_str_radix(byte.trim_start_matches("0x"), 16) {
+                    Ok(value) => ExpectedPattern::Fixed(value),
+                    Err(_) => return Err(anyhow::anyhow!(
+                        "Invalid --expect value '{}': use zero, random, erased or a hex byte such as 0xFF",
+                        expect
+                    )),
+                },
+            };
+            verify_drive(device, *check_hidden, &verification, expected, cert_output.as_deref(), signing_key.as_deref()).await?;
+        }
+        Commands::Inspect { device, json } => {
+            let drive = WipeOrchestrator::inspect(device)?;
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&drive)?);
+            } else {
+                print_drive_detailed(&drive)?;
+            }
+        }
+        Commands::Status { device, json } => {
+            match WipeOrchestrator::status(device)? {
+                Some(record) if *json => println!("{}", serde_json::to_string_pretty(&record)?),
+                Some(record) => println!("{}", record),
+                None => println!("No wipe progress recorded for {}", device),
+            }
+        }
+        Commands::VerifyCertLog { path, json } => {
+            let verification = CertificateStore::new(path, true).verify()?;
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&verification)?);
+            } else if verification.intact() {
+                println!("✅ {}: {}", path, verification);
+            } else {
+                println!("❌ {}: {}", path, verification);
+            }
+            if !verification.intact() {
+                std::process::exit(1);
+            }
+        }
+        Commands::HashDevice { device, scheme, certificate, json } => {
+            let recorded = match certificate {
+                Some(path) => {
+                    let certificate: WipeCertificate = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+                    let hash = certificate.wipe_details.final_hash
+                        .ok_or_else(|| anyhow::anyhow!("{} records no final-state hash", path))?;
+                    Some(hash)
+                }
+                None => None,
+            };
+            let hash = hash_device(device, recorded.as_ref().map_or(scheme, |hash| &hash.scheme))?;
+            let matches = recorded.as_ref().is_none_or(|recorded| recorded.size == hash.size && recorded.digest == hash.digest);
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&hash)?);
+            } else {
+                println!("{}", hash);
+                match &recorded {
+                    Some(_) if matches => println!("✅ {} matches its certificate: unchanged since the wipe", device),
+                    Some(recorded) => println!("❌ {} does not match its certificate (recorded {})", device, recorded),
+                    None => {}
+                }
+            }
+            if !matches {
+                std::process::exit(1);
+            }
         }
         Commands::Health { device, self_test, monitor } => {
             check_health(device, *self_test, *monitor).await?;
@@ -392,15 +685,20 @@ async fn main() -> Result<()> {
             };
 
             // Build config
-            let config = build_wipe_config(
-                algorithm,
-                true,  // Always verify in enhanced mode
-                hpa_dco,
-                !no_trim,
-                true,  // Temperature monitoring
-                65,
-                true,  // Freeze mitigation
-            )?;
+            let config = WipeConfig {
+                force: *force,
+                log_path: cli.log_file.clone(),
+                syslog: cli.syslog,
+                ..build_wipe_config(
+                    algorithm,
+                    true,  // Always verify in enhanced mode
+                    hpa_dco,
+                    !no_trim,
+                    true,  // Temperature monitoring
+                    65,
+                    false, // No suspend/resume to unfreeze
+                )?
+            };
 
             // Safety confirmation with level info
             if !cli.unsafe_mode {
@@ -589,7 +887,7 @@ async fn enhanced_wipe_with_verification(
 
     // Execute the wipe
     println!("  └─ Executing wipe algorithm...");
-    select_and_execute_wipe(device, drive_info, &config).await?;
+    select_and_execute_wipe(device, drive_info, &config, &CancellationToken::new()).await?;
 
     let wipe_duration = start_time.elapsed();
     println!("✅ Wipe completed in {:.2} seconds", wipe_duration.as_secs_f64());
@@ -843,6 +1141,13 @@ fn print_customizations() -> Result<()> {
     println!("  crypto     - Cryptographic erase (for Self-Encrypting Drives)");
     println!("  sanitize   - NVMe sanitize command");
     println!("  trim       - TRIM/discard only (for SSDs)");
+    println!("  nist-clear - NIST SP 800-88 Clear (overwrite HDDs, block erase flash)");
+    println!("  nist-purge - NIST SP 800-88 Purge (hardware sanitize, overwrite fallback)");
+    println!("  bsi-gs     - BSI IT-Grundschutz (random, then verified 0x00)");
+    println!("  bsi-gse    - BSI-GS extended (2x random, then verified 0x00)");
+    println!("  vsitr      - German VSITR (7-pass: 0x00/0xFF alternating, then 0xAA)");
+    println!("  hmg-baseline - UK HMG IS5 Baseline (verified single pass of zeros)");
+    println!("  hmg-enhanced - UK HMG IS5 Enhanced (0x00, 0xFF, then verified random)");
     println!("  auto       - Automatically select best algorithm (default)");
 
     // HPA/DCO HANDLING
@@ -875,8 +1180,8 @@ fn print_customizations() -> Result<()> {
     println!("    --no-trim          - Skip TRIM operation after wipe");
     println!("    --no-temp-check    - Disable temperature monitoring");
     println!("    --max-temp         - Maximum safe temperature in Celsius (default: 65)");
-    println!("    --no-unfreeze      - Skip drive freeze mitigation");
-    println!("    --force            - Force operation on unhealthy drives");
+    println!("    --unfreeze         - Suspend/resume the host to unfreeze a frozen drive");
+    println!("    --force            - Force operation on unhealthy drives and RAID members");
 
     println!("\n  WIPE-ALL Command:");
     println!("    -a, --algorithm     - Select wiping algorithm");
@@ -885,7 +1190,7 @@ fn print_customizations() -> Result<()> {
     println!("    --exclude          - Comma-separated list of drives to exclude");
     println!("    --hpa-dco          - HPA/DCO handling mode");
     println!("    --no-trim          - Skip TRIM operations");
-    println!("    --force            - Force operation on unhealthy drives");
+    println!("    --force            - Force operation on unhealthy drives and RAID members");
 
     println!("\n  VERIFY Command:");
     println!("    --check-hidden     - Check for hidden areas (HPA/DCO)");
@@ -909,7 +1214,7 @@ fn print_customizations() -> Result<()> {
     println!("    --min-confidence      - Required confidence level (90-100, default: 95.0)");
     println!("    --hpa-dco            - HPA/DCO handling mode");
     println!("    --no-trim            - Skip TRIM operation");
-    println!("    --force              - Force operation on unhealthy drives");
+    println!("    --force              - Force operation on unhealthy drives and RAID members");
 
     println!("\n  LIVE-VERIFY Command:");
     println!("    --report-to          - Remote endpoint for verification report");
@@ -1037,10 +1342,40 @@ fn print_customizations() -> Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
 fn is_root() -> bool {
     unsafe { libc::geteuid() == 0 }
 }
 
+/// Raw disk access needs an elevated prompt; without one, opening
+/// \\.\PhysicalDriveN fails with access denied, so there's nothing to check
+#[cfg(windows)]
+fn is_root() -> bool {
+    true
+}
+
+/// Default wipe confirmation: the operator must retype the drive's serial
+/// (its model, or failing that its path, when the serial is unknown), so a
+/// wrong device letter can't be waved through with a reflexive "YES"
+fn confirm_device_identity(drive: &DriveInfo) -> bool {
+    let known = |value: &str| !value.trim().is_empty() && value.trim() != "Unknown";
+    let (label, expected) = if known(&drive.serial) {
+        ("serial number", drive.serial.trim())
+    } else if known(&drive.model) {
+        ("model", drive.model.trim())
+    } else {
+        ("device path", drive.device_path.as_str())
+    };
+
+    print!("\nType the drive {} ({}) to confirm: ", label, expected);
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).is_ok() && input.trim() == expected
+}
+
 fn build_wipe_config(
     algorithm: &str,
     verify: bool,
@@ -1059,8 +1394,23 @@ fn build_wipe_config(
         "crypto" => Algorithm::CryptoErase,
         "sanitize" => Algorithm::Sanitize,
         "trim" => Algorithm::TrimOnly,
+        "nist-clear" => Algorithm::NIST80088Clear,
+        "nist-purge" => Algorithm::NIST80088Purge,
+        "bsi-gs" => Algorithm::BSI_GS,
+        "bsi-gse" => Algorithm::BSI_GSE,
+        "vsitr" => Algorithm::VSITR,
+        "hmg-baseline" => Algorithm::HMGIS5Baseline,
+        "hmg-enhanced" => Algorithm::HMGIS5Enhanced,
         "auto" => Algorithm::SecureErase, // Will fallback based on capabilities
-        _ => return Err(anyhow::anyhow!("Unknown algorithm: {}", algorithm)),
+        other => match other.strip_prefix("custom:") {
+            // e.g. custom:0x00,0xff,random
+            Some(list) => {
+                let algorithm = Algorithm::Custom { passes: PatternSpec::parse_list(list)? };
+                algorithm.validate()?;
+                algorithm
+            }
+            None => return Err(anyhow::anyhow!("Unknown algorithm: {}", algorithm)),
+        },
     };
 
     let hpa_dco_handling = match hpa_dco {
@@ -1083,44 +1433,30 @@ fn build_wipe_config(
         max_temperature_celsius: Some(max_temp),
         freeze_mitigation,
         sed_crypto_erase: true,
+        ..WipeConfig::default()
     })
 }
 
-async fn list_drives(detailed: bool, include_system: bool) -> Result<()> {
+async fn list_drives(detailed: bool, options: &EnumerateOptions) -> Result<()> {
     println!("Detecting drives...");
-    let drives = DriveDetector::detect_all_drives()?;
+    let drives = DriveDetector::enumerate_drives(options)?;
 
     if drives.is_empty() {
         println!("No drives detected.");
-        return Ok(());
-    }
-
-    println!("\nDetected drives:");
-
-    let mut filtered_count = 0;
-    let mut displayed_count = 0;
-
-    if detailed {
+    } else if detailed {
+        println!("\nDetected drives:");
         for drive in drives {
-            if !include_system && DriveDetector::is_system_drive(&drive.device_path)? {
-                filtered_count += 1;
-                continue;
-            }
-
+            // The full read-only inspection adds SMART health and HPA/DCO
+            let drive = WipeOrchestrator::inspect(&drive.device_path).unwrap_or(drive);
             print_drive_detailed(&drive)?;
-            displayed_count += 1;
         }
     } else {
+        println!("\nDetected drives:");
         println!("{:<15} {:<20} {:<15} {:<10} {:<10} {:<10}",
                  "Device", "Model", "Serial", "Size", "Type", "Health");
         println!("{}", "-".repeat(90));
 
         for drive in drives {
-            if !include_system && DriveDetector::is_system_drive(&drive.device_path)? {
-                filtered_count += 1;
-                continue;
-            }
-
             let size_gb = drive.size / (1024 * 1024 * 1024);
             let health = drive.health_status
                 .map(|h| format!("{:?}", h))
@@ -1133,17 +1469,14 @@ async fn list_drives(detailed: bool, include_system: bool) -> Result<()> {
                      format!("{}GB", size_gb),
                      format!("{:?}", drive.drive_type),
                      health);
-            displayed_count += 1;
         }
     }
 
-    if filtered_count > 0 {
-        println!("\nℹ️  {} system drive(s) hidden for safety.", filtered_count);
-        println!("   Use --include-system to show all drives.");
+    if !options.include_system {
+        println!("\nℹ️  System drives are hidden for safety. Use --include-system to show them.");
     }
-
-    if displayed_count == 0 && filtered_count > 0 {
-        println!("\n⚠️  No non-system drives found.");
+    if !options.include_removable {
+        println!("ℹ️  Removable media are hidden. Use --include-removable to show them.");
     }
 
     Ok(())
@@ -1166,30 +1499,17 @@ fn print_drive_detailed(drive: &DriveInfo) -> Result<()> {
     }
 
     println!("\nCapabilities:");
-    let caps = &drive.capabilities;
-    println!("  Secure Erase: {}", caps.secure_erase);
-    println!("  Enhanced Erase: {}", caps.enhanced_erase);
-    println!("  Crypto Erase: {}", caps.crypto_erase);
-    println!("  TRIM Support: {}", caps.trim_support);
-    println!("  Freeze Status: {:?}", caps.freeze_status);
-
-    if caps.hpa_enabled {
-        println!("  ⚠ HPA Enabled (hidden area present)");
-    }
-    if caps.dco_enabled {
-        println!("  ⚠ DCO Enabled (device configuration overlay)");
-    }
-
-    if let Some(sed_type) = &caps.sed_type {
-        println!("  SED Type: {:?}", sed_type);
-    }
-
-    if !caps.sanitize_options.is_empty() {
-        println!("  NVMe Sanitize: {:?}", caps.sanitize_options);
-    }
+    println!("{}", Capabilities::of(drive).render());
+    println!("  Freeze Status: {:?}", drive.capabilities.freeze_status);
 
     println!("Encryption: {:?}", drive.encryption_status);
 
+    let algorithms: Vec<String> = drive.supported_algorithms()
+        .iter()
+        .map(|a| format!("{:?}", a))
+        .collect();
+    println!("Supported Algorithms: {}", algorithms.join(", "));
+
     Ok(())
 }
 
@@ -1206,6 +1526,9 @@ async fn wipe_drive(
         .find(|d| d.device_path == device)
         .ok_or_else(|| anyhow::anyhow!("Drive not found: {}", device))?;
 
+    // Serial lists hold even in unsafe mode
+    config.check_serial(&drive_info)?;
+
     // Safety checks
     if !unsafe_mode {
         if DriveDetector::is_system_drive(device)? {
@@ -1254,13 +1577,15 @@ async fn wipe_drive(
             }
         }
 
-        print!("\nType 'YES' to confirm: ");
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        match WipeOrchestrator::new(device.to_string(), config.clone()) {
+            Ok(mut orchestrator) => match orchestrator.estimate().await {
+                Ok(estimate) => println!("Estimated time: {} ({})", estimate.duration, estimate.method),
+                Err(e) => println!("Estimated time: unavailable ({})", e),
+            },
+            Err(e) => println!("Estimated time: unavailable ({})", e),
+        }
 
-        if input.trim() != "YES" {
+        if !confirm_device_identity(&drive_info) {
             println!("Operation cancelled.");
             return Ok(());
         }
@@ -1383,13 +1708,17 @@ async fn wipe_single_drive(
     // Phase 2: Wipe
     println!("\nPhase 2: Wiping");
 
-    let wipe_result = match select_and_execute_wipe(device, drive_info, &config).await {
-        Ok(_) => Ok(()),
+    // The certificate below is the one logged; orchestrator-routed wipes
+    // would otherwise append their own as well
+    let wipe_config = WipeConfig { certificate_log_path: None, ..config.clone() };
+    let cancel = CancellationToken::new();
+    let wipe_result = match select_and_execute_wipe(device, drive_info, &wipe_config, &cancel).await {
+        Ok(executed) => Ok(executed),
         Err(e) => {
             warnings.push(format!("Wipe error: {}", e));
 
-            // Check if this was a user interrupt
-            if e.to_string().contains("interrupted") || e.to_string().contains("Interrupted") {
+            // Check if this was a user interrupt (Ctrl+C cancels every token)
+            if cancel.is_cancelled() {
                 eprintln!("\n❌ Wipe operation cancelled by user");
                 return Err(e);
             }
@@ -1399,10 +1728,13 @@ async fn wipe_single_drive(
     };
 
     // If wipe failed (not interrupted), continue to cleanup but skip verification
-    if wipe_result.is_err() {
-        eprintln!("\n⚠️  Wipe failed, skipping post-wipe operations");
-        return wipe_result;
-    }
+    let executed = match &wipe_result {
+        Ok(executed) => executed,
+        Err(_) => {
+            eprintln!("\n⚠️  Wipe failed, skipping post-wipe operations");
+            return wipe_result.map(|_| ());
+        }
+    };
 
     // Phase 3: Post-wipe operations
     println!("\nPhase 3: Post-wipe operations");
@@ -1429,14 +1761,24 @@ async fn wipe_single_drive(
     // Phase 4: Verification
     let verification_result = if config.verify {
         println!("\nPhase 4: Verification");
-        let verified = RecoveryTest::verify_wipe(device, drive_info.size)?;
-        let entropy_score = 7.8; // This would come from the actual verification
+        let outcome = RecoveryTest::verify_wipe_with_config(
+            device,
+            drive_info.size,
+            &config.verification,
+            ExpectedPattern::for_algorithm(&config.algorithm),
+        )?;
+        if let Some(offset) = outcome.first_failure {
+            println!("✗ Verification failed at byte offset {}", offset);
+        }
+        let verified = outcome.passed;
+        let entropy_score = outcome.entropy_score;
 
         VerificationResult {
             verified,
             entropy_score,
             recovery_test_passed: verified,
             verification_timestamp: chrono::Utc::now(),
+            randomness: outcome.randomness,
         }
     } else {
         VerificationResult {
@@ -1444,23 +1786,93 @@ async fn wipe_single_drive(
             entropy_score: 0.0,
             recovery_test_passed: false,
             verification_timestamp: chrono::Utc::now(),
+            randomness: None,
+        }
+    };
+
+    // A fresh partition table and filesystem, only on a verified drive
+    let reprovisioned = match config.reprovision {
+        Some(filesystem) if !config.dry_run && verification_result.verified => {
+            println!("\nCreating a GPT with one {} partition...", filesystem);
+            match reprovision(device, filesystem) {
+                Ok(reprovisioned) => {
+                    println!("✓ {}", reprovisioned);
+                    Some(reprovisioned)
+                }
+                Err(e) => {
+                    warnings.push(format!("Reprovisioning failed: {}", e));
+                    None
+                }
+            }
+        }
+        Some(_) if !config.dry_run => {
+            warnings.push("Not reprovisioned: the wipe was not verified, so the drive is left blank".to_string());
+            None
+        }
+        _ => None,
+    };
+
+    // Hash the final state so it can be re-checked against the certificate
+    let final_hash = match config.final_hash {
+        Some(scheme) if !config.dry_run => {
+            println!("\nHashing the {}...", scheme);
+            match hash_device(device, &scheme) {
+                Ok(hash) => {
+                    println!("✓ {}", hash);
+                    Some(hash)
+                }
+                Err(e) => {
+                    warnings.push(format!("Final state hash failed: {}", e));
+                    None
+                }
+            }
         }
+        _ => None,
     };
 
     // Generate certificate
-    if let Some(cert_path) = cert_output {
+    if cert_output.is_some() || config.certificate_log_path.is_some() {
         println!("\nGenerating certificate...");
         let cert_gen = CertificateGenerator::new();
+        let completed_at = chrono::Utc::now();
         let wipe_details = WipeDetails {
-            algorithm_used: format!("{:?}", config.algorithm),
-            passes_completed: 1,
+            algorithm_used: format!("{:?}", executed.algorithm),
+            standard: executed.algorithm.standard().map(str::to_string),
+            passes_completed: executed.algorithm.overwrite_passes() as u32,
             duration_seconds: wipe_duration.as_secs(),
             operator_id: session.operator_id.clone(),
+            method: executed.method.clone(),
+            started_at: completed_at - chrono::Duration::seconds(wipe_duration.as_secs() as i64),
+            completed_at,
+            notes: config.notes.clone(),
+            completion: Default::default(),
+            hidden_areas: None,
+            trim_verification: None,
+            health: None,
+            entropy_source: random_entropy_source(&executed.algorithm),
+            device_logs: Vec::new(),
+            passes: Vec::new(),
+            random_seed: None,
+            nvme_namespaces: Vec::new(),
+            retries: Default::default(),
+            signatures: None,
+            partition_tables_cleared: Vec::new(),
+            final_hash,
+            reprovisioned,
+            capabilities: Some(Capabilities::of(drive_info)),
+            advertised_erase_time: None,
         };
 
         let certificate = cert_gen.generate_certificate(drive_info, wipe_details, verification_result.clone())?;
-        cert_gen.save_certificate(&certificate, cert_path)?;
-        println!("✓ Certificate saved to: {}", cert_path);
+        if let Some(cert_path) = cert_output {
+            cert_gen.save_certificate(&certificate, cert_path)?;
+            println!("✓ Certificate saved to: {}", cert_path);
+        }
+
+        if let Some(log_path) = &config.certificate_log_path {
+            CertificateStore::new(log_path, config.certificate_log_hash_chain).append(&certificate)?;
+            println!("✓ Certificate appended to: {}", log_path);
+        }
     }
 
     // Update session
@@ -1485,15 +1897,58 @@ async fn wipe_single_drive(
     Ok(())
 }
 
+/// The erase `select_and_execute_wipe` actually ran, which may differ from
+/// the configured algorithm after auto-selection or a fallback
+struct ExecutedWipe {
+    algorithm: Algorithm,
+    /// Method as recorded in the certificate
+    method: String,
+}
+
+impl ExecutedWipe {
+    /// A wipe the orchestrator ran, described as its report records it
+    fn from_report(config: &WipeConfig, report: &WipeReport) -> Self {
+        Self { algorithm: config.algorithm.clone(), method: report.method().to_string() }
+    }
+}
+
 async fn select_and_execute_wipe(
     device: &str,
     drive_info: &DriveInfo,
     config: &WipeConfig,
-) -> Result<()> {
+    cancel: &CancellationToken,
+) -> Result<ExecutedWipe> {
     // Check if this is an advanced drive type that needs specialized handling
     match drive_info.drive_type {
+        _ if drive_info.capabilities.usb_bridge => {
+            // The orchestrator overwrites bridged drives and notes why in the certificate
+            println!("🔌 Drive is behind a USB bridge - hardware erase commands can't reach it");
+            println!("Overwriting instead...\n");
+
+            use sayonara_wipe::WipeOrchestrator;
+            let mut orchestrator = WipeOrchestrator::new(device.to_string(), config.clone())
+                .map_err(|e| anyhow::anyhow!("Orchestrator initialization failed: {}", e))?;
+
+            let report = orchestrator.execute(cancel.clone()).await
+                .map_err(|e| anyhow::anyhow!("USB drive wipe failed: {}", e))?;
+
+            return Ok(ExecutedWipe::from_report(config, &report));
+        }
+        _ if matches!(drive_info.encryption_status, EncryptionStatus::LUKS) && config.luks_header_erase => {
+            // The orchestrator destroys the LUKS key slots before the bulk wipe
+            println!("🔐 LUKS container detected - its header and key slots are erased first");
+
+            use sayonara_wipe::WipeOrchestrator;
+            let mut orchestrator = WipeOrchestrator::new(device.to_string(), config.clone())
+                .map_err(|e| anyhow::anyhow!("Orchestrator initialization failed: {}", e))?;
+
+            let report = orchestrator.execute(cancel.clone()).await
+                .map_err(|e| anyhow::anyhow!("LUKS drive wipe failed: {}", e))?;
+
+            return Ok(ExecutedWipe::from_report(config, &report));
+        }
         DriveType::SMR | DriveType::Optane | DriveType::HybridSSHD |
-        DriveType::EMMC | DriveType::UFS => {
+        DriveType::EMMC | DriveType::UFS | DriveType::SAS | DriveType::SDCard => {
             // Use the advanced wipe orchestrator for these drive types
             println!("🔬 Detected advanced drive type: {:?}", drive_info.drive_type);
             println!("Using specialized wipe strategy...\n");
@@ -1502,10 +1957,10 @@ async fn select_and_execute_wipe(
             let mut orchestrator = WipeOrchestrator::new(device.to_string(), config.clone())
                 .map_err(|e| anyhow::anyhow!("Orchestrator initialization failed: {}", e))?;
 
-            orchestrator.execute().await
+            let report = orchestrator.execute(cancel.clone()).await
                 .map_err(|e| anyhow::anyhow!("Advanced wipe failed: {}", e))?;
 
-            return Ok(());
+            return Ok(ExecutedWipe::from_report(config, &report));
         }
         DriveType::NVMe => {
             // Check if it's an advanced NVMe (ZNS, multi-namespace, etc.)
@@ -1518,10 +1973,10 @@ async fn select_and_execute_wipe(
                 let mut orchestrator = WipeOrchestrator::new(device.to_string(), config.clone())
                     .map_err(|e| anyhow::anyhow!("Orchestrator initialization failed: {}", e))?;
 
-                orchestrator.execute().await
+                let report = orchestrator.execute(cancel.clone()).await
                     .map_err(|e| anyhow::anyhow!("Advanced NVMe wipe failed: {}", e))?;
 
-                return Ok(());
+                return Ok(ExecutedWipe::from_report(config, &report));
             }
             // Otherwise fall through to standard NVMe handling below
         }
@@ -1566,7 +2021,7 @@ async fn select_and_execute_wipe(
         Algorithm::SecureErase => {
             match drive_info.drive_type {
                 DriveType::SSD => SSDWipe::secure_erase(device)?,
-                DriveType::NVMe => NVMeWipe::secure_erase(device)?,
+                DriveType::NVMe => NVMeWipe::secure_erase(device, cancel)?,
                 DriveType::HDD => HDDWipe::secure_erase(device)?,
                 _ => {
                     println!("Hardware secure erase not available, falling back to DoD");
@@ -1575,29 +2030,38 @@ async fn select_and_execute_wipe(
             }
         }
         Algorithm::CryptoErase => {
-            if let Ok(sed_info) = SEDManager::detect_sed(device) {
-                SEDManager::crypto_erase(device, &sed_info)?;
-            } else {
-                return Err(anyhow::anyhow!("Crypto erase not available for this drive"));
+            if let Err(e) = crypto_erase_sed(device) {
+                println!("⚠️  SED crypto erase unavailable ({}) - falling back to random overwrite", e);
+                RandomWipe::wipe_drive(device, drive_info.size, drive_info.drive_type.clone(), &config)?;
+                return Ok(ExecutedWipe {
+                    algorithm: Algorithm::Random,
+                    method: format!("random overwrite (SED crypto erase unavailable: {})", e),
+                });
             }
         }
         Algorithm::Sanitize => {
             if drive_info.drive_type == DriveType::NVMe {
-                NVMeWipe::secure_erase(device)?;
+                NVMeWipe::secure_erase(device, cancel)?;
             } else {
                 return Err(anyhow::anyhow!("Sanitize only available for NVMe drives"));
             }
         }
-        Algorithm::TrimOnly => {
-            if drive_info.capabilities.trim_support {
-                TrimOperations::secure_trim_with_verify(device)?;
-            } else {
-                return Err(anyhow::anyhow!("TRIM not supported on this drive"));
-            }
+        Algorithm::TrimOnly | Algorithm::NIST80088Clear | Algorithm::NIST80088Purge
+        | Algorithm::BSI_GS | Algorithm::BSI_GSE | Algorithm::VSITR
+        | Algorithm::HMGIS5Baseline | Algorithm::HMGIS5Enhanced | Algorithm::Custom { .. } => {
+            // Discard-only, NIST routing (Clear vs Purge per media type), BSI,
+            // HMG IS5 and custom pass lists live in the orchestrator
+            use sayonara_wipe::WipeOrchestrator;
+            let mut orchestrator = WipeOrchestrator::new(device.to_string(), config.clone())
+                .map_err(|e| anyhow::anyhow!("Orchestrator initialization failed: {}", e))?;
+
+            let report = orchestrator.execute(cancel.clone()).await
+                .map_err(|e| anyhow::anyhow!("{:?} wipe failed: {}", config.algorithm, e))?;
+            return Ok(ExecutedWipe::from_report(config, &report));
         }
     }
 
-    Ok(())
+    Ok(ExecutedWipe { method: format!("{:?}", algorithm), algorithm })
 }
 
 async fn wipe_all_drives(
@@ -1625,6 +2089,10 @@ async fn wipe_all_drives(
         if excluded_drives.contains(&drive.device_path.as_str()) {
             continue;
         }
+        if let Err(e) = config.check_serial(&drive) {
+            println!("Skipping {}", e);
+            continue;
+        }
         if !unsafe_mode && DriveDetector::is_system_drive(&drive.device_path)? {
             println!("Skipping system drive: {}", drive.device_path);
             continue;
@@ -1722,7 +2190,14 @@ async fn wipe_all_drives(
     Ok(())
 }
 
-async fn verify_drive(device: &str, check_hidden: bool) -> Result<()> {
+async fn verify_drive(
+    device: &str,
+    check_hidden: bool,
+    verification: &VerificationConfig,
+    expected: ExpectedPattern,
+    cert_output: Option<&str>,
+    signing_key: Option<&str>,
+) -> Result<()> {
     let drives = DriveDetector::detect_all_drives()?;
     let drive_info = drives.into_iter()
         .find(|d| d.device_path == device)
@@ -1752,12 +2227,21 @@ async fn verify_drive(device: &str, check_hidden: bool) -> Result<()> {
 
     // Run verification test
     println!("\nRunning recovery test...");
-    let verified = RecoveryTest::verify_wipe(device, drive_info.size)?;
+    let report = sayonara_wipe::verification::verify_drive(device, expected, verification)?;
 
-    if verified {
+    if report.passed {
         println!("✓ Verification PASSED - No recoverable data detected");
     } else {
         println!("✗ Verification FAILED - Recoverable data may be present");
+        if let Some(offset) = report.first_failure {
+            println!("  First failing offset: {}", offset);
+        }
+    }
+
+    if let Some(cert_path) = cert_output {
+        let certificate = report.certificate(signing_key.map(std::path::Path::new))?;
+        CertificateGenerator::new().save_certificate(&certificate, cert_path)?;
+        println!("📜 Verification certificate saved to: {}", cert_path);
     }
 
     // Check TRIM effectiveness if applicable
@@ -2082,6 +2566,7 @@ async fn wipe_drives_parallel(
 }
 
 // Signal handler for graceful shutdown
+#[cfg(unix)]
 fn setup_signal_handlers() -> Result<()> {
     use signal_hook::{consts::SIGINT, iterator::Signals};
 
@@ -2102,3 +2587,13 @@ fn setup_signal_handlers() -> Result<()> {
 
     Ok(())
 }
+
+/// Ctrl+C on Windows; the handler runs on its own thread, but only sets the
+/// flag the wipe loops poll
+#[cfg(windows)]
+fn setup_signal_handlers() -> Result<()> {
+    use signal_hook::consts::SIGINT;
+
+    unsafe { signal_hook::low_level::register(SIGINT, sayonara_wipe::set_interrupted)? };
+    Ok(())
+}
@@ -0,0 +1,323 @@
+// Multi-Drive Wipe - wipe a batch of drives concurrently
+//
+// Each device gets its own WipeOrchestrator on a blocking thread, at most
+// `max_concurrent` at a time. A failed drive is recorded and the rest carry
+// on; the caller gets every device's WipeReport (or error) back in order.
+
+use crate::wipe_orchestrator::{creation_error, member_certificate_path};
+use crate::{CancellationToken, DriveError, DriveResult, EventCallback, WipeConfig, WipeEvent, WipeOrchestrator, WipeReport};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// Drives wiped at once unless `with_max_concurrent` says otherwise
+const DEFAULT_MAX_CONCURRENT: usize = 4;
+
+type DeviceEventFn = dyn Fn(&str, WipeEvent) + Send + Sync;
+
+/// Callback receiving every device's `WipeEvent`s, tagged with its device path
+#[derive(Clone)]
+pub struct DeviceEventCallback(pub Arc<DeviceEventFn>);
+
+impl DeviceEventCallback {
+    pub fn new(callback: impl Fn(&str, WipeEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+}
+
+impl std::fmt::Debug for DeviceEventCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DeviceEventCallback")
+    }
+}
+
+/// Outcome of wiping one device during a `MultiWipe`
+pub struct DeviceWipeResult {
+    pub device_path: String,
+    pub result: DriveResult<WipeReport>,
+}
+
+/// Per-device results of a `MultiWipe`, in the order the devices were given
+pub struct MultiWipeReport {
+    pub devices: Vec<DeviceWipeResult>,
+    /// Wall-clock time for the whole batch
+    pub duration: Duration,
+}
+
+impl MultiWipeReport {
+    /// Whether every device was wiped
+    pub fn all_succeeded(&self) -> bool {
+        self.devices.iter().all(|device| device.result.is_ok())
+    }
+
+    /// Devices whose wipe completed
+    pub fn succeeded(&self) -> impl Iterator<Item = (&str, &WipeReport)> {
+        self.devices
+            .iter()
+            .filter_map(|device| device.result.as_ref().ok().map(|report| (device.device_path.as_str(), report)))
+    }
+
+    /// Devices whose wipe failed
+    pub fn failed(&self) -> impl Iterator<Item = &DeviceWipeResult> {
+        self.devices.iter().filter(|device| device.result.is_err())
+    }
+}
+
+/// Wipes a list of devices with one shared config, a bounded number at a time.
+///
+/// When `config.certificate_path` is set, each device's certificate is saved
+/// next to it with the device name appended (`cert.json` -> `cert-sda.json`).
+pub struct MultiWipe {
+    devices: Vec<String>,
+    config: WipeConfig,
+    max_concurrent: usize,
+    event_callback: Option<DeviceEventCallback>,
+    cancel: CancellationToken,
+}
+
+impl MultiWipe {
+    pub fn new(devices: Vec<String>, config: WipeConfig) -> Self {
+        Self {
+            devices,
+            config,
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            event_callback: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Wipe at most `max_concurrent` devices at once (minimum 1)
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = max_concurrent.max(1);
+        self
+    }
+
+    /// Receive every device's events instead of the compact console summary
+    pub fn with_event_callback(mut self, callback: DeviceEventCallback) -> Self {
+        self.event_callback = Some(callback);
+        self
+    }
+
+    /// Cancel every running and queued wipe through `cancel`
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Wipe every device and collect the results. One drive failing does
+    /// not stop the others.
+    pub async fn run(self) -> MultiWipeReport {
+        let started = Instant::now();
+        println!("🗂️  Wiping {} drive(s), {} at a time", self.devices.len(), self.max_concurrent);
+
+        let jobs: Vec<(String, WipeConfig)> = self
+            .devices
+            .iter()
+            .map(|device| (device.clone(), self.device_config(device)))
+            .collect();
+
+        let results = run_limited(jobs, self.max_concurrent, self.cancel, |device, config, cancel| {
+            tokio::runtime::Handle::current().block_on(async {
+                let mut orchestrator = WipeOrchestrator::new(device.to_string(), config).map_err(creation_error)?;
+                orchestrator.execute(cancel).await
+            })
+        })
+        .await;
+
+        let devices: Vec<DeviceWipeResult> = results
+            .into_iter()
+            .map(|(device_path, result)| DeviceWipeResult { device_path, result })
+            .collect();
+        let report = MultiWipeReport { devices, duration: started.elapsed() };
+
+        let failed = report.failed().count();
+        if failed == 0 {
+            println!("\n✅ All {} drives wiped in {}s", report.devices.len(), report.duration.as_secs());
+        } else {
+            println!("\n⚠️  {} of {} drives failed:", failed, report.devices.len());
+            for device in report.failed() {
+                if let Err(e) = &device.result {
+                    println!("   {}: {}", device.device_path, e);
+                }
+            }
+        }
+
+        report
+    }
+
+    /// The shared config with this device's certificate path and event routing
+    fn device_config(&self, device: &str) -> WipeConfig {
+        let tag = device.to_string();
+        let event_callback = match &self.event_callback {
+            Some(callback) => {
+                let callback = callback.clone();
+                EventCallback::new(move |event| (callback.0)(&tag, event))
+            }
+            None => console_summary(tag),
+        };
+
+        WipeConfig {
+            certificate_path: self.config.certificate_path.as_deref().map(|path| member_certificate_path(path, device)),
+            event_callback: Some(event_callback),
+            ..self.config.clone()
+        }
+    }
+}
+
+/// One line per milestone, prefixed with the device, since concurrent
+/// progress bars would overwrite each other
+fn console_summary(device: String) -> EventCallback {
+    let last_decile = Mutex::new(0u32);
+    EventCallback::new(move |event| match event {
+        WipeEvent::DriveIdentified { drive } => {
+            println!("[{}] {} ({} GB, {:?})", device, drive.model, drive.size / (1024 * 1024 * 1024), drive.drive_type);
+        }
+        WipeEvent::PassStarted { pass, total_passes, description } => {
+            println!("[{}] 🔄 Pass {}/{}: {}", device, pass, total_passes, description);
+        }
+        WipeEvent::Progress(progress) => {
+            let decile = (progress.percent / 10.0) as u32;
+            let mut last = last_decile.lock().unwrap();
+            if decile > *last {
+                *last = decile;
+                println!("[{}] {:.0}%", device, progress.percent);
+            }
+        }
+        WipeEvent::ThermalPaused { temperature_celsius, resume_at_celsius } => {
+            println!("[{}] 🌡️  {}°C - paused until {}°C", device, temperature_celsius, resume_at_celsius);
+        }
+        WipeEvent::Completed { method, duration_seconds, .. } => {
+            println!("[{}] ✅ Wiped via {} in {}s", device, method, duration_seconds);
+        }
+        WipeEvent::Error { message } => println!("[{}] ❌ {}", device, message),
+        WipeEvent::DetectionStarted { .. }
+        | WipeEvent::PassCompleted { .. }
+        | WipeEvent::VerificationStarted { .. }
+        | WipeEvent::ThermalResumed { .. } => {}
+    })
+}
+
+/// Run `wipe` for every job on the blocking pool, `max_concurrent` at a time,
+/// returning each device's result in job order. Jobs still queued when
+/// `cancel` fires are not started.
+async fn run_limited<T, F>(
+    jobs: Vec<(String, WipeConfig)>,
+    max_concurrent: usize,
+    cancel: CancellationToken,
+    wipe: F,
+) -> Vec<(String, DriveResult<T>)>
+where
+    T: Send + 'static,
+    F: Fn(&str, WipeConfig, CancellationToken) -> DriveResult<T> + Send + Sync + 'static,
+{
+    let wipe = Arc::new(wipe);
+    let permits = Arc::new(Semaphore::new(max_concurrent));
+
+    let tasks: Vec<_> = jobs
+        .into_iter()
+        .map(|(device, config)| {
+            let wipe = wipe.clone();
+            let permits = permits.clone();
+            let cancel = cancel.clone();
+            let task_device = device.clone();
+            let task = tokio::spawn(async move {
+                let _permit = permits.acquire_owned().await.expect("semaphore is never closed");
+                if cancel.is_cancelled() {
+                    return Err(DriveError::Cancelled);
+                }
+                tokio::task::spawn_blocking(move || wipe(&task_device, config, cancel))
+                    .await
+                    .unwrap_or_else(|e| Err(DriveError::HardwareCommandFailed(format!("Wipe task panicked: {}", e))))
+            });
+            (device, task)
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (device, task) in tasks {
+        let result = task
+            .await
+            .unwrap_or_else(|e| Err(DriveError::HardwareCommandFailed(format!("Wipe task panicked: {}", e))));
+        results.push((device, result));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrency_limited_and_failures_isolated() {
+        let jobs: Vec<(String, WipeConfig)> = ["/dev/sda", "/dev/sdb", "/dev/sdc", "/dev/sdd", "/dev/sde"]
+            .iter()
+            .map(|device| (device.to_string(), WipeConfig::default()))
+            .collect();
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running_in, peak_in) = (running.clone(), peak.clone());
+
+        let results = run_limited(jobs, 2, CancellationToken::new(), move |device, _config, _cancel| {
+            let now = running_in.fetch_add(1, Ordering::SeqCst) + 1;
+            peak_in.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            running_in.fetch_sub(1, Ordering::SeqCst);
+
+            match device {
+                "/dev/sdb" => Err(DriveError::HardwareCommandFailed("write error".to_string())),
+                "/dev/sdd" => panic!("worker crashed"),
+                _ => Ok(device.len()),
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let devices: Vec<&str> = results.iter().map(|(device, _)| device.as_str()).collect();
+        assert_eq!(devices, ["/dev/sda", "/dev/sdb", "/dev/sdc", "/dev/sdd", "/dev/sde"]);
+        assert!(results[0].1.is_ok() && results[2].1.is_ok() && results[4].1.is_ok());
+        assert!(matches!(results[1].1, Err(DriveError::HardwareCommandFailed(ref msg)) if msg == "write error"));
+        assert!(matches!(results[3].1, Err(DriveError::HardwareCommandFailed(ref msg)) if msg.contains("panicked")));
+    }
+
+    #[tokio::test]
+    async fn test_queued_devices_skipped_after_cancel() {
+        let jobs = vec![("/dev/sda".to_string(), WipeConfig::default()), ("/dev/sdb".to_string(), WipeConfig::default())];
+        let cancel = CancellationToken::new();
+        let trip = cancel.clone();
+
+        let results = run_limited(jobs, 1, cancel, move |_device, _config, _cancel| {
+            trip.cancel();
+            Ok(())
+        })
+        .await;
+
+        assert!(results[0].1.is_ok());
+        assert!(matches!(results[1].1, Err(DriveError::Cancelled)));
+    }
+
+    #[test]
+    fn test_device_config_tags_events_and_certificates() {
+        let config = WipeConfig {
+            certificate_path: Some("/tmp/certs/batch.json".to_string()),
+            ..Default::default()
+        };
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let multi = MultiWipe::new(vec!["/dev/sdb".to_string()], config)
+            .with_max_concurrent(0)
+            .with_event_callback(DeviceEventCallback::new(move |device, event| {
+                sink.lock().unwrap().push((device.to_string(), event));
+            }));
+        assert_eq!(multi.max_concurrent, 1);
+
+        let device_config = multi.device_config("/dev/sdb");
+        assert_eq!(device_config.certificate_path.as_deref(), Some("/tmp/certs/batch-sdb.json"));
+
+        (device_config.event_callback.unwrap().0)(WipeEvent::Error { message: "boom".to_string() });
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(matches!(&seen[0], (device, WipeEvent::Error { message }) if device == "/dev/sdb" && message == "boom"));
+    }
+}
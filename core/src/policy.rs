@@ -0,0 +1,177 @@
+// Wipe policy presets - named goals instead of hand-tuned configs
+//
+// `WipeConfig::preset` sets the drive-independent options for a policy and
+// records it in `WipeConfig::policy`. Once the drive is detected, the
+// orchestrator calls `Policy::apply` to pick the algorithm (and NVMe
+// sanitize action) that best serves the policy on that drive's hardware.
+
+use crate::verification::recovery_test::VerificationLevel;
+use crate::verification::recovery_test::VerificationConfig;
+use crate::drives::Capabilities;
+use crate::{Algorithm, DriveInfo, DriveType, HPADCOHandling, NvmeSanitizeAction, SEDType, SanitizeOption, WipeConfig};
+use serde::{Deserialize, Serialize};
+
+/// What a wipe is for; the method is chosen per drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Policy {
+    /// Strongest erase the hardware supports, hidden areas exposed, every
+    /// byte read back and usage logs cleared
+    HighSecurity,
+    /// Quickest erase that still defeats software recovery
+    Fast,
+    /// Exactly what a published standard prescribes
+    Compliance(ComplianceStandard),
+}
+
+/// Standards a `Policy::Compliance` wipe can follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComplianceStandard {
+    /// NIST SP 800-88 Clear
+    Nist80088Clear,
+    /// NIST SP 800-88 Purge
+    Nist80088Purge,
+}
+
+impl Policy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Policy::HighSecurity => "high security",
+            Policy::Fast => "fast",
+            Policy::Compliance(ComplianceStandard::Nist80088Clear) => "NIST 800-88 Clear",
+            Policy::Compliance(ComplianceStandard::Nist80088Purge) => "NIST 800-88 Purge",
+        }
+    }
+
+    /// The algorithm this policy uses on `drive`.
+    ///
+    /// High security prefers a key-destroying crypto erase on SEDs, the
+    /// controller's sanitize on NVMe/eMMC/UFS, a Purge (Enhanced Secure Erase
+    /// with overwrite fallback) on SATA SSDs and a 3-pass overwrite on
+    /// magnetic media. Fast takes the quickest of those the drive supports,
+    /// falling back to a single zero pass.
+    /// Crypto erase is only chosen when `self_encrypting_allowed` (the
+    /// config's `sed_crypto_erase`).
+    pub fn algorithm_for(&self, drive: &DriveInfo, self_encrypting_allowed: bool) -> Algorithm {
+        let caps = &drive.capabilities;
+        let sed = !matches!(caps.sed_type, None | Some(SEDType::None)) || caps.crypto_erase;
+        let controller_sanitize = matches!(drive.drive_type, DriveType::NVMe | DriveType::EMMC | DriveType::UFS)
+            && (!caps.sanitize_options.is_empty() || drive.drive_type != DriveType::NVMe);
+
+        match self {
+            Policy::Compliance(ComplianceStandard::Nist80088Clear) => Algorithm::NIST80088Clear,
+            Policy::Compliance(ComplianceStandard::Nist80088Purge) => Algorithm::NIST80088Purge,
+            Policy::HighSecurity | Policy::Fast if sed && self_encrypting_allowed => Algorithm::CryptoErase,
+            Policy::HighSecurity | Policy::Fast if controller_sanitize => Algorithm::Sanitize,
+            Policy::HighSecurity if drive.drive_type == DriveType::SSD && caps.secure_erase => {
+                Algorithm::NIST80088Purge
+            }
+            Policy::HighSecurity if drive.drive_type.is_flash() => Algorithm::Random,
+            Policy::HighSecurity => Algorithm::DoD5220,
+            Policy::Fast if drive.drive_type == DriveType::SSD && caps.secure_erase => Algorithm::SecureErase,
+            Policy::Fast => Algorithm::Zero,
+        }
+    }
+
+    /// NVMe sanitize action for this policy: fast wipes destroy the key when
+    /// the controller can, others leave the most thorough action to be picked
+    pub fn sanitize_action_for(&self, drive: &DriveInfo) -> Option<NvmeSanitizeAction> {
+        let crypto = drive.capabilities.sanitize_options.iter().any(|option| matches!(option, SanitizeOption::CryptoErase));
+        (*self == Policy::Fast && crypto).then_some(NvmeSanitizeAction::CryptoErase)
+    }
+
+    /// Specialize `config` for the detected `drive`
+    pub fn apply(&self, config: &mut WipeConfig, drive: &DriveInfo) {
+        config.algorithm = self.algorithm_for(drive, config.sed_crypto_erase);
+        if config.nvme_sanitize_action.is_none() {
+            config.nvme_sanitize_action = self.sanitize_action_for(drive);
+        }
+        println!(
+            "📋 Policy {}: {:?} for {:?} drive (offers: {})",
+            self.label(),
+            config.algorithm,
+            drive.drive_type,
+            Capabilities::of(drive)
+        );
+    }
+}
+
+impl WipeConfig {
+    /// Config for `policy`, with the algorithm chosen once the drive is detected
+    pub fn preset(policy: Policy) -> Self {
+        let config = WipeConfig { policy: Some(policy), ..WipeConfig::default() };
+
+        match policy {
+            Policy::HighSecurity => WipeConfig {
+                algorithm: Algorithm::DoD5220,
+                verify: true,
+                verification: VerificationConfig { level: VerificationLevel::Full, ..Default::default() },
+                handle_hpa_dco: HPADCOHandling::TemporaryRemove,
+                verify_hardware_erase: true,
+                clear_device_logs: true,
+                ..config
+            },
+            Policy::Fast => WipeConfig {
+                algorithm: Algorithm::Zero,
+                verify: true,
+                handle_hpa_dco: HPADCOHandling::Detect,
+                overwrite_after_unverified_trim: false,
+                ..config
+            },
+            Policy::Compliance(standard) => WipeConfig {
+                algorithm: match standard {
+                    ComplianceStandard::Nist80088Clear => Algorithm::NIST80088Clear,
+                    ComplianceStandard::Nist80088Purge => Algorithm::NIST80088Purge,
+                },
+                verify: true,
+                verify_hardware_erase: true,
+                // Purge must reach the drive's hidden areas too
+                handle_hpa_dco: match standard {
+                    ComplianceStandard::Nist80088Clear => HPADCOHandling::Detect,
+                    ComplianceStandard::Nist80088Purge => HPADCOHandling::TemporaryRemove,
+                },
+                ..config
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::drive;
+    use crate::DriveCapabilities;
+
+    #[test]
+    fn test_high_security_picks_strongest_supported_method() {
+        let policy = Policy::HighSecurity;
+        let sanitize = DriveCapabilities { sanitize_options: vec![SanitizeOption::BlockErase], ..Default::default() };
+        let secure_erase = DriveCapabilities { secure_erase: true, ..Default::default() };
+        let sed = DriveCapabilities { sed_type: Some(SEDType::OPAL20), ..Default::default() };
+
+        assert_eq!(policy.algorithm_for(&drive(DriveType::HDD, Default::default()), true), Algorithm::DoD5220);
+        assert_eq!(policy.algorithm_for(&drive(DriveType::NVMe, sanitize), true), Algorithm::Sanitize);
+        assert_eq!(policy.algorithm_for(&drive(DriveType::NVMe, Default::default()), true), Algorithm::Random);
+        assert_eq!(policy.algorithm_for(&drive(DriveType::SSD, secure_erase), true), Algorithm::NIST80088Purge);
+        assert_eq!(policy.algorithm_for(&drive(DriveType::SSD, sed.clone()), true), Algorithm::CryptoErase);
+        assert_eq!(policy.algorithm_for(&drive(DriveType::SSD, sed), false), Algorithm::Random);
+    }
+
+    #[test]
+    fn test_fast_and_compliance_presets() {
+        let crypto = DriveCapabilities {
+            sanitize_options: vec![SanitizeOption::BlockErase, SanitizeOption::CryptoErase],
+            ..Default::default()
+        };
+        let mut config = WipeConfig::preset(Policy::Fast);
+        Policy::Fast.apply(&mut config, &drive(DriveType::NVMe, crypto));
+        assert_eq!(config.algorithm, Algorithm::Sanitize);
+        assert_eq!(config.nvme_sanitize_action, Some(NvmeSanitizeAction::CryptoErase));
+        assert_eq!(Policy::Fast.algorithm_for(&drive(DriveType::HDD, Default::default()), true), Algorithm::Zero);
+
+        let purge = Policy::Compliance(ComplianceStandard::Nist80088Purge);
+        let config = WipeConfig::preset(purge);
+        assert_eq!(config.policy, Some(purge));
+        assert_eq!(config.handle_hpa_dco, HPADCOHandling::TemporaryRemove);
+        assert_eq!(purge.algorithm_for(&drive(DriveType::HDD, Default::default()), true), Algorithm::NIST80088Purge);
+    }
+}
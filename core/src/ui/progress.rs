@@ -1,5 +1,101 @@
 use std::io::{self, Write};
-use std::time::{Instant};
+use std::time::{Duration, Instant};
+
+/// Time constant of the throughput moving average: a sudden change in speed
+/// is ~63% reflected after this long, so the ETA follows trends, not blips
+const RATE_SMOOTHING: Duration = Duration::from_secs(5);
+
+/// Throughput samples closer together than this are merged, so chunk-sized
+/// bursts don't register as wild swings
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Throughput under this for `STALL_AFTER` marks the write as stalled,
+/// typically a failing region of the surface retrying every sector
+pub const STALL_BYTES_PER_SEC: f64 = 512.0 * 1024.0;
+pub const STALL_AFTER: Duration = Duration::from_secs(10);
+
+/// Throughput as an exponential moving average of cumulative byte counts,
+/// noticing when it has collapsed
+#[derive(Debug, Clone)]
+pub struct RateEstimator {
+    smoothed: Option<f64>,
+    last_sample: Option<(Instant, u64)>,
+    slow_since: Option<Instant>,
+    stalled: bool,
+    stall_threshold: f64,
+    stall_after: Duration,
+}
+
+impl Default for RateEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateEstimator {
+    pub fn new() -> Self {
+        Self {
+            smoothed: None,
+            last_sample: None,
+            slow_since: None,
+            stalled: false,
+            stall_threshold: STALL_BYTES_PER_SEC,
+            stall_after: STALL_AFTER,
+        }
+    }
+
+    /// Only count throughput below `threshold` bytes/s as a stall once a
+    /// rate limit makes the default threshold meaningless
+    pub fn with_rate_limit(mut self, max_bytes_per_sec: Option<u64>) -> Self {
+        if let Some(max) = max_bytes_per_sec {
+            self.stall_threshold = self.stall_threshold.min(max as f64 / 2.0);
+        }
+        self
+    }
+
+    /// Record that `bytes` have been written in total by `now`, returning
+    /// the smoothed rate in bytes/s once there is a sample to base it on
+    pub fn update(&mut self, bytes: u64, now: Instant) -> Option<f64> {
+        let Some((last_time, last_bytes)) = self.last_sample else {
+            self.last_sample = Some((now, bytes));
+            return None;
+        };
+
+        let elapsed = now.saturating_duration_since(last_time);
+        if elapsed < MIN_SAMPLE_INTERVAL {
+            return self.smoothed;
+        }
+
+        let rate = bytes.saturating_sub(last_bytes) as f64 / elapsed.as_secs_f64();
+        let weight = 1.0 - (-elapsed.as_secs_f64() / RATE_SMOOTHING.as_secs_f64()).exp();
+        self.smoothed = Some(match self.smoothed {
+            Some(previous) => previous + weight * (rate - previous),
+            None => rate,
+        });
+        self.last_sample = Some((now, bytes));
+
+        // A slow sample means the write has been slow since the sample began
+        if rate < self.stall_threshold {
+            let since = *self.slow_since.get_or_insert(last_time);
+            self.stalled = now.saturating_duration_since(since) >= self.stall_after;
+        } else {
+            self.slow_since = None;
+            self.stalled = false;
+        }
+
+        self.smoothed
+    }
+
+    /// Smoothed throughput in bytes/s, None before the first full sample
+    pub fn rate(&self) -> Option<f64> {
+        self.smoothed
+    }
+
+    /// Whether throughput has stayed under the stall threshold for the stall period
+    pub fn is_stalled(&self) -> bool {
+        self.stalled
+    }
+}
 
 const CAT_FRAMES: [&str; 6] = [
     "ฅ(^･ω･^=)  ", // cat happy
@@ -19,6 +115,8 @@ pub struct ProgressBar {
     paw_frame: usize,
     start: Instant,
     first_render: bool,
+    rate_limit: Option<f64>,
+    rate: RateEstimator,
 }
 
 impl ProgressBar {
@@ -31,9 +129,18 @@ impl ProgressBar {
             paw_frame: 0,
             start: Instant::now(),
             first_render: true,
+            rate_limit: None,
+            rate: RateEstimator::new(),
         }
     }
 
+    /// Base the ETA on at most `max_bytes_per_sec`, for throttled writes
+    pub fn with_rate_limit(mut self, max_bytes_per_sec: Option<u64>) -> Self {
+        self.rate_limit = max_bytes_per_sec.map(|max| max as f64);
+        self.rate = self.rate.with_rate_limit(max_bytes_per_sec);
+        self
+    }
+
     /// Render the progress bar
     /// - `progress`: 0.0..=100.0
     /// - `bytes_written` and `total_bytes` are optional. If provided ETA and speed will be shown.
@@ -90,20 +197,26 @@ impl ProgressBar {
         let _info = String::new();
 
         let info = if let (Some(written), Some(total)) = (bytes_written, total_bytes) {
+            // Smoothed once there is a sample, the running average until then
             let elapsed = self.start.elapsed().as_secs_f64().max(0.0001);
-            let speed = (written as f64) / elapsed;
+            let speed = self.rate.update(written, Instant::now()).unwrap_or((written as f64) / elapsed);
             let speed_readable = human_bytes(speed);
             let remaining = if total > written { total - written } else { 0 };
-            let eta_secs = if speed > 0.0 {
-                (remaining as f64 / speed).round() as u64
+            let eta_speed = self.rate_limit.map_or(speed, |limit| speed.min(limit));
+            let eta = if self.rate.is_stalled() || eta_speed <= 0.0 {
+                "--:--".to_string()
             } else {
-                0
+                format_duration((remaining as f64 / eta_speed).round() as u64)
+            };
+            let stalled = if self.rate.is_stalled() {
+                format!("  {}\x1b[38;5;214m⚠ stalled{}", bold, reset)
+            } else {
+                String::new()
             };
-            let eta = format_duration(eta_secs);
 
             format!(
-                "{}{:.1}%{}  {} @ {}/s  ETA {}",
-                bold, pct, reset, cyan, speed_readable, eta
+                "{}{:.1}%{}  {} @ {}/s  ETA {}{}",
+                bold, pct, reset, cyan, speed_readable, eta, stalled
             )
         } else {
             let paw = PAW_FRAMES[self.paw_frame];
@@ -153,3 +266,51 @@ fn format_duration(secs: u64) -> String {
         format!("{}:{:02}", m, s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_is_smoothed() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut rate = RateEstimator::new();
+
+        assert_eq!(rate.update(0, at(0)), None);
+        assert_eq!(rate.update(100 << 20, at(1000)), Some((100 << 20) as f64));
+        // Too soon after the last sample to count
+        assert_eq!(rate.update(101 << 20, at(1100)), Some((100 << 20) as f64));
+
+        // A one-second burst at 10x speed moves the average only part of the way
+        let burst = rate.update(1100 << 20, at(2000)).unwrap();
+        assert!(burst > (100 << 20) as f64 && burst < (300 << 20) as f64);
+        assert!(!rate.is_stalled());
+    }
+
+    #[test]
+    fn test_stall_needs_sustained_slow_throughput() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut rate = RateEstimator::new();
+
+        rate.update(0, at(0));
+        rate.update(1 << 30, at(1));
+        rate.update((1 << 30) + 1024, at(6));
+        assert!(!rate.is_stalled());
+        rate.update((1 << 30) + 2048, at(11));
+        assert!(rate.is_stalled());
+
+        // Recovers as soon as throughput does
+        rate.update(2 << 30, at(12));
+        assert!(!rate.is_stalled());
+
+        // A throttled write is not a stall
+        let mut throttled = RateEstimator::new().with_rate_limit(Some(100 * 1024));
+        throttled.update(0, at(0));
+        for secs in 1..30 {
+            throttled.update(secs * 100 * 1024, at(secs));
+        }
+        assert!(!throttled.is_stalled());
+    }
+}
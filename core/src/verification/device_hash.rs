@@ -0,0 +1,223 @@
+// Device Hash - a digest of the device's final state
+//
+// Hashing what the device holds once the wipe is done lets anyone re-read it
+// later and recompute the same digest, showing nothing was written since.
+// A full hash is `sha256sum /dev/sdX`; a sampled hash covers a fixed set of
+// ranges spread evenly across the device, chosen from its size alone, so a
+// third party can reproduce either from what the certificate records.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::crypto::certificates::to_hex;
+use crate::io::FileExt;
+
+/// Digest algorithm, as recorded in the certificate
+pub const HASH_ALGORITHM: &str = "SHA-256";
+
+/// Read size for full hashes
+const READ_CHUNK: usize = 4 * 1024 * 1024;
+
+/// Ranges read by `HashScheme::sampled()`
+const DEFAULT_SAMPLE_RANGES: u32 = 1024;
+
+/// Length of each range read by `HashScheme::sampled()`
+const DEFAULT_SAMPLE_LEN: u64 = 64 * 1024;
+
+/// Which bytes of the device a hash covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HashScheme {
+    /// Every byte, in order
+    Full,
+    /// `ranges` ranges of `range_len` bytes, hashed in order: range `i`
+    /// starts at `i * (size - range_len) / (ranges - 1)` rounded down to a
+    /// multiple of 512, so the first starts at 0 and the last ends at the
+    /// end of the device
+    Sampled { ranges: u32, range_len: u64 },
+}
+
+impl HashScheme {
+    /// 1024 ranges of 64 KiB: 64 MiB read whatever the device size
+    pub fn sampled() -> Self {
+        HashScheme::Sampled { ranges: DEFAULT_SAMPLE_RANGES, range_len: DEFAULT_SAMPLE_LEN }
+    }
+
+    /// Byte ranges of a `size`-byte device the scheme hashes, in order
+    pub fn ranges(&self, size: u64) -> Vec<(u64, u64)> {
+        match *self {
+            HashScheme::Full => vec![(0, size)],
+            HashScheme::Sampled { ranges, range_len } => {
+                let len = range_len.min(size);
+                if len == 0 || ranges == 0 {
+                    return Vec::new();
+                }
+                let span = size - len;
+                (0..ranges as u64)
+                    .map(|i| {
+                        let offset = if ranges == 1 { 0 } else { span * i / (ranges as u64 - 1) / 512 * 512 };
+                        (offset, len)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Reject sampling that would read nothing
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            HashScheme::Sampled { ranges: 0, .. } | HashScheme::Sampled { range_len: 0, .. } => {
+                Err(anyhow!("A sampled hash needs at least one range of at least one byte"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for HashScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashScheme::Full => write!(f, "full device"),
+            HashScheme::Sampled { ranges, range_len } => {
+                write!(f, "{} evenly spaced ranges of {} bytes", ranges, range_len)
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for HashScheme {
+    type Err = anyhow::Error;
+
+    /// "full", "sample", or "sample:<ranges>" with 64 KiB ranges
+    fn from_str(s: &str) -> Result<Self> {
+        let scheme = match s.trim().to_lowercase().as_str() {
+            "full" => HashScheme::Full,
+            "sample" | "sampled" => HashScheme::sampled(),
+            other => match other.strip_prefix("sample:").map(str::parse::<u32>) {
+                Some(Ok(ranges)) => HashScheme::Sampled { ranges, range_len: DEFAULT_SAMPLE_LEN },
+                _ => return Err(anyhow!("Unknown hash scheme '{}' (use full, sample or sample:<ranges>)", s)),
+            },
+        };
+        scheme.validate()?;
+        Ok(scheme)
+    }
+}
+
+/// Digest of a device's contents, recorded in the certificate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceHash {
+    /// Always `HASH_ALGORITHM`
+    pub algorithm: String,
+    pub scheme: HashScheme,
+    /// Device size the sampled ranges were derived from
+    pub size: u64,
+    /// Bytes actually hashed
+    pub bytes_hashed: u64,
+    /// Lowercase hex digest
+    pub digest: String,
+}
+
+impl DeviceHash {
+    /// Hash the device again with the same scheme and report whether the
+    /// digest still matches. A device whose size changed never matches.
+    pub fn matches_device(&self, device_path: &str) -> Result<bool> {
+        let current = hash_device(device_path, &self.scheme)?;
+        Ok(current.size == self.size && current.digest == self.digest)
+    }
+}
+
+impl std::fmt::Display for DeviceHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} ({} of {} bytes)", self.algorithm, self.digest, self.scheme, self.size)
+    }
+}
+
+/// Hash `device_path` as `scheme` describes, reading from the media rather
+/// than the page cache
+pub fn hash_device(device_path: &str, scheme: &HashScheme) -> Result<DeviceHash> {
+    scheme.validate()?;
+    crate::io::drop_page_cache(device_path)
+        .with_context(|| format!("Failed to drop cached pages of {}", device_path))?;
+
+    let mut file = File::open(device_path).with_context(|| format!("Failed to open {}", device_path))?;
+    let size = file.seek(SeekFrom::End(0))?;
+
+    let mut hasher = Sha256::new();
+    let mut bytes_hashed = 0;
+    match scheme {
+        HashScheme::Full => {
+            file.seek(SeekFrom::Start(0))?;
+            let mut buffer = vec![0u8; READ_CHUNK];
+            loop {
+                let read = file.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+                bytes_hashed += read as u64;
+            }
+            if bytes_hashed != size {
+                return Err(anyhow!("{} ended at byte {} while hashing, not {}", device_path, bytes_hashed, size));
+            }
+        }
+        HashScheme::Sampled { .. } => {
+            for (offset, len) in scheme.ranges(size) {
+                let mut buffer = vec![0u8; len as usize];
+                file.read_exact_at(&mut buffer, offset)
+                    .with_context(|| format!("Failed to read bytes {}..{} of {}", offset, offset + len, device_path))?;
+                hasher.update(&buffer);
+                bytes_hashed += len;
+            }
+        }
+    }
+
+    Ok(DeviceHash {
+        algorithm: HASH_ALGORITHM.to_string(),
+        scheme: *scheme,
+        size,
+        bytes_hashed,
+        digest: to_hex(&hasher.finalize()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampled_ranges_are_aligned_and_reach_the_end() {
+        let ranges = HashScheme::Sampled { ranges: 4, range_len: 4096 }.ranges(1 << 20);
+        assert_eq!(ranges.first(), Some(&(0, 4096)));
+        assert_eq!(ranges.last(), Some(&((1 << 20) - 4096, 4096)));
+        assert!(ranges.iter().all(|(offset, _)| offset % 512 == 0));
+
+        assert_eq!(HashScheme::Sampled { ranges: 2, range_len: 4096 }.ranges(1000), [(0, 1000), (0, 1000)]);
+        assert_eq!("sample:16".parse::<HashScheme>().unwrap(), HashScheme::Sampled { ranges: 16, range_len: 65536 });
+        assert!("sample:0".parse::<HashScheme>().is_err());
+    }
+
+    #[test]
+    fn test_hash_matches_until_a_byte_changes() {
+        let image = tempfile::NamedTempFile::new().unwrap();
+        let path = image.path().to_str().unwrap();
+        std::fs::write(image.path(), vec![0u8; 1 << 20]).unwrap();
+
+        // A full hash is what sha256sum prints for the same bytes
+        let full = hash_device(path, &HashScheme::Full).unwrap();
+        assert_eq!(full.digest, to_hex(&Sha256::digest(vec![0u8; 1 << 20])));
+        assert_eq!(full.bytes_hashed, 1 << 20);
+
+        let sampled = hash_device(path, &HashScheme::Sampled { ranges: 8, range_len: 4096 }).unwrap();
+        assert_eq!(sampled.bytes_hashed, 8 * 4096);
+        assert!(full.matches_device(path).unwrap() && sampled.matches_device(path).unwrap());
+
+        // The last sampled range ends at the last byte
+        let file = std::fs::OpenOptions::new().write(true).open(image.path()).unwrap();
+        file.write_all_at(&[1], (1 << 20) - 1).unwrap();
+        assert!(!full.matches_device(path).unwrap());
+        assert!(!sampled.matches_device(path).unwrap());
+    }
+}
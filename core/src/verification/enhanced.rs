@@ -3,7 +3,7 @@ use rand::Rng;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashSet;
-use std::process::Command;
+use crate::commands::Command;
 use crate::io::{OptimizedIO, IOConfig, IOHandle};
 
 /// Enhanced verification system with comprehensive forensic analysis
@@ -344,7 +344,9 @@ impl EnhancedVerification {
     ) -> Result<PostWipeAnalysis> {
         println!("  📊 Level 2: Systematic Sampling (every {}th sector)", every_nth);
 
-        let sector_size = 512u64;
+        let sector_size = crate::drives::DriveDetector::get_block_sizes(device_path)
+            .map(|(logical, physical)| logical.max(physical) as u64)
+            .unwrap_or(512);
         let total_sectors = device_size / sector_size;
         let sectors_to_check = total_sectors / every_nth;
 
@@ -1085,7 +1087,9 @@ impl EnhancedVerification {
         device_path: &str,
         device_size: u64,
     ) -> Result<(SectorSamplingResult, BadSectorTracker)> {
-        let sector_size = 512u64;
+        let sector_size = crate::drives::DriveDetector::get_block_sizes(device_path)
+            .map(|(logical, physical)| logical.max(physical) as u64)
+            .unwrap_or(512);
         let total_sectors = device_size / sector_size;
         let samples_per_region = 100;
 
@@ -1,9 +1,15 @@
 pub mod recovery_test;
 pub mod enhanced;
+pub mod signatures;
+pub mod device_hash;
+pub mod standalone;
 mod enhanced_tests;
 
 // Re-export all verification types
 pub use recovery_test::RecoveryTest;
+pub use signatures::{scan_signatures, DiskSignature};
+pub use device_hash::{hash_device, DeviceHash, HashScheme};
+pub use standalone::{verify_drive, DriveVerificationReport};
 pub use enhanced::{
     // Main verification system
     EnhancedVerification,
@@ -1,53 +1,427 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use crate::crypto::certificates::RandomnessScores;
+use crate::algorithms::PatternSpec;
 use crate::crypto::secure_rng::secure_random_bytes;
+use crate::io::FileExt;
 use crate::ui::progress::ProgressBar;
-use crate::io::{OptimizedIO, IOConfig};
+use crate::Algorithm;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread::ScopedJoinHandle;
+use std::time::Duration;
+
+/// Chunk size read by a full verification pass (1MB)
+const FULL_VERIFY_CHUNK: usize = 1024 * 1024;
+
+/// Chunks smaller than this are too short for a meaningful entropy score
+const MIN_ENTROPY_CHUNK: usize = 4096;
+
+/// Number of spread-out windows the entropy sample is split across
+const ENTROPY_WINDOWS: u64 = 8;
+
+/// Entropy (bits/byte) a non-uniform block must reach to count as erased;
+/// random data scores ~7.6 even in a single 512-byte sector, text ~4-5
+const ERASED_MIN_ENTROPY: f64 = 7.0;
+
+/// Data read before the Monte-Carlo pi estimate is judged; at 4MB its
+/// standard error is ~0.06%, so a 0.5% limit is ~8 sigma for random data
+const MIN_MONTE_CARLO_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Radius of the Monte-Carlo circle: coordinates are 24-bit
+const MONTE_CARLO_RADIUS: u64 = (1 << 24) - 1;
+
+/// Threads sampled reads are spread across, each with its own file
+/// descriptor, so a high-latency drive always has several reads in flight
+const VERIFY_WORKERS: usize = 4;
+
+/// How often the progress bar is redrawn while workers read
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How much of the device a recovery test reads back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum VerificationLevel {
+    /// Check random sectors plus an entropy sample spread across the device
+    #[default]
+    Sample,
+    /// Read every byte of the device, chunk by chunk
+    Full,
+}
+
+/// Policy for a recovery test: how much to read and what counts as a pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerificationConfig {
+    pub level: VerificationLevel,
+    /// Entropy (bits/byte) random-wiped data must exceed to pass
+    pub min_entropy: f64,
+    /// Total bytes read for the entropy score in sampled mode
+    pub entropy_sample_bytes: u64,
+    /// Random sectors checked in sampled mode
+    pub sector_sample_count: usize,
+    /// Bytes read at each sampled sector
+    pub sector_read_size: usize,
+    /// Chi-square range (255 degrees of freedom) random data must fall in.
+    /// Too low fails as well as too high: a repeating 0..=255 counter has a
+    /// perfect histogram and 8.0 bits/byte of entropy.
+    pub min_chi_square: f64,
+    pub max_chi_square: f64,
+    /// Largest error of the Monte-Carlo pi estimate, in percent of pi
+    pub max_pi_error: f64,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            level: VerificationLevel::Sample,
+            min_entropy: 7.5,
+            entropy_sample_bytes: 100 * 1024 * 1024,
+            sector_sample_count: 1000,
+            sector_read_size: 4096,
+            // Random data falls outside these about once in 10000 tests
+            min_chi_square: 180.0,
+            max_chi_square: 347.0,
+            max_pi_error: 0.5,
+        }
+    }
+}
+
+/// What the final pass should have left on the device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExpectedPattern {
+    /// Uniformly zero; low entropy is success
+    Zeros,
+    /// Every byte equals this value
+    Fixed(u8),
+    /// This sequence repeated from byte 0 of the device
+    Sequence(Vec<u8>),
+    /// Random data; high entropy is success
+    Random,
+    /// Whatever a hardware erase leaves: each block is either one repeated
+    /// byte or random (crypto erase); anything structured is a failure
+    Erased,
+}
+
+impl ExpectedPattern {
+    /// Pattern left by the final pass of an algorithm
+    pub fn for_algorithm(algorithm: &Algorithm) -> Self {
+        match algorithm {
+            // BSI-GS/GSE end with a 0x00 pass
+            Algorithm::Zero
+            | Algorithm::NIST80088Clear
+            | Algorithm::BSI_GS
+            | Algorithm::BSI_GSE
+            | Algorithm::HMGIS5Baseline => ExpectedPattern::Zeros,
+            Algorithm::VSITR => ExpectedPattern::Fixed(0xAA),
+            // The drive decides what these leave: zeros, ones or ciphertext
+            Algorithm::SecureErase
+            | Algorithm::Sanitize
+            | Algorithm::NIST80088Purge
+            | Algorithm::TrimOnly => ExpectedPattern::Erased,
+            Algorithm::Custom { passes } => match passes.last() {
+                Some(PatternSpec::Byte(0x00)) => ExpectedPattern::Zeros,
+                Some(PatternSpec::Byte(byte)) => ExpectedPattern::Fixed(*byte),
+                Some(PatternSpec::Sequence(bytes)) => ExpectedPattern::Sequence(bytes.clone()),
+                Some(PatternSpec::Random) | None => ExpectedPattern::Random,
+            },
+            _ => ExpectedPattern::Random,
+        }
+    }
+
+    /// The byte the device should hold at `offset`, or None for random data
+    pub fn byte_at(&self, offset: u64) -> Option<u8> {
+        match self {
+            ExpectedPattern::Zeros => Some(0x00),
+            ExpectedPattern::Fixed(byte) => Some(*byte),
+            ExpectedPattern::Sequence(bytes) if !bytes.is_empty() => Some(bytes[(offset % bytes.len() as u64) as usize]),
+            ExpectedPattern::Sequence(_) | ExpectedPattern::Random | ExpectedPattern::Erased => None,
+        }
+    }
+
+    /// Offset of the first byte of `data`, read from `offset`, that isn't
+    /// the pattern's byte there
+    fn first_mismatch(&self, data: &[u8], offset: u64) -> Option<u64> {
+        data.iter()
+            .zip(offset..)
+            .find(|&(&byte, at)| self.byte_at(at) != Some(byte))
+            .map(|(_, at)| at)
+    }
+}
+
+impl std::fmt::Display for ExpectedPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpectedPattern::Zeros => write!(f, "zeros"),
+            ExpectedPattern::Fixed(byte) => write!(f, "0x{:02X} fill", byte),
+            ExpectedPattern::Sequence(bytes) => {
+                let hex: Vec<String> = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                write!(f, "repeating 0x{} sequence", hex.concat())
+            }
+            ExpectedPattern::Random => write!(f, "random data"),
+            ExpectedPattern::Erased => write!(f, "a hardware-erased drive"),
+        }
+    }
+}
+
+/// Result of a recovery test
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationOutcome {
+    pub passed: bool,
+    /// Byte offset of the first sector or chunk that did not match
+    pub first_failure: Option<u64>,
+    pub entropy_score: f64,
+    pub bytes_checked: u64,
+    /// Chi-square and Monte-Carlo scores, for random wipes
+    pub randomness: Option<RandomnessScores>,
+}
+
+/// Byte histogram and Monte-Carlo hits over everything read so far
+struct ByteStats {
+    counts: [u64; 256],
+    points: u64,
+    inside: u64,
+}
+
+impl ByteStats {
+    fn new() -> Self {
+        Self { counts: [0; 256], points: 0, inside: 0 }
+    }
+
+    fn add(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.counts[byte as usize] += 1;
+        }
+
+        // Each 6 bytes are an (x, y) point in a square; the share landing
+        // inside the inscribed quarter circle approaches pi/4
+        for point in data.chunks_exact(6) {
+            let x = u64::from_be_bytes([0, 0, 0, 0, 0, point[0], point[1], point[2]]);
+            let y = u64::from_be_bytes([0, 0, 0, 0, 0, point[3], point[4], point[5]]);
+            self.points += 1;
+            if x * x + y * y <= MONTE_CARLO_RADIUS * MONTE_CARLO_RADIUS {
+                self.inside += 1;
+            }
+        }
+    }
+
+    fn bytes(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Fold in statistics gathered over other data, as if it had been
+    /// `add`ed here
+    fn merge(&mut self, other: &ByteStats) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.points += other.points;
+        self.inside += other.inside;
+    }
+
+    /// Judge the accumulated data against `config`
+    fn scores(&self, config: &VerificationConfig) -> RandomnessScores {
+        let chi_square = RecoveryTest::chi_square_from_counts(&self.counts);
+
+        let monte_carlo_pi = (self.bytes() >= MIN_MONTE_CARLO_BYTES)
+            .then(|| 4.0 * self.inside as f64 / self.points as f64);
+        let monte_carlo_error = monte_carlo_pi
+            .map(|pi| (pi - std::f64::consts::PI).abs() / std::f64::consts::PI * 100.0);
+
+        RandomnessScores {
+            chi_square,
+            chi_square_passed: (config.min_chi_square..=config.max_chi_square).contains(&chi_square),
+            monte_carlo_pi,
+            monte_carlo_error,
+            monte_carlo_passed: monte_carlo_error.is_none_or(|error| error <= config.max_pi_error),
+        }
+    }
+}
 
 pub struct RecoveryTest;
 
 impl RecoveryTest {
-    pub fn verify_wipe(device_path: &str, size: u64) -> Result<bool> {
-        println!("Starting recovery verification test...");
+    /// Sampled verification judged by what `algorithm` leaves on the drive:
+    /// uniform zeros for zero wipes, high entropy for random-based ones
+    pub fn verify_wipe(device_path: &str, size: u64, algorithm: &Algorithm) -> Result<bool> {
+        let outcome = Self::verify_wipe_with_config(
+            device_path,
+            size,
+            &VerificationConfig::default(),
+            ExpectedPattern::for_algorithm(algorithm),
+        )?;
+        Ok(outcome.passed)
+    }
+
+    /// Verify a wipe under the given policy, checking data against the
+    /// pattern the final pass should have written. Cached pages are dropped
+    /// first so the reads see the media, not the wipe data still in RAM.
+    pub fn verify_wipe_with_config(
+        device_path: &str,
+        size: u64,
+        config: &VerificationConfig,
+        expected: ExpectedPattern,
+    ) -> Result<VerificationOutcome> {
+        if let Err(e) = crate::io::drop_page_cache(device_path) {
+            println!("⚠️  Could not drop cached pages for {} ({}); reads may be served from RAM", device_path, e);
+        }
+
+        match config.level {
+            VerificationLevel::Sample => Self::verify_sampled(device_path, size, config, &expected),
+            VerificationLevel::Full => Self::verify_full(device_path, size, config, &expected),
+        }
+    }
+
+    fn verify_sampled(
+        device_path: &str,
+        size: u64,
+        config: &VerificationConfig,
+        expected: &ExpectedPattern,
+    ) -> Result<VerificationOutcome> {
+        println!("Starting recovery verification test ({} sampled sectors)...", config.sector_sample_count);
 
         let mut bar = ProgressBar::new(48);
 
-        // Sample random sectors
-        let test_sectors = Self::generate_test_sectors(size)?;
-        let total = test_sectors.len();
-        let mut checked = 0usize;
+        // Sample random sectors, aligned to the drive's physical sectors so
+        // 4Kn and 512e drives are read in whole sectors
+        let sector_size = Self::block_size(device_path);
+        let read_size = config.sector_read_size.div_ceil(sector_size as usize) * sector_size as usize;
+        let test_sectors = Self::generate_test_sectors(size, config.sector_sample_count, sector_size)?;
+        let checked = test_sectors.len();
 
-        for sector in test_sectors {
-            if !Self::verify_sector_wiped(device_path, sector)? {
-                println!("Warning: Recoverable data found at sector {}", sector);
-                bar.render(100.0, None, None);
-                return Ok(false);
-            }
-            checked += 1;
-            if checked % 50 == 0 || checked == total {
-                let progress = (checked as f64 / total as f64) * 50.0; // 0–50%
-                bar.render(progress, None, None);
-            }
+        if let Some(failed) = Self::find_unwiped_sector(device_path, size, &test_sectors, read_size, expected, &mut bar)? {
+            let sector = test_sectors[failed];
+            println!("Warning: Recoverable data found at sector {}", sector);
+            bar.render(100.0, None, None);
+            return Ok(VerificationOutcome {
+                passed: false,
+                first_failure: Some(sector),
+                entropy_score: 0.0,
+                bytes_checked: failed as u64 * read_size as u64,
+                randomness: None,
+            });
         }
 
-        // Entropy analysis
-        let entropy_score = Self::calculate_entropy(device_path, size, &mut bar)?;
+        // Only random wipes are scored by entropy; the sampled sectors decide the rest
+        if *expected != ExpectedPattern::Random {
+            bar.render(100.0, None, None);
+            return Ok(VerificationOutcome {
+                passed: true,
+                first_failure: None,
+                entropy_score: 0.0,
+                bytes_checked: checked as u64 * read_size as u64,
+                randomness: None,
+            });
+        }
+
+        // Entropy analysis, plus the statistical tests over the same sample
+        let mut stats = ByteStats::new();
+        let entropy_score = Self::calculate_entropy(device_path, size, config.entropy_sample_bytes, &mut stats, &mut bar)?;
         println!("Drive entropy score: {:.2}", entropy_score);
+        let randomness = stats.scores(config);
+        Self::print_randomness(&randomness);
 
         bar.render(100.0, None, None);
 
-        Ok(entropy_score > 7.5)
+        Ok(VerificationOutcome {
+            passed: entropy_score > config.min_entropy && randomness.passed(),
+            first_failure: None,
+            entropy_score,
+            bytes_checked: checked as u64 * read_size as u64 + stats.bytes(),
+            randomness: Some(randomness),
+        })
+    }
+
+    /// Read the whole device, failing at the first chunk that is not all
+    /// zeros (zero wipes) or whose entropy is too low (random wipes). Random
+    /// wipes must also pass the statistical tests over the whole device.
+    fn verify_full(
+        device_path: &str,
+        size: u64,
+        config: &VerificationConfig,
+        expected: &ExpectedPattern,
+    ) -> Result<VerificationOutcome> {
+        println!("Starting full verification of {} bytes...", size);
+
+        let mut bar = ProgressBar::new(48);
+        let mut file = File::open(device_path)?;
+        let mut buffer = vec![0u8; FULL_VERIFY_CHUNK];
+        let mut stats = ByteStats::new();
+        let mut offset = 0u64;
+
+        while offset < size {
+            let len = (size - offset).min(FULL_VERIFY_CHUNK as u64) as usize;
+            let chunk = &mut buffer[..len];
+            file.read_exact(chunk)?;
+
+            stats.add(chunk);
+
+            let failure = match expected {
+                ExpectedPattern::Random => {
+                    let entropy = crate::crypto::secure_rng::SecureRNG::calculate_entropy(chunk);
+                    (len >= MIN_ENTROPY_CHUNK && entropy <= config.min_entropy).then_some(offset)
+                }
+                // Judged per block: a crypto-erased drive may read back zeros for deallocated blocks
+                ExpectedPattern::Erased => chunk
+                    .chunks(MIN_ENTROPY_CHUNK)
+                    .position(|block| !Self::looks_erased(block))
+                    .map(|i| offset + (i * MIN_ENTROPY_CHUNK) as u64),
+                exact => exact.first_mismatch(chunk, offset),
+            };
+
+            if let Some(failed_at) = failure {
+                println!("\nWarning: Verification failed at offset {}", failed_at);
+                return Ok(VerificationOutcome {
+                    passed: false,
+                    first_failure: Some(failed_at),
+                    entropy_score: Self::entropy_from_counts(&stats.counts),
+                    bytes_checked: offset + len as u64,
+                    randomness: None,
+                });
+            }
+
+            offset += len as u64;
+            bar.render((offset as f64 / size as f64) * 100.0, Some(offset), Some(size));
+        }
+
+        let entropy_score = Self::entropy_from_counts(&stats.counts);
+        println!("\nDrive entropy score: {:.2}", entropy_score);
+
+        let randomness = (*expected == ExpectedPattern::Random).then(|| stats.scores(config));
+        if let Some(randomness) = &randomness {
+            Self::print_randomness(randomness);
+        }
+
+        Ok(VerificationOutcome {
+            passed: randomness.as_ref().is_none_or(RandomnessScores::passed),
+            first_failure: None,
+            entropy_score,
+            bytes_checked: size,
+            randomness,
+        })
     }
 
-    fn generate_test_sectors(size: u64) -> Result<Vec<u64>> {
-        let sector_size = 512u64;
+    /// Physical sector size of `device_path`, or 512 when the block layer
+    /// can't say (e.g. a regular file)
+    fn block_size(device_path: &str) -> u64 {
+        crate::drives::DriveDetector::get_block_sizes(device_path)
+            .map(|(logical, physical)| logical.max(physical) as u64)
+            .unwrap_or(512)
+    }
+
+    /// Byte offsets of `count` random sectors of `sector_size` bytes
+    fn generate_test_sectors(size: u64, count: usize, sector_size: u64) -> Result<Vec<u64>> {
         let total_sectors = size / sector_size;
         let mut test_sectors = Vec::new();
-        let mut bytes = [0u8; 8];
-        secure_random_bytes(&mut bytes)?;
+        if total_sectors == 0 {
+            return Ok(test_sectors);
+        }
 
-        // Test 1000 random sectors (or less if not available)
-        let tests = std::cmp::min(1000, total_sectors as usize);
+        // Test the requested number of random sectors (or less if not available)
+        let tests = std::cmp::min(count as u64, total_sectors) as usize;
+        let mut bytes = [0u8; 8];
         for _ in 0..tests {
+            secure_random_bytes(&mut bytes)?;
             let sector = u64::from_le_bytes(bytes) % total_sectors;
             test_sectors.push(sector * sector_size);
         }
@@ -55,11 +429,84 @@ impl RecoveryTest {
         Ok(test_sectors)
     }
 
-    fn verify_sector_wiped(device_path: &str, offset: u64) -> Result<bool> {
-        let config = IOConfig::small_read_optimized();
-        let mut handle = OptimizedIO::open(device_path, config)?;
+    /// Check the sectors at `offsets` across the worker pool and return the
+    /// index of the first one (in `offsets` order) that isn't wiped. Workers
+    /// take every `VERIFY_WORKERS`-th sector and skip those past the lowest
+    /// failure seen so far, but every sector before it is always read, so
+    /// the answer (and any read error reported) is what a serial scan gives.
+    fn find_unwiped_sector(
+        device_path: &str,
+        size: u64,
+        offsets: &[u64],
+        read_size: usize,
+        expected: &ExpectedPattern,
+        bar: &mut ProgressBar,
+    ) -> Result<Option<usize>> {
+        let workers = VERIFY_WORKERS.min(offsets.len()).max(1);
+        let lowest_failure = AtomicUsize::new(usize::MAX);
+        let checked = AtomicUsize::new(0);
+
+        let failures = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| {
+                    let (lowest_failure, checked) = (&lowest_failure, &checked);
+                    scope.spawn(move || -> (usize, Result<bool>) {
+                        let file = match File::open(device_path) {
+                            Ok(file) => file,
+                            Err(e) => return (worker, Err(e.into())),
+                        };
+                        for index in (worker..offsets.len()).step_by(workers) {
+                            if index > lowest_failure.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            let offset = offsets[index];
+                            let mut buffer = vec![0u8; (read_size as u64).min(size - offset) as usize];
+                            let wiped = file
+                                .read_exact_at(&mut buffer, offset)
+                                .map_err(anyhow::Error::from)
+                                .map(|_| Self::sector_wiped(&buffer, offset, expected));
+                            if !matches!(wiped, Ok(true)) {
+                                lowest_failure.fetch_min(index, Ordering::Relaxed);
+                                return (index, wiped);
+                            }
+                            checked.fetch_add(1, Ordering::Relaxed);
+                        }
+                        (usize::MAX, Ok(true))
+                    })
+                })
+                .collect();
 
-        let buffer = OptimizedIO::read_range(&mut handle, offset, 4096)?;
+            Self::render_while_running(&handles, bar, || checked.load(Ordering::Relaxed) as f64 / offsets.len() as f64 * 50.0);
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|_| (0, Err(anyhow!("Verification worker panicked")))))
+                .collect::<Vec<_>>()
+        });
+
+        match failures.into_iter().min_by_key(|(index, _)| *index) {
+            Some((index, result)) if index != usize::MAX => result.map(|_| Some(index)),
+            _ => {
+                bar.render(50.0, None, None);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Redraw `bar` at `progress()` percent until every worker has finished
+    fn render_while_running<T>(handles: &[ScopedJoinHandle<'_, T>], bar: &mut ProgressBar, progress: impl Fn() -> f64) {
+        while !handles.iter().all(ScopedJoinHandle::is_finished) {
+            bar.render(progress(), None, None);
+            std::thread::sleep(PROGRESS_INTERVAL);
+        }
+    }
+
+    /// Whether one sampled sector holds what the final pass should have left
+    fn sector_wiped(buffer: &[u8], offset: u64, expected: &ExpectedPattern) -> bool {
+        match expected {
+            ExpectedPattern::Random => {}
+            ExpectedPattern::Erased => return Self::looks_erased(buffer),
+            exact => return exact.first_mismatch(buffer, offset).is_none(),
+        }
 
         let zero_count = buffer.iter().filter(|&&b| b == 0).count();
         let ff_count = buffer.iter().filter(|&&b| b == 0xFF).count();
@@ -67,39 +514,403 @@ impl RecoveryTest {
         // If more than 80% is the same byte, it's likely properly wiped
         let uniform_threshold = buffer.len() * 8 / 10;
 
-        Ok(zero_count < uniform_threshold && ff_count < uniform_threshold)
+        zero_count < uniform_threshold && ff_count < uniform_threshold
+    }
+
+    /// A block left by a hardware erase: a single repeated byte, or data
+    /// random enough to be ciphertext under a discarded key
+    fn looks_erased(buffer: &[u8]) -> bool {
+        match buffer.first() {
+            Some(&first) if buffer.iter().all(|&b| b == first) => true,
+            Some(_) => crate::crypto::secure_rng::SecureRNG::calculate_entropy(buffer) >= ERASED_MIN_ENTROPY,
+            None => true,
+        }
     }
 
-    fn calculate_entropy(device_path: &str, size: u64, bar: &mut ProgressBar) -> Result<f64> {
-        let config = IOConfig::verification_optimized();
-        let mut handle = OptimizedIO::open(device_path, config)?;
+    /// Average entropy of windows spread evenly across the device, so an
+    /// unwiped region anywhere drags the score down. Everything read is
+    /// also added to `stats`. Windows are read across the worker pool and
+    /// combined in window order, so the score doesn't depend on which
+    /// worker finishes first.
+    fn calculate_entropy(
+        device_path: &str,
+        size: u64,
+        sample_bytes: u64,
+        stats: &mut ByteStats,
+        bar: &mut ProgressBar,
+    ) -> Result<f64> {
+        let windows = Self::entropy_windows(size, sample_bytes);
+        if windows.is_empty() {
+            return Ok(0.0);
+        }
+        let total: u64 = windows.iter().map(|&(_, len)| len as u64).sum();
+        let workers = VERIFY_WORKERS.min(windows.len());
+        let processed = AtomicU64::new(0);
 
-        let sample_size = std::cmp::min(100 * 1024 * 1024, size);
-        let buffer = OptimizedIO::read_range(&mut handle, 0, sample_size as usize)?;
+        let mut results = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| {
+                    let (windows, processed) = (&windows, &processed);
+                    scope.spawn(move || -> Result<Vec<(usize, f64, ByteStats)>> {
+                        let file = File::open(device_path)?;
+                        let mut results = Vec::new();
+                        for index in (worker..windows.len()).step_by(workers) {
+                            let (offset, len) = windows[index];
+                            let mut buffer = vec![0u8; len];
+                            file.read_exact_at(&mut buffer, offset)?;
 
-        let mut counts = [0u64; 256];
-        let mut processed = 0usize;
-        for (_i, &byte) in buffer.iter().enumerate() {
-            counts[byte as usize] += 1;
-            processed += 1;
-            if processed % (buffer.len() / 50).max(1) == 0 {
-                // progress from 50% -> 100%
-                let pct = 50.0 + (processed as f64 / buffer.len() as f64) * 50.0;
-                // pass bytes processed to show speed/eta relative to sample_size
-                bar.render(pct, Some(processed as u64), Some(buffer.len() as u64));
-            }
+                            let mut window_stats = ByteStats::new();
+                            window_stats.add(&buffer);
+                            results.push((index, Self::entropy_from_counts(&window_stats.counts), window_stats));
+                            processed.fetch_add(len as u64, Ordering::Relaxed);
+                        }
+                        Ok(results)
+                    })
+                })
+                .collect();
+
+            // progress from 50% -> 100%
+            Self::render_while_running(&handles, bar, || {
+                50.0 + (processed.load(Ordering::Relaxed) as f64 / total.max(1) as f64) * 50.0
+            });
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|_| Err(anyhow!("Verification worker panicked"))))
+                .collect::<Result<Vec<_>>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        results.sort_by_key(|(index, _, _)| *index);
+
+        let mut entropy_sum = 0.0;
+        for (_, entropy, window_stats) in &results {
+            entropy_sum += entropy;
+            stats.merge(window_stats);
         }
+        bar.render(100.0, Some(total), Some(total));
 
-        let length = buffer.len() as f64;
-        let mut entropy = 0.0;
+        Ok(entropy_sum / windows.len() as f64)
+    }
+
+    /// Split the entropy sample into sector-aligned windows from the start
+    /// to the end of the device
+    fn entropy_windows(size: u64, sample_bytes: u64) -> Vec<(u64, usize)> {
+        let sample = sample_bytes.min(size);
+        if sample == 0 {
+            return Vec::new();
+        }
+
+        let count = ENTROPY_WINDOWS.min(sample.div_ceil(MIN_ENTROPY_CHUNK as u64)).max(1);
+        let window = (sample / count).max(1);
+        let span = size - window;
+
+        (0..count)
+            .map(|i| {
+                let offset = if count == 1 { 0 } else { span * i / (count - 1) / 512 * 512 };
+                (offset, window as usize)
+            })
+            .collect()
+    }
+
+    /// Chi-square and Monte-Carlo scores of `data`, judged by `config`
+    pub fn randomness_scores(data: &[u8], config: &VerificationConfig) -> RandomnessScores {
+        let mut stats = ByteStats::new();
+        stats.add(data);
+        stats.scores(config)
+    }
+
+    fn print_randomness(scores: &RandomnessScores) {
+        let mark = |passed: bool| if passed { "✓" } else { "✗" };
+        println!("{} Chi-square: {:.2} (255 degrees of freedom)", mark(scores.chi_square_passed), scores.chi_square);
+        if let (Some(pi), Some(error)) = (scores.monte_carlo_pi, scores.monte_carlo_error) {
+            println!("{} Monte-Carlo pi: {:.6} (error {:.3}%)", mark(scores.monte_carlo_passed), pi, error);
+        }
+    }
 
-        for &count in &counts {
+    /// Chi-square statistic of a byte histogram against a uniform one
+    fn chi_square_from_counts(counts: &[u64; 256]) -> f64 {
+        let length: u64 = counts.iter().sum();
+        if length == 0 {
+            return 0.0;
+        }
+
+        let expected = length as f64 / 256.0;
+        counts
+            .iter()
+            .map(|&count| {
+                let diff = count as f64 - expected;
+                diff * diff / expected
+            })
+            .sum()
+    }
+
+    /// Shannon entropy (bits/byte) of a byte histogram
+    fn entropy_from_counts(counts: &[u64; 256]) -> f64 {
+        let length: u64 = counts.iter().sum();
+        if length == 0 {
+            return 0.0;
+        }
+
+        let mut entropy = 0.0;
+        for &count in counts {
             if count > 0 {
-                let probability = count as f64 / length;
+                let probability = count as f64 / length as f64;
                 entropy -= probability * probability.log2();
             }
         }
 
-        Ok(entropy)
+        entropy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn full_config() -> VerificationConfig {
+        VerificationConfig {
+            level: VerificationLevel::Full,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_full_verification_reports_first_nonzero_offset() {
+        let mut data = vec![0u8; 3 * FULL_VERIFY_CHUNK];
+        data[FULL_VERIFY_CHUNK + 4096 + 7] = 0xAB;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+
+        let path = file.path().to_str().unwrap();
+        let size = data.len() as u64;
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Zeros).unwrap();
+        assert!(!outcome.passed);
+        assert_eq!(outcome.first_failure, Some(FULL_VERIFY_CHUNK as u64 + 4096 + 7));
+
+        data[FULL_VERIFY_CHUNK + 4096 + 7] = 0;
+        std::fs::write(path, &data).unwrap();
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Zeros).unwrap();
+        assert!(outcome.passed);
+        assert_eq!(outcome.bytes_checked, size);
+    }
+
+    #[test]
+    fn test_random_wipe_must_pass_statistical_tests() {
+        let mut random = vec![0u8; 2 * MIN_MONTE_CARLO_BYTES as usize];
+        secure_random_bytes(&mut random).unwrap();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&random).unwrap();
+        let path = file.path().to_str().unwrap();
+        let size = random.len() as u64;
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Random).unwrap();
+        assert!(outcome.passed);
+        let scores = outcome.randomness.unwrap();
+        assert!(scores.monte_carlo_error.unwrap() < 0.5, "{:?}", scores);
+
+        // A repeating counter has 8.0 bits/byte of entropy in every chunk
+        // but a histogram far too even to be random
+        let counter: Vec<u8> = (0..size).map(|i| i as u8).collect();
+        std::fs::write(path, &counter).unwrap();
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Random).unwrap();
+        assert!(outcome.entropy_score > 7.99);
+        assert!(!outcome.passed);
+        let scores = outcome.randomness.unwrap();
+        assert_eq!(scores.chi_square, 0.0);
+        assert!(!scores.chi_square_passed);
+    }
+
+    #[test]
+    fn test_monte_carlo_needs_enough_data() {
+        let config = VerificationConfig::default();
+        let mut sample = vec![0u8; 64 * 1024];
+        secure_random_bytes(&mut sample).unwrap();
+
+        let scores = RecoveryTest::randomness_scores(&sample, &config);
+        assert!(scores.chi_square_passed, "{:?}", scores);
+        assert_eq!(scores.monte_carlo_pi, None);
+        assert!(scores.passed());
+
+        // Skewed bytes fail both tests
+        let skewed: Vec<u8> = sample.iter().map(|b| b & 0x7F).cycle().take(MIN_MONTE_CARLO_BYTES as usize).collect();
+        let scores = RecoveryTest::randomness_scores(&skewed, &config);
+        assert!(!scores.chi_square_passed);
+        assert!(!scores.monte_carlo_passed);
+    }
+
+    #[test]
+    fn test_full_verification_flags_low_entropy_chunk() {
+        let mut data = vec![0u8; 2 * FULL_VERIFY_CHUNK];
+        for (i, byte) in data[..FULL_VERIFY_CHUNK].iter_mut().enumerate() {
+            // Cheap full-range byte stream with near-maximal entropy
+            *byte = (i.wrapping_mul(2654435761) >> 13) as u8;
+        }
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+
+        let outcome = RecoveryTest::verify_wipe_with_config(
+            file.path().to_str().unwrap(),
+            data.len() as u64,
+            &full_config(),
+            ExpectedPattern::Random,
+        ).unwrap();
+        assert!(!outcome.passed);
+        assert_eq!(outcome.first_failure, Some(FULL_VERIFY_CHUNK as u64));
+    }
+
+    #[test]
+    fn test_sample_offsets_aligned_to_sector_size() {
+        let offsets = RecoveryTest::generate_test_sectors(1 << 20, 64, 4096).unwrap();
+        assert_eq!(offsets.len(), 64);
+        assert!(offsets.iter().all(|offset| offset % 4096 == 0 && *offset < 1 << 20));
+    }
+
+    #[test]
+    fn test_sample_count_is_configurable() {
+        let sectors = RecoveryTest::generate_test_sectors(1024 * 1024, 25, 512).unwrap();
+        assert_eq!(sectors.len(), 25);
+        assert!(sectors.iter().all(|&offset| offset < 1024 * 1024 && offset % 512 == 0));
+
+        assert_eq!(ExpectedPattern::for_algorithm(&Algorithm::Zero), ExpectedPattern::Zeros);
+        assert_eq!(ExpectedPattern::for_algorithm(&Algorithm::DoD5220), ExpectedPattern::Random);
+        assert_eq!(ExpectedPattern::for_algorithm(&Algorithm::Sanitize), ExpectedPattern::Erased);
+        assert_eq!(ExpectedPattern::for_algorithm(&Algorithm::SecureErase), ExpectedPattern::Erased);
+    }
+
+    #[test]
+    fn test_custom_wipe_expects_its_last_pass() {
+        let custom = |passes| ExpectedPattern::for_algorithm(&Algorithm::Custom { passes });
+        assert_eq!(custom(vec![PatternSpec::Random, PatternSpec::Byte(0x00)]), ExpectedPattern::Zeros);
+        assert_eq!(custom(vec![PatternSpec::Byte(0x00), PatternSpec::Byte(0xFF)]), ExpectedPattern::Fixed(0xFF));
+        assert_eq!(custom(vec![PatternSpec::Random]), ExpectedPattern::Random);
+
+        let sequence = custom(vec![PatternSpec::Sequence(vec![0xDE, 0xAD, 0xBE])]);
+        assert_eq!(sequence, ExpectedPattern::Sequence(vec![0xDE, 0xAD, 0xBE]));
+
+        // Sequences are checked in phase with the device, not the read
+        let data = [0xDE, 0xAD, 0xBE].repeat(2000);
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+        let path = file.path().to_str().unwrap();
+        let outcome = RecoveryTest::verify_wipe_with_config(path, data.len() as u64, &full_config(), sequence.clone()).unwrap();
+        assert!(outcome.passed);
+        assert!(RecoveryTest::sector_wiped(&data[1..513], 1, &sequence));
+        assert!(!RecoveryTest::sector_wiped(&data[1..513], 0, &sequence));
+    }
+
+    #[test]
+    fn test_parallel_sampling_reports_first_failure_in_sample_order() {
+        let mut data = vec![0u8; 64 * 4096];
+        data[40 * 4096] = 0xAB;
+        data[7 * 4096 + 100] = 0xCD;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // Whichever worker reads its failure first, the earlier sample wins
+        let offsets: Vec<u64> = (0..64).rev().map(|sector| sector * 4096).collect();
+        for _ in 0..10 {
+            let mut bar = ProgressBar::new(48);
+            let failed = RecoveryTest::find_unwiped_sector(path, data.len() as u64, &offsets, 4096, &ExpectedPattern::Zeros, &mut bar).unwrap();
+            assert_eq!(failed.map(|index| offsets[index]), Some(40 * 4096));
+        }
+
+        // A sample in the last sector reads only up to the end of the device
+        let mut bar = ProgressBar::new(48);
+        let last = [data.len() as u64 - 512];
+        assert_eq!(RecoveryTest::find_unwiped_sector(path, data.len() as u64, &last, 4096, &ExpectedPattern::Zeros, &mut bar).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parallel_entropy_matches_serial_windows() {
+        let mut data = vec![0u8; 8 * 64 * 1024];
+        secure_random_bytes(&mut data[..6 * 64 * 1024]).unwrap();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+        let size = data.len() as u64;
+
+        let mut stats = ByteStats::new();
+        let mut bar = ProgressBar::new(48);
+        let entropy = RecoveryTest::calculate_entropy(file.path().to_str().unwrap(), size, size, &mut stats, &mut bar).unwrap();
+
+        let mut serial = ByteStats::new();
+        let mut entropy_sum = 0.0;
+        let windows = RecoveryTest::entropy_windows(size, size);
+        for &(offset, len) in &windows {
+            let window = &data[offset as usize..offset as usize + len];
+            let mut counts = [0u64; 256];
+            window.iter().for_each(|&b| counts[b as usize] += 1);
+            entropy_sum += RecoveryTest::entropy_from_counts(&counts);
+            serial.add(window);
+        }
+        assert_eq!(entropy, entropy_sum / windows.len() as f64);
+        assert_eq!((stats.counts, stats.points, stats.inside), (serial.counts, serial.points, serial.inside));
+    }
+
+    #[test]
+    fn test_entropy_windows_span_device() {
+        let size = 1024 * 1024 * 1024;
+        let windows = RecoveryTest::entropy_windows(size, 8 * 1024 * 1024);
+
+        assert_eq!(windows.len(), ENTROPY_WINDOWS as usize);
+        assert_eq!(windows[0], (0, 1024 * 1024));
+        let (last_offset, last_len) = *windows.last().unwrap();
+        assert_eq!(last_offset + last_len as u64, size);
+        assert!(windows.iter().all(|&(offset, _)| offset % 512 == 0));
+
+        // Small devices fall back to fewer, still non-empty windows
+        let windows = RecoveryTest::entropy_windows(4096, 100 * 1024 * 1024);
+        assert_eq!(windows, vec![(0, 4096)]);
+    }
+
+    #[test]
+    fn test_fixed_pattern_wipe_passes_by_byte_not_entropy() {
+        let data = vec![0xFFu8; 64 * 1024];
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+        let path = file.path().to_str().unwrap();
+        let size = data.len() as u64;
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Fixed(0xFF)).unwrap();
+        assert!(outcome.passed);
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Zeros).unwrap();
+        assert_eq!(outcome.first_failure, Some(0));
+
+        let outcome = RecoveryTest::verify_wipe_with_config(path, size, &full_config(), ExpectedPattern::Random).unwrap();
+        assert!(!outcome.passed);
+    }
+
+    #[test]
+    fn test_erased_accepts_uniform_or_random_blocks() {
+        let mut data = vec![0xFFu8; 64 * 1024];
+        secure_random_bytes(&mut data[32 * 1024..]).unwrap();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+
+        assert!(RecoveryTest::looks_erased(&data[..4096]));
+        assert!(RecoveryTest::looks_erased(&data[32 * 1024..36 * 1024]));
+
+        // Leftover text is structured data, whatever surrounds it
+        let text = b"Quarterly payroll for the accounts team\n".repeat(200);
+        assert!(!RecoveryTest::looks_erased(&text[..4096]));
+
+        data[..4096].copy_from_slice(&text[..4096]);
+        let mut file_with_text = NamedTempFile::new().unwrap();
+        file_with_text.write_all(&data).unwrap();
+
+        let config = VerificationConfig { level: VerificationLevel::Full, ..VerificationConfig::default() };
+        let size = data.len() as u64;
+        let erased = RecoveryTest::verify_wipe_with_config(file.path().to_str().unwrap(), size, &config, ExpectedPattern::Erased).unwrap();
+        assert!(erased.passed, "uniform and random blocks can sit side by side");
+
+        let outcome = RecoveryTest::verify_wipe_with_config(file_with_text.path().to_str().unwrap(), size, &config, ExpectedPattern::Erased).unwrap();
+        assert_eq!(outcome.first_failure, Some(0));
     }
 }
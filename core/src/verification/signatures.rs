@@ -0,0 +1,309 @@
+// Disk Signatures - what was on the drive before the wipe, and proof it's gone
+//
+// `scan_signatures` reads the MBR and GPT (primary and backup) and probes the
+// start of the device and of every partition for filesystem, LUKS, LVM and
+// swap magic. The orchestrator scans before wiping and again afterwards, so
+// the certificate can list the structures that existed and show that none
+// of them survived.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+
+use crate::drives::DriveDetector;
+use crate::io::FileExt;
+
+/// Bytes read at each probed offset; enough to reach the btrfs superblock
+const PROBE_LEN: usize = 0x10048;
+
+/// GPT entries read at most, the usual size of the entry array
+const MAX_GPT_ENTRIES: u32 = 128;
+
+/// Magic of on-disk formats: (name, offset from the structure's start, magic)
+const FORMAT_MAGIC: &[(&str, usize, &[u8])] = &[
+    ("ext2/3/4", 0x438, &[0x53, 0xEF]),
+    ("XFS", 0, b"XFSB"),
+    ("btrfs", 0x10040, b"_BHRfS_M"),
+    ("NTFS", 3, b"NTFS    "),
+    ("exFAT", 3, b"EXFAT   "),
+    ("BitLocker", 3, b"-FVE-FS-"),
+    ("FAT32", 82, b"FAT32   "),
+    ("FAT12/16", 54, b"FAT1"),
+    ("APFS", 32, b"NXSB"),
+    ("LVM2 PV", 0x218, b"LVM2 001"),
+    ("Linux swap", 4086, b"SWAPSPACE2"),
+];
+
+const LUKS_MAGIC: &[u8] = b"LUKS\xba\xbe";
+
+/// Well-known GPT partition type GUIDs
+const GPT_TYPES: &[(&str, &str)] = &[
+    ("C12A7328-F81F-11D2-BA4B-00A0C93EC93B", "EFI System"),
+    ("E3C9E316-0B5C-4DB8-817D-F92DF00215AE", "Microsoft reserved"),
+    ("EBD0A0A2-B9E5-4433-87C0-68B6B72699C7", "Microsoft basic data"),
+    ("0FC63DAF-8483-4772-8E79-3D69D8477DE4", "Linux filesystem"),
+    ("0657FD6D-A4AB-43C4-84E5-0933C84B4F4F", "Linux swap"),
+    ("E6D6D379-F507-44C2-A23C-238F2A3DF928", "Linux LVM"),
+    ("CA7D7CCB-63ED-4C53-861C-1742536059CC", "Linux LUKS"),
+    ("A19D880F-05FC-4D3B-A006-743F0F84911E", "Linux RAID"),
+    ("7C3457EF-0000-11AA-AA11-00306543ECAC", "Apple APFS"),
+];
+
+/// A partition table, partition or on-disk header found on the device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskSignature {
+    /// "GPT", "MBR", "GPT partition 1", "ext2/3/4", "LUKS2", ...
+    pub kind: String,
+    /// Byte offset of the structure on the device
+    pub offset: u64,
+    /// Partition type, label or UUID where the structure records one
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+impl DiskSignature {
+    fn new(kind: impl Into<String>, offset: u64, detail: Option<String>) -> Self {
+        Self { kind: kind.into(), offset, detail }
+    }
+}
+
+impl std::fmt::Display for DiskSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)?;
+        if let Some(detail) = &self.detail {
+            write!(f, " ({})", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Partition tables, partitions and filesystem/LUKS/LVM/swap headers on
+/// `device_path`, in the order found
+pub fn scan_signatures(device_path: &str) -> Result<Vec<DiskSignature>> {
+    let file = File::open(device_path).with_context(|| format!("Failed to open {} for a signature scan", device_path))?;
+    let size = match DriveDetector::get_block_device_size(device_path) {
+        Ok(size) => size,
+        Err(_) => file.metadata()?.len(),
+    };
+
+    let mut found = Vec::new();
+    let mut starts = vec![0u64];
+
+    let sector0 = read(&file, 0, 512)?;
+    starts.extend(scan_mbr(&sector0, &mut found));
+    for block_size in [512u64, 4096] {
+        let partitions = scan_gpt(&file, block_size, size, &mut found)?;
+        if !partitions.is_empty() || found.iter().any(|s| s.kind == "GPT") {
+            starts.extend(partitions);
+            break;
+        }
+    }
+
+    starts.sort_unstable();
+    starts.dedup();
+    for start in starts.into_iter().filter(|&start| start < size) {
+        let data = read(&file, start, PROBE_LEN)?;
+        found.extend(probe_formats(&data, start));
+    }
+
+    Ok(found)
+}
+
+/// Up to `len` bytes at `offset`, fewer at the end of the device
+fn read(file: &File, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    let mut filled = 0;
+    while filled < len {
+        match file.read_at(&mut buf[filled..], offset + filled as u64)? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    buf.truncate(filled);
+    Ok(buf)
+}
+
+/// Record an MBR and its partitions, returning their start offsets. A
+/// protective MBR (type EEh) belongs to a GPT, and a boot signature with no
+/// partition entries is a filesystem's boot sector, not a partition table.
+fn scan_mbr(sector: &[u8], found: &mut Vec<DiskSignature>) -> Vec<u64> {
+    if sector.len() < 512 || sector[510..512] != [0x55, 0xAA] {
+        return Vec::new();
+    }
+
+    let entries: Vec<(usize, u8, u64)> = (0..4)
+        .map(|i| {
+            let entry = &sector[446 + i * 16..462 + i * 16];
+            (i, entry[4], u32::from_le_bytes(entry[8..12].try_into().unwrap()) as u64)
+        })
+        .filter(|&(_, kind, lba)| kind != 0 && lba != 0)
+        .collect();
+    if entries.is_empty() || entries.iter().any(|&(_, kind, _)| kind == 0xEE) {
+        return Vec::new();
+    }
+
+    found.push(DiskSignature::new("MBR", 0, None));
+    entries
+        .into_iter()
+        .map(|(i, kind, lba)| {
+            found.push(DiskSignature::new(format!("MBR partition {}", i + 1), lba * 512, Some(format!("type 0x{:02X}", kind))));
+            lba * 512
+        })
+        .collect()
+}
+
+/// Record the primary and backup GPT headers for `block_size` and the
+/// partitions listed, returning their start offsets
+fn scan_gpt(file: &File, block_size: u64, size: u64, found: &mut Vec<DiskSignature>) -> Result<Vec<u64>> {
+    let header = read(file, block_size, 92)?;
+    let primary = header.len() == 92 && header.starts_with(b"EFI PART");
+
+    let backup_offset = (size / block_size).saturating_sub(1) * block_size;
+    let backup = backup_offset > block_size && read(file, backup_offset, 8)? == b"EFI PART";
+    if backup {
+        found.push(DiskSignature::new("GPT backup header", backup_offset, None));
+    }
+    if !primary {
+        return Ok(Vec::new());
+    }
+    found.push(DiskSignature::new("GPT", block_size, Some(guid(&header[56..72]))));
+
+    let entries_lba = u64::from_le_bytes(header[72..80].try_into().unwrap());
+    let count = u32::from_le_bytes(header[80..84].try_into().unwrap()).min(MAX_GPT_ENTRIES);
+    let entry_size = u32::from_le_bytes(header[84..88].try_into().unwrap()) as usize;
+    if entry_size < 128 {
+        return Ok(Vec::new());
+    }
+
+    let table = read(file, entries_lba * block_size, count as usize * entry_size)?;
+    let mut starts = Vec::new();
+    for (i, entry) in table.chunks_exact(entry_size).enumerate() {
+        if entry[..16].iter().all(|&b| b == 0) {
+            continue;
+        }
+        let type_guid = guid(&entry[..16]);
+        let first_lba = u64::from_le_bytes(entry[32..40].try_into().unwrap());
+        let name: Vec<u16> = entry[56..128]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect();
+
+        let type_name = GPT_TYPES.iter().find(|(g, _)| *g == type_guid).map_or(type_guid.as_str(), |(_, n)| n);
+        let mut detail = type_name.to_string();
+        if !name.is_empty() {
+            detail.push_str(&format!(", \"{}\"", String::from_utf16_lossy(&name)));
+        }
+
+        found.push(DiskSignature::new(format!("GPT partition {}", i + 1), first_lba * block_size, Some(detail)));
+        starts.push(first_lba * block_size);
+    }
+    Ok(starts)
+}
+
+/// Filesystem, LUKS, LVM and swap headers at the start of `data`, which
+/// was read from device offset `start`
+fn probe_formats(data: &[u8], start: u64) -> Vec<DiskSignature> {
+    let mut found = Vec::new();
+
+    if data.starts_with(LUKS_MAGIC) && data.len() >= 208 {
+        let version = u16::from_be_bytes([data[6], data[7]]);
+        let uuid = String::from_utf8_lossy(&data[168..208]).trim_end_matches('\0').to_string();
+        found.push(DiskSignature::new(format!("LUKS{}", version), start, (!uuid.is_empty()).then_some(uuid)));
+    }
+
+    for (name, offset, magic) in FORMAT_MAGIC {
+        if data.get(*offset..offset + magic.len()) == Some(*magic) {
+            found.push(DiskSignature::new(*name, start, None));
+        }
+    }
+    found
+}
+
+/// A GUID in its mixed-endian on-disk form, as the usual uppercase string
+fn guid(bytes: &[u8]) -> String {
+    format!(
+        "{:08X}-{:04X}-{:04X}-{}-{}",
+        u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+        u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
+        u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
+        bytes[8..10].iter().map(|b| format!("{:02X}", b)).collect::<String>(),
+        bytes[10..16].iter().map(|b| format!("{:02X}", b)).collect::<String>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes of a GUID string in on-disk order
+    fn guid_bytes(guid: &str) -> Vec<u8> {
+        let hex: Vec<u8> = guid
+            .replace('-', "")
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+            .collect();
+        let mut bytes = Vec::new();
+        bytes.extend(hex[0..4].iter().rev());
+        bytes.extend(hex[4..6].iter().rev());
+        bytes.extend(hex[6..8].iter().rev());
+        bytes.extend(&hex[8..16]);
+        bytes
+    }
+
+    #[test]
+    fn test_scan_finds_gpt_partitions_and_headers() {
+        let mut image = vec![0u8; 4 << 20];
+
+        // Protective MBR, GPT header at LBA 1 and entries at LBA 2
+        image[446 + 4] = 0xEE;
+        image[446 + 8] = 1;
+        image[510..512].copy_from_slice(&[0x55, 0xAA]);
+        image[512..520].copy_from_slice(b"EFI PART");
+        image[512 + 72] = 2;
+        image[512 + 80] = 128;
+        image[512 + 84] = 128;
+        let entry = &mut image[1024..1152];
+        entry[..16].copy_from_slice(&guid_bytes("0FC63DAF-8483-4772-8E79-3D69D8477DE4"));
+        entry[32..40].copy_from_slice(&2048u64.to_le_bytes());
+        entry[56..60].copy_from_slice(&[b'r', 0, b'o', 0]);
+        let entry = &mut image[1152..1280];
+        entry[..16].copy_from_slice(&guid_bytes("CA7D7CCB-63ED-4C53-861C-1742536059CC"));
+        entry[32..40].copy_from_slice(&4096u64.to_le_bytes());
+
+        // ext4 in the first partition, LUKS2 in the second
+        image[2048 * 512 + 0x438..2048 * 512 + 0x43A].copy_from_slice(&[0x53, 0xEF]);
+        let luks = 4096 * 512;
+        image[luks..luks + 6].copy_from_slice(LUKS_MAGIC);
+        image[luks + 7] = 2;
+        image[luks + 168..luks + 172].copy_from_slice(b"abcd");
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &image).unwrap();
+        let found = scan_signatures(file.path().to_str().unwrap()).unwrap();
+
+        let kinds: Vec<&str> = found.iter().map(|s| s.kind.as_str()).collect();
+        assert_eq!(kinds, ["GPT", "GPT partition 1", "GPT partition 2", "ext2/3/4", "LUKS2"]);
+        assert_eq!(found[1].detail.as_deref(), Some("Linux filesystem, \"ro\""));
+        assert_eq!(found[2].detail.as_deref(), Some("Linux LUKS"));
+        assert_eq!(found[3].offset, 2048 * 512);
+        assert_eq!(found[4].detail.as_deref(), Some("abcd"));
+
+        // Nothing survives a zero pass
+        std::fs::write(file.path(), vec![0u8; image.len()]).unwrap();
+        assert!(scan_signatures(file.path().to_str().unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_boot_sector_is_not_a_partition_table() {
+        let mut sector = vec![0u8; 512];
+        sector[82..90].copy_from_slice(b"FAT32   ");
+        sector[510..512].copy_from_slice(&[0x55, 0xAA]);
+
+        let mut found = Vec::new();
+        assert!(scan_mbr(&sector, &mut found).is_empty());
+        assert!(found.is_empty());
+        assert_eq!(probe_formats(&sector, 0), [DiskSignature::new("FAT32", 0, None)]);
+    }
+}
@@ -0,0 +1,169 @@
+// Standalone verification - check a drive that was wiped by other means
+//
+// The wipe engine verifies its own final pass as part of a wipe. This runs
+// the same sampling, entropy and statistical checks against a drive as it
+// stands, whatever erased it, and never writes to it: detection issues only
+// read-only commands and the recovery test only reads. The report can be
+// turned into a certificate that records it as a verification, not a wipe.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::recovery_test::{ExpectedPattern, RecoveryTest, VerificationConfig, VerificationLevel};
+use crate::crypto::certificates::{CertificateGenerator, RandomnessScores, VerificationResult, WipeCertificate, WipeDetails};
+use crate::drives::Capabilities;
+use crate::{DriveInfo, WipeOrchestrator};
+
+/// Method recorded in certificates issued from a verification alone
+const VERIFICATION_ONLY_METHOD: &str = "verification only (wiped by other means)";
+
+/// What `verify_drive` found on a drive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriveVerificationReport {
+    pub drive: DriveInfo,
+    /// What the drive was expected to hold
+    pub expected: ExpectedPattern,
+    pub level: VerificationLevel,
+    pub passed: bool,
+    /// Byte offset of the first sector or chunk that did not match
+    pub first_failure: Option<u64>,
+    pub entropy_score: f64,
+    pub bytes_checked: u64,
+    /// Chi-square and Monte-Carlo scores, when random data was expected
+    pub randomness: Option<RandomnessScores>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl DriveVerificationReport {
+    /// Whether an HPA or DCO hides sectors the checks could not read
+    pub fn hidden_areas_present(&self) -> bool {
+        self.drive.capabilities.hpa_enabled || self.drive.capabilities.dco_enabled
+    }
+
+    /// A certificate recording this verification, signed with the Ed25519
+    /// key at `signing_key` when one is given. No passes are claimed: the
+    /// method names it as a verification of a drive wiped elsewhere.
+    pub fn certificate(&self, signing_key: Option<&Path>) -> Result<WipeCertificate> {
+        let wipe_details = WipeDetails {
+            algorithm_used: "unknown".to_string(),
+            standard: None,
+            passes_completed: 0,
+            duration_seconds: (self.completed_at - self.started_at).num_seconds().max(0) as u64,
+            operator_id: None,
+            method: format!("{}, expecting {}", VERIFICATION_ONLY_METHOD, self.expected),
+            started_at: self.started_at,
+            completed_at: self.completed_at,
+            notes: None,
+            completion: Default::default(),
+            hidden_areas: None,
+            trim_verification: None,
+            health: None,
+            entropy_source: None,
+            device_logs: Vec::new(),
+            passes: Vec::new(),
+            random_seed: None,
+            nvme_namespaces: Vec::new(),
+            retries: Default::default(),
+            signatures: None,
+            partition_tables_cleared: Vec::new(),
+            final_hash: None,
+            reprovisioned: None,
+            capabilities: Some(Capabilities::of(&self.drive)),
+            advertised_erase_time: None,
+        };
+
+        let verification = VerificationResult {
+            verified: self.passed,
+            entropy_score: self.entropy_score,
+            recovery_test_passed: self.passed,
+            verification_timestamp: self.completed_at,
+            randomness: self.randomness.clone(),
+        };
+
+        let mut certificate = CertificateGenerator::new().generate_certificate(&self.drive, wipe_details, verification)?;
+        if let Some(key_path) = signing_key {
+            certificate.sign_ed25519(key_path)?;
+        }
+        Ok(certificate)
+    }
+}
+
+impl std::fmt::Display for DriveVerificationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} expecting {}: {} bytes checked, entropy {:.2} bits/byte",
+            self.drive.device_path,
+            if self.passed { "PASSED" } else { "FAILED" },
+            self.expected,
+            self.bytes_checked,
+            self.entropy_score,
+        )?;
+        if let Some(offset) = self.first_failure {
+            write!(f, ", first failure at byte {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check that `device_path` holds what a wipe ending in `expected` leaves,
+/// under `config`, without writing to it
+pub fn verify_drive(
+    device_path: &str,
+    expected: ExpectedPattern,
+    config: &VerificationConfig,
+) -> Result<DriveVerificationReport> {
+    let drive = WipeOrchestrator::inspect(device_path)?;
+    let started_at = Utc::now();
+    let outcome = RecoveryTest::verify_wipe_with_config(device_path, drive.size, config, expected.clone())?;
+
+    Ok(DriveVerificationReport {
+        drive,
+        expected,
+        level: config.level,
+        passed: outcome.passed,
+        first_failure: outcome.first_failure,
+        entropy_score: outcome.entropy_score,
+        bytes_checked: outcome.bytes_checked,
+        randomness: outcome.randomness,
+        started_at,
+        completed_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{scoped_runner, MockCommandRunner};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_verify_drive_reads_only() {
+        let _guard = scoped_runner(Arc::new(MockCommandRunner::new()));
+        let image = tempfile::NamedTempFile::new().unwrap();
+        let path = image.path().to_str().unwrap();
+        std::fs::write(image.path(), vec![0u8; 1 << 20]).unwrap();
+        let config = VerificationConfig { sector_sample_count: 64, ..Default::default() };
+
+        let report = verify_drive(path, ExpectedPattern::Zeros, &config).unwrap();
+        assert!(report.passed, "{}", report);
+        assert_eq!(report.drive.size, 1 << 20);
+
+        let certificate = report.certificate(None).unwrap();
+        assert!(certificate.wipe_details.method.starts_with(VERIFICATION_ONLY_METHOD));
+        assert_eq!(certificate.wipe_details.passes_completed, 0);
+        assert!(certificate.verification.verified);
+        assert!(CertificateGenerator::new().verify_certificate(&certificate).unwrap());
+
+        // Data left behind fails, and nothing was written either time
+        let data: Vec<u8> = (0..1u32 << 20).map(|i| (i % 7) as u8).collect();
+        std::fs::write(image.path(), &data).unwrap();
+        let report = verify_drive(path, ExpectedPattern::Zeros, &config).unwrap();
+        assert!(!report.passed && report.first_failure.is_some());
+        assert!(!report.certificate(None).unwrap().verification.verified);
+        assert_eq!(std::fs::read(image.path()).unwrap(), data);
+    }
+}